@@ -0,0 +1,320 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The kernel interface of autofs, see linux/auto_dev-ioctl.h and linux/auto_fs.h.
+//!
+//! The mount point is mounted as a direct autofs mount, the kernel writes a packet to
+//! the pipe given at mount time when a process accesses the mount point or when it has
+//! been idle long enough, and the process is blocked until the token of the packet is
+//! acknowledged through the ioctls of /dev/autofs.
+
+use nix::errno::Errno;
+use nix::mount::{mount, umount2, MntFlags, MsFlags};
+use nix::sys::stat::stat;
+use nix::unistd::getpgrp;
+use nix::{ioctl_readwrite, Result};
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+const AUTOFS_DEV: &str = "/dev/autofs";
+const AUTOFS_SOURCE: &str = "sysmaster";
+const AUTOFS_PROTO_VERSION: u32 = 5;
+
+const AUTOFS_DEV_IOCTL_VERSION_MAJOR: u32 = 1;
+const AUTOFS_DEV_IOCTL_VERSION_MINOR: u32 = 0;
+
+const AUTOFS_IOCTL: u8 = 0x93;
+const AUTOFS_DEV_IOCTL_VERSION_CMD: u8 = 0x71;
+const AUTOFS_DEV_IOCTL_OPENMOUNT_CMD: u8 = 0x74;
+const AUTOFS_DEV_IOCTL_READY_CMD: u8 = 0x76;
+const AUTOFS_DEV_IOCTL_FAIL_CMD: u8 = 0x77;
+const AUTOFS_DEV_IOCTL_SETPIPEFD_CMD: u8 = 0x78;
+const AUTOFS_DEV_IOCTL_CATATONIC_CMD: u8 = 0x79;
+const AUTOFS_DEV_IOCTL_TIMEOUT_CMD: u8 = 0x7a;
+const AUTOFS_DEV_IOCTL_EXPIRE_CMD: u8 = 0x7c;
+
+const AUTOFS_PTYPE_MISSING_DIRECT: i32 = 5;
+const AUTOFS_PTYPE_EXPIRE_DIRECT: i32 = 6;
+
+/// sizeof(union autofs_packet_union), the v5 packet with a name of NAME_MAX + 1 bytes
+pub(super) const AUTOFS_PACKET_SIZE: usize = 304;
+
+/// struct autofs_dev_ioctl, the union of the arguments is accessed as two words
+#[repr(C, align(8))]
+#[derive(Debug, Default)]
+struct AutofsDevIoctl {
+    ver_major: u32,
+    ver_minor: u32,
+    size: u32,
+    ioctlfd: i32,
+    args: [u32; 2],
+}
+
+impl AutofsDevIoctl {
+    fn new(ioctlfd: RawFd) -> Self {
+        AutofsDevIoctl {
+            ver_major: AUTOFS_DEV_IOCTL_VERSION_MAJOR,
+            ver_minor: AUTOFS_DEV_IOCTL_VERSION_MINOR,
+            size: std::mem::size_of::<AutofsDevIoctl>() as u32,
+            ioctlfd,
+            args: [0; 2],
+        }
+    }
+
+    fn set_u64(&mut self, v: u64) {
+        let bytes = v.to_ne_bytes();
+        self.args[0] = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        self.args[1] = u32::from_ne_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    }
+}
+
+ioctl_readwrite!(
+    autofs_dev_ioctl_version,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_VERSION_CMD,
+    AutofsDevIoctl
+);
+ioctl_readwrite!(
+    autofs_dev_ioctl_openmount,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_OPENMOUNT_CMD,
+    AutofsDevIoctl
+);
+ioctl_readwrite!(
+    autofs_dev_ioctl_ready,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_READY_CMD,
+    AutofsDevIoctl
+);
+ioctl_readwrite!(
+    autofs_dev_ioctl_fail,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_FAIL_CMD,
+    AutofsDevIoctl
+);
+ioctl_readwrite!(
+    autofs_dev_ioctl_setpipefd,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_SETPIPEFD_CMD,
+    AutofsDevIoctl
+);
+ioctl_readwrite!(
+    autofs_dev_ioctl_catatonic,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_CATATONIC_CMD,
+    AutofsDevIoctl
+);
+ioctl_readwrite!(
+    autofs_dev_ioctl_timeout,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_TIMEOUT_CMD,
+    AutofsDevIoctl
+);
+ioctl_readwrite!(
+    autofs_dev_ioctl_expire,
+    AUTOFS_IOCTL,
+    AUTOFS_DEV_IOCTL_EXPIRE_CMD,
+    AutofsDevIoctl
+);
+
+/// The control device /dev/autofs, closed when dropped
+pub(super) struct AutofsDev {
+    file: File,
+}
+
+impl AutofsDev {
+    /// open /dev/autofs and check the version of the ioctl interface
+    pub(super) fn open() -> Result<AutofsDev> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_CLOEXEC)
+            .open(AUTOFS_DEV)
+            .map_err(|e| Errno::from_i32(e.raw_os_error().unwrap_or(libc::EIO)))?;
+
+        let mut param = AutofsDevIoctl::new(-1);
+        unsafe { autofs_dev_ioctl_version(file.as_raw_fd(), &mut param) }?;
+        log::debug!(
+            "Autofs ioctl interface version {}.{}",
+            param.ver_major,
+            param.ver_minor
+        );
+        Ok(AutofsDev { file })
+    }
+
+    fn fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+
+    /// open the ioctl fd of the autofs mount on path, the caller owns the fd
+    pub(super) fn open_mount(&self, path: &str) -> Result<RawFd> {
+        let st = stat(path)?;
+        let header = std::mem::size_of::<AutofsDevIoctl>();
+        let size = header + path.len() + 1;
+
+        /* the path follows the header, use u64 to keep the alignment of the header */
+        let mut buf = vec![0u64; size / 8 + 1];
+        let param = buf.as_mut_ptr() as *mut AutofsDevIoctl;
+        unsafe {
+            *param = AutofsDevIoctl::new(-1);
+            (*param).size = size as u32;
+            (*param).args[0] = st.st_dev as u32;
+            let dst = (buf.as_mut_ptr() as *mut u8).add(header);
+            std::ptr::copy_nonoverlapping(path.as_ptr(), dst, path.len());
+            autofs_dev_ioctl_openmount(self.fd(), param)?;
+            Ok((*param).ioctlfd)
+        }
+    }
+
+    /// set the idle timeout in seconds of the mount, 0 disables the expiration
+    pub(super) fn set_timeout(&self, ioctl_fd: RawFd, sec: u64) -> Result<()> {
+        let mut param = AutofsDevIoctl::new(ioctl_fd);
+        param.set_u64(sec);
+        unsafe { autofs_dev_ioctl_timeout(self.fd(), &mut param) }?;
+        Ok(())
+    }
+
+    /// wake up the processes waiting for the token, with an error if status is not 0
+    pub(super) fn send_ready(&self, ioctl_fd: RawFd, token: u32, status: i32) -> Result<()> {
+        let mut param = AutofsDevIoctl::new(ioctl_fd);
+        param.args[0] = token;
+        if status == 0 {
+            unsafe { autofs_dev_ioctl_ready(self.fd(), &mut param) }?;
+        } else {
+            param.args[1] = status as u32;
+            unsafe { autofs_dev_ioctl_fail(self.fd(), &mut param) }?;
+        }
+        Ok(())
+    }
+
+    /// hand the mount over to a new pipe, the pending requests are failed by the kernel
+    pub(super) fn set_pipe(&self, ioctl_fd: RawFd, pipe_fd: RawFd) -> Result<()> {
+        let mut param = AutofsDevIoctl::new(ioctl_fd);
+        unsafe { autofs_dev_ioctl_catatonic(self.fd(), &mut param) }?;
+
+        let mut param = AutofsDevIoctl::new(ioctl_fd);
+        param.args[0] = pipe_fd as u32;
+        unsafe { autofs_dev_ioctl_setpipefd(self.fd(), &mut param) }?;
+        Ok(())
+    }
+
+    /// ask the kernel to expire the mount if it is idle, blocks until the expire packet
+    /// is acknowledged, EAGAIN if there is nothing to expire
+    pub(super) fn expire(&self, ioctl_fd: RawFd) -> Result<()> {
+        let mut param = AutofsDevIoctl::new(ioctl_fd);
+        unsafe { autofs_dev_ioctl_expire(self.fd(), &mut param) }?;
+        Ok(())
+    }
+}
+
+/// A request read from the pipe of the autofs mount
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum AutofsPacket {
+    Missing(u32),
+    Expire(u32),
+    Unknown(i32),
+}
+
+impl AutofsPacket {
+    /// parse struct autofs_v5_packet: the protocol version, the type and the token
+    pub(super) fn parse(buf: &[u8]) -> Option<AutofsPacket> {
+        if buf.len() < 12 {
+            return None;
+        }
+        let word = |i: usize| [buf[i], buf[i + 1], buf[i + 2], buf[i + 3]];
+        let ptype = i32::from_ne_bytes(word(4));
+        let token = u32::from_ne_bytes(word(8));
+        let packet = match ptype {
+            AUTOFS_PTYPE_MISSING_DIRECT => AutofsPacket::Missing(token),
+            AUTOFS_PTYPE_EXPIRE_DIRECT => AutofsPacket::Expire(token),
+            _ => AutofsPacket::Unknown(ptype),
+        };
+        Some(packet)
+    }
+}
+
+/// the options of the direct autofs mount talking to the write end of the pipe
+pub(super) fn mount_options(pipe_fd: RawFd) -> String {
+    format!(
+        "fd={},pgrp={},minproto={},maxproto={},direct",
+        pipe_fd,
+        getpgrp(),
+        AUTOFS_PROTO_VERSION,
+        AUTOFS_PROTO_VERSION
+    )
+}
+
+/// mount autofs on path, the kernel writes the requests to the write end of the pipe
+pub(super) fn mount_autofs(path: &str, pipe_fd: RawFd) -> Result<()> {
+    let options = mount_options(pipe_fd);
+    mount(
+        Some(AUTOFS_SOURCE),
+        path,
+        Some("autofs"),
+        MsFlags::empty(),
+        Some(options.as_str()),
+    )
+}
+
+/// unmount everything on path, including the file system mounted on top of autofs
+pub(super) fn umount_autofs(path: &str) {
+    loop {
+        if let Err(e) = umount2(path, MntFlags::MNT_DETACH | MntFlags::UMOUNT_NOFOLLOW) {
+            if e != Errno::EINVAL {
+                log::warn!("Failed to unmount {}: {}", path, e);
+            }
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autofs_dev_ioctl_size() {
+        /* AUTOFS_DEV_IOCTL_SIZE is encoded in the ioctl numbers */
+        assert_eq!(std::mem::size_of::<AutofsDevIoctl>(), 24);
+
+        let mut param = AutofsDevIoctl::new(3);
+        param.set_u64(300);
+        let mut bytes = [0u8; 8];
+        bytes[..4].copy_from_slice(&param.args[0].to_ne_bytes());
+        bytes[4..].copy_from_slice(&param.args[1].to_ne_bytes());
+        assert_eq!(u64::from_ne_bytes(bytes), 300);
+    }
+
+    #[test]
+    fn test_autofs_packet_parse() {
+        let mut buf = [0u8; AUTOFS_PACKET_SIZE];
+        buf[0..4].copy_from_slice(&5i32.to_ne_bytes());
+        buf[4..8].copy_from_slice(&AUTOFS_PTYPE_MISSING_DIRECT.to_ne_bytes());
+        buf[8..12].copy_from_slice(&42u32.to_ne_bytes());
+        assert_eq!(AutofsPacket::parse(&buf), Some(AutofsPacket::Missing(42)));
+
+        buf[4..8].copy_from_slice(&AUTOFS_PTYPE_EXPIRE_DIRECT.to_ne_bytes());
+        assert_eq!(AutofsPacket::parse(&buf), Some(AutofsPacket::Expire(42)));
+
+        buf[4..8].copy_from_slice(&3i32.to_ne_bytes());
+        assert_eq!(AutofsPacket::parse(&buf), Some(AutofsPacket::Unknown(3)));
+
+        assert_eq!(AutofsPacket::parse(&buf[..8]), None);
+    }
+
+    #[test]
+    fn test_mount_options() {
+        let options = mount_options(7);
+        assert!(options.starts_with("fd=7,pgrp="));
+        assert!(options.ends_with(",minproto=5,maxproto=5,direct"));
+    }
+}
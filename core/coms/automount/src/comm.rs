@@ -0,0 +1,178 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//!  The comm module provides management of common objects, mainly including weak references to UnitManager and Unit objects.
+//!  The method provided by the public object needs to be called.
+//!
+use super::rentry::{AutomountRe, AutomountResult, AutomountState, SectionAutomount};
+use core::rel::Reliability;
+use core::unit::{UmIf, UnitBase};
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, RwLock};
+
+pub(super) struct AutomountUnitComm {
+    owner: RefCell<Option<Weak<dyn UnitBase>>>,
+    umcomm: Arc<AutomountUmComm>,
+}
+
+impl AutomountUnitComm {
+    pub(super) fn new() -> Self {
+        AutomountUnitComm {
+            owner: RefCell::new(None),
+            umcomm: AutomountUmComm::get_instance(),
+        }
+    }
+
+    pub(super) fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.owner.replace(Some(Rc::downgrade(&unit)));
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.umcomm.attach_um(um)
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.umcomm.attach_reli(reli);
+    }
+
+    pub(super) fn owner(&self) -> Option<Rc<dyn UnitBase>> {
+        if let Some(ref unit) = *self.owner.borrow() {
+            unit.upgrade()
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn get_owner_id(&self) -> String {
+        self.owner().map_or_else(|| "None".to_string(), |u| u.id())
+    }
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        self.umcomm.um()
+    }
+
+    pub(super) fn rentry_conf_insert(&self, automount: &SectionAutomount) {
+        if let Some(u) = self.owner() {
+            self.rentry().conf_insert(&u.id(), automount)
+        }
+    }
+
+    pub(super) fn rentry_conf_get(&self) -> Option<SectionAutomount> {
+        self.owner().map(|u| self.rentry().conf_get(&u.id()))?
+    }
+
+    pub(super) fn rentry_mng_insert(&self, state: AutomountState, result: AutomountResult) {
+        if let Some(u) = self.owner() {
+            self.rentry().mng_insert(&u.id(), state, result)
+        }
+    }
+
+    pub(super) fn rentry_mng_get(&self) -> Option<(AutomountState, AutomountResult)> {
+        self.owner().map(|u| self.rentry().mng_get(&u.id()))?
+    }
+
+    fn rentry(&self) -> Rc<AutomountRe> {
+        self.umcomm.rentry()
+    }
+}
+
+static AUTOMOUNT_UM_COMM: Lazy<Arc<AutomountUmComm>> = Lazy::new(|| {
+    let comm = AutomountUmComm::new();
+    Arc::new(comm)
+});
+
+pub(super) struct AutomountUmComm {
+    data: RwLock<AutomountUmCommData>,
+}
+
+unsafe impl Send for AutomountUmComm {}
+
+unsafe impl Sync for AutomountUmComm {}
+
+impl AutomountUmComm {
+    pub(super) fn new() -> Self {
+        AutomountUmComm {
+            data: RwLock::new(AutomountUmCommData::new()),
+        }
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_um(um);
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_reli(reli);
+    }
+
+    pub(super) fn get_instance() -> Arc<AutomountUmComm> {
+        AUTOMOUNT_UM_COMM.clone()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        let rdata = self.data.read().unwrap();
+        rdata.um().unwrap()
+    }
+
+    pub(super) fn rentry(&self) -> Rc<AutomountRe> {
+        let rdata = self.data.read().unwrap();
+        rdata.rentry()
+    }
+}
+
+struct AutomountUmCommData {
+    // associated objects
+    um: Option<Rc<dyn UmIf>>,
+    reli: Weak<Reliability>,
+    rentry: Option<Rc<AutomountRe>>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl AutomountUmCommData {
+    pub(self) fn new() -> AutomountUmCommData {
+        AutomountUmCommData {
+            um: None,
+            reli: Weak::new(),
+            rentry: None,
+        }
+    }
+
+    pub(self) fn attach_um(&mut self, um: Rc<dyn UmIf>) {
+        if self.um.is_none() {
+            log::debug!("AutomountUmCommData attach_um action.");
+            self.um = Some(um);
+        }
+    }
+
+    pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
+        let old = self.reli.clone().upgrade();
+        if old.is_none() {
+            log::debug!("AutomountUmCommData attach_reli action.");
+            self.reli = Rc::downgrade(&reli);
+            self.rentry.replace(Rc::new(AutomountRe::new(&reli)));
+        }
+    }
+
+    pub(self) fn um(&self) -> Option<Rc<dyn UmIf>> {
+        if let Some(ref um) = self.um {
+            Some(Rc::clone(um))
+        } else {
+            None
+        }
+    }
+
+    pub(self) fn rentry(&self) -> Rc<AutomountRe> {
+        self.rentry.as_ref().cloned().unwrap()
+    }
+}
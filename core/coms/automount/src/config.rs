@@ -0,0 +1,115 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+//
+
+//! automount_config mod load the conf file list and convert it to structure which is defined in this mod.
+//!
+#![allow(non_snake_case)]
+use crate::{comm::AutomountUnitComm, rentry::SectionAutomount};
+use core::error::*;
+use core::rel::ReStation;
+use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
+use unit_parser::prelude::UnitConfig;
+
+#[derive(UnitConfig, Default)]
+#[allow(non_snake_case)]
+pub(super) struct AutomountConfigData {
+    pub Automount: SectionAutomount,
+}
+
+impl AutomountConfigData {
+    pub(self) fn new(Automount: SectionAutomount) -> AutomountConfigData {
+        AutomountConfigData { Automount }
+    }
+}
+
+pub(super) struct AutomountConfig {
+    // associated objects
+    comm: Rc<AutomountUnitComm>,
+
+    // owned objects
+    data: Rc<RefCell<AutomountConfigData>>,
+}
+
+impl ReStation for AutomountConfig {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        if reload {
+            return;
+        }
+
+        if let Some(data) = self.comm.rentry_conf_get() {
+            // AutomountConfigData
+            self.data.replace(AutomountConfigData::new(data));
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_conf_insert(&self.data.borrow().Automount);
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl AutomountConfig {
+    pub(super) fn new(commr: &Rc<AutomountUnitComm>) -> Self {
+        AutomountConfig {
+            comm: Rc::clone(commr),
+            data: Rc::new(RefCell::new(AutomountConfigData::default())),
+        }
+    }
+
+    pub(super) fn load(&self, paths: Vec<PathBuf>, name: &str, update: bool) -> Result<()> {
+        let data = match AutomountConfigData::load_config(paths, name) {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Invalid Configuration: {}", e);
+                return Err(Error::ConfigureError {
+                    msg: format!("Invalid Configuration: {}", e),
+                });
+            }
+        };
+
+        // record original configuration
+        *self.data.borrow_mut() = data;
+
+        if update {
+            self.db_update();
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn config_data(&self) -> Rc<RefCell<AutomountConfigData>> {
+        self.data.clone()
+    }
+
+    pub(super) fn automount_where(&self) -> String {
+        self.data.borrow().Automount.Where.clone()
+    }
+
+    pub(super) fn directory_mode(&self) -> u32 {
+        self.data.borrow().Automount.DirectoryMode
+    }
+
+    pub(super) fn timeout_idle(&self) -> u64 {
+        self.data.borrow().Automount.TimeoutIdleSec
+    }
+
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        AutomountConfigData::verify_config_located(paths, name)
+    }
+}
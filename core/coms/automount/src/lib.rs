@@ -0,0 +1,52 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Automount is one of the unit types supported in sysmaster. An automount unit mounts an autofs
+//! file system on the path given by Where=, and activates the mount unit of the same path when
+//! the path is accessed for the first time.
+//! The Automount configuration file contains three sections: Unit,Automount,and Install.
+//!
+//! # Example:
+//! ``` toml
+//! [Unit]
+//! Description=test automount
+//!
+//! [Automount]
+//! Where=/mnt/data
+//! DirectoryMode=0755
+//! TimeoutIdleSec=10min
+//!
+//! [Install]
+//! WantedBy="local-fs.target"
+//! ```
+//! `[Automount]` section related configuration
+//!
+//! The unit must be named after the mount point, e.g. mnt-data.automount for the example above,
+//! and it triggers the mount unit with the same name, e.g. mnt-data.mount.
+//!
+
+#[cfg(all(feature = "plugin", feature = "noplugin"))]
+compile_error!("feature plugin and noplugin cannot be enabled at the same time");
+
+pub use {manager::__um_obj_create, unit::__subunit_create_with_params};
+
+// dependency:
+// base -> rentry -> {comm | config}
+// autofs -> mng -> unit -> manager
+mod autofs;
+mod base;
+mod comm;
+mod config;
+mod manager;
+mod mng;
+mod rentry;
+mod unit;
@@ -0,0 +1,706 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! automount_mng is the core of the automount unit，implement the state transition, the autofs
+//! request handling and the idle expiration.
+//!
+
+use super::autofs::{self, AutofsDev, AutofsPacket, AUTOFS_PACKET_SIZE};
+use super::comm::AutomountUnitComm;
+use super::config::AutomountConfig;
+use super::rentry::{AutomountResult, AutomountState};
+use basic::fd::close;
+use basic::fs::mkdir_p_label;
+use basic::mount::is_mount_point;
+use basic::time::USEC_PER_SEC;
+use basic::IN_SET;
+use constants::INVALID_FD;
+use core::error::*;
+use core::rel::ReStation;
+use core::unit::{UnitActiveState, UnitNotifyFlags};
+use event::{EventState, EventType, Events, Source};
+use nix::errno::Errno;
+use nix::fcntl::{fcntl, FcntlArg, OFlag};
+use nix::unistd::{pipe2, read};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::os::unix::prelude::RawFd;
+use std::path::Path;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+impl AutomountState {
+    fn to_unit_active_state(self) -> UnitActiveState {
+        match self {
+            AutomountState::Dead => UnitActiveState::InActive,
+            AutomountState::Waiting => UnitActiveState::Active,
+            AutomountState::Running => UnitActiveState::Active,
+            AutomountState::Failed => UnitActiveState::Failed,
+        }
+    }
+}
+
+pub(crate) struct AutomountMng {
+    // associated objects
+    comm: Rc<AutomountUnitComm>,
+    config: Rc<AutomountConfig>,
+
+    // owned objects
+    state: RefCell<AutomountState>,
+    result: RefCell<AutomountResult>,
+    pipe: Rc<AutomountPipe>,
+    expire: Rc<AutomountExpire>,
+    ioctl_fd: RefCell<RawFd>,
+    /* pending mount requests and expire requests of the kernel */
+    tokens: RefCell<HashSet<u32>>,
+    expire_tokens: RefCell<HashSet<u32>>,
+    expire_running: Arc<AtomicBool>,
+}
+
+impl ReStation for AutomountMng {
+    // input: do nothing
+
+    // compensate: do nothing
+
+    // data
+    fn db_map(&self, _reload: bool) {
+        if let Some((state, result)) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+            *self.result.borrow_mut() = result;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_mng_insert(self.state(), self.result());
+    }
+
+    // reload: entry-only
+    fn entry_coldplug(&self) {
+        if !IN_SET!(
+            self.state(),
+            AutomountState::Waiting,
+            AutomountState::Running
+        ) {
+            return;
+        }
+
+        /* the autofs mount survives us, only the pipe and the ioctl fd need to be recreated */
+        if let Err(e) = self.connect(true) {
+            log::error!(
+                "Failed to reconnect to the automounter on {}: {}",
+                self.config.automount_where(),
+                e
+            );
+            self.disconnect();
+            self.enter_dead(AutomountResult::FailureResources);
+            return;
+        }
+
+        if self.state() == AutomountState::Running {
+            self.start_expire();
+        }
+    }
+
+    fn entry_clear(&self) {
+        self.disconnect();
+    }
+}
+
+// the declaration "pub(self)" is for identification only.
+impl AutomountMng {
+    pub(crate) fn new(
+        commr: &Rc<AutomountUnitComm>,
+        configr: &Rc<AutomountConfig>,
+    ) -> AutomountMng {
+        AutomountMng {
+            comm: Rc::clone(commr),
+            config: Rc::clone(configr),
+            state: RefCell::new(AutomountState::Dead),
+            result: RefCell::new(AutomountResult::Success),
+            pipe: Rc::new(AutomountPipe::new()),
+            expire: Rc::new(AutomountExpire::new()),
+            ioctl_fd: RefCell::new(INVALID_FD),
+            tokens: RefCell::new(HashSet::new()),
+            expire_tokens: RefCell::new(HashSet::new()),
+            expire_running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub(crate) fn attach_sources(&self, mng: &Rc<AutomountMng>) {
+        self.pipe.attach_mng(mng);
+        self.expire.attach_mng(mng);
+    }
+
+    pub(crate) fn start_action(&self) -> Result<()> {
+        if !IN_SET!(self.state(), AutomountState::Dead, AutomountState::Failed) {
+            return Ok(());
+        }
+
+        let automount_where = self.config.automount_where();
+        if is_mount_point(Path::new(&automount_where)) {
+            log::error!(
+                "Path {} is already a mount point, refusing start.",
+                automount_where
+            );
+            return Err(Error::Nix {
+                source: Errno::EEXIST,
+            });
+        }
+
+        let u = match self.comm.owner() {
+            None => return Ok(()),
+            Some(u) => u,
+        };
+        if !self.comm.um().test_trigger_loaded(&u.id()) {
+            return Err(Error::Nix {
+                source: Errno::ENOENT,
+            });
+        }
+
+        *self.result.borrow_mut() = AutomountResult::Success;
+
+        self.enter_waiting();
+
+        self.db_update();
+        Ok(())
+    }
+
+    pub(crate) fn stop_action(&self) {
+        if !IN_SET!(
+            self.state(),
+            AutomountState::Waiting,
+            AutomountState::Running
+        ) {
+            return;
+        }
+
+        self.enter_dead(AutomountResult::Success);
+        self.db_update();
+    }
+
+    pub(crate) fn reset_failed(&self) {
+        if self.state() == AutomountState::Failed {
+            self.set_state(AutomountState::Dead);
+        }
+
+        self.set_result(AutomountResult::Success);
+        self.db_update();
+    }
+
+    /* the state of the triggered mount unit changed */
+    pub(crate) fn trigger_notify(&self, other: &str) {
+        if !IN_SET!(
+            self.state(),
+            AutomountState::Waiting,
+            AutomountState::Running
+        ) {
+            return;
+        }
+
+        /* wait for the job of the mount unit to finish */
+        let um = self.comm.um();
+        if um.has_job(other) {
+            return;
+        }
+
+        let active_state = um.current_active_state(other);
+        if active_state == UnitActiveState::Active {
+            self.send_ready(false, 0);
+            self.set_state(AutomountState::Running);
+        } else if active_state.is_inactive_or_failed() {
+            /* the mount failed or the mount point got released, both kinds of requests are done */
+            self.send_ready(false, -(Errno::ENODEV as i32));
+            self.send_ready(true, 0);
+            self.set_state(AutomountState::Waiting);
+        }
+        self.db_update();
+    }
+
+    pub(crate) fn state(&self) -> AutomountState {
+        *self.state.borrow()
+    }
+
+    pub(crate) fn get_state(&self) -> String {
+        let state = *self.state.borrow();
+        state.to_string()
+    }
+
+    fn set_state(&self, state: AutomountState) {
+        let old_state = self.state();
+        self.state.replace(state);
+
+        if state == AutomountState::Running {
+            self.start_expire();
+        } else {
+            self.stop_expire();
+        }
+
+        if IN_SET!(old_state, AutomountState::Waiting, AutomountState::Running)
+            && !IN_SET!(state, AutomountState::Waiting, AutomountState::Running)
+        {
+            self.umount();
+        }
+
+        if state != old_state {
+            log::debug!("Changed {} -> {}", old_state.to_string(), state.to_string());
+        }
+
+        if let Some(u) = self.comm.owner() {
+            u.notify(
+                old_state.to_unit_active_state(),
+                state.to_unit_active_state(),
+                UnitNotifyFlags::EMPTY,
+            )
+        }
+    }
+
+    fn result(&self) -> AutomountResult {
+        *self.result.borrow()
+    }
+
+    fn set_result(&self, res: AutomountResult) {
+        *self.result.borrow_mut() = res;
+    }
+
+    fn db_update(&self) {
+        self.db_insert();
+    }
+
+    pub(super) fn current_active_state(&self) -> UnitActiveState {
+        self.state().to_unit_active_state()
+    }
+
+    fn ioctl_fd(&self) -> RawFd {
+        *self.ioctl_fd.borrow()
+    }
+
+    fn enter_waiting(&self) {
+        let automount_where = self.config.automount_where();
+        if let Err(e) = mkdir_p_label(Path::new(&automount_where), self.config.directory_mode()) {
+            log::warn!("mkdir({}) failed: {}", automount_where, e);
+        }
+
+        if let Err(e) = self.connect(false) {
+            log::error!(
+                "Failed to initialize automounter on {}: {}",
+                automount_where,
+                e
+            );
+            self.disconnect();
+            autofs::umount_autofs(&automount_where);
+            self.enter_dead(AutomountResult::FailureResources);
+            return;
+        }
+
+        self.set_state(AutomountState::Waiting);
+    }
+
+    fn enter_dead(&self, result: AutomountResult) {
+        if self.result() == AutomountResult::Success {
+            self.set_result(result);
+        }
+
+        if self.result() != AutomountResult::Success {
+            self.set_state(AutomountState::Failed)
+        } else {
+            self.set_state(AutomountState::Dead)
+        }
+    }
+
+    /* Mount the autofs file system on Where, or reuse the one left by the previous instance when
+     * reconnecting, and watch the pipe the kernel sends its requests to. */
+    fn connect(&self, reconnect: bool) -> Result<()> {
+        let automount_where = self.config.automount_where();
+        let dev = AutofsDev::open().context(NixSnafu)?;
+        let (read_fd, write_fd) = pipe2(OFlag::O_CLOEXEC).context(NixSnafu)?;
+        self.pipe.set_fd(read_fd);
+
+        let ret = if reconnect {
+            self.open_mount(&dev, &automount_where)
+                .and_then(|_| dev.set_pipe(self.ioctl_fd(), write_fd))
+        } else {
+            autofs::mount_autofs(&automount_where, write_fd)
+                .and_then(|_| self.open_mount(&dev, &automount_where))
+        };
+        close(write_fd);
+        ret.context(NixSnafu)?;
+
+        /* round up to seconds, u64::MAX must not overflow */
+        let idle = self.config.timeout_idle();
+        let timeout = idle / USEC_PER_SEC + u64::from(idle % USEC_PER_SEC != 0);
+        dev.set_timeout(self.ioctl_fd(), timeout)
+            .context(NixSnafu)?;
+
+        let events = self.comm.um().events();
+        events.add_source(self.pipe.clone())?;
+        events.set_enabled(self.pipe.clone(), EventState::On)?;
+        Ok(())
+    }
+
+    fn open_mount(&self, dev: &AutofsDev, path: &str) -> nix::Result<()> {
+        let ioctl_fd = dev.open_mount(path)?;
+        *self.ioctl_fd.borrow_mut() = ioctl_fd;
+        Ok(())
+    }
+
+    /* Drop the pipe and the ioctl fd, the autofs mount itself is left alone. */
+    fn disconnect(&self) {
+        let events = self.comm.um().events();
+        self.stop_expire();
+
+        let pipe_fd = self.pipe.fd();
+        if pipe_fd >= 0 {
+            if events.has_source(self.pipe.clone()) {
+                let _ = events.del_source(self.pipe.clone());
+            }
+            close(pipe_fd);
+            self.pipe.set_fd(INVALID_FD);
+        }
+
+        let ioctl_fd = self.ioctl_fd();
+        if ioctl_fd >= 0 {
+            close(ioctl_fd);
+            *self.ioctl_fd.borrow_mut() = INVALID_FD;
+        }
+    }
+
+    fn umount(&self) {
+        if self.pipe.fd() < 0 {
+            return;
+        }
+
+        /* nobody is going to answer the pending requests anymore */
+        self.send_ready(false, -(Errno::EHOSTDOWN as i32));
+        self.send_ready(true, -(Errno::EHOSTDOWN as i32));
+
+        self.disconnect();
+        autofs::umount_autofs(&self.config.automount_where());
+    }
+
+    fn send_ready(&self, expire: bool, status: i32) {
+        let tokens: Vec<u32> = if expire {
+            self.expire_tokens.borrow_mut().drain().collect()
+        } else {
+            self.tokens.borrow_mut().drain().collect()
+        };
+        if tokens.is_empty() || self.ioctl_fd() < 0 {
+            return;
+        }
+
+        let dev = match AutofsDev::open() {
+            Ok(dev) => dev,
+            Err(e) => {
+                log::error!("Failed to open the autofs device: {}", e);
+                return;
+            }
+        };
+
+        for token in tokens {
+            log::debug!("Sending {} to autofs token {}", status, token);
+            if let Err(e) = dev.send_ready(self.ioctl_fd(), token, status) {
+                log::warn!("Failed to answer the autofs token {}: {}", token, e);
+            }
+        }
+    }
+
+    fn dispatch_pipe(&self) -> i32 {
+        if !IN_SET!(
+            self.state(),
+            AutomountState::Waiting,
+            AutomountState::Running
+        ) {
+            return 0;
+        }
+
+        let mut buf = [0u8; AUTOFS_PACKET_SIZE];
+        let packet = match read(self.pipe.fd(), &mut buf) {
+            Ok(0) => {
+                log::error!(
+                    "The autofs pipe of {} is closed, the automount point is gone.",
+                    self.config.automount_where()
+                );
+                self.enter_dead(AutomountResult::FailureUnmounted);
+                self.db_update();
+                return 0;
+            }
+            Ok(size) => AutofsPacket::parse(&buf[..size]),
+            Err(Errno::EAGAIN) | Err(Errno::EINTR) => return 0,
+            Err(e) => {
+                log::error!("Failed to read from the autofs pipe: {}", e);
+                None
+            }
+        };
+
+        match packet {
+            Some(AutofsPacket::Missing(token)) => self.handle_missing(token),
+            Some(AutofsPacket::Expire(token)) => self.handle_expire(token),
+            Some(AutofsPacket::Unknown(ptype)) => {
+                log::warn!("Received unknown automount request {}", ptype);
+            }
+            None => {
+                log::error!("Invalid read from the autofs pipe.");
+                self.enter_dead(AutomountResult::FailureResources);
+            }
+        }
+
+        self.db_update();
+        0
+    }
+
+    fn handle_missing(&self, token: u32) {
+        log::debug!(
+            "Got direct mount request on {}",
+            self.config.automount_where()
+        );
+        self.tokens.borrow_mut().insert(token);
+
+        let u = match self.comm.owner() {
+            None => return,
+            Some(u) => u,
+        };
+        let um = self.comm.um();
+
+        /* we don't take mount requests anymore if we are supposed to shut down anyway */
+        if um.has_stop_job(&u.id()) {
+            self.send_ready(false, -(Errno::EHOSTDOWN as i32));
+            return;
+        }
+
+        let trigger = um.unit_get_trigger(&u.id());
+        if trigger.is_empty() {
+            log::error!("{} Unit to trigger vanished.", u.id());
+            self.enter_dead(AutomountResult::FailureResources);
+            return;
+        }
+
+        /* The mount unit may have been started before the request arrived, no state change of it
+         * would answer the request then. */
+        if um.current_active_state(&trigger) == UnitActiveState::Active {
+            self.send_ready(false, 0);
+            self.set_state(AutomountState::Running);
+            return;
+        }
+
+        if let Err(err) = um.unit_start_by_job(&trigger) {
+            log::error!("Failed to queue mount startup job: {:?}", err);
+            self.enter_dead(AutomountResult::FailureResources);
+            return;
+        }
+
+        self.set_state(AutomountState::Running);
+    }
+
+    fn handle_expire(&self, token: u32) {
+        log::debug!(
+            "Got direct umount request on {}",
+            self.config.automount_where()
+        );
+        self.expire_tokens.borrow_mut().insert(token);
+
+        let u = match self.comm.owner() {
+            None => return,
+            Some(u) => u,
+        };
+        let um = self.comm.um();
+        let trigger = um.unit_get_trigger(&u.id());
+        if trigger.is_empty() || um.current_active_state(&trigger).is_inactive_or_failed() {
+            self.send_ready(true, 0);
+            return;
+        }
+
+        if let Err(err) = um.unit_stop_by_job(&trigger) {
+            log::error!("Failed to queue umount job: {:?}", err);
+            self.send_ready(true, -(Errno::EIO as i32));
+        }
+    }
+
+    fn start_expire(&self) {
+        let timeout = self.config.timeout_idle();
+        if timeout == 0 || self.ioctl_fd() < 0 {
+            return;
+        }
+
+        let usec = std::cmp::max(timeout / 3, USEC_PER_SEC);
+        let events = self.comm.um().events();
+        let expire = self.expire.clone();
+        if events.has_source(expire.clone()) {
+            let _ = events.del_source(expire.clone());
+        }
+
+        expire.set_time(usec);
+        if let Err(e) = events.add_source(expire.clone()) {
+            log::warn!("Failed to add the expire timer: {}", e);
+            return;
+        }
+        if let Err(e) = events.set_enabled(expire, EventState::OneShot) {
+            log::warn!("Failed to enable the expire timer: {}", e);
+        }
+    }
+
+    fn stop_expire(&self) {
+        let events = self.comm.um().events();
+        if events.has_source(self.expire.clone()) {
+            let _ = events.del_source(self.expire.clone());
+        }
+    }
+
+    fn dispatch_expire(&self) -> i32 {
+        if self.state() != AutomountState::Running || self.ioctl_fd() < 0 {
+            return 0;
+        }
+
+        /* The expire ioctl blocks until the expire request it generates is answered by us, so it
+         * can't be issued from the event loop. */
+        if !self.expire_running.swap(true, Ordering::SeqCst) {
+            if let Err(e) = self.spawn_expire() {
+                log::warn!("Failed to start the autofs expiration: {}", e);
+                self.expire_running.store(false, Ordering::SeqCst);
+            }
+        }
+
+        self.start_expire();
+        0
+    }
+
+    fn spawn_expire(&self) -> nix::Result<()> {
+        let dev = AutofsDev::open()?;
+        let ioctl_fd = fcntl(self.ioctl_fd(), FcntlArg::F_DUPFD_CLOEXEC(0))?;
+        let running = self.expire_running.clone();
+        thread::spawn(move || {
+            /* expire until the kernel has nothing more to expire */
+            while dev.expire(ioctl_fd).is_ok() {}
+            close(ioctl_fd);
+            running.store(false, Ordering::SeqCst);
+        });
+        Ok(())
+    }
+}
+
+pub(super) struct AutomountPipe {
+    fd: RefCell<RawFd>,
+    mng: RefCell<Weak<AutomountMng>>,
+}
+
+impl AutomountPipe {
+    fn new() -> AutomountPipe {
+        AutomountPipe {
+            fd: RefCell::new(INVALID_FD),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    fn attach_mng(&self, mng: &Rc<AutomountMng>) {
+        *self.mng.borrow_mut() = Rc::downgrade(mng)
+    }
+
+    fn set_fd(&self, fd: RawFd) {
+        *self.fd.borrow_mut() = fd
+    }
+
+    fn mng(&self) -> Rc<AutomountMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for AutomountPipe {
+    fn fd(&self) -> RawFd {
+        *self.fd.borrow()
+    }
+
+    fn event_type(&self) -> EventType {
+        EventType::Io
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn dispatch(&self, _event: &Events) -> i32 {
+        self.mng().dispatch_pipe()
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn description(&self) -> String {
+        String::from("AutomountPipe")
+    }
+}
+
+pub(super) struct AutomountExpire {
+    time: RefCell<u64>,
+    mng: RefCell<Weak<AutomountMng>>,
+}
+
+impl AutomountExpire {
+    fn new() -> AutomountExpire {
+        AutomountExpire {
+            time: RefCell::new(0),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    fn attach_mng(&self, mng: &Rc<AutomountMng>) {
+        *self.mng.borrow_mut() = Rc::downgrade(mng)
+    }
+
+    fn set_time(&self, usec: u64) {
+        *self.time.borrow_mut() = usec
+    }
+
+    fn mng(&self) -> Rc<AutomountMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for AutomountExpire {
+    fn fd(&self) -> RawFd {
+        0
+    }
+
+    fn event_type(&self) -> EventType {
+        EventType::TimerMonotonic
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn time_relative(&self) -> u64 {
+        *self.time.borrow()
+    }
+
+    fn dispatch(&self, _: &Events) -> i32 {
+        self.mng().dispatch_expire()
+    }
+
+    fn priority(&self) -> i8 {
+        0
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn description(&self) -> String {
+        String::from("AutomountExpire")
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+//
+#![allow(non_snake_case)]
+use basic::time::parse_sec;
+use core::error::*;
+use core::exec::parse_mode;
+use core::rel::{ReDb, ReDbRwTxn, ReDbTable, ReliSwitch, Reliability};
+use macros::{EnumDisplay, UnitSection};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+const RELI_DB_HAUTOMOUNT_CONF: &str = "automountconf";
+const RELI_DB_HAUTOMOUNT_MNG: &str = "automountmng";
+
+#[derive(UnitSection, Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SectionAutomount {
+    #[entry(default = String::new(), parser = parse_where)]
+    pub Where: String,
+    #[entry(default = 0o755, parser = parse_mode)]
+    pub DirectoryMode: u32,
+    /// 0 means the mount point is never unmounted for being idle
+    #[entry(default = 0, parser = parse_timeout_idle)]
+    pub TimeoutIdleSec: u64,
+}
+
+fn parse_where(s: &str) -> Result<String> {
+    let path = basic::fs::parse_absolute_path(s).map_err(|_| Error::ConfigureError {
+        msg: format!("Invalid Where: {}", s),
+    })?;
+    Ok(path)
+}
+
+fn parse_timeout_idle(s: &str) -> Result<u64> {
+    let usec = parse_sec(s).context(NixSnafu)?;
+    /* "infinity" disables the idle timeout too */
+    if usec == u64::MAX {
+        return Ok(0);
+    }
+    Ok(usec)
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, EnumDisplay)]
+pub(crate) enum AutomountState {
+    Dead,
+    Waiting,
+    Running,
+    Failed,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub(super) enum AutomountResult {
+    Success,
+    FailureResources,
+    FailureUnmounted,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AutomountReConf {
+    automount: SectionAutomount,
+}
+
+impl AutomountReConf {
+    fn new(automountr: &SectionAutomount) -> AutomountReConf {
+        AutomountReConf {
+            automount: automountr.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AutomountReMng {
+    state: AutomountState,
+    result: AutomountResult,
+}
+
+impl AutomountReMng {
+    fn new(state: AutomountState, result: AutomountResult) -> AutomountReMng {
+        AutomountReMng { state, result }
+    }
+}
+
+struct AutomountReDb<K, V>(ReDb<K, V>);
+
+pub(super) struct AutomountRe {
+    // database: multi-instance(N)
+    conf: Rc<AutomountReDb<String, AutomountReConf>>, // RELI_DB_HAUTOMOUNT_CONF; key: unit_id, data: config;
+    mng: Rc<AutomountReDb<String, AutomountReMng>>, // RELI_DB_HAUTOMOUNT_MNG; key: unit_id, data: state+result;
+}
+
+impl AutomountRe {
+    pub(super) fn new(relir: &Rc<Reliability>) -> AutomountRe {
+        let conf = Rc::new(AutomountReDb(ReDb::new(relir, RELI_DB_HAUTOMOUNT_CONF)));
+        let mng = Rc::new(AutomountReDb(ReDb::new(relir, RELI_DB_HAUTOMOUNT_MNG)));
+        let rentry = AutomountRe { conf, mng };
+        rentry.register(relir);
+        rentry
+    }
+
+    pub(super) fn conf_insert(&self, unit_id: &str, automount: &SectionAutomount) {
+        let conf = AutomountReConf::new(automount);
+        self.conf.0.insert(unit_id.to_string(), conf);
+    }
+
+    pub(super) fn conf_get(&self, unit_id: &str) -> Option<SectionAutomount> {
+        let conf = self.conf.0.get(&unit_id.to_string());
+        conf.map(|c| c.automount)
+    }
+
+    pub(super) fn mng_insert(&self, unit_id: &str, state: AutomountState, result: AutomountResult) {
+        let mng = AutomountReMng::new(state, result);
+        self.mng.0.insert(unit_id.to_string(), mng);
+    }
+
+    pub(super) fn mng_get(&self, unit_id: &str) -> Option<(AutomountState, AutomountResult)> {
+        let mng = self.mng.0.get(&unit_id.to_string());
+        mng.map(|m| (m.state, m.result))
+    }
+
+    fn register(&self, relir: &Reliability) {
+        // rel-db: RELI_DB_HAUTOMOUNT_CONF
+        let db = Rc::clone(&self.conf);
+        relir.history_db_register(RELI_DB_HAUTOMOUNT_CONF, db);
+
+        // rel-db: RELI_DB_HAUTOMOUNT_MNG
+        let db = Rc::clone(&self.mng);
+        relir.history_db_register(RELI_DB_HAUTOMOUNT_MNG, db);
+    }
+}
+
+impl ReDbTable for AutomountReDb<String, AutomountReConf> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
+
+impl ReDbTable for AutomountReDb<String, AutomountReMng> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
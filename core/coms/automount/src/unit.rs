@@ -0,0 +1,230 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! automount unit is entry of automount type of unit，need impl
+//! UnitObj,UnitMngUtil, UnitSubClass trait
+
+use super::comm::AutomountUnitComm;
+use super::config::AutomountConfig;
+use super::mng::AutomountMng;
+use basic::mount::mount_point_to_unit_name;
+use basic::{SHUTDOWN_TARGET, SYSINIT_TARGET};
+use core::error::*;
+use core::rel::{ReStation, Reliability};
+use core::unit::{
+    section_properties, SubUnit, UmIf, UnitActiveState, UnitBase, UnitDependencyMask, UnitMngUtil,
+    UnitRelations,
+};
+use nix::sys::wait::WaitStatus;
+use std::path::PathBuf;
+use std::rc::Rc;
+use unit_parser::error::LocatedError;
+
+struct AutomountUnit {
+    comm: Rc<AutomountUnitComm>,
+    mng: Rc<AutomountMng>,
+    config: Rc<AutomountConfig>,
+}
+
+impl ReStation for AutomountUnit {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        self.config.db_map(reload);
+        self.mng.db_map(reload);
+    }
+
+    fn db_insert(&self) {
+        self.config.db_insert();
+        self.mng.db_insert();
+    }
+
+    // reload: no external connections, entry-only
+    fn entry_coldplug(&self) {
+        // rebuild external connections, like: autofs pipe, timer, ...
+        self.mng.entry_coldplug();
+    }
+
+    fn entry_clear(&self) {
+        // release external connection, like: autofs pipe, timer, ...
+        self.mng.entry_clear();
+    }
+}
+
+impl AutomountUnit {
+    fn new(_um: Rc<dyn UmIf>) -> AutomountUnit {
+        let comm = Rc::new(AutomountUnitComm::new());
+        let config = Rc::new(AutomountConfig::new(&comm));
+        let mng = Rc::new(AutomountMng::new(&comm, &config));
+        mng.attach_sources(&mng);
+        AutomountUnit {
+            comm: Rc::clone(&comm),
+            mng,
+            config: Rc::clone(&config),
+        }
+    }
+
+    fn verify(&self) -> Result<()> {
+        let automount_where = self.config.automount_where();
+        if automount_where.is_empty() {
+            log::error!("Automount unit lacks Where setting. Refusing.");
+            return Err(Error::ConfigureError {
+                msg: "Where is not set".to_string(),
+            });
+        }
+
+        /* the automount unit must be named after the mount point it manages */
+        let unit_name = self.comm.get_owner_id();
+        let mount_name = mount_point_to_unit_name(&automount_where);
+        if unit_name.strip_suffix(".automount") != mount_name.strip_suffix(".mount") {
+            log::error!(
+                "Where={} doesn't match the unit name {}. Refusing.",
+                automount_where,
+                unit_name
+            );
+            return Err(Error::ConfigureError {
+                msg: "unit name doesn't match Where".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn add_extras(&self) -> Result<()> {
+        let um = self.comm.um();
+        let u = match self.comm.owner() {
+            None => {
+                return Ok(());
+            }
+            Some(v) => v,
+        };
+
+        self.add_trigger_dependencies(&um, &u)?;
+
+        self.add_default_dependencies(&um, &u)
+    }
+
+    fn add_trigger_dependencies(&self, um: &Rc<dyn UmIf>, u: &Rc<dyn UnitBase>) -> Result<()> {
+        let mount_name = mount_point_to_unit_name(&self.config.automount_where());
+        um.unit_add_two_dependency(
+            &u.id(),
+            UnitRelations::UnitBefore,
+            UnitRelations::UnitTriggers,
+            &mount_name,
+            true,
+            UnitDependencyMask::Implicit,
+        )
+    }
+
+    fn add_default_dependencies(&self, um: &Rc<dyn UmIf>, u: &Rc<dyn UnitBase>) -> Result<()> {
+        if !u.default_dependencies() {
+            return Ok(());
+        }
+
+        um.unit_add_dependency(
+            &u.id(),
+            UnitRelations::UnitAfter,
+            SYSINIT_TARGET,
+            true,
+            UnitDependencyMask::Default,
+        )?;
+
+        um.unit_add_two_dependency(
+            &u.id(),
+            UnitRelations::UnitBefore,
+            UnitRelations::UnitConflicts,
+            SHUTDOWN_TARGET,
+            true,
+            UnitDependencyMask::Default,
+        )
+    }
+}
+
+impl SubUnit for AutomountUnit {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn load(&self, paths: Vec<PathBuf>) -> Result<()> {
+        let unit_name = self.comm.get_owner_id();
+        self.config.load(paths, &unit_name, true)?;
+
+        self.verify()?;
+
+        self.add_extras()
+    }
+
+    fn current_active_state(&self) -> UnitActiveState {
+        self.mng.current_active_state()
+    }
+
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Automount)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        AutomountConfig::verify(paths, name)
+    }
+
+    fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.comm.attach_unit(unit);
+        self.db_insert();
+    }
+
+    fn init(&self) {}
+
+    fn done(&self) {}
+
+    fn dump(&self) {}
+
+    fn start(&self) -> Result<()> {
+        log::info!("Automount start {:?}", self.comm.get_owner_id());
+
+        self.mng.start_action()
+    }
+
+    fn stop(&self, _force: bool) -> Result<()> {
+        self.mng.stop_action();
+        Ok(())
+    }
+
+    fn trigger(&self, other: &str) {
+        /* Invoked whenever the mount unit we trigger changes state or gains or loses a job */
+        self.mng.trigger_notify(other);
+    }
+
+    fn release_resources(&self) {}
+
+    fn sigchld_events(&self, _wait_status: WaitStatus) {}
+
+    fn reset_failed(&self) {
+        self.mng.reset_failed()
+    }
+}
+
+impl UnitMngUtil for AutomountUnit {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+use core::declare_unitobj_plugin_with_param;
+declare_unitobj_plugin_with_param!(AutomountUnit, AutomountUnit::new);
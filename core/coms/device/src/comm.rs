@@ -0,0 +1,178 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//!  Device association unit object
+//! *  You need to notify the Unit object and change the method
+//! *  Get the attributes of the unit object
+//! *  Call relation: device_ unit->device_ mng->device_ comm
+
+use super::rentry::{DeviceRe, DeviceState};
+use core::rel::Reliability;
+use core::unit::{UmIf, UnitBase};
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, RwLock};
+
+pub(super) struct DeviceUnitComm {
+    owner: RefCell<Option<Weak<dyn UnitBase>>>,
+    umcomm: Arc<DeviceUmComm>,
+}
+
+impl DeviceUnitComm {
+    pub(super) fn new() -> Self {
+        DeviceUnitComm {
+            owner: RefCell::new(None),
+            umcomm: DeviceUmComm::get_instance(),
+        }
+    }
+
+    pub(super) fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.owner.replace(Some(Rc::downgrade(&unit)));
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.umcomm.attach_um(um)
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.umcomm.attach_reli(reli)
+    }
+
+    pub(super) fn owner(&self) -> Option<Rc<dyn UnitBase>> {
+        if let Some(ref unit) = *self.owner.borrow() {
+            unit.upgrade()
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn get_owner_id(&self) -> String {
+        self.owner().map_or_else(|| "None".to_string(), |u| u.id())
+    }
+
+    pub(super) fn rentry_mng_insert(&self, state: DeviceState, sysfs: &str) {
+        self.rentry().mng_insert(&self.get_owner_id(), state, sysfs)
+    }
+
+    pub(super) fn rentry_mng_get(&self) -> Option<(DeviceState, String)> {
+        self.rentry().mng_get(&self.get_owner_id())
+    }
+
+    fn rentry(&self) -> Rc<DeviceRe> {
+        self.umcomm.rentry()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        self.umcomm.um()
+    }
+}
+
+static DEVICE_UM_COMM: Lazy<Arc<DeviceUmComm>> = Lazy::new(|| {
+    let comm = DeviceUmComm::new();
+    Arc::new(comm)
+});
+
+pub(super) struct DeviceUmComm {
+    data: RwLock<DeviceUmCommData>,
+}
+
+unsafe impl Send for DeviceUmComm {}
+
+unsafe impl Sync for DeviceUmComm {}
+
+impl DeviceUmComm {
+    pub(super) fn new() -> Self {
+        DeviceUmComm {
+            data: RwLock::new(DeviceUmCommData::new()),
+        }
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_um(um);
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_reli(reli);
+    }
+
+    pub(super) fn get_instance() -> Arc<DeviceUmComm> {
+        DEVICE_UM_COMM.clone()
+    }
+
+    pub(super) fn reli(&self) -> Rc<Reliability> {
+        let rdata = self.data.read().unwrap();
+        rdata.reli()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        let rdata = self.data.read().unwrap();
+        rdata.um().unwrap()
+    }
+
+    pub(super) fn rentry(&self) -> Rc<DeviceRe> {
+        let rdata = self.data.read().unwrap();
+        rdata.rentry()
+    }
+}
+
+struct DeviceUmCommData {
+    // associated objects
+    um: Option<Rc<dyn UmIf>>,
+    reli: Weak<Reliability>,
+    rentry: Option<Rc<DeviceRe>>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl DeviceUmCommData {
+    pub(self) fn new() -> DeviceUmCommData {
+        DeviceUmCommData {
+            um: None,
+            reli: Weak::new(),
+            rentry: None,
+        }
+    }
+
+    pub(self) fn attach_um(&mut self, um: Rc<dyn UmIf>) {
+        if self.um.is_none() {
+            log::debug!("DeviceUmComm attach_um action.");
+            self.um = Some(um);
+        }
+    }
+
+    pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
+        let old = self.reli.clone().upgrade();
+        if old.is_none() {
+            log::debug!("DeviceUmComm attach_reli action.");
+            self.reli = Rc::downgrade(&reli);
+            self.rentry.replace(Rc::new(DeviceRe::new(&reli)));
+        }
+    }
+
+    pub(self) fn um(&self) -> Option<Rc<dyn UmIf>> {
+        if let Some(ref um) = self.um {
+            Some(Rc::clone(um))
+        } else {
+            None
+        }
+    }
+
+    pub(self) fn reli(&self) -> Rc<Reliability> {
+        self.reli.clone().upgrade().unwrap()
+    }
+
+    pub(self) fn rentry(&self) -> Rc<DeviceRe> {
+        self.rentry.as_ref().cloned().unwrap()
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+//! Device is one of the unit types supported in sysmaster. A device unit represents a device
+//! tagged with "sysmaster" by the devmaster rules, it is created when the uevent of the device is
+//! received and becomes active while the device is plugged. Other units can bind to the device
+//! with BindsTo= and After=, or wait for it with Requires= and After=.
+//!
+//! A device unit doesn't need a configuration file. It is named after the escaped sysfs path of
+//! the device, and after its device node and symlinks, e.g. sys-devices-...-sda1.device,
+//! dev-sda1.device and dev-disk-by\x2duuid-....device all refer to the same device.
+//!
+//! The following properties of the device are supported:
+//!
+//! SYSTEMD_WANTS: the units started when the device is plugged, separated by spaces. A template
+//! such as foo@.service is instantiated with the escaped sysfs path of the device.
+//!
+//! SYSTEMD_READY: the device is treated as unplugged if it is set to 0.
+//!
+//! A unit file of the device unit is optional, e.g. to set JobTimeoutSec= to limit the time a job
+//! waits for the device to show up.
+//!
+//! # Example:
+//! ``` toml
+//! [Unit]
+//! Description=data disk
+//! JobTimeoutSec=90
+//! ```
+
+#[cfg(all(feature = "plugin", feature = "noplugin"))]
+compile_error!("feature plugin and noplugin cannot be enabled at the same time");
+
+pub use {manager::__um_obj_create, unit::__subunit_create_with_params};
+
+// dependency:
+// base -> rentry -> comm -> mng -> unit -> manager
+mod base;
+mod comm;
+mod manager;
+mod mng;
+mod rentry;
+mod unit;
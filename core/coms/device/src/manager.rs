@@ -0,0 +1,410 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+#[cfg(feature = "plugin")]
+use crate::base::PLUGIN_NAME;
+#[cfg(feature = "plugin")]
+use constants::LOG_FILE_PATH;
+
+use super::comm::DeviceUmComm;
+use super::rentry::{DeviceRe, DeviceReFrame};
+use basic::unit_name::unit_name_from_path;
+use core::rel::{ReStation, ReliLastFrame, Reliability};
+use core::unit::{UmIf, UnitManagerObj, UnitMngUtil, UnitType};
+use device::device_enumerator::{DeviceEnumerator, MatchInitializedType};
+use device::device_monitor::{DeviceMonitor, MonitorNetlinkGroup};
+use device::{Device, DeviceAction};
+use event::{EventState, EventType, Events, Source};
+use std::collections::HashSet;
+use std::os::unix::io::RawFd;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// only the devices tagged by devmaster with it get device units
+const DEVICE_TAG: &str = "sysmaster";
+
+struct DeviceManager {
+    // owned objects
+    // data
+    comm: Arc<DeviceUmComm>,
+    monitor: DeviceMonitorSrc,
+}
+
+impl ReStation for DeviceManager {
+    // input
+    fn input_rebuild(&self) {
+        self.monitor.defer_enable(true);
+    }
+
+    // compensate
+    fn db_compensate_last(&self, _lframe: (u32, Option<u32>, Option<u32>), lunit: Option<&String>) {
+        assert!(lunit.is_some());
+
+        let frame = self.comm.rentry().last_frame();
+        if frame.is_none() {
+            // debug
+            return;
+        }
+
+        let unit_id = lunit.unwrap();
+        match frame.unwrap() {
+            DeviceReFrame::Monitor => self.comm.um().rentry_trigger_merge(unit_id, true), // merge to trigger
+        }
+    }
+
+    fn do_compensate_last(&self, _lframe: (u32, Option<u32>, Option<u32>), lunit: Option<&String>) {
+        assert!(lunit.is_some());
+
+        let frame = self.comm.rentry().last_frame();
+        if frame.is_none() {
+            // debug
+            return;
+        }
+
+        let unit_id = lunit.unwrap();
+        match frame.unwrap() {
+            DeviceReFrame::Monitor => self.comm.um().trigger_unit(unit_id), // re-run
+        }
+    }
+
+    // no data
+
+    // reload
+    fn register_ex(&self) {
+        self.monitor.register_ex();
+    }
+}
+
+impl UnitManagerObj for DeviceManager {
+    fn private_section(&self, _unit_type: UnitType) -> String {
+        "Device".into()
+    }
+
+    fn can_transient(&self, _unit_type: UnitType) -> bool {
+        false
+    }
+}
+
+// the declaration "pub(self)" is for identification only.
+impl DeviceManager {
+    pub fn new() -> DeviceManager {
+        let _comm = DeviceUmComm::get_instance();
+        DeviceManager {
+            comm: Arc::clone(&_comm),
+            monitor: DeviceMonitorSrc::new(&_comm),
+        }
+    }
+
+    fn register(&self) {
+        self.monitor.register();
+    }
+}
+
+struct DeviceMonitorSrc {
+    // owned objects
+    data: Rc<DeviceMonitorData>,
+    defer: Rc<DeviceMonitorDefer>,
+    io: Rc<DeviceMonitorIo>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl DeviceMonitorSrc {
+    pub fn new(commr: &Arc<DeviceUmComm>) -> DeviceMonitorSrc {
+        let _data = Rc::new(DeviceMonitorData::new(commr));
+        let _defer = Rc::new(DeviceMonitorDefer::new(&_data));
+        DeviceMonitorSrc {
+            data: Rc::clone(&_data),
+            defer: Rc::clone(&_defer),
+            io: Rc::new(DeviceMonitorIo::new(&_data, &_defer)),
+        }
+    }
+
+    pub fn register_ex(&self) {
+        let events = self.data.comm.um().events();
+
+        // io
+        let io = Rc::clone(&self.io);
+        events.add_source(io.clone()).unwrap();
+        events.set_enabled(io, EventState::On).unwrap();
+    }
+
+    pub fn defer_enable(&self, enable: bool) -> i32 {
+        self.io.defer_enable(enable)
+    }
+
+    fn register(&self) {
+        let events = self.data.comm.um().events();
+
+        // defer
+        let defer = Rc::clone(&self.defer);
+        events.add_source(defer).unwrap();
+    }
+}
+
+struct DeviceMonitorIo {
+    // associated objects
+    data: Rc<DeviceMonitorData>,
+    defer: Rc<DeviceMonitorDefer>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl DeviceMonitorIo {
+    pub fn new(datar: &Rc<DeviceMonitorData>, deferr: &Rc<DeviceMonitorDefer>) -> DeviceMonitorIo {
+        DeviceMonitorIo {
+            data: Rc::clone(datar),
+            defer: Rc::clone(deferr),
+        }
+    }
+
+    pub fn defer_enable(&self, enable: bool) -> i32 {
+        let source = Rc::clone(&self.defer);
+        let state = match enable {
+            true => EventState::OneShot,
+            false => EventState::Off,
+        };
+        let events = self.data.comm.um().events();
+        match events.set_enabled(source, state) {
+            Ok(_) => 0,
+            Err(_) => -1,
+        }
+    }
+
+    fn reli(&self) -> Rc<Reliability> {
+        self.data.comm.reli()
+    }
+
+    fn rentry(&self) -> Rc<DeviceRe> {
+        self.data.comm.rentry()
+    }
+}
+
+impl Source for DeviceMonitorIo {
+    fn event_type(&self) -> EventType {
+        EventType::Io
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn dispatch(&self, _e: &Events) -> i32 {
+        self.reli().set_last_frame2(
+            ReliLastFrame::SubManager as u32,
+            UnitType::UnitDevice as u32,
+        );
+        self.rentry().set_last_frame(DeviceReFrame::Monitor);
+        self.data.dispatch_uevents();
+        self.rentry().clear_last_frame();
+        self.reli().clear_last_frame();
+        0
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn fd(&self) -> RawFd {
+        self.data.monitor.fd()
+    }
+
+    fn priority(&self) -> i8 {
+        -10i8
+    }
+}
+
+struct DeviceMonitorDefer {
+    // associated objects
+    data: Rc<DeviceMonitorData>,
+}
+
+impl DeviceMonitorDefer {
+    pub fn new(datar: &Rc<DeviceMonitorData>) -> DeviceMonitorDefer {
+        DeviceMonitorDefer {
+            data: Rc::clone(datar),
+        }
+    }
+
+    fn reli(&self) -> Rc<Reliability> {
+        self.data.comm.reli()
+    }
+
+    fn rentry(&self) -> Rc<DeviceRe> {
+        self.data.comm.rentry()
+    }
+}
+
+impl Source for DeviceMonitorDefer {
+    fn event_type(&self) -> EventType {
+        EventType::Defer
+    }
+
+    fn epoll_event(&self) -> u32 {
+        0
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn dispatch(&self, _event: &Events) -> i32 {
+        self.reli().set_last_frame2(
+            ReliLastFrame::SubManager as u32,
+            UnitType::UnitDevice as u32,
+        );
+        self.rentry().set_last_frame(DeviceReFrame::Monitor);
+        self.data.enumerate_devices();
+        self.rentry().clear_last_frame();
+        self.reli().clear_last_frame();
+        0
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+}
+
+struct DeviceMonitorData {
+    // associated objects
+    comm: Arc<DeviceUmComm>,
+
+    // owned objects
+    monitor: DeviceMonitor,
+}
+
+impl DeviceMonitorData {
+    pub fn new(commr: &Arc<DeviceUmComm>) -> Self {
+        /* listen to the uevents broadcast by devmaster after the rules are processed */
+        let mut monitor = DeviceMonitor::new(MonitorNetlinkGroup::Userspace, None);
+        if let Err(e) = monitor.filter_add_match_tag(DEVICE_TAG) {
+            log::error!("Failed to add the tag filter of device monitor: {}", e);
+        }
+        if let Err(e) = monitor.bpf_filter_update() {
+            log::error!("Failed to update the filter of device monitor: {}", e);
+        }
+        DeviceMonitorData {
+            comm: Arc::clone(commr),
+            monitor,
+        }
+    }
+
+    pub fn dispatch_uevents(&self) {
+        loop {
+            match self.monitor.receive_device() {
+                Ok(Some(device)) => {
+                    let found = device
+                        .get_action()
+                        .map_or(true, |a| a != DeviceAction::Remove);
+                    self.setup_device(&device, found);
+                }
+                Ok(None) => continue,
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// coldplug the devices initialized before sysmaster starts, and drop the ones removed meanwhile
+    pub fn enumerate_devices(&self) {
+        let mut enumerator = DeviceEnumerator::new();
+        if let Err(e) = enumerator.add_match_tag(DEVICE_TAG) {
+            log::error!("Failed to add the tag match of device enumerator: {}", e);
+            return;
+        }
+        if let Err(e) = enumerator.add_match_is_initialized(MatchInitializedType::Yes) {
+            log::error!(
+                "Failed to add the initialized match of device enumerator: {}",
+                e
+            );
+            return;
+        }
+
+        let mut plugged = HashSet::new();
+        for device in enumerator.iter() {
+            if let Some(names) = self.setup_device(&device, true) {
+                plugged.extend(names);
+            }
+        }
+
+        let um = self.comm.um();
+        for unit in um.units_get_all(Some(UnitType::UnitDevice)) {
+            if !plugged.contains(&unit) {
+                um.setup_device(&unit, "", &[], false);
+            }
+        }
+    }
+
+    /// update the device units of the device, return the names of them if it is plugged
+    fn setup_device(&self, device: &Device, found: bool) -> Option<Vec<String>> {
+        let sysfs = match device.get_syspath() {
+            Ok(v) => v,
+            Err(e) => {
+                log::debug!("Failed to get the syspath of device, ignoring: {}", e);
+                return None;
+            }
+        };
+        let um = self.comm.um();
+
+        /* SYSTEMD_READY=0 means the device is not usable yet, treat it as unplugged */
+        let ready = device
+            .get_property_value("SYSTEMD_READY")
+            .map_or(true, |v| v != "0");
+        if !found || !ready {
+            for unit in um.units_get_all(Some(UnitType::UnitDevice)) {
+                um.setup_device(&unit, &sysfs, &[], false);
+            }
+            return None;
+        }
+
+        let wants: Vec<String> = device
+            .get_property_value("SYSTEMD_WANTS")
+            .map(|v| v.split_whitespace().map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+
+        let mut names = vec![unit_name_from_path(&sysfs, ".device")];
+        um.setup_device(&names[0], &sysfs, &wants, true);
+
+        let mut aliases = Vec::new();
+        if let Ok(devname) = device.get_devname() {
+            aliases.push(devname);
+        }
+        for devlink in &device.devlink_iter() {
+            aliases.push(devlink.to_string());
+        }
+        for alias in aliases {
+            let name = unit_name_from_path(&alias, ".device");
+            um.setup_device(&name, &sysfs, &[], true);
+            names.push(name);
+        }
+        Some(names)
+    }
+}
+
+impl UnitMngUtil for DeviceManager {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+        self.register();
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+impl Default for DeviceManager {
+    fn default() -> Self {
+        DeviceManager::new()
+    }
+}
+
+use core::declare_umobj_plugin;
+declare_umobj_plugin!(DeviceManager, DeviceManager::default);
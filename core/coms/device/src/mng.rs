@@ -0,0 +1,177 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//!  The core logic of the device subclass
+use super::comm::DeviceUnitComm;
+use super::rentry::DeviceState;
+use core::rel::ReStation;
+use core::unit::{UnitActiveState, UnitDependencyMask, UnitNotifyFlags, UnitRelations};
+use std::{cell::RefCell, rc::Rc};
+
+impl DeviceState {
+    fn device_state_to_unit_state(&self) -> UnitActiveState {
+        match *self {
+            DeviceState::Dead => UnitActiveState::InActive,
+            DeviceState::Plugged => UnitActiveState::Active,
+        }
+    }
+}
+
+pub(super) struct DeviceMng {
+    comm: Rc<DeviceUnitComm>,
+    state: RefCell<DeviceState>,
+    sysfs: RefCell<String>,
+}
+
+impl ReStation for DeviceMng {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, _reload: bool) {
+        if let Some((state, sysfs)) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+            *self.sysfs.borrow_mut() = sysfs;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_mng_insert(self.state(), &self.sysfs());
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl DeviceMng {
+    pub(super) fn new(commr: &Rc<DeviceUnitComm>) -> Self {
+        DeviceMng {
+            comm: Rc::clone(commr),
+            state: RefCell::new(DeviceState::Dead),
+            sysfs: RefCell::new(String::new()),
+        }
+    }
+
+    pub(super) fn setup_device(&self, sysfs: &str, wants: &[String], found: bool) {
+        if !found {
+            /* another device may have taken over the name, e.g. a symlink in /dev/disk */
+            if !self.sysfs().is_empty() && !sysfs.is_empty() && self.sysfs() != sysfs {
+                return;
+            }
+            self.set_state(DeviceState::Dead);
+            return;
+        }
+
+        if self.sysfs() != sysfs {
+            log::debug!(
+                "{} is now backed by the device {}",
+                self.comm.get_owner_id(),
+                sysfs
+            );
+            *self.sysfs.borrow_mut() = sysfs.to_string();
+        }
+
+        let wants = self.add_wants(wants);
+        let old_state = self.state();
+        self.set_state(DeviceState::Plugged);
+        if old_state != DeviceState::Plugged {
+            self.start_wants(&wants);
+        }
+    }
+
+    /// the units in SYSTEMD_WANTS, the templates are instantiated with the escaped sysfs path
+    fn add_wants(&self, wants: &[String]) -> Vec<String> {
+        let u = match self.comm.owner() {
+            None => return Vec::new(),
+            Some(v) => v,
+        };
+        let um = self.comm.um();
+        let mut units = Vec::new();
+        for want in wants {
+            let unit = match want.split_once("@.") {
+                None => want.to_string(),
+                Some((prefix, suffix)) => {
+                    let instance = basic::unit_name::unit_name_from_path(&self.sysfs(), "");
+                    format!("{}@{}.{}", prefix, instance, suffix)
+                }
+            };
+            if let Err(e) = um.unit_add_dependency(
+                &u.id(),
+                UnitRelations::UnitWants,
+                &unit,
+                true,
+                UnitDependencyMask::Implicit,
+            ) {
+                log::warn!("Failed to add the dependency on {}: {}", unit, e);
+                continue;
+            }
+            units.push(unit);
+        }
+        units
+    }
+
+    /* the device is not started by a job, so the units it wants have to be started here */
+    fn start_wants(&self, wants: &[String]) {
+        let um = self.comm.um();
+        for unit in wants {
+            if um.has_start_job(unit) {
+                continue;
+            }
+            if let Err(e) = um.unit_start_by_job(unit) {
+                log::warn!(
+                    "Failed to start {} wanted by {}: {}",
+                    unit,
+                    self.comm.get_owner_id(),
+                    e
+                );
+            }
+        }
+    }
+
+    pub fn get_state(&self) -> String {
+        let state = *self.state.borrow();
+        state.to_string()
+    }
+
+    fn set_state(&self, new_state: DeviceState) {
+        let old_state = self.state();
+        self.state.replace(new_state);
+
+        if new_state != old_state {
+            log::debug!(
+                "{} original state[{:?}] -> new state[{:?}]",
+                self.comm.get_owner_id(),
+                old_state,
+                new_state,
+            );
+            if let Some(u) = self.comm.owner() {
+                u.notify(
+                    old_state.device_state_to_unit_state(),
+                    new_state.device_state_to_unit_state(),
+                    UnitNotifyFlags::EMPTY,
+                )
+            }
+        }
+
+        self.db_update();
+    }
+
+    pub fn state(&self) -> DeviceState {
+        *self.state.borrow()
+    }
+
+    pub(super) fn sysfs(&self) -> String {
+        self.sysfs.borrow().clone()
+    }
+
+    pub(super) fn device_state_to_unit_state(&self) -> UnitActiveState {
+        self.state().device_state_to_unit_state()
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! device unit is entry of device type of unit，need impl
+//! UnitObj,UnitMngUtil, UnitSubClass trait
+
+use super::comm::DeviceUnitComm;
+use super::mng::DeviceMng;
+use core::error::*;
+use core::rel::{ReStation, Reliability};
+use core::unit::{SubUnit, UmIf, UnitActiveState, UnitBase, UnitMngUtil};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+struct DeviceUnit {
+    comm: Rc<DeviceUnitComm>,
+    mng: Rc<DeviceMng>,
+}
+
+impl ReStation for DeviceUnit {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        self.mng.db_map(reload);
+    }
+
+    fn db_insert(&self) {
+        self.mng.db_insert();
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl DeviceUnit {
+    fn new(_um: Rc<dyn UmIf>) -> DeviceUnit {
+        let comm = Rc::new(DeviceUnitComm::new());
+        let mng = Rc::new(DeviceMng::new(&comm));
+        DeviceUnit { comm, mng }
+    }
+}
+
+impl SubUnit for DeviceUnit {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn load(&self, _paths: Vec<PathBuf>) -> Result<()> {
+        /* devices come and go with the hardware, isolating to a target never stops them */
+        if let Some(u) = self.comm.owner() {
+            u.set_ignore_on_isolate(true);
+        }
+        Ok(())
+    }
+
+    fn current_active_state(&self) -> UnitActiveState {
+        self.mng.device_state_to_unit_state()
+    }
+
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.comm.attach_unit(unit);
+        self.db_insert();
+    }
+
+    fn init(&self) {}
+
+    fn done(&self) {}
+
+    fn dump(&self) {}
+
+    fn start(&self) -> Result<()> {
+        /* nothing to do, the job waits until the device shows up */
+        log::debug!("Waiting for the device {}", self.comm.get_owner_id());
+        Ok(())
+    }
+
+    fn stop(&self, _force: bool) -> Result<()> {
+        /* a plugged device can't be stopped, it is gone when it is unplugged */
+        Err(Error::UnitActionEBadR)
+    }
+
+    fn release_resources(&self) {}
+
+    fn setup_device(&self, sysfs: &str, wants: &[String], found: bool) {
+        self.mng.setup_device(sysfs, wants, found)
+    }
+}
+
+impl UnitMngUtil for DeviceUnit {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+use core::declare_unitobj_plugin_with_param;
+declare_unitobj_plugin_with_param!(DeviceUnit, DeviceUnit::new);
@@ -16,6 +16,7 @@ use core::{error::Result, rel::ReStation};
 use std::{cell::RefCell, path::PathBuf, rc::Rc, str::FromStr};
 
 use nix::sys::signal::Signal;
+use unit_parser::error::LocatedError;
 use unit_parser::prelude::UnitConfig;
 
 use crate::{comm::MountUnitComm, rentry::SectionMount};
@@ -120,6 +121,11 @@ impl MountConfig {
         self.data.clone()
     }
 
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        MountConfigData::verify_config_located(paths, name)
+    }
+
     pub(super) fn mount_where(&self) -> String {
         self.data.borrow().Mount.Where.clone()
     }
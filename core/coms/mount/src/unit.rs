@@ -22,10 +22,12 @@ use basic::mount::mount_point_to_unit_name;
 use core::error::*;
 use core::exec::ExecContext;
 use core::rel::{ReStation, Reliability};
-use core::unit::{SubUnit, UmIf, UnitActiveState, UnitBase, UnitMngUtil};
+use core::unit::{section_properties, SubUnit, UmIf, UnitActiveState, UnitBase, UnitMngUtil};
 use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
 use std::path::PathBuf;
 use std::rc::Rc;
+use unit_parser::error::LocatedError;
 
 struct MountUnit {
     comm: Rc<MountUnitComm>,
@@ -163,6 +165,18 @@ impl SubUnit for MountUnit {
         self.mng.get_state()
     }
 
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Mount)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        MountConfig::verify(paths, name)
+    }
+
+    fn control_pid(&self) -> Option<Pid> {
+        self.mng.control_pid()
+    }
+
     fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
         self.comm.attach_unit(unit);
         self.db_insert();
@@ -197,8 +211,6 @@ impl SubUnit for MountUnit {
         Ok(())
     }
 
-    fn kill(&self) {}
-
     fn release_resources(&self) {}
 
     fn sigchld_events(&self, wait_status: WaitStatus) {
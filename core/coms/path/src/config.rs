@@ -18,6 +18,7 @@ use crate::{comm::PathUnitComm, rentry::SectionPath};
 use core::error::*;
 use core::rel::ReStation;
 use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
 use unit_parser::prelude::UnitConfig;
 
 #[derive(UnitConfig, Default)]
@@ -99,6 +100,11 @@ impl PathConfig {
         self.data.clone()
     }
 
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        PathConfigData::verify_config_located(paths, name)
+    }
+
     pub(super) fn set_property(&self, key: &str, value: &str) -> Result<()> {
         let ret = self.data.borrow_mut().set_property(key, value);
         self.db_update();
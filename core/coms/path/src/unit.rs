@@ -26,14 +26,15 @@ use core::rel::{ReStation, Reliability};
 use core::unit::unit_name_to_type;
 use core::unit::PathSpec;
 use core::unit::{
-    PathType, SubUnit, UmIf, UnitActiveState, UnitBase, UnitDependencyMask, UnitMngUtil,
-    UnitRelations, UnitType,
+    section_properties, PathType, SubUnit, UmIf, UnitActiveState, UnitBase, UnitDependencyMask,
+    UnitMngUtil, UnitRelations, UnitType,
 };
 use nix::sys::wait::WaitStatus;
 use nix::NixPath;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
+use unit_parser::error::LocatedError;
 struct PathUnit {
     comm: Rc<PathUnitComm>,
     mng: Rc<PathMng>,
@@ -286,6 +287,14 @@ impl SubUnit for PathUnit {
         self.mng.get_state()
     }
 
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Path)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        PathConfig::verify(paths, name)
+    }
+
     fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
         self.comm.attach_unit(unit);
         self.db_insert();
@@ -344,8 +353,6 @@ impl SubUnit for PathUnit {
         }
     }
 
-    fn kill(&self) {}
-
     fn release_resources(&self) {}
 
     fn sigchld_events(&self, _wait_status: WaitStatus) {}
@@ -0,0 +1,174 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//!  The method provided by the public object needs to be called.
+//!
+use super::rentry::{ScopeRe, ScopeResult, ScopeState, SectionScope};
+use core::rel::Reliability;
+use core::unit::{UmIf, UnitBase};
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, RwLock};
+
+pub(super) struct ScopeUnitComm {
+    owner: RefCell<Option<Weak<dyn UnitBase>>>,
+    umcomm: Arc<ScopeUmComm>,
+}
+
+impl ScopeUnitComm {
+    pub(super) fn new() -> Self {
+        ScopeUnitComm {
+            owner: RefCell::new(None),
+            umcomm: ScopeUmComm::get_instance(),
+        }
+    }
+
+    pub(super) fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.owner.replace(Some(Rc::downgrade(&unit)));
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.umcomm.attach_um(um)
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.umcomm.attach_reli(reli)
+    }
+
+    pub(super) fn owner(&self) -> Option<Rc<dyn UnitBase>> {
+        if let Some(ref unit) = *self.owner.borrow() {
+            unit.upgrade()
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn get_owner_id(&self) -> String {
+        self.owner().map_or_else(String::new, |u| u.id())
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        self.umcomm.um()
+    }
+
+    pub(super) fn rentry(&self) -> Rc<ScopeRe> {
+        self.umcomm.rentry()
+    }
+
+    pub(super) fn rentry_conf_insert(&self, scope: &SectionScope) {
+        if let Some(u) = self.owner() {
+            self.rentry().conf_insert(&u.id(), scope)
+        }
+    }
+
+    pub(super) fn rentry_conf_get(&self) -> Option<SectionScope> {
+        self.owner().map(|u| self.rentry().conf_get(&u.id()))?
+    }
+
+    pub(super) fn rentry_mng_insert(&self, state: ScopeState, result: ScopeResult) {
+        if let Some(u) = self.owner() {
+            self.rentry().mng_insert(&u.id(), state, result)
+        }
+    }
+
+    pub(super) fn rentry_mng_get(&self) -> Option<(ScopeState, ScopeResult)> {
+        self.owner().map(|u| self.rentry().mng_get(&u.id()))?
+    }
+}
+
+static SCOPE_UM_COMM: Lazy<Arc<ScopeUmComm>> = Lazy::new(|| {
+    let comm = ScopeUmComm::new();
+    Arc::new(comm)
+});
+
+pub(super) struct ScopeUmComm {
+    data: RwLock<ScopeUmCommData>,
+}
+
+unsafe impl Send for ScopeUmComm {}
+
+unsafe impl Sync for ScopeUmComm {}
+
+impl ScopeUmComm {
+    pub(super) fn new() -> Self {
+        ScopeUmComm {
+            data: RwLock::new(ScopeUmCommData::new()),
+        }
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_um(um);
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_reli(reli);
+    }
+
+    pub(super) fn get_instance() -> Arc<ScopeUmComm> {
+        SCOPE_UM_COMM.clone()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        let rdata = self.data.read().unwrap();
+        rdata.um().unwrap()
+    }
+
+    pub(super) fn rentry(&self) -> Rc<ScopeRe> {
+        let rdata = self.data.read().unwrap();
+        rdata.rentry()
+    }
+}
+
+struct ScopeUmCommData {
+    // associated objects
+    um: Option<Rc<dyn UmIf>>,
+    reli: Weak<Reliability>,
+    rentry: Option<Rc<ScopeRe>>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl ScopeUmCommData {
+    pub(self) fn new() -> ScopeUmCommData {
+        ScopeUmCommData {
+            um: None,
+            reli: Weak::new(),
+            rentry: None,
+        }
+    }
+
+    pub(self) fn attach_um(&mut self, um: Rc<dyn UmIf>) {
+        if self.um.is_none() {
+            log::debug!("ScopeUmComm attach_um action.");
+            self.um = Some(um)
+        }
+    }
+
+    pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
+        let old = self.reli.clone().upgrade();
+        if old.is_none() {
+            log::debug!("ScopeUmComm attach_reli action.");
+            self.reli = Rc::downgrade(&reli);
+            self.rentry.replace(Rc::new(ScopeRe::new(&reli)));
+        }
+    }
+
+    pub(self) fn um(&self) -> Option<Rc<dyn UmIf>> {
+        self.um.as_ref().cloned()
+    }
+
+    pub(self) fn rentry(&self) -> Rc<ScopeRe> {
+        self.rentry.as_ref().cloned().unwrap()
+    }
+}
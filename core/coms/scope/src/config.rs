@@ -0,0 +1,137 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+#![allow(non_snake_case)]
+use crate::{comm::ScopeUnitComm, rentry::SectionScope};
+use core::error::*;
+use core::rel::ReStation;
+use core::unit::KillContext;
+use nix::sys::signal::Signal;
+use std::{cell::RefCell, path::PathBuf, rc::Rc, str::FromStr};
+use unit_parser::error::LocatedError;
+use unit_parser::prelude::UnitConfig;
+
+#[derive(UnitConfig, Default, Debug)]
+pub(crate) struct ScopeConfigData {
+    pub Scope: SectionScope,
+}
+
+impl ScopeConfigData {
+    pub(self) fn new(Scope: SectionScope) -> ScopeConfigData {
+        ScopeConfigData { Scope }
+    }
+
+    pub(self) fn set_property(&mut self, key: &str, value: &str) -> Result<()> {
+        self.Scope.set_property(key, value)
+    }
+}
+
+pub struct ScopeConfig {
+    // associated objects
+    comm: Rc<ScopeUnitComm>,
+
+    // owned objects
+    /* original */
+    data: Rc<RefCell<ScopeConfigData>>,
+    /* processed */
+    kill_context: Rc<KillContext>,
+}
+
+impl ReStation for ScopeConfig {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        if reload {
+            return;
+        }
+        if let Some(conf) = self.comm.rentry_conf_get() {
+            self.data.replace(ScopeConfigData::new(conf));
+            if let Err(e) = self.parse_kill_context() {
+                log::warn!("Failed to restore the kill context of scope: {}", e);
+            }
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_conf_insert(&self.data.borrow().Scope);
+    }
+}
+
+impl ScopeConfig {
+    pub(super) fn new(commr: &Rc<ScopeUnitComm>) -> Self {
+        ScopeConfig {
+            comm: Rc::clone(commr),
+            data: Rc::new(RefCell::new(ScopeConfigData::default())),
+            kill_context: Rc::new(KillContext::default()),
+        }
+    }
+
+    pub(super) fn reset(&self) {
+        self.data.replace(ScopeConfigData::default());
+        self.db_update();
+    }
+
+    pub(super) fn load(&self, paths: Vec<PathBuf>, update: bool) -> Result<()> {
+        let name = paths[0].file_name().unwrap().to_string_lossy().to_string();
+        let data = match ScopeConfigData::load_config(paths, &name) {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Invalid Configuration: {}", e);
+                return Err(Error::ConfigureError {
+                    msg: format!("Invalid Configuration: {}", e),
+                });
+            }
+        };
+
+        // record original configuration
+        *self.data.borrow_mut() = data;
+
+        // parse and record processed configuration
+        if let Err(e) = self.parse_kill_context() {
+            self.reset(); // fallback
+            return Err(e);
+        }
+
+        if update {
+            self.db_update();
+        }
+
+        Ok(())
+    }
+
+    fn parse_kill_context(&self) -> Result<()> {
+        let data = self.data.borrow();
+        self.kill_context.set_kill_mode(data.Scope.KillMode);
+        let signal = Signal::from_str(&data.Scope.KillSignal)?;
+        self.kill_context.set_kill_signal(signal);
+        Ok(())
+    }
+
+    pub(super) fn config_data(&self) -> Rc<RefCell<ScopeConfigData>> {
+        self.data.clone()
+    }
+
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        ScopeConfigData::verify_config_located(paths, name)
+    }
+
+    pub(super) fn kill_context(&self) -> Rc<KillContext> {
+        self.kill_context.clone()
+    }
+
+    pub(super) fn set_property(&self, key: &str, value: &str) -> Result<()> {
+        let ret = self.data.borrow_mut().set_property(key, value);
+        self.db_update();
+        ret
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! # Scope is used to manage a group of processes which are not forked by sysmaster.
+//!  The processes are forked by others, i.e. a container runtime or a login session manager, and
+//!  handed to sysmaster through the command socket. sysmaster moves them into the cgroup of the scope,
+//!  tracks them until the cgroup becomes empty, and kills them when the scope is stopped.
+//!  Scope can only be created as a transient unit, the configuration file is generated by sysmaster.
+//! #  Example:
+//! ``` toml
+//!  [Unit]
+//!  Description="session of user foo"
+//!
+//!  [Scope]
+//!  PIDs=1234 1235
+//!  Slice=user.slice
+//! ```
+//!  `[Scope]` section related configuration
+//!
+//!  PIDs
+//!
+//!  The processes which are moved into the cgroup of the scope when it is started
+//!
+//!  Slice
+//!
+//!  The slice which the scope belongs to
+//!
+//!  KillMode, KillSignal
+//!
+//!  How to kill the processes when the scope is stopped
+//!
+//!  TimeoutStopSec
+//!
+//!  The time to wait for the processes to exit after KillSignal is sent, SIGKILL is sent after that
+//! ##  Automatic dependency
+//!
+//! ###  Default Dependency
+//!  If DefaultDependencies=true is set, the following dependencies will be added by default:
+//!  Conflicts="shutdown.target", Before="shutdown.target"
+
+#[cfg(all(feature = "plugin", feature = "noplugin"))]
+compile_error!("feature plugin and noplugin cannot be enabled at the same time");
+
+pub use {manager::__um_obj_create, unit::__subunit_create_with_params};
+
+// dependency:
+// scope_base -> scope_rentry -> {scope_comm | scope_config}
+// scope_mng -> scope_bus -> scope_unit -> scope_manager
+mod base;
+mod bus;
+mod comm;
+mod config;
+mod manager;
+mod mng;
+mod rentry;
+mod unit;
@@ -0,0 +1,594 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use super::comm::ScopeUnitComm;
+use super::config::ScopeConfig;
+use super::rentry::{ScopeResult, ScopeState};
+use basic::IN_SET;
+use core::error::*;
+use core::rel::ReStation;
+use core::unit::{KillOperation, PathSpec, PathType, UnitActiveState, UnitNotifyFlags};
+use event::{EventState, EventType, Events, Source};
+use nix::libc;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::cell::RefCell;
+use std::os::unix::prelude::RawFd;
+use std::path::Path;
+use std::rc::{Rc, Weak};
+
+const CGROUP_EVENTS: &str = "cgroup.events";
+
+impl ScopeState {
+    fn to_unit_state(self) -> UnitActiveState {
+        match self {
+            ScopeState::Dead | ScopeState::StateMax => UnitActiveState::InActive,
+            ScopeState::Running => UnitActiveState::Active,
+            ScopeState::StopSigterm | ScopeState::StopSigkill => UnitActiveState::DeActivating,
+            ScopeState::Failed => UnitActiveState::Failed,
+        }
+    }
+
+    fn to_kill_operation(self) -> KillOperation {
+        match self {
+            ScopeState::StopSigterm => KillOperation::KillTerminate,
+            _ => KillOperation::KillKill,
+        }
+    }
+}
+
+pub(super) struct ScopeMng {
+    // associated objects
+    comm: Rc<ScopeUnitComm>,
+    config: Rc<ScopeConfig>,
+
+    // owned objects
+    state: RefCell<ScopeState>,
+    result: RefCell<ScopeResult>,
+    timer: Rc<ScopeTimer>,
+    cgroup_watch: Rc<ScopeCgroupWatch>,
+}
+
+impl ReStation for ScopeMng {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, _reload: bool) {
+        if let Some((state, result)) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+            *self.result.borrow_mut() = result;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_mng_insert(self.state(), self.result());
+    }
+
+    // reload: entry-only
+    fn entry_coldplug(&self) {
+        // the processes of the scope are not children of sysmaster, so the cgroup must be watched again.
+        if IN_SET!(
+            self.state(),
+            ScopeState::Running,
+            ScopeState::StopSigterm,
+            ScopeState::StopSigkill
+        ) {
+            if let Err(e) = self.watch_cgroup() {
+                log::warn!("Failed to watch the cgroup of scope: {}", e);
+            }
+        }
+
+        if IN_SET!(
+            self.state(),
+            ScopeState::StopSigterm,
+            ScopeState::StopSigkill
+        ) {
+            self.enable_timer(self.config.config_data().borrow().Scope.TimeoutStopSec);
+        }
+    }
+
+    fn entry_clear(&self) {
+        self.delete_timer();
+        self.unwatch_cgroup();
+    }
+}
+
+impl ScopeMng {
+    pub(super) fn new(commr: &Rc<ScopeUnitComm>, configr: &Rc<ScopeConfig>) -> ScopeMng {
+        ScopeMng {
+            comm: Rc::clone(commr),
+            config: Rc::clone(configr),
+            state: RefCell::new(ScopeState::StateMax),
+            result: RefCell::new(ScopeResult::Success),
+            timer: Rc::new(ScopeTimer::new(0)),
+            cgroup_watch: Rc::new(ScopeCgroupWatch::new()),
+        }
+    }
+
+    pub(super) fn attach_mng(&self, mng: Weak<ScopeMng>) {
+        self.timer.attach_mng(mng.clone());
+        self.cgroup_watch.attach_mng(mng);
+    }
+
+    pub(super) fn start_check(&self) -> Result<()> {
+        if IN_SET!(
+            self.state(),
+            ScopeState::StopSigterm,
+            ScopeState::StopSigkill
+        ) {
+            return Err(Error::UnitActionEAgain);
+        }
+
+        if self.state() == ScopeState::Running {
+            return Err(Error::UnitActionEAlready);
+        }
+
+        // the processes of a scope are handed over at creation, starting it again is meaningless.
+        if let Some(unit) = self.comm.owner() {
+            if !unit.transient() {
+                log::error!("Scope {} can only be created transiently.", unit.id());
+                return Err(Error::UnitActionEOpNotSupp);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn start_action(&self) {
+        let unit = match self.comm.owner() {
+            None => return,
+            Some(u) => u,
+        };
+
+        self.set_result(ScopeResult::Success);
+        if let Err(e) = unit.prepare_exec() {
+            log::error!("Failed to prepare the cgroup of {}: {}", unit.id(), e);
+            self.enter_dead(ScopeResult::FailureResources);
+            return;
+        }
+
+        let cg_path = unit.cg_path();
+        let pids = self.config.config_data().borrow().Scope.PIDs.clone();
+        let mut attached = 0;
+        for pid in pids {
+            let pid = Pid::from_raw(pid);
+            if let Err(e) = cgroup::cg_attach(pid, &cg_path) {
+                log::warn!("Failed to move process {} into {}: {}", pid, unit.id(), e);
+                continue;
+            }
+            self.comm.um().child_watch_pid(&unit.id(), pid);
+            attached += 1;
+        }
+
+        if attached == 0 {
+            log::error!("No process could be moved into {}, refusing.", unit.id());
+            self.enter_dead(ScopeResult::FailureResources);
+            return;
+        }
+
+        if let Err(e) = self.watch_cgroup() {
+            log::warn!("Failed to watch the cgroup of {}: {}", unit.id(), e);
+        }
+        self.set_state(ScopeState::Running);
+
+        // the processes may have exited before the watch is established.
+        self.check_cgroup_empty();
+    }
+
+    pub(super) fn stop_check(&self) -> Result<()> {
+        if IN_SET!(self.state(), ScopeState::Dead, ScopeState::Failed) {
+            return Err(Error::UnitActionEAlready);
+        }
+
+        if IN_SET!(
+            self.state(),
+            ScopeState::StopSigterm,
+            ScopeState::StopSigkill
+        ) {
+            return Err(Error::UnitActionEAgain);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn stop_action(&self) {
+        self.enter_signal(ScopeState::StopSigterm, ScopeResult::Success);
+    }
+
+    /// send the signal to the adopted processes, the scope is dead once the cgroup is empty
+    pub(super) fn kill(&self, signal: Signal) -> Result<()> {
+        let unit = match self.comm.owner() {
+            None => return Ok(()),
+            Some(u) => u,
+        };
+
+        unit.kill_context(
+            self.config.kill_context(),
+            None,
+            None,
+            KillOperation::KillSignal(signal),
+            false,
+        )?;
+        Ok(())
+    }
+
+    pub(super) fn reset_failed(&self) {
+        if self.state() == ScopeState::Failed {
+            self.set_state(ScopeState::Dead);
+        }
+        self.set_result(ScopeResult::Success);
+    }
+
+    fn enter_signal(&self, state: ScopeState, res: ScopeResult) {
+        if self.result() == ScopeResult::Success {
+            self.set_result(res);
+        }
+
+        let unit = match self.comm.owner() {
+            None => return,
+            Some(u) => u,
+        };
+
+        if let Err(e) = unit.kill_context(
+            self.config.kill_context(),
+            None,
+            None,
+            state.to_kill_operation(),
+            false,
+        ) {
+            log::error!("Failed to kill the processes of {}: {}", unit.id(), e);
+            self.enter_dead(ScopeResult::FailureResources);
+            return;
+        }
+
+        if self.cgroup_empty() {
+            self.enter_dead(ScopeResult::Success);
+            return;
+        }
+
+        self.enable_timer(self.config.config_data().borrow().Scope.TimeoutStopSec);
+        self.set_state(state);
+    }
+
+    fn enter_dead(&self, res: ScopeResult) {
+        if self.result() == ScopeResult::Success {
+            self.set_result(res);
+        }
+
+        self.delete_timer();
+        self.unwatch_cgroup();
+        self.comm
+            .um()
+            .child_unwatch_all_pids(&self.comm.get_owner_id());
+
+        let state = if self.result() == ScopeResult::Success {
+            ScopeState::Dead
+        } else {
+            ScopeState::Failed
+        };
+        self.set_state(state);
+    }
+
+    fn cgroup_empty(&self) -> bool {
+        match self.comm.owner() {
+            None => true,
+            Some(u) => cgroup::cg_is_empty_recursive(&u.cg_path()).unwrap_or(true),
+        }
+    }
+
+    /// the scope is finished once all the processes in its cgroup are gone.
+    pub(super) fn check_cgroup_empty(&self) {
+        if !IN_SET!(
+            self.state(),
+            ScopeState::Running,
+            ScopeState::StopSigterm,
+            ScopeState::StopSigkill
+        ) {
+            return;
+        }
+
+        if self.cgroup_empty() {
+            log::debug!("The cgroup of {} is empty.", self.comm.get_owner_id());
+            self.enter_dead(ScopeResult::Success);
+        }
+    }
+
+    fn watch_cgroup(&self) -> Result<()> {
+        let cg_path = match self.comm.owner() {
+            None => return Ok(()),
+            Some(u) => u.cg_path(),
+        };
+
+        let path = cgroup::cg_attribute_path(&cg_path, CGROUP_EVENTS).context(CgroupSnafu)?;
+        self.unwatch_cgroup();
+        self.cgroup_watch.watch(&path)?;
+
+        let events = self.comm.um().events();
+        events.add_source(self.cgroup_watch.clone())?;
+        events.set_enabled(self.cgroup_watch.clone(), EventState::On)?;
+        Ok(())
+    }
+
+    fn unwatch_cgroup(&self) {
+        if !self.cgroup_watch.watching() {
+            return;
+        }
+
+        let events = self.comm.um().events();
+        if let Err(e) = events.del_source(self.cgroup_watch.clone()) {
+            log::warn!("Failed to delete the cgroup watch source: {}", e);
+        }
+        self.cgroup_watch.unwatch();
+    }
+
+    fn enable_timer(&self, usec: u64) {
+        let events = self.comm.um().events();
+        if let Err(e) = events.del_source(self.timer.clone()) {
+            log::debug!("Failed to delete the scope timer: {}", e);
+        }
+
+        if usec == u64::MAX {
+            return;
+        }
+
+        self.timer.set_time(usec);
+        if let Err(e) = events.add_source(self.timer.clone()) {
+            log::error!("Failed to add the scope timer: {}", e);
+            return;
+        }
+        if let Err(e) = events.set_enabled(self.timer.clone(), EventState::OneShot) {
+            log::error!("Failed to enable the scope timer: {}", e);
+        }
+    }
+
+    fn delete_timer(&self) {
+        let events = self.comm.um().events();
+        if let Err(e) = events.del_source(self.timer.clone()) {
+            log::debug!("Failed to delete the scope timer: {}", e);
+        }
+    }
+
+    fn dispatch_timer(&self) {
+        match self.state() {
+            ScopeState::StopSigterm => {
+                log::warn!("{} stopping timed out, killing.", self.comm.get_owner_id());
+                self.enter_signal(ScopeState::StopSigkill, ScopeResult::FailureTimeout);
+            }
+            ScopeState::StopSigkill => {
+                log::warn!(
+                    "{} still around after SIGKILL, ignoring.",
+                    self.comm.get_owner_id()
+                );
+                self.enter_dead(ScopeResult::FailureTimeout);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn get_state(&self) -> String {
+        self.state().to_string()
+    }
+
+    fn set_state(&self, new_state: ScopeState) {
+        let old_state = self.state();
+        self.state.replace(new_state);
+        self.state_notify(new_state, old_state);
+    }
+
+    fn state_notify(&self, new_state: ScopeState, old_state: ScopeState) {
+        if let Some(unit) = self.comm.owner() {
+            if new_state != old_state {
+                log::debug!(
+                    "{} original state[{:?}] ->new state[{:?}]",
+                    unit.id(),
+                    old_state,
+                    new_state,
+                );
+            }
+            unit.notify(
+                old_state.to_unit_state(),
+                new_state.to_unit_state(),
+                UnitNotifyFlags::RELOAD_FAILURE,
+            );
+        }
+
+        self.db_update();
+    }
+
+    fn state(&self) -> ScopeState {
+        *self.state.borrow()
+    }
+
+    fn set_result(&self, result: ScopeResult) {
+        *self.result.borrow_mut() = result;
+    }
+
+    fn result(&self) -> ScopeResult {
+        *self.result.borrow()
+    }
+
+    pub fn to_unit_state(&self) -> UnitActiveState {
+        self.state().to_unit_state()
+    }
+}
+
+struct ScopeTimer {
+    time: RefCell<u64>,
+    mng: RefCell<Weak<ScopeMng>>,
+}
+
+impl ScopeTimer {
+    fn new(usec: u64) -> Self {
+        ScopeTimer {
+            time: RefCell::new(usec),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    fn attach_mng(&self, mng: Weak<ScopeMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    fn set_time(&self, usec: u64) {
+        *self.time.borrow_mut() = usec
+    }
+}
+
+impl Source for ScopeTimer {
+    fn fd(&self) -> RawFd {
+        0
+    }
+
+    fn event_type(&self) -> EventType {
+        EventType::TimerMonotonic
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn time_relative(&self) -> u64 {
+        *self.time.borrow()
+    }
+
+    fn dispatch(&self, _: &Events) -> i32 {
+        if let Some(mng) = self.mng.borrow().upgrade() {
+            mng.dispatch_timer();
+        }
+        0
+    }
+
+    fn priority(&self) -> i8 {
+        0
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+/// cgroup.events is modified when the "populated" field of the cgroup changes.
+struct ScopeCgroupWatch {
+    spec: RefCell<Option<PathSpec>>,
+    mng: RefCell<Weak<ScopeMng>>,
+}
+
+impl ScopeCgroupWatch {
+    fn new() -> Self {
+        ScopeCgroupWatch {
+            spec: RefCell::new(None),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    fn attach_mng(&self, mng: Weak<ScopeMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    fn watch(&self, path: &Path) -> Result<()> {
+        let spec = PathSpec::new(path.to_path_buf(), PathType::Modified);
+        spec.watch()?;
+        self.spec.replace(Some(spec));
+        Ok(())
+    }
+
+    fn unwatch(&self) {
+        if let Some(spec) = self.spec.take() {
+            spec.unwatch();
+        }
+    }
+
+    fn watching(&self) -> bool {
+        self.spec.borrow().is_some()
+    }
+}
+
+impl Source for ScopeCgroupWatch {
+    fn fd(&self) -> RawFd {
+        self.spec.borrow().as_ref().map_or(-1, |s| s.inotify_fd())
+    }
+
+    fn event_type(&self) -> EventType {
+        EventType::Io
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn dispatch(&self, _: &Events) -> i32 {
+        if let Some(spec) = self.spec.borrow().as_ref() {
+            if let Err(e) = spec.read_fd_event() {
+                log::error!("Failed to read the cgroup events: {}", e);
+                return -1;
+            }
+        }
+
+        if let Some(mng) = self.mng.borrow().upgrade() {
+            mng.check_cgroup_empty();
+        }
+        0
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScopeMng, ScopeState};
+    use crate::comm::ScopeUnitComm;
+    use crate::config::ScopeConfig;
+    use core::unit::UnitActiveState;
+    use std::rc::Rc;
+
+    fn create_mng() -> ScopeMng {
+        let comm = Rc::new(ScopeUnitComm::new());
+        let config = Rc::new(ScopeConfig::new(&comm));
+        ScopeMng::new(&comm, &config)
+    }
+
+    #[test]
+    fn test_scope_state_to_unit_state() {
+        assert_eq!(ScopeState::Running.to_unit_state(), UnitActiveState::Active);
+        assert_eq!(
+            ScopeState::StopSigkill.to_unit_state(),
+            UnitActiveState::DeActivating
+        );
+        assert_eq!(ScopeState::Failed.to_unit_state(), UnitActiveState::Failed);
+    }
+
+    #[test]
+    fn test_scope_stop_check() {
+        let mng = create_mng();
+        mng.state.replace(ScopeState::Dead);
+        assert!(mng.stop_check().is_err());
+        mng.state.replace(ScopeState::Running);
+        assert!(mng.stop_check().is_ok());
+    }
+
+    #[test]
+    fn test_scope_start_check() {
+        let mng = create_mng();
+        mng.state.replace(ScopeState::Running);
+        assert!(mng.start_check().is_err());
+        mng.state.replace(ScopeState::StopSigterm);
+        assert!(mng.start_check().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+#![allow(non_snake_case)]
+use basic::time::USEC_PER_SEC;
+use core::{
+    error::*,
+    rel::{ReDb, ReDbRwTxn, ReDbTable, ReliSwitch, Reliability},
+    unit::KillMode,
+};
+use macros::{EnumDisplay, UnitSection};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use unit_parser::internal::UnitEntry;
+
+struct ScopeReDb<K, V>(ReDb<K, V>);
+const RELI_DB_HSCOPE_CONF: &str = "scopeconf";
+const RELI_DB_HSCOPE_MNG: &str = "scopemng";
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, EnumDisplay)]
+pub(super) enum ScopeState {
+    Dead,
+    Running,
+    StopSigterm,
+    StopSigkill,
+    Failed,
+    StateMax,
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub(super) enum ScopeResult {
+    Success,
+    FailureResources,
+    FailureTimeout,
+}
+
+pub(super) fn parse_pids(s: &str) -> Result<Vec<i32>> {
+    let mut pids = Vec::new();
+    for word in s.split_whitespace() {
+        let pid = word.parse::<i32>()?;
+        if pid <= 0 {
+            return Err(Error::ConfigureError {
+                msg: format!("invalid PID: {}", word),
+            });
+        }
+        pids.push(pid);
+    }
+    Ok(pids)
+}
+
+fn parse_sec(s: &str) -> Result<u64> {
+    basic::time::parse_sec(s).context(NixSnafu)
+}
+
+#[derive(UnitSection, Default, Clone, Debug, Serialize, Deserialize)]
+pub struct SectionScope {
+    #[entry(append, parser = parse_pids)]
+    pub PIDs: Vec<i32>,
+    #[entry(default = String::new())]
+    pub Slice: String,
+    #[entry(default = KillMode::ControlGroup)]
+    pub KillMode: KillMode,
+    #[entry(default = String::from("SIGTERM"))]
+    pub KillSignal: String,
+    #[entry(default = 90 * USEC_PER_SEC, parser = parse_sec)]
+    pub TimeoutStopSec: u64,
+}
+
+impl SectionScope {
+    pub(super) fn set_property(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "PIDs" => self.PIDs = parse_pids(value)?,
+            "Slice" => self.Slice = value.to_string(),
+            "KillMode" => self.KillMode = KillMode::parse_from_str(value)?,
+            "KillSignal" => self.KillSignal = value.to_string(),
+            "TimeoutStopSec" => self.TimeoutStopSec = parse_sec(value)?,
+            str_key => {
+                return Err(Error::NotFound {
+                    what: format!("set scope property:{}", str_key),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ScopeReConf {
+    scope: SectionScope,
+}
+
+impl ScopeReConf {
+    fn new(scope: &SectionScope) -> ScopeReConf {
+        ScopeReConf {
+            scope: scope.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ScopeReMng {
+    state: ScopeState,
+    result: ScopeResult,
+}
+
+impl ScopeReMng {
+    fn new(state: ScopeState, result: ScopeResult) -> ScopeReMng {
+        ScopeReMng { state, result }
+    }
+}
+
+pub(super) struct ScopeRe {
+    // database: multi-instance(N)
+    conf: Rc<ScopeReDb<String, ScopeReConf>>, // RELI_DB_HSCOPE_CONF;
+    mng: Rc<ScopeReDb<String, ScopeReMng>>,   // RELI_DB_HSCOPE_MNG;
+}
+
+impl ScopeRe {
+    pub(super) fn new(relir: &Rc<Reliability>) -> ScopeRe {
+        let conf = Rc::new(ScopeReDb(ReDb::new(relir, RELI_DB_HSCOPE_CONF)));
+        let mng = Rc::new(ScopeReDb(ReDb::new(relir, RELI_DB_HSCOPE_MNG)));
+        let rentry = ScopeRe { conf, mng };
+        rentry.register(relir);
+        rentry
+    }
+
+    pub(super) fn conf_insert(&self, unit_id: &str, scope: &SectionScope) {
+        let conf = ScopeReConf::new(scope);
+        self.conf.0.insert(unit_id.to_string(), conf);
+    }
+
+    pub(super) fn conf_get(&self, unit_id: &str) -> Option<SectionScope> {
+        let conf = self.conf.0.get(&unit_id.to_string());
+        conf.map(|c| c.scope)
+    }
+
+    pub(super) fn mng_insert(&self, unit_id: &str, state: ScopeState, result: ScopeResult) {
+        let mng = ScopeReMng::new(state, result);
+        self.mng.0.insert(unit_id.to_string(), mng);
+    }
+
+    pub(super) fn mng_get(&self, unit_id: &str) -> Option<(ScopeState, ScopeResult)> {
+        let mng = self.mng.0.get(&unit_id.to_string());
+        mng.map(|m| (m.state, m.result))
+    }
+
+    fn register(&self, relir: &Reliability) {
+        // rel-db: RELI_DB_HSCOPE_CONF
+        let db = Rc::clone(&self.conf);
+        relir.history_db_register(RELI_DB_HSCOPE_CONF, db);
+
+        // rel-db: RELI_DB_HSCOPE_MNG
+        let db = Rc::clone(&self.mng);
+        relir.history_db_register(RELI_DB_HSCOPE_MNG, db);
+    }
+}
+
+impl ReDbTable for ScopeReDb<String, ScopeReConf> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
+
+impl ReDbTable for ScopeReDb<String, ScopeReMng> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_pids;
+
+    #[test]
+    fn test_parse_pids() {
+        assert_eq!(parse_pids("1234").unwrap(), vec![1234]);
+        assert_eq!(parse_pids("1 2  3").unwrap(), vec![1, 2, 3]);
+        assert!(parse_pids("0").is_err());
+        assert!(parse_pids("-5").is_err());
+        assert!(parse_pids("abc").is_err());
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use super::bus::ScopeBus;
+use super::comm::ScopeUnitComm;
+use super::config::ScopeConfig;
+use super::mng::ScopeMng;
+use basic::SHUTDOWN_TARGET;
+use core::error::*;
+use core::rel::{ReStation, Reliability};
+use core::unit::{
+    section_properties, SubUnit, UmIf, UnitActiveState, UnitBase, UnitDependencyMask, UnitMngUtil,
+    UnitRelations, UnitWriteFlags,
+};
+use nix::sys::signal::Signal;
+use nix::sys::wait::WaitStatus;
+use std::{path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
+
+struct Scope {
+    um: Rc<dyn UmIf>,
+    comm: Rc<ScopeUnitComm>,
+    config: Rc<ScopeConfig>,
+    mng: Rc<ScopeMng>,
+    bus: ScopeBus,
+}
+
+impl ReStation for Scope {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        self.config.db_map(reload);
+        self.mng.db_map(reload);
+    }
+
+    fn db_insert(&self) {
+        self.config.db_insert();
+        self.mng.db_insert();
+    }
+
+    // reload: entry-only
+    fn entry_coldplug(&self) {
+        self.mng.entry_coldplug();
+    }
+
+    fn entry_clear(&self) {
+        self.mng.entry_clear();
+    }
+}
+
+impl Scope {
+    fn new(um_if: Rc<dyn UmIf>) -> Scope {
+        let comm = Rc::new(ScopeUnitComm::new());
+        let config = Rc::new(ScopeConfig::new(&comm));
+        let mng = Rc::new(ScopeMng::new(&comm, &config));
+        mng.attach_mng(Rc::downgrade(&mng));
+        Scope {
+            um: Rc::clone(&um_if),
+            comm: Rc::clone(&comm),
+            config: Rc::clone(&config),
+            mng,
+            bus: ScopeBus::new(&comm, &config),
+        }
+    }
+
+    pub(self) fn owner(&self) -> Option<Rc<dyn UnitBase>> {
+        self.comm.owner()
+    }
+
+    pub(self) fn add_slice(&self) -> Result<()> {
+        let u = match self.owner() {
+            None => return Ok(()),
+            Some(u) => u,
+        };
+
+        let slice = self.config.config_data().borrow().Scope.Slice.clone();
+        if slice.is_empty() {
+            return Ok(());
+        }
+        self.um.unit_set_slice(&u.id(), &slice)
+    }
+
+    pub(self) fn add_default_dependencies(&self) -> Result<()> {
+        let u = match self.owner() {
+            None => return Ok(()),
+            Some(u) => u,
+        };
+
+        if !u.default_dependencies() {
+            return Ok(());
+        }
+
+        log::debug!("Adding default dependencies for scope: {}", u.id());
+        self.um.unit_add_two_dependency(
+            &u.id(),
+            UnitRelations::UnitBefore,
+            UnitRelations::UnitConflicts,
+            SHUTDOWN_TARGET,
+            true,
+            UnitDependencyMask::Default,
+        )
+    }
+}
+
+impl SubUnit for Scope {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn load(&self, paths: Vec<PathBuf>) -> Result<()> {
+        log::debug!("scope begin to load conf file");
+        self.config.load(paths, true)?;
+
+        let ret = self
+            .add_slice()
+            .and_then(|_| self.add_default_dependencies());
+        if ret.is_err() {
+            self.config.reset();
+        }
+        ret
+    }
+
+    fn current_active_state(&self) -> UnitActiveState {
+        self.mng.to_unit_state()
+    }
+
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Scope)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        ScopeConfig::verify(paths, name)
+    }
+
+    fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.comm.attach_unit(Rc::clone(&unit));
+        self.db_insert();
+    }
+
+    fn init(&self) {}
+
+    fn done(&self) {}
+
+    fn dump(&self) {}
+
+    fn start(&self) -> Result<()> {
+        log::info!("Starting {}", self.comm.get_owner_id());
+        self.mng.start_check()?;
+
+        self.mng.start_action();
+        Ok(())
+    }
+
+    fn stop(&self, force: bool) -> Result<()> {
+        if !force {
+            self.mng.stop_check()?;
+        }
+
+        self.mng.stop_action();
+        Ok(())
+    }
+
+    fn kill(&self, signal: Signal) -> Result<()> {
+        self.mng.kill(signal)
+    }
+
+    fn release_resources(&self) {}
+
+    fn sigchld_events(&self, _wait_status: WaitStatus) {
+        self.mng.check_cgroup_empty();
+    }
+
+    fn reset_failed(&self) {
+        self.mng.reset_failed()
+    }
+
+    fn unit_set_property(&self, key: &str, value: &str, flags: UnitWriteFlags) -> Result<()> {
+        self.bus.unit_set_property(key, value, flags)
+    }
+}
+
+impl UnitMngUtil for Scope {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+use core::declare_unitobj_plugin_with_param;
+declare_unitobj_plugin_with_param!(Scope, Scope::new);
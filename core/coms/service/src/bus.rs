@@ -16,6 +16,17 @@ use core::error::*;
 use core::unit::{self, UnitWriteFlags};
 use std::rc::Rc;
 
+/// the cgroup resource control properties, they can be changed for the loaded service
+pub(super) const CGROUP_PROPERTIES: [&str; 7] = [
+    "MemoryMax",
+    "MemoryHigh",
+    "CPUWeight",
+    "CPUQuota",
+    "TasksMax",
+    "IOWeight",
+    "IODeviceReadBandwidthMax",
+];
+
 pub struct ServiceBus {
     // associated objects
     comm: Rc<ServiceUnitComm>,
@@ -50,12 +61,32 @@ impl ServiceBus {
                 if let Err(Error::NotFound { what: _ }) = ret {
                     ret = self.kill_set_transient_property(key, value, flags);
                 }
+            } else {
+                ret = self.service_set_live_property(key, value, flags);
             }
         }
 
         ret
     }
 
+    /* The settings which are read every time they are used, so the new value takes
+     * effect at once for the loaded service. */
+    fn service_set_live_property(
+        &self,
+        key: &str,
+        value: &str,
+        flags: UnitWriteFlags,
+    ) -> Result<()> {
+        let real_flags = flags | UnitWriteFlags::PRIVATE;
+        match key {
+            "RestartSec" | "RestartSteps" | "RestartMaxDelaySec" | "TimeoutStartSec"
+            | "TimeoutStopSec" => self.unit_write_property(key, value, real_flags, true),
+            str_key => Err(Error::NotFound {
+                what: format!("set live property:{}", str_key),
+            }),
+        }
+    }
+
     fn unit_set_transient_property(
         &self,
         key: &str,
@@ -71,9 +102,16 @@ impl ServiceBus {
             | "TimeoutStopSec"
             | "WatchdogSec"
             | "NotifyAccess"
+            | "FileDescriptorStoreMax"
+            | "ReloadSignal"
             | "PIDFile"
             | "Restart"
-            | "RestartPreventExitStatus" => self.unit_write_property(key, value, real_flags, false),
+            | "RestartPreventExitStatus"
+            | "RestartForceExitStatus"
+            | "SuccessExitStatus"
+            | "RestartMode"
+            | "RestartSteps"
+            | "RestartMaxDelaySec" => self.unit_write_property(key, value, real_flags, false),
             "ExecStart" | "ExecStartPre" | "ExecStartPost" | "ExecStop" | "ExecStopPost"
             | "ExecReload" | "ExecCondition" => {
                 self.unit_write_property(key, value, real_flags, false)
@@ -107,6 +145,19 @@ impl ServiceBus {
             "LimitCORE" | "LimitNOFILE" | "LimitNPROC" => {
                 self.unit_write_property(key, value, real_flags, false)
             }
+            "ProtectSystem" | "ProtectHome" | "PrivateTmp" | "ReadOnlyPaths"
+            | "InaccessiblePaths" | "BindPaths" | "BindReadOnlyPaths" => {
+                self.unit_write_property(key, value, real_flags, false)
+            }
+            "CapabilityBoundingSet" | "AmbientCapabilities" | "NoNewPrivileges" | "SecureBits" => {
+                self.unit_write_property(key, value, real_flags, false)
+            }
+            "SystemCallFilter" | "SystemCallErrorNumber" | "SystemCallArchitectures" => {
+                self.unit_write_property(key, value, real_flags, false)
+            }
+            "StandardInput" | "StandardOutput" | "StandardError" | "TTYPath" => {
+                self.unit_write_property(key, value, real_flags, false)
+            }
             str_key => Err(Error::NotFound {
                 what: format!("set exec property:{}", str_key),
             }),
@@ -131,13 +182,17 @@ impl ServiceBus {
     fn cgroup_set_transient_property(
         &self,
         key: &str,
-        _value: &str,
-        _flags: UnitWriteFlags,
+        value: &str,
+        flags: UnitWriteFlags,
     ) -> Result<()> {
-        // not supported now
-        Err(Error::NotFound {
-            what: format!("set cgroup property:{}", key),
-        })
+        if !CGROUP_PROPERTIES.contains(&key) {
+            return Err(Error::NotFound {
+                what: format!("set cgroup property:{}", key),
+            });
+        }
+
+        let real_flags = flags | UnitWriteFlags::PRIVATE;
+        self.unit_write_property(key, value, real_flags, true)
     }
 
     fn unit_write_property(
@@ -21,6 +21,7 @@ use log::Level;
 use nix::unistd::Pid;
 use once_cell::sync::Lazy;
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::{Rc, Weak};
 use std::sync::{Arc, RwLock};
 
@@ -90,6 +91,8 @@ impl ServiceUnitComm {
         restarts: u32,
         exit_status: ExitStatus,
         monitor: ServiceMonitor,
+        fdstore: Vec<(i32, String)>,
+        private_tmp: Option<PathBuf>,
     ) {
         if let Some(u) = self.owner() {
             self.rentry().mng_insert(
@@ -107,6 +110,8 @@ impl ServiceUnitComm {
                 restarts,
                 exit_status,
                 monitor,
+                fdstore,
+                private_tmp,
             )
         }
     }
@@ -128,12 +133,15 @@ impl ServiceUnitComm {
         u32,
         ExitStatus,
         ServiceMonitor,
+        Vec<(i32, String)>,
+        Option<PathBuf>,
     )> {
         self.owner().map(|u| self.rentry().mng_get(&u.id()))?
     }
 
-    pub(super) fn _reli(&self) -> Rc<Reliability> {
-        self.umcomm._reli()
+    /// None if the reliability is not attached yet
+    pub(super) fn reli(&self) -> Option<Rc<Reliability>> {
+        self.umcomm.reli()
     }
 
     pub(super) fn log(&self, level: Level, msg: &str) {
@@ -189,9 +197,9 @@ impl ServiceUmComm {
         SERVICE_UM_COMM.clone()
     }
 
-    pub(super) fn _reli(&self) -> Rc<Reliability> {
+    pub(super) fn reli(&self) -> Option<Rc<Reliability>> {
         let rdata = self.data.read().unwrap();
-        rdata._reli()
+        rdata.reli()
     }
 
     pub(super) fn um(&self) -> Rc<dyn UmIf> {
@@ -208,7 +216,7 @@ impl ServiceUmComm {
 struct ServiceUmCommData {
     // associated objects
     um: Option<Rc<dyn UmIf>>,
-    _reli: Weak<Reliability>,
+    reli: Weak<Reliability>,
     rentry: Option<Rc<ServiceRe>>,
 }
 
@@ -217,7 +225,7 @@ impl ServiceUmCommData {
     pub(self) fn new() -> ServiceUmCommData {
         ServiceUmCommData {
             um: None,
-            _reli: Weak::new(),
+            reli: Weak::new(),
             rentry: None,
         }
     }
@@ -230,10 +238,10 @@ impl ServiceUmCommData {
     }
 
     pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
-        let old = self._reli.clone().upgrade();
+        let old = self.reli.clone().upgrade();
         if old.is_none() {
             log::debug!("ServiceUmComm attach_reli action.");
-            self._reli = Rc::downgrade(&reli);
+            self.reli = Rc::downgrade(&reli);
             self.rentry.replace(Rc::new(ServiceRe::new(&reli)));
         }
     }
@@ -246,8 +254,8 @@ impl ServiceUmCommData {
         }
     }
 
-    pub(self) fn _reli(&self) -> Rc<Reliability> {
-        self._reli.clone().upgrade().unwrap()
+    pub(self) fn reli(&self) -> Option<Rc<Reliability>> {
+        self.reli.clone().upgrade()
     }
 
     pub(self) fn rentry(&self) -> Rc<ServiceRe> {
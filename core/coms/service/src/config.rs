@@ -17,12 +17,13 @@ use basic::unit_name::unit_name_to_instance;
 use core::error::*;
 use core::exec::ExecCommand;
 use core::rel::ReStation;
-use core::specifier::{UnitSpecifierData, LONG_LINE_MAX};
-use core::unit::KillContext;
+use core::specifier::{unit_string_specifier_escape, UnitSpecifierData, LONG_LINE_MAX};
+use core::unit::{CgroupContext, KillContext};
 use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 use std::rc::Rc;
+use unit_parser::error::LocatedError;
 use unit_parser::prelude::UnitConfig;
 
 pub(super) struct ServiceConfig {
@@ -34,6 +35,7 @@ pub(super) struct ServiceConfig {
 
     // resolved from ServiceConfigData
     kill_context: Rc<KillContext>,
+    cgroup_context: Rc<CgroupContext>,
 }
 
 impl ReStation for ServiceConfig {
@@ -62,6 +64,7 @@ impl ServiceConfig {
             comm: Rc::clone(commr),
             data: Rc::new(RefCell::new(ServiceConfigData::default())),
             kill_context: Rc::new(KillContext::default()),
+            cgroup_context: Rc::new(CgroupContext::default()),
         }
     }
 
@@ -95,6 +98,11 @@ impl ServiceConfig {
         self.data.clone()
     }
 
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        ServiceConfigData::verify_config_located(paths, name)
+    }
+
     pub(super) fn get_exec_cmds(&self, cmd_type: ServiceCommand) -> Option<VecDeque<ExecCommand>> {
         self.data.borrow().get_exec_cmds(cmd_type)
     }
@@ -124,6 +132,10 @@ impl ServiceConfig {
         self.kill_context.clone()
     }
 
+    pub(super) fn cgroup_context(&self) -> Rc<CgroupContext> {
+        self.cgroup_context.clone()
+    }
+
     pub(super) fn flush_timeout(&self) {
         let time_out = self.data.borrow().Service.TimeoutSec;
         if time_out == 0 {
@@ -203,6 +215,22 @@ impl ServiceConfigData {
             LONG_LINE_MAX,
             unit_specifier_data,
         );
+
+        /* i.e. User=%i of user@.service */
+        for s in [&mut self.Service.User, &mut self.Service.Group] {
+            if let Ok(ret) = unit_string_specifier_escape(s, LONG_LINE_MAX, unit_specifier_data) {
+                *s = ret;
+            }
+        }
+        if let Some(envs) = self.Service.Environment.as_mut() {
+            for value in envs.values_mut() {
+                if let Ok(ret) =
+                    unit_string_specifier_escape(value, LONG_LINE_MAX, unit_specifier_data)
+                {
+                    *value = ret;
+                }
+            }
+        }
     }
 
     pub(self) fn set_property(&mut self, key: &str, value: &str) -> Result<()> {
@@ -214,11 +242,13 @@ impl ServiceConfigData {
 mod tests {
     use crate::comm::ServiceUnitComm;
     use crate::config::ServiceConfig;
-    use crate::rentry::ServiceType;
+    use crate::rentry::{RestartMode, ServiceType};
+    use basic::time::{USEC_PER_MSEC, USEC_PER_SEC};
     use basic::unit_name::unit_name_to_instance;
     use core::exec::ExecCommand;
     use core::specifier::UnitSpecifierData;
     use libtests::get_project_root;
+    use std::collections::HashMap;
     use std::rc::Rc;
 
     #[test]
@@ -265,6 +295,11 @@ mod tests {
             src.push(cmd);
         }
         config.data.borrow_mut().Service.ExecStart = src;
+        config.data.borrow_mut().Service.User = "%i".to_string();
+        config.data.borrow_mut().Service.Environment = Some(HashMap::from([(
+            "XDG_RUNTIME_DIR".to_string(),
+            "/run/user/%i".to_string(),
+        )]));
 
         // Construct instance="Hal\\xc3\\xb6-chen"
         let mut unit_specifier_data = UnitSpecifierData::new();
@@ -284,5 +319,81 @@ mod tests {
         }
 
         assert_eq!(config.data.borrow().Service.ExecStart, dst);
+        assert_eq!(config.data.borrow().Service.User, "Hal\\xc3\\xb6-chen");
+        assert_eq!(
+            config.data.borrow().Service.Environment.as_ref().unwrap()["XDG_RUNTIME_DIR"],
+            "/run/user/Hal\\xc3\\xb6-chen"
+        );
+    }
+
+    #[test]
+    fn test_service_set_io_device_limit() {
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+
+        assert!(config
+            .set_property("IODeviceReadBandwidthMax", "/dev/sda 1M")
+            .is_ok());
+        assert!(config
+            .set_property("IODeviceReadBandwidthMax", "/dev/sdb 2M")
+            .is_ok());
+        assert!(config
+            .set_property("IODeviceReadBandwidthMax", "/dev/sdc")
+            .is_err());
+        let devices = config
+            .data
+            .borrow()
+            .Service
+            .IODeviceReadBandwidthMax
+            .clone();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].path.to_str(), Some("/dev/sda"));
+        assert_eq!(devices[1].limit, 2 * 1024 * 1024);
+
+        assert!(config.set_property("IODeviceReadBandwidthMax", "").is_ok());
+        assert!(config
+            .data
+            .borrow()
+            .Service
+            .IODeviceReadBandwidthMax
+            .is_empty());
+    }
+
+    #[test]
+    fn test_service_set_timeout() {
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+
+        /* the same time spans as the configuration file */
+        assert!(config.set_property("TimeoutStartSec", "1min").is_ok());
+        assert!(config.set_property("TimeoutStopSec", "0").is_ok());
+        assert!(config.set_property("RestartSec", "500ms").is_ok());
+        assert!(config.set_property("WatchdogSec", "2").is_ok());
+        assert!(config.set_property("TimeoutStartSec", "1foo").is_err());
+        assert!(config.set_property("RestartSec", "").is_err());
+
+        let data = config.data.borrow();
+        assert_eq!(data.Service.TimeoutStartSec, 60 * USEC_PER_SEC);
+        assert_eq!(data.Service.TimeoutStopSec, u64::MAX);
+        assert_eq!(data.Service.RestartSec, 500 * USEC_PER_MSEC);
+        assert_eq!(data.Service.WatchdogSec, 2 * USEC_PER_SEC);
+    }
+
+    #[test]
+    fn test_service_set_restart() {
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+
+        assert!(config.set_property("RestartMode", "direct").is_ok());
+        assert!(config.set_property("RestartMode", "foo").is_err());
+        assert!(config.set_property("RestartSteps", "5").is_ok());
+        assert!(config.set_property("RestartSteps", "-1").is_err());
+        assert!(config.set_property("RestartMaxDelaySec", "1min").is_ok());
+        assert!(config.set_property("RestartMaxDelaySec", "1foo").is_err());
+
+        let data = config.data.borrow();
+        assert_eq!(data.Service.RestartMode, RestartMode::Direct);
+        assert_eq!(data.Service.RestartSteps, 5);
+        assert_eq!(data.Service.RestartMaxDelaySec, 60 * USEC_PER_SEC);
     }
 }
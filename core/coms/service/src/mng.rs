@@ -14,13 +14,15 @@ use super::comm::ServiceUnitComm;
 use super::config::ServiceConfig;
 use super::pid::ServicePid;
 use super::rentry::{
-    NotifyState, ServiceCommand, ServiceRestart, ServiceResult, ServiceState, ServiceType,
+    NotifyState, RestartMode, ServiceCommand, ServiceRestart, ServiceResult, ServiceState,
+    ServiceType,
 };
 use super::spawn::ServiceSpawn;
 use crate::monitor::ServiceMonitor;
 use crate::rentry::{ExitStatus, NotifyAccess};
+use basic::time::{now_clockid, usec_add};
 use basic::{do_entry_log, IN_SET};
-use basic::{fs, process};
+use basic::{fd, fs, process};
 use core::error::*;
 use core::exec::{ExecCommand, ExecContext, ExecFlag, ExecFlags, PreserveMode};
 use core::rel::ReStation;
@@ -39,6 +41,7 @@ use std::fmt;
 use std::os::unix::prelude::RawFd;
 use std::path::PathBuf;
 use std::rc::{Rc, Weak};
+use std::str::FromStr;
 
 pub(super) struct ServiceMng {
     // associated objects
@@ -82,6 +85,8 @@ impl ReStation for ServiceMng {
             restarts,
             exit_status,
             monitor,
+            fdstore,
+            private_tmp,
         )) = self.comm.rentry_mng_get()
         {
             *self.state.borrow_mut() = state;
@@ -96,6 +101,8 @@ impl ReStation for ServiceMng {
             self.rd.set_restarts(restarts);
             self.rd.set_wait_status(WaitStatus::from(exit_status));
             *self.monitor.borrow_mut() = monitor;
+            self.rd.fdstore_map(fdstore);
+            self.rd.set_private_tmp(private_tmp);
         }
     }
 
@@ -115,6 +122,8 @@ impl ReStation for ServiceMng {
             self.rd.restarts(),
             exit_status,
             *self.monitor.borrow(),
+            self.rd.fdstore(),
+            self.rd.private_tmp(),
         );
     }
 
@@ -205,6 +214,8 @@ impl ServiceMng {
         }
         self.set_result(ServiceResult::Success);
         self.rd.set_forbid_restart(false);
+        self.rd.set_status_text("");
+        self.rd.set_notify_access_override(None);
         self.enter_contion();
         self.db_update();
     }
@@ -254,6 +265,8 @@ impl ServiceMng {
             ServiceState::Start,
             ServiceState::StartPost,
             ServiceState::Reload,
+            ServiceState::ReloadSignal,
+            ServiceState::ReloadNotify,
             ServiceState::StopWatchdog,
         ]
         .contains(&self.state())
@@ -400,7 +413,7 @@ impl ServiceMng {
                 self.pid.set_control(pid);
                 self.set_state(ServiceState::Start);
             }
-            ServiceType::Oneshot | ServiceType::Notify => {
+            ServiceType::Oneshot | ServiceType::Notify | ServiceType::NotifyReload => {
                 let _ = self.pid.set_main(pid);
                 self.set_state(ServiceState::Start);
             }
@@ -594,13 +607,15 @@ impl ServiceMng {
             }
         }
 
-        self.set_state(state);
+        /* RestartMode=direct goes to auto-restart without passing through dead or failed,
+         * so the units depending on it are not stopped and OnFailure= is not triggered */
+        if !restart || self.config.config_data().borrow().Service.RestartMode != RestartMode::Direct
+        {
+            self.set_state(state);
+        }
         if restart {
             self.rd.set_will_auto_restart(false);
-            if let Err(e) = self
-                .rd
-                .enable_timer(self.config.config_data().borrow().Service.RestartSec)
-            {
+            if let Err(e) = self.rd.enable_timer(self.restart_usec()) {
                 self.log(
                     Level::Warn,
                     &format!("auto restart start timer error: {}", e),
@@ -628,6 +643,12 @@ impl ServiceMng {
             let _ = self.comm.um().unit_destroy_runtime_data(runtime_directory);
         }
 
+        /* Keep the private /tmp and the stored fds across automatic restarts. */
+        if !self.rd.will_restart() {
+            self.spawn.destroy_private_tmp();
+            self.rd.fdstore_release();
+        }
+
         if let Some(p) = self.config.pid_file() {
             do_entry_log!(nix::unistd::unlink, p, "unlink");
         }
@@ -640,6 +661,24 @@ impl ServiceMng {
         self.control_command_fill(ServiceCommand::Reload);
         self.set_reload_result(ServiceResult::Success);
 
+        /* RELOADING=1 sent before this point belongs to an earlier reload cycle */
+        self.rd
+            .set_reload_begin_usec(now_clockid(libc::CLOCK_MONOTONIC));
+
+        let signaled = match self.kill_main_by_reload_signal() {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!(
+                    "Failed to send the reload signal to {}: {}",
+                    self.comm.get_owner_id(),
+                    e
+                );
+                self.set_reload_result(ServiceResult::FailureResources);
+                self.enter_running(ServiceResult::Success);
+                return;
+            }
+        };
+
         match self.control_command_pop() {
             Some(cmd) => {
                 *self.current_control_command.borrow_mut() = cmd.clone();
@@ -658,10 +697,52 @@ impl ServiceMng {
                 }
                 self.set_state(ServiceState::Reload);
             }
+            None if signaled => {
+                if let Err(e) = self
+                    .rd
+                    .enable_timer(self.config.config_data().borrow().Service.TimeoutStartSec)
+                {
+                    self.log(
+                        Level::Warn,
+                        &format!("reload by signal enable timer error: {}", e),
+                    );
+                }
+                self.set_state(ServiceState::ReloadSignal);
+            }
             None => self.enter_running(ServiceResult::Success),
         }
     }
 
+    /// send ReloadSignal= to the main process of Type=notify-reload service, return whether it is sent
+    fn kill_main_by_reload_signal(&self) -> Result<bool> {
+        if self.config.service_type() != ServiceType::NotifyReload {
+            return Ok(false);
+        }
+        let pid = match self.pid.main() {
+            None => return Ok(false),
+            Some(v) => v,
+        };
+
+        let signal = Signal::from_str(&self.config.config_data().borrow().Service.ReloadSignal)?;
+        process::kill_and_cont(pid, signal)?;
+        Ok(true)
+    }
+
+    fn enter_reload_by_notify(&self) {
+        self.set_reload_result(ServiceResult::Success);
+        if let Err(e) = self
+            .rd
+            .enable_timer(self.config.config_data().borrow().Service.TimeoutStartSec)
+        {
+            self.log(
+                Level::Warn,
+                &format!("reload by notify enable timer error: {}", e),
+            );
+        }
+
+        self.set_state(ServiceState::ReloadNotify);
+    }
+
     fn enter_restart(&self) {
         if self
             .comm
@@ -793,6 +874,8 @@ impl ServiceMng {
                 | ServiceState::StartPost
                 | ServiceState::Running
                 | ServiceState::Reload
+                | ServiceState::ReloadSignal
+                | ServiceState::ReloadNotify
                 | ServiceState::Stop
                 | ServiceState::StopWatchdog
                 | ServiceState::StopSigterm
@@ -814,6 +897,8 @@ impl ServiceMng {
             ServiceState::StartPost,
             ServiceState::Running,
             ServiceState::Reload,
+            ServiceState::ReloadSignal,
+            ServiceState::ReloadNotify,
             ServiceState::Stop,
             ServiceState::StopWatchdog,
             ServiceState::StopSigterm,
@@ -964,6 +1049,29 @@ impl ServiceMng {
         *self.state.borrow()
     }
 
+    pub(super) fn main_pid(&self) -> Option<Pid> {
+        self.pid.main()
+    }
+
+    pub(super) fn control_pid(&self) -> Option<Pid> {
+        self.pid.control()
+    }
+
+    pub(super) fn status_text(&self) -> Option<String> {
+        let text = self.rd.status_text();
+        if text.is_empty() {
+            return None;
+        }
+        Some(text)
+    }
+
+    pub(super) fn exit_status(&self) -> Option<WaitStatus> {
+        match self.rd.wait_status() {
+            WaitStatus::StillAlive => None,
+            v => Some(v),
+        }
+    }
+
     fn set_result(&self, result: ServiceResult) {
         *self.result.borrow_mut() = result;
     }
@@ -1219,6 +1327,17 @@ impl ServiceMng {
             return false;
         }
 
+        if self
+            .config
+            .config_data()
+            .borrow()
+            .Service
+            .RestartForceExitStatus
+            .exit_status_enabled(self.rd.wait_status())
+        {
+            return true;
+        }
+
         match self.config.config_data().borrow().Service.Restart {
             ServiceRestart::No => false,
             ServiceRestart::OnSuccess => self.result() == ServiceResult::Success,
@@ -1243,6 +1362,18 @@ impl ServiceMng {
         }
     }
 
+    /// the delay before the next automatic restart
+    fn restart_usec(&self) -> u64 {
+        let cfg_data = self.config.config_data();
+        let service = &cfg_data.borrow().Service;
+        restart_delay(
+            service.RestartSec,
+            service.RestartMaxDelaySec,
+            service.RestartSteps,
+            self.rd.restarts().saturating_add(1),
+        )
+    }
+
     fn restart_watchdog(&self) {
         self.monitor
             .borrow_mut()
@@ -1312,7 +1443,11 @@ impl ServiceMng {
             | ServiceState::StartPre
             | ServiceState::Start
             | ServiceState::StartPost
-            | ServiceState::Reload => self.config.config_data().borrow().Service.TimeoutStartSec,
+            | ServiceState::Reload
+            | ServiceState::ReloadSignal
+            | ServiceState::ReloadNotify => {
+                self.config.config_data().borrow().Service.TimeoutStartSec
+            }
 
             ServiceState::Running => 0, // todo => TimeoutMaxSec,
 
@@ -1330,7 +1465,7 @@ impl ServiceMng {
                 self.config.config_data().borrow().Service.TimeoutStopSec
             }
 
-            ServiceState::AutoRestart => self.config.config_data().borrow().Service.RestartSec,
+            ServiceState::AutoRestart => self.restart_usec(),
 
             ServiceState::Cleaning => todo!(), // TimeoutCleanSec,
 
@@ -1354,6 +1489,23 @@ impl ServiceMng {
         unit.reset_start_limit();
     }
 
+    /// send the signal to the main and control process, and the cgroup depending on KillMode=
+    pub(super) fn kill(&self, signal: Signal) -> Result<()> {
+        let unit = match self.comm.owner() {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+
+        unit.kill_context(
+            self.config.kill_context(),
+            self.pid.main(),
+            self.pid.control(),
+            KillOperation::KillSignal(signal),
+            self.pid.main_pid_alien(),
+        )?;
+        Ok(())
+    }
+
     pub(super) fn set_socket_fd(&self, fd: i32) {
         self.spawn.set_socket_fd(fd)
     }
@@ -1370,6 +1522,17 @@ impl ServiceMng {
     }
 
     fn sigchld_result(&self, wait_status: WaitStatus) -> ServiceResult {
+        if self
+            .config
+            .config_data()
+            .borrow()
+            .Service
+            .SuccessExitStatus
+            .exit_status_enabled(wait_status)
+        {
+            return ServiceResult::Success;
+        }
+
         match wait_status {
             WaitStatus::Exited(_, status) => {
                 if status == 0 {
@@ -1451,7 +1614,12 @@ impl ServiceMng {
                             self.enter_signal(ServiceState::StopSigterm, res);
                         }
                     }
-                    ServiceState::Start if self.config.service_type() == ServiceType::Notify => {
+                    ServiceState::Start
+                        if matches!(
+                            self.config.service_type(),
+                            ServiceType::Notify | ServiceType::NotifyReload
+                        ) =>
+                    {
                         if res != ServiceResult::Success {
                             self.enter_signal(ServiceState::StopSigterm, res);
                         } else {
@@ -1467,7 +1635,11 @@ impl ServiceMng {
 
                     ServiceState::Running => self.enter_running(res),
 
-                    ServiceState::StartPost | ServiceState::Reload | ServiceState::Stop => {
+                    ServiceState::StartPost
+                    | ServiceState::Reload
+                    | ServiceState::ReloadSignal
+                    | ServiceState::ReloadNotify
+                    | ServiceState::Stop => {
                         if !self.pid.control_pid_avail() {
                             self.enter_stop(res);
                         }
@@ -1663,11 +1835,21 @@ impl ServiceMng {
 }
 
 impl ServiceMng {
+    /// NOTIFYACCESS= sent by the service takes precedence over the configuration
+    fn notify_access(&self) -> NotifyAccess {
+        if let Some(v) = self.rd.notify_access_override() {
+            return v;
+        }
+        self.config
+            .config_data()
+            .borrow()
+            .Service
+            .NotifyAccess
+            .unwrap_or(NotifyAccess::None)
+    }
+
     fn notify_message_authorized(&self, pid: Pid) -> bool {
-        let notify_access = match self.config.config_data().borrow().Service.NotifyAccess {
-            None => NotifyAccess::None,
-            Some(v) => v,
-        };
+        let notify_access = self.notify_access();
 
         if notify_access == NotifyAccess::None {
             log::warn!(
@@ -1677,13 +1859,12 @@ impl ServiceMng {
             return false;
         }
 
-        let main_pid = match self.pid.main() {
-            None => {
-                log::warn!("Couldn't determine main pid.");
-                Pid::from_raw(0)
-            }
-            Some(v) => v,
-        };
+        /* the message is dispatched by the pid, so it comes from the cgroup of the unit */
+        if notify_access == NotifyAccess::All {
+            return true;
+        }
+
+        let main_pid = self.pid.main().unwrap_or(Pid::from_raw(0));
 
         if notify_access == NotifyAccess::Main && pid != main_pid {
             if main_pid.as_raw() == 0 {
@@ -1697,13 +1878,7 @@ impl ServiceMng {
             return false;
         }
 
-        let control_pid = match self.pid.control() {
-            None => {
-                log::warn!("Couldn't determine control pid.");
-                Pid::from_raw(0)
-            }
-            Some(v) => v,
-        };
+        let control_pid = self.pid.control().unwrap_or(Pid::from_raw(0));
 
         if notify_access == NotifyAccess::Exec && pid != main_pid && pid != control_pid {
             if main_pid.as_raw() != 0 && control_pid.as_raw() != 0 {
@@ -1733,9 +1908,9 @@ impl ServiceMng {
         &self,
         ucred: &UnixCredentials,
         messages: &HashMap<&str, &str>,
-        _fds: Vec<i32>,
+        fds: Vec<i32>,
     ) -> Result<()> {
-        let ret = self.do_notify_message(ucred, messages, _fds);
+        let ret = self.do_notify_message(ucred, messages, fds);
         self.db_update();
         ret
     }
@@ -1744,12 +1919,38 @@ impl ServiceMng {
         &self,
         ucred: &UnixCredentials,
         messages: &HashMap<&str, &str>,
-        _fds: Vec<i32>,
+        fds: Vec<i32>,
     ) -> Result<()> {
         if !self.notify_message_authorized(Pid::from_raw(ucred.pid())) {
+            fds.into_iter().for_each(fd::close);
             return Ok(());
         }
 
+        /* take over the fds first, they must not leak whatever happens to the rest */
+        if messages.get("FDSTORE") == Some(&"1") {
+            let name = messages
+                .get("FDNAME")
+                .copied()
+                .unwrap_or(FDSTORE_DEFAULT_NAME);
+            self.fdstore_add(fds, name);
+        } else if !fds.is_empty() {
+            log::warn!(
+                "{} sent file descriptors without FDSTORE=1, closing them.",
+                self.comm.get_owner_id()
+            );
+            fds.into_iter().for_each(fd::close);
+        }
+
+        if messages.get("FDSTOREREMOVE") == Some(&"1") {
+            match messages.get("FDNAME") {
+                Some(name) => self.rd.fdstore_remove(name),
+                None => log::warn!(
+                    "{} sent FDSTOREREMOVE=1 without FDNAME=, ignoring.",
+                    self.comm.get_owner_id()
+                ),
+            }
+        }
+
         if let Some(&pidr) = messages.get("MAINPID") {
             if IN_SET!(
                 self.state(),
@@ -1772,22 +1973,44 @@ impl ServiceMng {
             }
         };
 
-        for (&key, &value) in messages {
-            if key == "READY" && value == "1" {
-                log::debug!("service plugin get READY=1");
-                self.rd.set_notify_state(NotifyState::Ready);
-                if self.config.service_type() == ServiceType::Notify
-                    && self.state() == ServiceState::Start
-                {
-                    self.enter_start_post();
-                }
+        /* the state changes are handled in a fixed order, RELOADING=1 and READY=1 may come together */
+        if messages.get("STOPPING") == Some(&"1") {
+            self.rd.set_notify_state(NotifyState::Stopping);
+            if self.state() == ServiceState::Running {
+                self.enter_stop_by_notify();
             }
+        } else if messages.get("RELOADING") == Some(&"1") {
+            self.rd.set_notify_state(NotifyState::Reloading);
+            if self.state() == ServiceState::Running {
+                self.enter_reload_by_notify();
+            } else if self.state() == ServiceState::ReloadSignal
+                && self.reloading_is_current(messages.get("MONOTONIC_USEC").copied())
+            {
+                /* the service has received ReloadSignal= and begins to reload */
+                self.set_state(ServiceState::ReloadNotify);
+            }
+        }
 
-            if key == "STOPPING" && value == "1" {
-                self.rd.set_notify_state(NotifyState::Stopping);
-                if self.state() == ServiceState::Running {
-                    self.enter_stop_by_notify();
-                }
+        if messages.get("READY") == Some(&"1") {
+            log::debug!("service plugin get READY=1");
+            self.rd.set_notify_state(NotifyState::Ready);
+            if matches!(
+                self.config.service_type(),
+                ServiceType::Notify | ServiceType::NotifyReload
+            ) && self.state() == ServiceState::Start
+            {
+                self.enter_start_post();
+            }
+
+            /* the reloading announced by RELOADING=1 is finished */
+            if self.state() == ServiceState::ReloadNotify {
+                self.enter_running(ServiceResult::Success);
+            }
+        }
+
+        for (&key, &value) in messages {
+            if key == "STATUS" {
+                self.rd.set_status_text(value);
             }
 
             if key == "ERRNO" {
@@ -1800,6 +2023,33 @@ impl ServiceMng {
                 self.rd.set_errno(err.unwrap());
             }
 
+            if key == "EXTEND_TIMEOUT_USEC" {
+                match value.parse::<u64>() {
+                    Ok(v) => self.extend_timeout(v),
+                    Err(_) => {
+                        log::warn!("failed to parse notify message of EXTEND_TIMEOUT_USEC item")
+                    }
+                }
+            }
+
+            if key == "NOTIFYACCESS" {
+                let notify_access = match value {
+                    "none" => NotifyAccess::None,
+                    "all" => NotifyAccess::All,
+                    "main" => NotifyAccess::Main,
+                    "exec" => NotifyAccess::Exec,
+                    _ => {
+                        log::warn!(
+                            "{} sent invalid NOTIFYACCESS={}, ignoring.",
+                            self.comm.get_owner_id(),
+                            value
+                        );
+                        continue;
+                    }
+                };
+                self.rd.set_notify_access_override(Some(notify_access));
+            }
+
             if key == "WATCHDOG" {
                 if value == "1" {
                     self.restart_watchdog();
@@ -1824,6 +2074,121 @@ impl ServiceMng {
 
         Ok(())
     }
+
+    /// RELOADING=1 with MONOTONIC_USEC= earlier than the reload signal is sent is a stale one
+    fn reloading_is_current(&self, monotonic_usec: Option<&str>) -> bool {
+        let usec = match monotonic_usec {
+            None => return true,
+            Some(v) => v,
+        };
+        match usec.parse::<u64>() {
+            Ok(v) => v >= self.rd.reload_begin_usec(),
+            Err(_) => {
+                log::warn!(
+                    "{} sent invalid MONOTONIC_USEC={}, ignoring.",
+                    self.comm.get_owner_id(),
+                    usec
+                );
+                true
+            }
+        }
+    }
+
+    /// push the deadline of the running start or stop operation to at least usec from now
+    fn extend_timeout(&self, usec: u64) {
+        if IN_SET!(
+            self.state(),
+            ServiceState::Dead,
+            ServiceState::Failed,
+            ServiceState::Running,
+            ServiceState::Exited,
+            ServiceState::AutoRestart
+        ) {
+            log::debug!(
+                "{} is not starting or stopping, ignoring EXTEND_TIMEOUT_USEC.",
+                self.comm.get_owner_id()
+            );
+            return;
+        }
+
+        if let Err(e) = self.rd.extend_timer(usec) {
+            self.log(Level::Warn, &format!("extend timer error: {}", e));
+        }
+    }
+
+    /// store the fds passed by FDSTORE=1, the duplicated ones are ignored
+    fn fdstore_add(&self, fds: Vec<RawFd>, name: &str) {
+        let max = self
+            .config
+            .config_data()
+            .borrow()
+            .Service
+            .FileDescriptorStoreMax as usize;
+        let name = if fdname_is_valid(name) {
+            name
+        } else {
+            log::warn!(
+                "{} sent invalid FDNAME={}, using {}.",
+                self.comm.get_owner_id(),
+                name,
+                FDSTORE_DEFAULT_NAME
+            );
+            FDSTORE_DEFAULT_NAME
+        };
+
+        for fd in fds {
+            if self.rd.fdstore_contains(fd) {
+                fd::close(fd);
+                continue;
+            }
+
+            if self.rd.fdstore_len() >= max {
+                log::warn!(
+                    "{} tried to store more than FileDescriptorStoreMax={} fds, closing the rest.",
+                    self.comm.get_owner_id(),
+                    max
+                );
+                fd::close(fd);
+                continue;
+            }
+
+            log::debug!("{} stores fd {} as {}", self.comm.get_owner_id(), fd, name);
+            self.rd.fdstore_push(fd, name);
+        }
+    }
+}
+
+/// the delay before the n-th automatic restart, it grows exponentially from RestartSec to
+/// RestartMaxDelaySec in RestartSteps steps
+fn restart_delay(restart_usec: u64, max_delay_usec: u64, steps: u32, n: u32) -> u64 {
+    if n <= 1
+        || steps == 0
+        || restart_usec == 0
+        || max_delay_usec == u64::MAX
+        || restart_usec >= max_delay_usec
+    {
+        return restart_usec;
+    }
+
+    if n > steps {
+        return max_delay_usec;
+    }
+
+    let ratio = max_delay_usec as f64 / restart_usec as f64;
+    let delay = restart_usec as f64 * ratio.powf((n - 1) as f64 / steps as f64);
+    (delay as u64).clamp(restart_usec, max_delay_usec)
+}
+
+/// the name of the stored fds if FDNAME= is not sent
+const FDSTORE_DEFAULT_NAME: &str = "stored";
+
+/// the fd names are passed in LISTEN_FDNAMES, separated by ':'
+fn fdname_is_valid(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 255
+        && name
+            .chars()
+            .all(|c| (c == ' ' || c.is_ascii_graphic()) && c != ':')
 }
 
 impl ServiceState {
@@ -1836,7 +2201,9 @@ impl ServiceState {
             | ServiceState::StartPost
             | ServiceState::AutoRestart => UnitActiveState::Activating,
             ServiceState::Running | ServiceState::Exited => UnitActiveState::Active,
-            ServiceState::Reload => UnitActiveState::Reloading,
+            ServiceState::Reload | ServiceState::ReloadSignal | ServiceState::ReloadNotify => {
+                UnitActiveState::Reloading
+            }
             ServiceState::Stop
             | ServiceState::StopWatchdog
             | ServiceState::StopPost
@@ -1859,7 +2226,9 @@ impl ServiceState {
             | ServiceState::StartPost
             | ServiceState::Running
             | ServiceState::Exited => UnitActiveState::Active,
-            ServiceState::Reload => UnitActiveState::Reloading,
+            ServiceState::Reload | ServiceState::ReloadSignal | ServiceState::ReloadNotify => {
+                UnitActiveState::Reloading
+            }
             ServiceState::Stop
             | ServiceState::StopWatchdog
             | ServiceState::StopPost
@@ -1932,6 +2301,97 @@ impl RunningData {
         self.data.borrow().notify_state()
     }
 
+    pub(self) fn set_status_text(&self, status_text: &str) {
+        self.data.borrow_mut().set_status_text(status_text);
+    }
+
+    pub(self) fn status_text(&self) -> String {
+        self.data.borrow().status_text()
+    }
+
+    pub(self) fn set_notify_access_override(&self, notify_access: Option<NotifyAccess>) {
+        self.data
+            .borrow_mut()
+            .set_notify_access_override(notify_access);
+    }
+
+    pub(self) fn notify_access_override(&self) -> Option<NotifyAccess> {
+        self.data.borrow().notify_access_override()
+    }
+
+    /// the stored fds must survive the re-exec of sysmaster, so they are retained in the reliability
+    pub(self) fn fdstore_push(&self, fd: RawFd, name: &str) {
+        if let Some(reli) = self.comm.reli() {
+            if let Err(e) = reli.fd_cloexec(fd, false) {
+                log::error!("Failed to retain the stored fd {}: {:?}", fd, e);
+                fd::close(fd);
+                return;
+            }
+        }
+        self.data.borrow_mut().fdstore_push(fd, name);
+    }
+
+    pub(self) fn fdstore_contains(&self, fd: RawFd) -> bool {
+        self.data.borrow().fdstore_contains(fd)
+    }
+
+    pub(self) fn fdstore_len(&self) -> usize {
+        self.data.borrow().fdstore_len()
+    }
+
+    pub(self) fn fdstore_remove(&self, name: &str) {
+        let fds = self.data.borrow_mut().fdstore_remove(name);
+        fds.into_iter().for_each(|fd| self.fdstore_close(fd));
+    }
+
+    pub(self) fn fdstore_release(&self) {
+        let fdstore = self.data.borrow_mut().fdstore_take();
+        fdstore
+            .into_iter()
+            .for_each(|(fd, _)| self.fdstore_close(fd));
+    }
+
+    pub(super) fn fdstore(&self) -> Vec<(RawFd, String)> {
+        self.data.borrow().fdstore()
+    }
+
+    /// take the stored fds over from the reliability after re-exec
+    pub(self) fn fdstore_map(&self, fdstore: Vec<(RawFd, String)>) {
+        let fdstore = match self.comm.reli() {
+            None => fdstore,
+            Some(reli) => fdstore
+                .into_iter()
+                .map(|(fd, name)| (reli.fd_take(fd), name))
+                .collect(),
+        };
+        self.data.borrow_mut().set_fdstore(fdstore);
+    }
+
+    fn fdstore_close(&self, fd: RawFd) {
+        if let Some(reli) = self.comm.reli() {
+            if let Err(e) = reli.fd_cloexec(fd, true) {
+                log::error!("Failed to remark the stored fd {}: {:?}", fd, e);
+            }
+        }
+        fd::close(fd);
+    }
+
+    pub(super) fn set_private_tmp(&self, private_tmp: Option<PathBuf>) {
+        self.data.borrow_mut().set_private_tmp(private_tmp);
+    }
+
+    pub(super) fn private_tmp(&self) -> Option<PathBuf> {
+        self.data.borrow().private_tmp()
+    }
+
+    pub(self) fn set_reload_begin_usec(&self, usec: u64) {
+        self.data.borrow_mut().set_reload_begin_usec(usec);
+    }
+
+    pub(self) fn reload_begin_usec(&self) -> u64 {
+        self.data.borrow().reload_begin_usec()
+    }
+
     pub(self) fn set_forbid_restart(&self, forbid_restart: bool) {
         self.data.borrow_mut().set_forbid_restart(forbid_restart);
     }
@@ -1995,9 +2455,13 @@ impl RunningData {
                 let timer = self.timer();
                 events.del_source(timer)?;
             }
+            self.data.borrow_mut().set_timer_deadline(u64::MAX);
             return Ok(0);
         }
         log::debug!("Enable a timer: {}us", usec);
+        self.data
+            .borrow_mut()
+            .set_timer_deadline(usec_add(now_clockid(libc::CLOCK_MONOTONIC), usec));
         if self.armd_timer() {
             let timer = self.timer();
             events.del_source(timer.clone())?;
@@ -2017,7 +2481,24 @@ impl RunningData {
         Ok(0)
     }
 
+    /// re-arm the timer if it would fire within usec, the disabled timer is left alone
+    pub(self) fn extend_timer(&self, usec: u64) -> Result<i32> {
+        let deadline = self.data.borrow().timer_deadline();
+        if deadline == u64::MAX {
+            return Ok(0);
+        }
+
+        let extended = usec_add(now_clockid(libc::CLOCK_MONOTONIC), usec);
+        if extended <= deadline {
+            return Ok(0);
+        }
+
+        log::debug!("Extend the timer by {}us", usec);
+        self.enable_timer(usec)
+    }
+
     pub(self) fn delete_timer(&self) {
+        self.data.borrow_mut().set_timer_deadline(u64::MAX);
         if !self.armd_timer() {
             return;
         }
@@ -2069,6 +2550,10 @@ impl RunningData {
 struct Rtdata {
     errno: i32,
     notify_state: NotifyState,
+    status_text: String,
+    notify_access_override: Option<NotifyAccess>,
+    fdstore: Vec<(RawFd, String)>,
+    private_tmp: Option<PathBuf>,
     path_inotify: Option<Rc<PathInotify>>,
 
     forbid_restart: bool,
@@ -2076,6 +2561,8 @@ struct Rtdata {
     will_auto_restart: bool,
     restarts: u32,
     timer: Option<Rc<ServiceTimer>>,
+    timer_deadline: u64, /* CLOCK_MONOTONIC usec, u64::MAX if the timer is disabled */
+    reload_begin_usec: u64, /* CLOCK_MONOTONIC usec */
 
     exec_status: WaitStatus,
 
@@ -2087,6 +2574,10 @@ impl Rtdata {
         Rtdata {
             errno: 0,
             notify_state: NotifyState::Unknown,
+            status_text: String::new(),
+            notify_access_override: None,
+            fdstore: Vec::new(),
+            private_tmp: None,
             path_inotify: None,
 
             forbid_restart: false,
@@ -2094,6 +2585,8 @@ impl Rtdata {
             will_auto_restart: false,
             restarts: 0,
             timer: None,
+            timer_deadline: u64::MAX,
+            reload_begin_usec: 0,
             exec_status: WaitStatus::StillAlive,
             watchdog: None,
         }
@@ -2111,6 +2604,65 @@ impl Rtdata {
         self.errno = errno;
     }
 
+    pub(self) fn set_status_text(&mut self, status_text: &str) {
+        self.status_text = status_text.to_string();
+    }
+
+    pub(self) fn status_text(&self) -> String {
+        self.status_text.clone()
+    }
+
+    pub(self) fn set_notify_access_override(&mut self, notify_access: Option<NotifyAccess>) {
+        self.notify_access_override = notify_access;
+    }
+
+    pub(self) fn notify_access_override(&self) -> Option<NotifyAccess> {
+        self.notify_access_override
+    }
+
+    pub(self) fn fdstore_push(&mut self, fd: RawFd, name: &str) {
+        self.fdstore.push((fd, name.to_string()));
+    }
+
+    pub(self) fn fdstore_contains(&self, fd: RawFd) -> bool {
+        self.fdstore
+            .iter()
+            .any(|(stored, _)| fd::same_fd(*stored, fd).unwrap_or(false))
+    }
+
+    pub(self) fn fdstore_len(&self) -> usize {
+        self.fdstore.len()
+    }
+
+    /// return the removed fds, which are not closed yet
+    pub(self) fn fdstore_remove(&mut self, name: &str) -> Vec<RawFd> {
+        let (removed, kept) = std::mem::take(&mut self.fdstore)
+            .into_iter()
+            .partition(|(_, n)| n == name);
+        self.fdstore = kept;
+        removed.into_iter().map(|(fd, _)| fd).collect()
+    }
+
+    pub(self) fn fdstore_take(&mut self) -> Vec<(RawFd, String)> {
+        std::mem::take(&mut self.fdstore)
+    }
+
+    pub(self) fn fdstore(&self) -> Vec<(RawFd, String)> {
+        self.fdstore.clone()
+    }
+
+    pub(self) fn set_fdstore(&mut self, fdstore: Vec<(RawFd, String)>) {
+        self.fdstore = fdstore;
+    }
+
+    pub(self) fn set_private_tmp(&mut self, private_tmp: Option<PathBuf>) {
+        self.private_tmp = private_tmp;
+    }
+
+    pub(self) fn private_tmp(&self) -> Option<PathBuf> {
+        self.private_tmp.clone()
+    }
+
     #[allow(dead_code)]
     pub(self) fn errno(&mut self) -> i32 {
         self.errno
@@ -2160,6 +2712,22 @@ impl Rtdata {
         self.timer.is_some()
     }
 
+    pub(self) fn set_timer_deadline(&mut self, deadline: u64) {
+        self.timer_deadline = deadline;
+    }
+
+    pub(self) fn timer_deadline(&self) -> u64 {
+        self.timer_deadline
+    }
+
+    pub(self) fn set_reload_begin_usec(&mut self, usec: u64) {
+        self.reload_begin_usec = usec;
+    }
+
+    pub(self) fn reload_begin_usec(&self) -> u64 {
+        self.reload_begin_usec
+    }
+
     pub(self) fn add_restarts(&mut self) {
         self.restarts += 1;
     }
@@ -2337,7 +2905,7 @@ impl ServiceTimer {
                 );
                 self.mng().enter_stop(ServiceResult::FailureTimeout);
             }
-            ServiceState::Reload => {
+            ServiceState::Reload | ServiceState::ReloadSignal | ServiceState::ReloadNotify => {
                 self.mng().log(
                     Level::Warn,
                     "Reload operation time out, kill control process and enter running",
@@ -2594,4 +3162,71 @@ mod tests {
         assert!(rt.armd_watchdog());
         assert_eq!(rt.watchdog().time(), 15);
     }
+
+    #[test]
+    fn test_notify_status_and_fdstore() {
+        use crate::rentry::NotifyAccess;
+        use nix::sys::socket::UnixCredentials;
+        use std::os::unix::prelude::IntoRawFd;
+
+        let (mng, rt, config) = create_mng();
+        mng.config.set_notify_access(NotifyAccess::All);
+        config
+            .config_data()
+            .borrow_mut()
+            .Service
+            .FileDescriptorStoreMax = 1;
+
+        let ucred = UnixCredentials::new();
+        let mut messages = HashMap::new();
+        messages.insert("STATUS", "Serving a=b");
+        assert!(mng.notify_message(&ucred, &messages, vec![]).is_ok());
+        assert_eq!(mng.status_text(), Some("Serving a=b".to_string()));
+
+        let null = std::fs::File::open("/dev/null").unwrap().into_raw_fd();
+        let dup = nix::unistd::dup(null).unwrap();
+        let zero = std::fs::File::open("/dev/zero").unwrap().into_raw_fd();
+        messages.clear();
+        messages.insert("FDSTORE", "1");
+        messages.insert("FDNAME", "conn");
+        assert!(mng
+            .notify_message(&ucred, &messages, vec![null, dup, zero])
+            .is_ok());
+        /* the duplicated one and the one over FileDescriptorStoreMax are not stored */
+        assert_eq!(rt.fdstore_len(), 1);
+
+        messages.clear();
+        messages.insert("FDSTOREREMOVE", "1");
+        messages.insert("FDNAME", "conn");
+        assert!(mng.notify_message(&ucred, &messages, vec![]).is_ok());
+        assert_eq!(rt.fdstore_len(), 0);
+    }
+
+    #[test]
+    fn test_reloading_is_current() {
+        let (mng, rt, _config) = create_mng();
+        rt.set_reload_begin_usec(100);
+
+        assert!(mng.reloading_is_current(None));
+        assert!(mng.reloading_is_current(Some("100")));
+        assert!(mng.reloading_is_current(Some("150")));
+        /* sent before the reload signal */
+        assert!(!mng.reloading_is_current(Some("50")));
+    }
+
+    #[test]
+    fn test_restart_delay() {
+        use super::restart_delay;
+
+        /* no backoff without RestartSteps= or RestartMaxDelaySec= */
+        assert_eq!(restart_delay(100, u64::MAX, 3, 5), 100);
+        assert_eq!(restart_delay(100, 10000, 0, 5), 100);
+        assert_eq!(restart_delay(100, 50, 3, 5), 100);
+
+        /* 100 * (10000 / 100) ^ ((n - 1) / 2) */
+        assert_eq!(restart_delay(100, 10000, 2, 1), 100);
+        assert_eq!(restart_delay(100, 10000, 2, 2), 1000);
+        assert_eq!(restart_delay(100, 10000, 2, 3), 10000);
+        assert_eq!(restart_delay(100, 10000, 2, 10), 10000);
+    }
 }
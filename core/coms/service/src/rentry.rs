@@ -29,9 +29,17 @@ use std::str::FromStr;
 use unit_parser::internal::UnitEntry;
 
 use core::error::*;
-use core::exec::{ExecCommand, Rlimit, RuntimeDirectory, StateDirectory, WorkingDirectory};
+use core::exec::{
+    parse_bind_paths, parse_capabilities, parse_sandbox_paths, parse_syscall_architectures,
+    parse_syscall_error_number, parse_syscall_filter, BindPath, CapabilityList, ExecCommand,
+    ExecInput, ExecOutput, ProtectHome, ProtectSystem, Rlimit, RuntimeDirectory, SandboxPath,
+    SecureBits, StateDirectory, SyscallFilterList, WorkingDirectory,
+};
 use core::rel::{ReDb, ReDbRwTxn, ReDbTable, ReliSwitch, Reliability};
-use core::unit::KillMode;
+use core::unit::{
+    parse_cgroup_weight, parse_cpu_quota, parse_io_device_limit, parse_memory_limit,
+    parse_tasks_max, IODeviceLimit, KillMode,
+};
 
 use basic::time::USEC_PER_MSEC;
 use basic::time::USEC_PER_SEC;
@@ -52,6 +60,8 @@ pub enum ServiceType {
     Oneshot,
     #[serde(alias = "notify")]
     Notify,
+    #[serde(alias = "notify-reload")]
+    NotifyReload,
     Idle,
     Exec,
     TypeMax,
@@ -67,6 +77,7 @@ impl UnitEntry for ServiceType {
             "forking" => ServiceType::Forking,
             "oneshot" => ServiceType::Oneshot,
             "notify" => ServiceType::Notify,
+            "notify-reload" => ServiceType::NotifyReload,
             _ => ServiceType::Simple,
         })
     }
@@ -145,6 +156,34 @@ impl Default for ServiceRestart {
     }
 }
 
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum RestartMode {
+    #[serde(alias = "normal")]
+    Normal,
+    #[serde(alias = "direct")]
+    Direct,
+}
+
+impl Default for RestartMode {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl UnitEntry for RestartMode {
+    type Error = core::error::Error;
+
+    fn parse_from_str<S: AsRef<str>>(input: S) -> std::result::Result<Self, Self::Error> {
+        match input.as_ref() {
+            "normal" => Ok(RestartMode::Normal),
+            "direct" => Ok(RestartMode::Direct),
+            s => Err(Error::ConfigureError {
+                msg: format!("invalid RestartMode: {}", s),
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ExitStatusSet {
     status: Vec<u8>,
@@ -196,7 +235,7 @@ impl UnitEntry for ExitStatusSet {
                 status_set.add_signal(cmd.to_string());
                 continue;
             }
-            log::warn!("RestartPreventExitStatus: invalid config value {}", cmd);
+            log::warn!("Invalid exit status or signal {}, ignoring.", cmd);
         }
 
         Ok(status_set)
@@ -236,12 +275,12 @@ fn parse_sec(s: &str) -> Result<u64> {
     basic::time::parse_sec(s).context(NixSnafu)
 }
 
+/// the same as parse_sec, but 0 means no timeout
 fn parse_timeout(s: &str) -> Result<u64> {
-    let timeout = s.parse::<u64>().unwrap();
-    if timeout == 0 {
-        return Ok(u64::MAX);
+    match parse_sec(s)? {
+        0 => Ok(u64::MAX),
+        timeout => Ok(timeout),
     }
-    parse_sec(s)
 }
 
 #[derive(UnitSection, Serialize, Deserialize, Debug, Default, Clone)]
@@ -271,14 +310,26 @@ pub struct SectionService {
     #[entry(default = false)]
     pub RemainAfterExit: bool,
     pub NotifyAccess: Option<NotifyAccess>,
+    #[entry(default = 0)]
+    pub FileDescriptorStoreMax: u32,
     #[entry(default = false)]
     pub NonBlocking: bool,
     #[entry(default = ServiceRestart::No)]
     pub Restart: ServiceRestart,
     #[entry(default = ExitStatusSet::default())]
     pub RestartPreventExitStatus: ExitStatusSet,
+    #[entry(default = ExitStatusSet::default())]
+    pub RestartForceExitStatus: ExitStatusSet,
+    #[entry(default = ExitStatusSet::default())]
+    pub SuccessExitStatus: ExitStatusSet,
+    #[entry(default = RestartMode::Normal)]
+    pub RestartMode: RestartMode,
     #[entry(default = 100 * USEC_PER_MSEC, parser = parse_sec)]
     pub RestartSec: u64,
+    #[entry(default = 0)]
+    pub RestartSteps: u32,
+    #[entry(default = u64::MAX, parser = parse_sec)]
+    pub RestartMaxDelaySec: u64,
     #[entry(default = 90 * USEC_PER_SEC, parser = parse_timeout)]
     pub TimeoutSec: u64,
     #[entry(default = 90 * USEC_PER_SEC, parser = parse_timeout)]
@@ -286,6 +337,26 @@ pub struct SectionService {
     #[entry(default = 90 * USEC_PER_SEC, parser = parse_timeout)]
     pub TimeoutStopSec: u64,
 
+    // Cgroup
+    #[entry(default = String::new())]
+    pub Slice: String,
+    #[entry(parser = parse_memory_limit)]
+    pub MemoryMax: Option<u64>,
+    #[entry(parser = parse_memory_limit)]
+    pub MemoryHigh: Option<u64>,
+    #[entry(parser = parse_cgroup_weight)]
+    pub CPUWeight: Option<u64>,
+    #[entry(parser = parse_cpu_quota)]
+    pub CPUQuota: Option<u64>,
+    #[entry(parser = parse_tasks_max)]
+    pub TasksMax: Option<u64>,
+    #[entry(parser = parse_cgroup_weight)]
+    pub IOWeight: Option<u64>,
+    #[entry(append, parser = parse_io_device_limit)]
+    pub IODeviceReadBandwidthMax: Vec<IODeviceLimit>,
+    #[entry(default = false)]
+    pub Delegate: bool,
+
     // Exec
     #[entry(default = String::new())]
     pub User: String,
@@ -312,11 +383,57 @@ pub struct SectionService {
     pub EnvironmentFile: Vec<String>,
     pub SELinuxContext: Option<String>,
 
+    // Sandbox
+    #[entry(default = ProtectSystem::No)]
+    pub ProtectSystem: ProtectSystem,
+    #[entry(default = ProtectHome::No)]
+    pub ProtectHome: ProtectHome,
+    #[entry(default = false)]
+    pub PrivateTmp: bool,
+    #[entry(append, parser = parse_sandbox_paths)]
+    pub ReadOnlyPaths: Vec<SandboxPath>,
+    #[entry(append, parser = parse_sandbox_paths)]
+    pub InaccessiblePaths: Vec<SandboxPath>,
+    #[entry(append, parser = parse_bind_paths)]
+    pub BindPaths: Vec<BindPath>,
+    #[entry(append, parser = parse_bind_paths)]
+    pub BindReadOnlyPaths: Vec<BindPath>,
+
+    // Privileges
+    #[entry(append, parser = parse_capabilities)]
+    pub CapabilityBoundingSet: Vec<CapabilityList>,
+    #[entry(append, parser = parse_capabilities)]
+    pub AmbientCapabilities: Vec<CapabilityList>,
+    #[entry(default = false)]
+    pub NoNewPrivileges: bool,
+    #[entry(default = SecureBits::empty())]
+    pub SecureBits: SecureBits,
+
+    // Seccomp
+    #[entry(append, parser = parse_syscall_filter)]
+    pub SystemCallFilter: Vec<SyscallFilterList>,
+    #[entry(parser = parse_syscall_error_number)]
+    pub SystemCallErrorNumber: Option<i32>,
+    #[entry(append, parser = parse_syscall_architectures)]
+    pub SystemCallArchitectures: Vec<String>,
+
+    // Stdio
+    #[entry(default = ExecInput::Null)]
+    pub StandardInput: ExecInput,
+    #[entry(default = ExecOutput::Inherit)]
+    pub StandardOutput: ExecOutput,
+    #[entry(default = ExecOutput::Inherit)]
+    pub StandardError: ExecOutput,
+    #[entry(parser = basic::fs::parse_pathbuf)]
+    pub TTYPath: Option<PathBuf>,
+
     // Kill
     #[entry(default = KillMode::ControlGroup)]
     pub KillMode: KillMode,
     #[entry(default = String::from("SIGTERM"))]
     pub KillSignal: String,
+    #[entry(default = String::from("SIGHUP"))]
+    pub ReloadSignal: String,
 }
 
 impl SectionService {
@@ -347,20 +464,47 @@ impl SectionService {
             "ExecReload" => self.ExecReload = core::exec::parse_exec_command(value)?,
             "ExecCondition" => self.ExecCondition = core::exec::parse_exec_command(value)?,
             "Sockets" => self.Sockets = value.split_whitespace().map(|s| s.to_string()).collect(),
-            "WatchdogSec" => self.WatchdogSec = value.parse::<u64>()?,
+            "WatchdogSec" => self.WatchdogSec = parse_sec(value)?,
             "PIDFile" => self.PIDFile = Some(parse_pidfile(value)?),
             "RemainAfterExit" => self.RemainAfterExit = basic::config::parse_boolean(value)?,
             "NotifyAccess" => self.NotifyAccess = Some(NotifyAccess::parse_from_str(value)?),
+            "FileDescriptorStoreMax" => self.FileDescriptorStoreMax = value.parse::<u32>()?,
             "NonBlocking" => self.NonBlocking = basic::config::parse_boolean(value)?,
             "Restart" => self.Restart = ServiceRestart::parse_from_str(value)?,
             "RestartPreventExitStatus" => {
                 self.RestartPreventExitStatus = ExitStatusSet::parse_from_str(value)?
             }
-            "RestartSec" => self.RestartSec = value.parse::<u64>()?,
+            "RestartForceExitStatus" => {
+                self.RestartForceExitStatus = ExitStatusSet::parse_from_str(value)?
+            }
+            "SuccessExitStatus" => self.SuccessExitStatus = ExitStatusSet::parse_from_str(value)?,
+            "RestartMode" => self.RestartMode = RestartMode::parse_from_str(value)?,
+            "RestartSec" => self.RestartSec = parse_sec(value)?,
+            "RestartSteps" => self.RestartSteps = u32::parse_from_str(value)?,
+            "RestartMaxDelaySec" => self.RestartMaxDelaySec = parse_sec(value)?,
             "TimeoutSec" => self.TimeoutSec = parse_timeout(value)?,
             "TimeoutStartSec" => self.TimeoutStartSec = parse_timeout(value)?,
             "TimeoutStopSec" => self.TimeoutStopSec = parse_timeout(value)?,
 
+            //cgroup
+            "Slice" => self.Slice = value.to_string(),
+            "MemoryMax" => self.MemoryMax = Some(parse_memory_limit(value)?),
+            "MemoryHigh" => self.MemoryHigh = Some(parse_memory_limit(value)?),
+            "CPUWeight" => self.CPUWeight = Some(parse_cgroup_weight(value)?),
+            "CPUQuota" => self.CPUQuota = Some(parse_cpu_quota(value)?),
+            "TasksMax" => self.TasksMax = Some(parse_tasks_max(value)?),
+            "IOWeight" => self.IOWeight = Some(parse_cgroup_weight(value)?),
+            "IODeviceReadBandwidthMax" => {
+                /* the same as the configuration file, an empty value resets the list */
+                if value.is_empty() {
+                    self.IODeviceReadBandwidthMax.clear();
+                } else {
+                    self.IODeviceReadBandwidthMax
+                        .extend(parse_io_device_limit(value)?);
+                }
+            }
+            "Delegate" => self.Delegate = basic::config::parse_boolean(value)?,
+
             //exec context
             "User" => self.User = value.to_string(),
             "Group" => self.User = value.to_string(),
@@ -385,9 +529,40 @@ impl SectionService {
             }
             "SELinuxContext" => self.SELinuxContext = Some(value.to_string()),
 
+            //sandbox
+            "ProtectSystem" => self.ProtectSystem = ProtectSystem::parse_from_str(value)?,
+            "ProtectHome" => self.ProtectHome = ProtectHome::parse_from_str(value)?,
+            "PrivateTmp" => self.PrivateTmp = basic::config::parse_boolean(value)?,
+            "ReadOnlyPaths" => self.ReadOnlyPaths = parse_sandbox_paths(value)?,
+            "InaccessiblePaths" => self.InaccessiblePaths = parse_sandbox_paths(value)?,
+            "BindPaths" => self.BindPaths = parse_bind_paths(value)?,
+            "BindReadOnlyPaths" => self.BindReadOnlyPaths = parse_bind_paths(value)?,
+
+            //privileges
+            "CapabilityBoundingSet" => self.CapabilityBoundingSet = parse_capabilities(value)?,
+            "AmbientCapabilities" => self.AmbientCapabilities = parse_capabilities(value)?,
+            "NoNewPrivileges" => self.NoNewPrivileges = basic::config::parse_boolean(value)?,
+            "SecureBits" => self.SecureBits = SecureBits::parse_from_str(value)?,
+
+            //seccomp
+            "SystemCallFilter" => self.SystemCallFilter = parse_syscall_filter(value)?,
+            "SystemCallErrorNumber" => {
+                self.SystemCallErrorNumber = Some(parse_syscall_error_number(value)?)
+            }
+            "SystemCallArchitectures" => {
+                self.SystemCallArchitectures = parse_syscall_architectures(value)?
+            }
+
+            //stdio
+            "StandardInput" => self.StandardInput = ExecInput::parse_from_str(value)?,
+            "StandardOutput" => self.StandardOutput = ExecOutput::parse_from_str(value)?,
+            "StandardError" => self.StandardError = ExecOutput::parse_from_str(value)?,
+            "TTYPath" => self.TTYPath = Some(parse_pathbuf(value)?),
+
             //kill context
             "KillMode" => self.KillMode = KillMode::parse_from_str(value)?,
             "KillSignal" => self.KillSignal = value.to_string(),
+            "ReloadSignal" => self.ReloadSignal = value.to_string(),
             str_key => {
                 return Err(Error::NotFound {
                     what: format!("set property:{}", str_key),
@@ -421,6 +596,8 @@ pub(super) enum ServiceState {
     Running,
     Exited,
     Reload,
+    ReloadSignal,
+    ReloadNotify,
     Stop,
     StopWatchdog,
     StopPost,
@@ -476,6 +653,7 @@ pub(super) enum ServiceCommand {
 pub(super) enum NotifyState {
     Unknown,
     Ready,
+    Reloading,
     Stopping,
 }
 
@@ -530,6 +708,8 @@ struct ServiceReMng {
     restarts: u32,
     exit_status: ExitStatus,
     monitor: ServiceMonitor,
+    fdstore: Vec<(i32, String)>, // the fds are kept open across re-exec
+    private_tmp: Option<PathBuf>,
 }
 
 impl ServiceReMng {
@@ -548,6 +728,8 @@ impl ServiceReMng {
         restarts: u32,
         exit_status: ExitStatus,
         monitor: ServiceMonitor,
+        fdstore: Vec<(i32, String)>,
+        private_tmp: Option<PathBuf>,
     ) -> ServiceReMng {
         ServiceReMng {
             state,
@@ -563,6 +745,8 @@ impl ServiceReMng {
             restarts,
             exit_status,
             monitor,
+            fdstore,
+            private_tmp,
         }
     }
 }
@@ -570,7 +754,7 @@ impl ServiceReMng {
 pub(super) struct ServiceRe {
     // database: multi-instance(N)
     conf: Rc<ServiceReDb<String, ServiceReConf>>, // RELI_DB_ESERVICE_CONF; key: unit_id, data: config;
-    mng: Rc<ServiceReDb<String, ServiceReMng>>, // RELI_DB_HSERVICE_MNG; key: unit_id, data: state+result+main(pid+cmd)+control(pid+cmd)+notify_state;
+    mng: Rc<ServiceReDb<String, ServiceReMng>>, // RELI_DB_HSERVICE_MNG; key: unit_id, data: state+result+main(pid+cmd)+control(pid+cmd)+notify_state+fdstore+private_tmp;
 }
 
 impl ServiceRe {
@@ -613,6 +797,8 @@ impl ServiceRe {
         restarts: u32,
         exit_status: ExitStatus,
         monitor: ServiceMonitor,
+        fdstore: Vec<(i32, String)>,
+        private_tmp: Option<PathBuf>,
     ) {
         let m_pid = main_pid.map(|x| x.as_raw());
         let c_pid = control_pid.map(|x| x.as_raw());
@@ -630,6 +816,8 @@ impl ServiceRe {
             restarts,
             exit_status,
             monitor,
+            fdstore,
+            private_tmp,
         );
         self.mng.0.insert(unit_id.to_string(), mng);
     }
@@ -656,6 +844,8 @@ impl ServiceRe {
         u32,
         ExitStatus,
         ServiceMonitor,
+        Vec<(i32, String)>,
+        Option<PathBuf>,
     )> {
         let mng = self.mng.0.get(&unit_id.to_string());
         mng.map(|m| {
@@ -673,6 +863,8 @@ impl ServiceRe {
                 m.restarts,
                 m.exit_status,
                 m.monitor,
+                m.fdstore,
+                m.private_tmp,
             )
         })
     }
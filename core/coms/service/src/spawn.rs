@@ -85,14 +85,52 @@ impl ServiceSpawn {
         };
         let um = self.comm.um();
         unit.prepare_exec()?;
+        if let Err(e) = self.config.cgroup_context().apply(&unit.cg_path()) {
+            log::warn!(
+                "Failed to apply cgroup resource limits of {}: {}",
+                unit.id(),
+                e
+            );
+        }
+        if let Some(user) = self.exec_ctx.user() {
+            let gid = self.exec_ctx.group().map_or(user.gid, |g| g.gid);
+            if let Err(e) = self
+                .config
+                .cgroup_context()
+                .delegate_to(&unit.cg_path(), user.uid, gid)
+            {
+                log::error!("Failed to delegate the cgroup of {}: {}", unit.id(), e);
+                return Err(e);
+            }
+        }
 
         self.rd.enable_timer(time_out)?;
 
-        if ec_flags.contains(ExecFlags::PASS_FDS) {
-            params.insert_fds(self.collect_socket_fds());
+        if self.exec_ctx.sandbox().private_tmp {
+            params.set_private_tmp(self.setup_private_tmp(&unit.id())?);
+        }
+
+        /* The connection socket is passed as the stdio instead of LISTEN_FDS. */
+        if self.exec_ctx.stdio().uses_socket() {
+            if self.get_socket_fd() < 0 {
+                log::error!(
+                    "{} uses the socket as stdio, but it is not activated by a socket with Accept=yes",
+                    unit.id()
+                );
+                return Err("spawn exec return error".to_string().into());
+            }
+            params.set_stdio_fd(self.get_socket_fd());
+        } else if ec_flags.contains(ExecFlags::PASS_FDS) {
+            let (fds, fd_names) = self.collect_fds().into_iter().unzip();
+            params.insert_fds(fds);
+            params.insert_fd_names(fd_names);
         }
 
-        if self.config.service_type() == ServiceType::Notify || service_config.WatchdogSec > 0 {
+        if matches!(
+            self.config.service_type(),
+            ServiceType::Notify | ServiceType::NotifyReload
+        ) || service_config.WatchdogSec > 0
+        {
             let notify_sock = um.notify_socket().unwrap();
             log::debug!("add NOTIFY_SOCKET env: {}", notify_sock.to_str().unwrap());
             params.add_env("NOTIFY_SOCKET", notify_sock.to_str().unwrap().to_string());
@@ -120,12 +158,44 @@ impl ServiceSpawn {
         self.exec_ctx.runtime_directory().directory()
     }
 
-    fn collect_socket_fds(&self) -> Vec<i32> {
-        if self.get_socket_fd() >= 0 {
-            vec![self.get_socket_fd()]
+    /// the private tmp directory is created once and kept across automatic restarts
+    fn setup_private_tmp(&self, unit_id: &str) -> Result<PathBuf> {
+        if let Some(dir) = self.rd.private_tmp() {
+            if dir.is_dir() && core::exec::private_var_tmp(&dir).is_dir() {
+                return Ok(dir);
+            }
+            self.destroy_private_tmp();
+        }
+
+        let dir = core::exec::setup_private_tmp(unit_id)?;
+        self.rd.set_private_tmp(Some(dir.clone()));
+        Ok(dir)
+    }
+
+    /// only remove the directories recorded by setup_private_tmp
+    pub(super) fn destroy_private_tmp(&self) {
+        let dir = match self.rd.private_tmp() {
+            None => return,
+            Some(v) => v,
+        };
+        self.rd.set_private_tmp(None);
+        if !core::exec::is_private_tmp(&dir) {
+            log::warn!("Refusing to remove the unexpected private tmp {:?}", dir);
+            return;
+        }
+        let var_dir = core::exec::private_var_tmp(&dir);
+        let _ = self.comm.um().unit_destroy_runtime_data(vec![dir, var_dir]);
+    }
+
+    /// the socket fds come first, followed by the fds in the fd store
+    fn collect_fds(&self) -> Vec<(i32, String)> {
+        let mut fds = if self.get_socket_fd() >= 0 {
+            vec![(self.get_socket_fd(), "connection".to_string())]
         } else {
             self.comm.um().collect_socket_fds(&self.comm.get_owner_id())
-        }
+        };
+        fds.extend(self.rd.fdstore());
+        fds
     }
 
     fn watchdog_timer(&self) -> u64 {
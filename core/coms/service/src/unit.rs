@@ -12,7 +12,7 @@
 
 use crate::rentry::ServiceRestart;
 
-use super::bus::ServiceBus;
+use super::bus::{ServiceBus, CGROUP_PROPERTIES};
 use super::comm::ServiceUnitComm;
 use super::config::ServiceConfig;
 use super::mng::RunningData;
@@ -22,18 +22,23 @@ use basic::{BASIC_TARGET, SHUTDOWN_TARGET, SYSINIT_TARGET};
 use core::error::*;
 use core::rel::{ReStation, Reliability};
 use core::unit::{
-    SubUnit, UmIf, UnitActiveState, UnitBase, UnitDependencyMask, UnitMngUtil, UnitRelations,
-    UnitWriteFlags,
+    section_properties, SubUnit, UmIf, UnitActiveState, UnitBase, UnitDependencyMask, UnitMngUtil,
+    UnitRelations, UnitWriteFlags,
 };
 use nix::sys::signal::Signal;
 use nix::sys::socket::UnixCredentials;
 use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::str::FromStr;
 
-use core::exec::ExecContext;
+use core::exec::{
+    capabilities_mask, syscall_filter_merge, ExecContext, ExecStdio, Privileges, Sandbox, Seccomp,
+    DEFAULT_TTY_PATH,
+};
+use unit_parser::error::LocatedError;
 
 struct ServiceUnit {
     comm: Rc<ServiceUnitComm>,
@@ -129,18 +134,22 @@ impl SubUnit for ServiceUnit {
     }
 
     fn reload(&self) -> Result<()> {
+        self.apply_cgroup_context();
         self.mng.reload_action();
         Ok(())
     }
 
     fn can_reload(&self) -> bool {
-        self.config
-            .get_exec_cmds(ServiceCommand::Reload)
-            .map_or(false, |cmds| !cmds.is_empty())
+        /* Type=notify-reload services are reloaded by the signal, ExecReload= is optional */
+        self.config.service_type() == ServiceType::NotifyReload
+            || self
+                .config
+                .get_exec_cmds(ServiceCommand::Reload)
+                .map_or(false, |cmds| !cmds.is_empty())
     }
 
-    fn kill(&self) {
-        todo!()
+    fn kill(&self, signal: Signal) -> Result<()> {
+        self.mng.kill(signal)
     }
 
     fn release_resources(&self) {
@@ -163,6 +172,14 @@ impl SubUnit for ServiceUnit {
         self.mng.get_state()
     }
 
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Service)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        ServiceConfig::verify(paths, name)
+    }
+
     fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
         self.comm.attach_unit(unit);
         self.db_insert();
@@ -191,8 +208,32 @@ impl SubUnit for ServiceUnit {
         self.mng.release_socket_fd(fd);
     }
 
+    fn main_pid(&self) -> Option<Pid> {
+        self.mng.main_pid()
+    }
+
+    fn control_pid(&self) -> Option<Pid> {
+        self.mng.control_pid()
+    }
+
+    fn exit_status(&self) -> Option<WaitStatus> {
+        self.mng.exit_status()
+    }
+
+    fn status_text(&self) -> Option<String> {
+        self.mng.status_text()
+    }
+
     fn unit_set_property(&self, key: &str, value: &str, flags: UnitWriteFlags) -> Result<()> {
-        self.bus.unit_set_property(key, value, flags)
+        self.bus.unit_set_property(key, value, flags)?;
+
+        if CGROUP_PROPERTIES.contains(&key) {
+            self.parse_cgroup_context();
+            if self.current_active_state() == UnitActiveState::Active {
+                self.apply_cgroup_context();
+            }
+        }
+        Ok(())
     }
 }
 
@@ -235,6 +276,91 @@ impl ServiceUnit {
         Ok(())
     }
 
+    fn parse_sandbox(&self) {
+        let cfg_data = self.config.config_data();
+        let service = &cfg_data.borrow().Service;
+        self.exec_ctx.set_sandbox(Sandbox {
+            protect_system: service.ProtectSystem,
+            protect_home: service.ProtectHome,
+            private_tmp: service.PrivateTmp,
+            read_only_paths: service.ReadOnlyPaths.clone(),
+            inaccessible_paths: service.InaccessiblePaths.clone(),
+            bind_paths: service.BindPaths.clone(),
+            bind_read_only_paths: service.BindReadOnlyPaths.clone(),
+        });
+    }
+
+    fn parse_privileges(&self) {
+        let cfg_data = self.config.config_data();
+        let service = &cfg_data.borrow().Service;
+        self.exec_ctx.set_privileges(Privileges {
+            capability_bounding_set: capabilities_mask(&service.CapabilityBoundingSet),
+            ambient_capabilities: capabilities_mask(&service.AmbientCapabilities).unwrap_or(0),
+            no_new_privileges: service.NoNewPrivileges,
+            secure_bits: service.SecureBits,
+        });
+    }
+
+    fn parse_seccomp(&self) {
+        let cfg_data = self.config.config_data();
+        let service = &cfg_data.borrow().Service;
+        self.exec_ctx.set_seccomp(Seccomp {
+            filter: syscall_filter_merge(&service.SystemCallFilter),
+            error_number: service.SystemCallErrorNumber,
+            native_arch_only: !service.SystemCallArchitectures.is_empty(),
+        });
+    }
+
+    fn parse_stdio(&self) {
+        let cfg_data = self.config.config_data();
+        let service = &cfg_data.borrow().Service;
+        self.exec_ctx.set_stdio(ExecStdio {
+            input: service.StandardInput.clone(),
+            output: service.StandardOutput.clone(),
+            error: service.StandardError.clone(),
+            tty_path: service
+                .TTYPath
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_TTY_PATH)),
+        });
+    }
+
+    fn parse_cgroup_context(&self) {
+        let cfg_data = self.config.config_data();
+        let service = &cfg_data.borrow().Service;
+        let cgroup_context = self.config.cgroup_context();
+        cgroup_context.set_memory_max(service.MemoryMax);
+        cgroup_context.set_memory_high(service.MemoryHigh);
+        cgroup_context.set_cpu_weight(service.CPUWeight);
+        cgroup_context.set_cpu_quota(service.CPUQuota);
+        cgroup_context.set_tasks_max(service.TasksMax);
+        cgroup_context.set_io_weight(service.IOWeight);
+        cgroup_context.set_io_device_read_bandwidth(service.IODeviceReadBandwidthMax.clone());
+        cgroup_context.set_delegate(service.Delegate);
+    }
+
+    /* The limits are written when the processes are spawned, write them again
+     * for the running service, so the changed configuration takes effect. */
+    fn apply_cgroup_context(&self) {
+        let unit = match self.comm.owner() {
+            None => return,
+            Some(v) => v,
+        };
+
+        let cg_path = unit.cg_path();
+        if cg_path.as_os_str().is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.config.cgroup_context().apply(&cg_path) {
+            log::warn!(
+                "Failed to apply cgroup resource limits of {}: {}",
+                unit.id(),
+                e
+            );
+        }
+    }
+
     fn parse(&self) -> Result<()> {
         // if TimeoutSec is set, flush it's value to TimeoutStartSec and TimeoutStopSec
         self.config.flush_timeout();
@@ -276,6 +402,11 @@ impl ServiceUnit {
         self.exec_ctx
             .set_selinux_context(cfg_data.borrow().Service.SELinuxContext.clone());
 
+        self.parse_sandbox();
+        self.parse_privileges();
+        self.parse_seccomp();
+        self.parse_stdio();
+
         #[cfg(feature = "linux")]
         if let Err(e) = self.exec_ctx.set_user(&cfg_data.borrow().Service.User) {
             log::error!("Failed to set user: {}", e);
@@ -331,19 +462,32 @@ impl ServiceUnit {
                     }
                 }
             }
+
+            let slice = cfg_data.borrow().Service.Slice.clone();
+            if !slice.is_empty() {
+                if let Err(e) = self.comm.um().unit_set_slice(&owner.id(), &slice) {
+                    log::error!("Failed to put {} into slice {}: {}", owner.id(), slice, e);
+                    return Err(e);
+                }
+            }
         }
 
         self.parse_kill_context()?;
+        self.parse_cgroup_context();
+        if self.current_active_state() == UnitActiveState::Active {
+            self.apply_cgroup_context();
+        }
 
         Ok(())
     }
 
     fn service_add_extras(&self) -> Result<()> {
-        if self.config.service_type() == ServiceType::Notify
-            && (self.config.config_data().borrow().Service.NotifyAccess == None
-                || self.config.config_data().borrow().Service.NotifyAccess
-                    == Some(NotifyAccess::None)
-                || self.config.config_data().borrow().Service.WatchdogSec > 0)
+        if matches!(
+            self.config.service_type(),
+            ServiceType::Notify | ServiceType::NotifyReload
+        ) && (self.config.config_data().borrow().Service.NotifyAccess == None
+            || self.config.config_data().borrow().Service.NotifyAccess == Some(NotifyAccess::None)
+            || self.config.config_data().borrow().Service.WatchdogSec > 0)
         {
             self.config.set_notify_access(NotifyAccess::Main);
         }
@@ -406,6 +550,19 @@ impl ServiceUnit {
         });
         }
 
+        let reload_signal = self
+            .config
+            .config_data()
+            .borrow()
+            .Service
+            .ReloadSignal
+            .clone();
+        if Signal::from_str(&reload_signal).is_err() {
+            return Err(Error::ConfigureError {
+                msg: format!("Invalid ReloadSignal={}", reload_signal),
+            });
+        }
+
         Ok(())
     }
 
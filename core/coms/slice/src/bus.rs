@@ -0,0 +1,69 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use super::comm::SliceUnitComm;
+use super::config::SliceConfig;
+use core::error::*;
+use core::unit::{self, UnitWriteFlags};
+use std::rc::Rc;
+
+pub struct SliceBus {
+    // associated objects
+    comm: Rc<SliceUnitComm>,
+    config: Rc<SliceConfig>,
+    // owned objects
+}
+
+impl SliceBus {
+    pub(super) fn new(commr: &Rc<SliceUnitComm>, configr: &Rc<SliceConfig>) -> SliceBus {
+        SliceBus {
+            comm: Rc::clone(commr),
+            config: Rc::clone(configr),
+        }
+    }
+
+    /* The resource control properties can be changed for the loaded slice,
+     * the new limits are written to the cgroup by the caller. */
+    pub(super) fn unit_set_property(
+        &self,
+        key: &str,
+        value: &str,
+        flags: UnitWriteFlags,
+    ) -> Result<()> {
+        let real_flags = flags | UnitWriteFlags::PRIVATE;
+        match key {
+            "MemoryMax"
+            | "MemoryHigh"
+            | "CPUWeight"
+            | "CPUQuota"
+            | "TasksMax"
+            | "IOWeight"
+            | "IODeviceReadBandwidthMax" => self.unit_write_property(key, value, real_flags),
+            str_key => Err(Error::NotFound {
+                what: format!("set property:{}", str_key),
+            }),
+        }
+    }
+
+    fn unit_write_property(&self, key: &str, value: &str, flags: UnitWriteFlags) -> Result<()> {
+        if unit::unit_write_flags_is_noop(flags) {
+            return Ok(());
+        }
+
+        let unit = self.comm.owner().unwrap();
+        let um = self.comm.um();
+        let ps = um.private_section(unit.unit_type());
+
+        self.config.set_property(key, value)?;
+        unit::unit_write_settingf(unit, &ps, flags, key, format_args!("{}={}", key, value))
+    }
+}
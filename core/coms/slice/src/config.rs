@@ -0,0 +1,189 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+#![allow(non_snake_case)]
+use crate::{comm::SliceUnitComm, rentry::SectionSlice};
+use core::error::*;
+use core::rel::ReStation;
+use core::unit::CgroupContext;
+use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
+use unit_parser::prelude::UnitConfig;
+
+#[derive(UnitConfig, Default, Debug)]
+pub(crate) struct SliceConfigData {
+    pub Slice: SectionSlice,
+}
+
+impl SliceConfigData {
+    pub(self) fn new(Slice: SectionSlice) -> SliceConfigData {
+        SliceConfigData { Slice }
+    }
+
+    pub(self) fn set_property(&mut self, key: &str, value: &str) -> Result<()> {
+        self.Slice.set_property(key, value)
+    }
+}
+
+pub struct SliceConfig {
+    // associated objects
+    comm: Rc<SliceUnitComm>,
+
+    // owned objects
+    /* original */
+    data: Rc<RefCell<SliceConfigData>>,
+    /* processed */
+    cgroup_context: Rc<CgroupContext>,
+}
+
+impl ReStation for SliceConfig {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        if reload {
+            return;
+        }
+        if let Some(conf) = self.comm.rentry_conf_get() {
+            self.data.replace(SliceConfigData::new(conf));
+            self.parse_cgroup_context();
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_conf_insert(&self.data.borrow().Slice);
+    }
+}
+
+impl SliceConfig {
+    pub(super) fn new(commr: &Rc<SliceUnitComm>) -> Self {
+        SliceConfig {
+            comm: Rc::clone(commr),
+            data: Rc::new(RefCell::new(SliceConfigData::default())),
+            cgroup_context: Rc::new(CgroupContext::default()),
+        }
+    }
+
+    pub(super) fn load(&self, paths: Vec<PathBuf>, update: bool) -> Result<()> {
+        let name = paths[0].file_name().unwrap().to_string_lossy().to_string();
+        let data = match SliceConfigData::load_config(paths, &name) {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Invalid Configuration: {}", e);
+                return Err(Error::ConfigureError {
+                    msg: format!("Invalid Configuration: {}", e),
+                });
+            }
+        };
+
+        // record original configuration
+        *self.data.borrow_mut() = data;
+
+        // parse and record processed configuration
+        self.parse_cgroup_context();
+
+        if update {
+            self.db_update();
+        }
+
+        Ok(())
+    }
+
+    fn parse_cgroup_context(&self) {
+        let data = self.data.borrow();
+        let slice = &data.Slice;
+        self.cgroup_context.set_memory_max(slice.MemoryMax);
+        self.cgroup_context.set_memory_high(slice.MemoryHigh);
+        self.cgroup_context.set_cpu_weight(slice.CPUWeight);
+        self.cgroup_context.set_cpu_quota(slice.CPUQuota);
+        self.cgroup_context.set_tasks_max(slice.TasksMax);
+        self.cgroup_context.set_io_weight(slice.IOWeight);
+        self.cgroup_context
+            .set_io_device_read_bandwidth(slice.IODeviceReadBandwidthMax.clone());
+    }
+
+    pub(super) fn config_data(&self) -> Rc<RefCell<SliceConfigData>> {
+        self.data.clone()
+    }
+
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        SliceConfigData::verify_config_located(paths, name)
+    }
+
+    pub(super) fn cgroup_context(&self) -> Rc<CgroupContext> {
+        self.cgroup_context.clone()
+    }
+
+    pub(super) fn set_property(&self, key: &str, value: &str) -> Result<()> {
+        let ret = self.data.borrow_mut().set_property(key, value);
+        self.parse_cgroup_context();
+        self.db_update();
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::comm::SliceUnitComm;
+    use crate::config::SliceConfig;
+    use libtests::get_project_root;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_slice_parse() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/test.slice");
+
+        let comm = Rc::new(SliceUnitComm::new());
+        let config = SliceConfig::new(&comm);
+        assert!(config.load(vec![file_path], false).is_ok());
+
+        let data = config.config_data();
+        let slice = &data.borrow().Slice;
+        assert_eq!(slice.MemoryMax, Some(512 * 1024 * 1024));
+        assert_eq!(slice.MemoryHigh, None);
+        assert_eq!(slice.CPUWeight, Some(200));
+        assert_eq!(slice.TasksMax, Some(u64::MAX));
+        assert_eq!(slice.IODeviceReadBandwidthMax.len(), 2);
+        assert_eq!(
+            slice.IODeviceReadBandwidthMax[1].path,
+            PathBuf::from("/dev/sdb")
+        );
+    }
+
+    #[test]
+    fn test_slice_set_property() {
+        let comm = Rc::new(SliceUnitComm::new());
+        let config = SliceConfig::new(&comm);
+        assert!(config.set_property("MemoryMax", "1G").is_ok());
+        assert!(config.set_property("CPUWeight", "0").is_err());
+        assert!(config.set_property("CPUQuota", "20%").is_ok());
+        assert!(config
+            .set_property("IODeviceReadBandwidthMax", "/dev/sda 1M")
+            .is_ok());
+        assert!(config
+            .set_property("IODeviceReadBandwidthMax", "/dev/sdb 2M")
+            .is_ok());
+        assert!(config.set_property("Slice", "foo.slice").is_err());
+
+        let data = config.config_data();
+        assert_eq!(data.borrow().Slice.MemoryMax, Some(1024 * 1024 * 1024));
+        assert_eq!(data.borrow().Slice.CPUWeight, None);
+        assert_eq!(data.borrow().Slice.CPUQuota, Some(200000));
+        assert_eq!(data.borrow().Slice.IODeviceReadBandwidthMax.len(), 2);
+
+        assert!(config.set_property("IODeviceReadBandwidthMax", "").is_ok());
+        assert!(data.borrow().Slice.IODeviceReadBandwidthMax.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! # Slice is used to organize units hierarchically in the cgroup tree. A slice does not contain any process itself,
+//!  it only creates a cgroup node, and the units which are put into the slice create their cgroup under it.
+//!  The hierarchy is encoded in the name of the slice: "a-b.slice" is placed under "a.slice", and its cgroup path is "a.slice/a-b.slice".
+//!  The resource limits in the [Slice] section are written to the cgroup of the slice, and apply to all the units in it.
+//!  Other units are put into the slice with Slice= in their own configuration file.
+//! #  Example:
+//! ``` toml
+//!  [Unit]
+//!  Description="Slice for the example services"
+//!
+//!  [Slice]
+//!  MemoryMax=1G
+//!  CPUWeight=200
+//!
+//!  [Install]
+//!  WantedBy=
+//! ```
+//! ##  Automatic dependency
+//!
+//! ###  Implicit dependency
+//!  Requires= and After= are added to the parent slice automatically, i.e. "a-b.slice" requires "a.slice".
+//!
+//! ###  Default Dependency
+//!  If DefaultDependencies=true is set, the following dependencies will be added by default:
+//!  Conflicts="shutdown.target", Before="shutdown.target"
+
+#[cfg(all(feature = "plugin", feature = "noplugin"))]
+compile_error!("feature plugin and noplugin cannot be enabled at the same time");
+
+pub use {manager::__um_obj_create, unit::__subunit_create_with_params};
+
+// dependency: slice_base -> slice_rentry -> slice_comm -> slice_config -> {slice_mng | slice_bus} -> slice_unit -> slice_manager
+mod base;
+mod bus;
+mod comm;
+mod config;
+mod manager;
+mod mng;
+mod rentry;
+mod unit;
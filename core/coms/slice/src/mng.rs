@@ -0,0 +1,189 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! is the core of the slice unit
+//!
+use super::comm::SliceUnitComm;
+use super::config::SliceConfig;
+use super::rentry::SliceState;
+use basic::IN_SET;
+use core::error::*;
+use core::rel::ReStation;
+use core::unit::{UnitActiveState, UnitNotifyFlags};
+use std::{cell::RefCell, rc::Rc};
+
+impl SliceState {
+    fn to_unit_state(self) -> UnitActiveState {
+        match self {
+            SliceState::Dead | SliceState::StateMax => UnitActiveState::InActive,
+            SliceState::Active => UnitActiveState::Active,
+        }
+    }
+}
+
+pub(super) struct SliceMng {
+    comm: Rc<SliceUnitComm>,
+    config: Rc<SliceConfig>,
+    state: RefCell<SliceState>,
+}
+
+impl ReStation for SliceMng {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, _reload: bool) {
+        if let Some(state) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_mng_insert(self.state());
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl SliceMng {
+    pub(super) fn new(_comm: &Rc<SliceUnitComm>, configr: &Rc<SliceConfig>) -> Self {
+        SliceMng {
+            comm: Rc::clone(_comm),
+            config: Rc::clone(configr),
+            state: RefCell::new(SliceState::StateMax),
+        }
+    }
+
+    pub(super) fn start_check(&self) -> Result<()> {
+        if IN_SET!(self.state(), SliceState::Active) {
+            return Err(Error::UnitActionEAgain);
+        }
+        Ok(())
+    }
+
+    pub(super) fn start_action(&self, notify: bool) -> Result<()> {
+        // the cgroup of the slice must exist before the units in it are started
+        if let Some(unit) = self.comm.owner() {
+            unit.prepare_exec()?;
+            if let Err(e) = self.config.cgroup_context().apply(&unit.cg_path()) {
+                log::warn!(
+                    "Failed to apply cgroup resource limits of {}: {}",
+                    unit.id(),
+                    e
+                );
+            }
+        }
+
+        self.set_state(SliceState::Active, notify);
+        Ok(())
+    }
+
+    pub(super) fn stop_check(&self) -> Result<()> {
+        if IN_SET!(self.state(), SliceState::Dead) {
+            return Err(Error::UnitActionEAgain);
+        }
+        Ok(())
+    }
+
+    pub(super) fn stop_action(&self, notify: bool) {
+        // the units in the slice are stopped before it, the cgroup is empty now
+        if let Some(unit) = self.comm.owner() {
+            let cg_path = unit.cg_path();
+            if !cg_path.as_os_str().is_empty() {
+                if let Err(e) = cgroup::cg_remove(&cg_path) {
+                    log::warn!("Failed to remove the cgroup of {}: {}", unit.id(), e);
+                }
+            }
+        }
+
+        self.set_state(SliceState::Dead, notify);
+    }
+
+    pub fn get_state(&self) -> String {
+        let state = *self.state.borrow();
+        state.to_string()
+    }
+
+    fn set_state(&self, new_state: SliceState, notify: bool) {
+        let old_state = self.state();
+        self.state.replace(new_state);
+
+        if notify {
+            self.state_notify(new_state, old_state);
+        }
+    }
+
+    fn state_notify(&self, new_state: SliceState, old_state: SliceState) {
+        if let Some(unit) = self.comm.owner() {
+            if new_state != old_state {
+                log::debug!(
+                    "{} original state[{:?}] ->new state[{:?}]",
+                    unit.id(),
+                    old_state,
+                    new_state,
+                );
+            }
+            let old_unit_state = old_state.to_unit_state();
+            let new_unit_state = new_state.to_unit_state();
+            unit.notify(
+                old_unit_state,
+                new_unit_state,
+                UnitNotifyFlags::RELOAD_FAILURE,
+            );
+
+            self.db_update();
+        }
+    }
+
+    fn state(&self) -> SliceState {
+        *self.state.borrow()
+    }
+
+    pub fn to_unit_state(&self) -> UnitActiveState {
+        self.state().to_unit_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SliceConfig;
+    use super::SliceMng;
+    use super::SliceState;
+    use super::SliceUnitComm;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_slice_set_state() {
+        let _comm = Rc::new(SliceUnitComm::new());
+        let config = Rc::new(SliceConfig::new(&_comm));
+        let sm = SliceMng::new(&_comm, &config);
+        sm.set_state(SliceState::Active, false);
+        assert_eq!(sm.state(), SliceState::Active)
+    }
+
+    #[test]
+    fn test_slice_stop_action() {
+        let comm = Rc::new(SliceUnitComm::new());
+        let config = Rc::new(SliceConfig::new(&comm));
+        let sm = SliceMng::new(&comm, &config);
+        sm.stop_action(false);
+        assert_eq!(sm.state(), SliceState::Dead)
+    }
+
+    #[test]
+    fn test_slice_start_action() {
+        let comm = Rc::new(SliceUnitComm::new());
+        let config = Rc::new(SliceConfig::new(&comm));
+        let sm = SliceMng::new(&comm, &config);
+        assert!(sm.start_action(false).is_ok());
+        assert_eq!(sm.state(), SliceState::Active)
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+#![allow(non_snake_case)]
+use core::error::*;
+use core::rel::{ReDb, ReDbRwTxn, ReDbTable, ReliSwitch, Reliability};
+use core::unit::{
+    parse_cgroup_weight, parse_cpu_quota, parse_io_device_limit, parse_memory_limit,
+    parse_tasks_max, IODeviceLimit,
+};
+use macros::{EnumDisplay, UnitSection};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+const RELI_DB_HSLICE_CONF: &str = "sliceconf";
+const RELI_DB_HSLICE_MNG: &str = "slicemng";
+
+// slice contain Dead and Active state，correspond the inactive and active of the unit
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, EnumDisplay)]
+pub(super) enum SliceState {
+    Dead,
+    Active,
+    StateMax,
+}
+
+#[derive(UnitSection, Default, Clone, Debug, Serialize, Deserialize)]
+pub struct SectionSlice {
+    #[entry(parser = parse_memory_limit)]
+    pub MemoryMax: Option<u64>,
+    #[entry(parser = parse_memory_limit)]
+    pub MemoryHigh: Option<u64>,
+    #[entry(parser = parse_cgroup_weight)]
+    pub CPUWeight: Option<u64>,
+    #[entry(parser = parse_cpu_quota)]
+    pub CPUQuota: Option<u64>,
+    #[entry(parser = parse_tasks_max)]
+    pub TasksMax: Option<u64>,
+    #[entry(parser = parse_cgroup_weight)]
+    pub IOWeight: Option<u64>,
+    #[entry(append, parser = parse_io_device_limit)]
+    pub IODeviceReadBandwidthMax: Vec<IODeviceLimit>,
+}
+
+impl SectionSlice {
+    pub(super) fn set_property(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "MemoryMax" => self.MemoryMax = Some(parse_memory_limit(value)?),
+            "MemoryHigh" => self.MemoryHigh = Some(parse_memory_limit(value)?),
+            "CPUWeight" => self.CPUWeight = Some(parse_cgroup_weight(value)?),
+            "CPUQuota" => self.CPUQuota = Some(parse_cpu_quota(value)?),
+            "TasksMax" => self.TasksMax = Some(parse_tasks_max(value)?),
+            "IOWeight" => self.IOWeight = Some(parse_cgroup_weight(value)?),
+            "IODeviceReadBandwidthMax" => {
+                /* the same as the configuration file, an empty value resets the list */
+                if value.is_empty() {
+                    self.IODeviceReadBandwidthMax.clear();
+                } else {
+                    self.IODeviceReadBandwidthMax
+                        .extend(parse_io_device_limit(value)?);
+                }
+            }
+            str_key => {
+                return Err(Error::NotFound {
+                    what: format!("set slice property:{}", str_key),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SliceReConf {
+    slice: SectionSlice,
+}
+
+impl SliceReConf {
+    fn new(slice: &SectionSlice) -> SliceReConf {
+        SliceReConf {
+            slice: slice.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SliceReMng {
+    state: SliceState,
+}
+
+impl SliceReMng {
+    fn new(state: SliceState) -> SliceReMng {
+        SliceReMng { state }
+    }
+}
+
+struct SliceReDb<K, V>(ReDb<K, V>);
+
+pub(super) struct SliceRe {
+    // database: multi-instance(N)
+    conf: Rc<SliceReDb<String, SliceReConf>>, // RELI_DB_HSLICE_CONF; key: unit_id, data: slice;
+    mng: Rc<SliceReDb<String, SliceReMng>>,   // RELI_DB_HSLICE_MNG; key: unit_id, data: state;
+}
+
+impl SliceRe {
+    pub(super) fn new(relir: &Rc<Reliability>) -> SliceRe {
+        let conf = Rc::new(SliceReDb(ReDb::new(relir, RELI_DB_HSLICE_CONF)));
+        let mng = Rc::new(SliceReDb(ReDb::new(relir, RELI_DB_HSLICE_MNG)));
+        let rentry = SliceRe { conf, mng };
+        rentry.register(relir);
+        rentry
+    }
+
+    pub(super) fn conf_insert(&self, unit_id: &str, slice: &SectionSlice) {
+        let conf = SliceReConf::new(slice);
+        self.conf.0.insert(unit_id.to_string(), conf);
+    }
+
+    pub(super) fn conf_get(&self, unit_id: &str) -> Option<SectionSlice> {
+        let conf = self.conf.0.get(&unit_id.to_string());
+        conf.map(|c| c.slice)
+    }
+
+    pub(super) fn mng_insert(&self, unit_id: &str, state: SliceState) {
+        let mng = SliceReMng::new(state);
+        self.mng.0.insert(unit_id.to_string(), mng);
+    }
+
+    pub(super) fn mng_get(&self, unit_id: &str) -> Option<SliceState> {
+        let mng = self.mng.0.get(&unit_id.to_string());
+        mng.map(|m| m.state)
+    }
+
+    fn register(&self, relir: &Reliability) {
+        // rel-db: RELI_DB_HSLICE_CONF
+        let db = Rc::clone(&self.conf);
+        relir.history_db_register(RELI_DB_HSLICE_CONF, db);
+
+        // rel-db: RELI_DB_HSLICE_MNG
+        let db = Rc::clone(&self.mng);
+        relir.history_db_register(RELI_DB_HSLICE_MNG, db);
+    }
+}
+
+impl ReDbTable for SliceReDb<String, SliceReConf> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
+
+impl ReDbTable for SliceReDb<String, SliceReMng> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
@@ -0,0 +1,305 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! SliceUnit is used to manage the cgroup hierarchy of the units
+//! SliceUnit is the entrance of the sub unit，implement the trait UnitObj,UnitMngUtil and UnitSubClass.
+//! Trait UnitObj defines the behavior of the sub unit.
+//! Trait UnitMngUtil is used to attach the Unitmanager to the sub unit.
+//! Trait UnitSubClass implement the convert from sub unit to UnitObj.
+use super::bus::SliceBus;
+use super::comm::SliceUnitComm;
+use super::config::SliceConfig;
+use super::mng::SliceMng;
+use basic::SHUTDOWN_TARGET;
+use core::error::*;
+use core::rel::{ReStation, Reliability};
+use core::unit::UnitBase;
+use core::unit::{
+    section_properties, SubUnit, UmIf, UnitActiveState, UnitDependencyMask, UnitMngUtil,
+    UnitRelations, UnitWriteFlags,
+};
+use nix::sys::wait::WaitStatus;
+use std::{path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
+
+const ROOT_SLICE: &str = "-.slice";
+
+struct Slice {
+    um: Rc<dyn UmIf>,
+    comm: Rc<SliceUnitComm>,
+    config: Rc<SliceConfig>,
+    mng: Rc<SliceMng>,
+    bus: SliceBus,
+}
+
+impl ReStation for Slice {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        self.config.db_map(reload);
+        self.mng.db_map(reload);
+    }
+
+    fn db_insert(&self) {
+        self.config.db_insert();
+        self.mng.db_insert();
+    }
+
+    // reload: entry-only
+    fn entry_coldplug(&self) {
+        // rebuild external connections, like: timer, ...
+        // do nothing now
+    }
+
+    fn entry_clear(&self) {
+        // release external connection, like: timer, ...
+        // do nothing now
+    }
+}
+
+/// return the parent of the slice, "a-b-c.slice" -> "a-b.slice",
+/// the top level slices have no parent and return None
+fn slice_parent(slice: &str) -> Option<String> {
+    let prefix = slice.strip_suffix(".slice")?;
+    let (parent, _) = prefix.rsplit_once('-')?;
+    if parent.is_empty() {
+        return None;
+    }
+    Some(format!("{}.slice", parent))
+}
+
+impl Slice {
+    fn new(um_if: Rc<dyn UmIf>) -> Slice {
+        let _comm = Rc::new(SliceUnitComm::new());
+        let _config = Rc::new(SliceConfig::new(&_comm));
+        Slice {
+            um: Rc::clone(&um_if),
+            comm: Rc::clone(&_comm),
+            config: Rc::clone(&_config),
+            mng: Rc::new(SliceMng::new(&_comm, &_config)),
+            bus: SliceBus::new(&_comm, &_config),
+        }
+    }
+
+    pub(self) fn owner(&self) -> Option<Rc<dyn UnitBase>> {
+        if let Some(ref unit) = self.comm.owner() {
+            Some(Rc::clone(unit))
+        } else {
+            None
+        }
+    }
+
+    /* The limits are written when the slice is started, write them again for
+     * the active slice, so the changed configuration takes effect. */
+    pub(self) fn apply_cgroup_context(&self) {
+        let u = match self.owner() {
+            None => return,
+            Some(u) => u,
+        };
+
+        let cg_path = u.cg_path();
+        if cg_path.as_os_str().is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.config.cgroup_context().apply(&cg_path) {
+            log::warn!(
+                "Failed to apply cgroup resource limits of {}: {}",
+                u.id(),
+                e
+            );
+        }
+    }
+
+    pub(self) fn verify(&self) -> Result<()> {
+        let u = match self.owner() {
+            None => return Ok(()),
+            Some(u) => u,
+        };
+
+        let id = u.id();
+        let valid = match id.strip_suffix(".slice") {
+            None => false,
+            Some(_) if id == ROOT_SLICE => true,
+            Some(prefix) => {
+                !prefix.is_empty()
+                    && !prefix.starts_with('-')
+                    && !prefix.ends_with('-')
+                    && !prefix.contains("--")
+            }
+        };
+        if !valid {
+            log::error!("{} is not a valid slice name, refusing to load.", id);
+            return Err(Error::ConfigureError {
+                msg: format!("invalid slice name: {}", id),
+            });
+        }
+        Ok(())
+    }
+
+    pub(self) fn add_parent_slice(&self) -> Result<()> {
+        let u = match self.owner() {
+            None => return Ok(()),
+            Some(u) => u,
+        };
+
+        let parent = match slice_parent(&u.id()) {
+            None => return Ok(()),
+            Some(p) => p,
+        };
+
+        log::debug!("Adding parent slice {} for slice: {}", parent, u.id());
+        let um = self.um.clone();
+        um.unit_add_dependency(
+            &u.id(),
+            UnitRelations::UnitInSlice,
+            &parent,
+            true,
+            UnitDependencyMask::Implicit,
+        )?;
+        um.unit_add_two_dependency(
+            &u.id(),
+            UnitRelations::UnitAfter,
+            UnitRelations::UnitRequires,
+            &parent,
+            true,
+            UnitDependencyMask::Implicit,
+        )
+    }
+
+    pub(self) fn add_default_dependencies(&self) -> Result<()> {
+        let u = match self.owner() {
+            None => return Ok(()),
+            Some(u) => u,
+        };
+
+        if !u.default_dependencies() {
+            return Ok(());
+        }
+
+        log::debug!("Adding default dependencies for slice: {}", u.id());
+        self.um.unit_add_two_dependency(
+            &u.id(),
+            UnitRelations::UnitBefore,
+            UnitRelations::UnitConflicts,
+            SHUTDOWN_TARGET,
+            true,
+            UnitDependencyMask::Default,
+        )
+    }
+}
+
+impl SubUnit for Slice {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn load(&self, paths: Vec<PathBuf>) -> Result<()> {
+        log::debug!("load for slice");
+        self.verify()?;
+        self.config.load(paths, true)?;
+        self.add_parent_slice()?;
+        self.add_default_dependencies()?;
+
+        if self.current_active_state() == UnitActiveState::Active {
+            self.apply_cgroup_context();
+        }
+        Ok(())
+    }
+
+    fn current_active_state(&self) -> UnitActiveState {
+        self.mng.to_unit_state()
+    }
+
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Slice)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        SliceConfig::verify(paths, name)
+    }
+
+    fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.comm.attach_unit(Rc::clone(&unit));
+        self.db_insert();
+    }
+
+    fn init(&self) {}
+
+    fn done(&self) {}
+
+    fn dump(&self) {}
+
+    fn start(&self) -> Result<()> {
+        log::info!("Starting {}", self.comm.owner().unwrap().id());
+        //if current state is not valid, just return.
+        self.mng.start_check()?;
+
+        self.mng.start_action(true)
+    }
+
+    fn stop(&self, force: bool) -> Result<()> {
+        if !force {
+            self.mng.stop_check()?;
+        }
+
+        self.mng.stop_action(true);
+        Ok(())
+    }
+
+    fn release_resources(&self) {}
+
+    fn sigchld_events(&self, _wait_status: WaitStatus) {}
+
+    fn reset_failed(&self) {}
+
+    fn unit_set_property(&self, key: &str, value: &str, flags: UnitWriteFlags) -> Result<()> {
+        self.bus.unit_set_property(key, value, flags)?;
+
+        if self.current_active_state() == UnitActiveState::Active {
+            self.apply_cgroup_context();
+        }
+        Ok(())
+    }
+}
+
+impl UnitMngUtil for Slice {
+    fn attach_um(&self, _um: Rc<dyn UmIf>) {
+        self.comm.attach_um(_um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+use core::declare_unitobj_plugin_with_param;
+declare_unitobj_plugin_with_param!(Slice, Slice::new);
+
+#[cfg(test)]
+mod tests {
+    use super::slice_parent;
+
+    #[test]
+    fn test_slice_parent() {
+        assert_eq!(slice_parent("-.slice"), None);
+        assert_eq!(slice_parent("system.slice"), None);
+        assert_eq!(slice_parent("a-b.slice"), Some("a.slice".to_string()));
+        assert_eq!(slice_parent("a-b-c.slice"), Some("a-b.slice".to_string()));
+        assert_eq!(slice_parent("foo.service"), None);
+    }
+}
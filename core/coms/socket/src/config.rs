@@ -39,6 +39,7 @@ use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::str::FromStr;
+use unit_parser::error::LocatedError;
 use unit_parser::prelude::UnitConfig;
 
 ///
@@ -168,6 +169,11 @@ impl SocketConfig {
         self.data.clone()
     }
 
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        SocketConfigData::verify_config_located(paths, name)
+    }
+
     pub(super) fn get_exec_cmds(&self, cmd_type: SocketCommand) -> Option<VecDeque<ExecCommand>> {
         self.data.borrow().get_exec_cmds(cmd_type)
     }
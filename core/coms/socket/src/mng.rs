@@ -35,7 +35,7 @@ use core::{
 use event::EventState;
 use event::{EventType, Events, Source};
 use nix::sys::{socket, wait::WaitStatus};
-use nix::unistd::{Gid, Uid};
+use nix::unistd::{Gid, Pid, Uid};
 use nix::{
     libc::{self},
     unistd::unlink,
@@ -714,6 +714,10 @@ impl SocketMng {
         *self.state.borrow()
     }
 
+    pub(crate) fn control_pid(&self) -> Option<Pid> {
+        self.pid.control()
+    }
+
     fn control_command_fill(&self, cmd_type: SocketCommand) {
         if let Some(cmds) = self.config.get_exec_cmds(cmd_type) {
             *self.control_command.borrow_mut() = cmds
@@ -23,10 +23,14 @@ use crate::{comm::SocketUnitComm, config::SocketConfig, load::SocketLoad, mng::S
 use core::error::*;
 use core::exec::ExecContext;
 use core::rel::{ReStation, Reliability};
-use core::unit::{SubUnit, UmIf, UnitActiveState, UnitBase, UnitMngUtil, UnitWriteFlags};
+use core::unit::{
+    section_properties, SubUnit, UmIf, UnitActiveState, UnitBase, UnitMngUtil, UnitWriteFlags,
+};
 use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
 use std::any::Any;
 use std::{path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
 
 // the structuer of the socket unit type
 struct SocketUnit {
@@ -156,10 +160,22 @@ impl SubUnit for SocketUnit {
         self.mng.state().to_string()
     }
 
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Socket)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        SocketConfig::verify(paths, name)
+    }
+
     fn collect_fds(&self) -> Vec<i32> {
         self.mng.collect_fds()
     }
 
+    fn control_pid(&self) -> Option<Pid> {
+        self.mng.control_pid()
+    }
+
     fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
         self.comm.attach_unit(unit);
         self.db_insert();
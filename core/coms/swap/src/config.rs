@@ -0,0 +1,127 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+//
+#![allow(non_snake_case)]
+use core::error::*;
+use core::rel::ReStation;
+use core::unit::KillContext;
+use nix::sys::signal::Signal;
+use std::{cell::RefCell, path::PathBuf, rc::Rc, str::FromStr};
+use unit_parser::error::LocatedError;
+use unit_parser::prelude::UnitConfig;
+
+use crate::{comm::SwapUnitComm, rentry::SectionSwap};
+
+#[derive(UnitConfig, Default)]
+pub(super) struct SwapConfigData {
+    pub Swap: SectionSwap,
+}
+
+impl SwapConfigData {
+    pub(self) fn new(Swap: SectionSwap) -> SwapConfigData {
+        SwapConfigData { Swap }
+    }
+}
+
+pub(super) struct SwapConfig {
+    // associated objects
+    comm: Rc<SwapUnitComm>,
+
+    // owned objects
+    data: Rc<RefCell<SwapConfigData>>,
+    kill_context: Rc<KillContext>,
+}
+
+impl ReStation for SwapConfig {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        if reload {
+            return;
+        }
+        if let Some(conf) = self.comm.rentry_conf_get() {
+            self.data.replace(SwapConfigData::new(conf));
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_conf_insert(&self.data.borrow().Swap);
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl SwapConfig {
+    pub(super) fn new(commr: &Rc<SwapUnitComm>) -> Self {
+        SwapConfig {
+            comm: Rc::clone(commr),
+            data: Rc::new(RefCell::new(SwapConfigData::default())),
+            kill_context: Rc::new(KillContext::default()),
+        }
+    }
+
+    pub(super) fn load(&self, paths: Vec<PathBuf>, name: &str, update: bool) -> Result<()> {
+        log::debug!("Loading {} config from: {:?}", name, paths);
+        let swap_config = match SwapConfigData::load_config(paths, name) {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Invalid Configuration: {}", e);
+                return Err(Error::ConfigureError {
+                    msg: format!("Invalid Configuration: {}", e),
+                });
+            }
+        };
+        *self.data.borrow_mut() = swap_config;
+        self.parse_kill_context()?;
+
+        if update {
+            self.db_update();
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn config_data(&self) -> Rc<RefCell<SwapConfigData>> {
+        self.data.clone()
+    }
+
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        SwapConfigData::verify_config_located(paths, name)
+    }
+
+    pub(super) fn swap_what(&self) -> String {
+        self.data.borrow().Swap.What.clone()
+    }
+
+    pub(super) fn priority(&self) -> i32 {
+        self.data.borrow().Swap.Priority
+    }
+
+    pub(super) fn swap_options(&self) -> String {
+        self.data.borrow().Swap.Options.clone()
+    }
+
+    pub(super) fn kill_context(&self) -> Rc<KillContext> {
+        self.kill_context.clone()
+    }
+
+    fn parse_kill_context(&self) -> Result<()> {
+        self.kill_context
+            .set_kill_mode(self.config_data().borrow().Swap.KillMode);
+
+        let signal = Signal::from_str(&self.config_data().borrow().Swap.KillSignal)?;
+        self.kill_context.set_kill_signal(signal);
+        Ok(())
+    }
+}
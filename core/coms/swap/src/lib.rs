@@ -0,0 +1,55 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+//! Swap is one of the unit types supported in sysmaster. A swap unit activates the swap device or
+//! swap file given by What= with swapon, and deactivates it with swapoff. The state of the unit is
+//! kept in sync with /proc/swaps, so swaps turned on or off by others are noticed too.
+//! The Swap configuration file contains three sections: Unit,Swap,and Install.
+//!
+//! # Example:
+//! ``` toml
+//! [Unit]
+//! Description=test swap
+//!
+//! [Swap]
+//! What=/dev/sdb2
+//! Priority=10
+//!
+//! [Install]
+//! WantedBy="swap.target"
+//! ```
+//! `[Swap]` section related configuration
+//!
+//! The unit must be named after the escaped path of What=, e.g. dev-sdb2.swap for the example above.
+//!
+//! ## Automatic dependency
+//! ### Implicit dependency
+//! A swap device gets BindsTo and After dependencies on its device unit, e.g. dev-sdb2.device.
+//! A swap file gets Requires and After dependencies on the mount units of the file systems holding it.
+//! ### Default Dependency
+//! Conflicts and Before dependencies on shutdown.target are added if DefaultDependencies=true.
+
+#[cfg(all(feature = "plugin", feature = "noplugin"))]
+compile_error!("feature plugin and noplugin cannot be enabled at the same time");
+
+pub use {manager::__um_obj_create, unit::__subunit_create_with_params};
+
+// dependency:
+// base -> rentry -> {comm | config}
+// spawn -> mng -> unit -> manager
+mod base;
+mod comm;
+mod config;
+mod manager;
+mod mng;
+mod rentry;
+mod spawn;
+mod unit;
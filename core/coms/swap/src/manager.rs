@@ -0,0 +1,331 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+#[cfg(feature = "plugin")]
+use crate::base::PLUGIN_NAME;
+#[cfg(feature = "plugin")]
+use constants::LOG_FILE_PATH;
+
+use super::comm::SwapUmComm;
+use super::rentry::{SwapRe, SwapReFrame};
+use core::rel::{ReStation, ReliLastFrame, Reliability};
+use core::unit::{UmIf, UnitManagerObj, UnitMngUtil, UnitType};
+use event::{EventState, EventType, Events, Source};
+use std::fs::File;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::rc::Rc;
+use std::sync::Arc;
+
+const PROC_SWAPS: &str = "/proc/swaps";
+
+struct SwapManager {
+    // owned objects
+    // data
+    comm: Arc<SwapUmComm>,
+    monitor: SwapMonitor,
+}
+
+impl ReStation for SwapManager {
+    // input
+    fn input_rebuild(&self) {
+        self.monitor.defer_enable(true);
+    }
+
+    // compensate
+    fn db_compensate_last(&self, _lframe: (u32, Option<u32>, Option<u32>), lunit: Option<&String>) {
+        assert!(lunit.is_some());
+
+        let frame = self.comm.rentry().last_frame();
+        if frame.is_none() {
+            // debug
+            return;
+        }
+
+        let unit_id = lunit.unwrap();
+        match frame.unwrap() {
+            SwapReFrame::Monitor => self.comm.um().rentry_trigger_merge(unit_id, true), // merge to trigger
+        }
+    }
+
+    fn do_compensate_last(&self, _lframe: (u32, Option<u32>, Option<u32>), lunit: Option<&String>) {
+        assert!(lunit.is_some());
+
+        let frame = self.comm.rentry().last_frame();
+        if frame.is_none() {
+            // debug
+            return;
+        }
+
+        let unit_id = lunit.unwrap();
+        match frame.unwrap() {
+            SwapReFrame::Monitor => self.comm.um().trigger_unit(unit_id), // re-run
+        }
+    }
+
+    // no data
+
+    // reload
+    fn register_ex(&self) {
+        self.monitor.register_ex();
+    }
+}
+
+impl UnitManagerObj for SwapManager {
+    fn private_section(&self, _unit_type: UnitType) -> String {
+        "Swap".into()
+    }
+
+    fn can_transient(&self, _unit_type: UnitType) -> bool {
+        false
+    }
+}
+
+// the declaration "pub(self)" is for identification only.
+impl SwapManager {
+    pub fn new() -> SwapManager {
+        let _comm = SwapUmComm::get_instance();
+        SwapManager {
+            comm: Arc::clone(&_comm),
+            monitor: SwapMonitor::new(&_comm),
+        }
+    }
+
+    fn register(&self) {
+        self.monitor.register();
+    }
+}
+
+struct SwapMonitor {
+    // owned objects
+    data: Rc<SwapMonitorData>,
+    defer: Rc<SwapMonitorDefer>,
+    io: Rc<SwapMonitorIo>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl SwapMonitor {
+    pub fn new(commr: &Arc<SwapUmComm>) -> SwapMonitor {
+        let _data = Rc::new(SwapMonitorData::new(commr));
+        let _defer = Rc::new(SwapMonitorDefer::new(&_data));
+        SwapMonitor {
+            data: Rc::clone(&_data),
+            defer: Rc::clone(&_defer),
+            io: Rc::new(SwapMonitorIo::new(&_data, &_defer)),
+        }
+    }
+
+    pub fn register_ex(&self) {
+        if self.data.file.is_none() {
+            return;
+        }
+        let events = self.data.comm.um().events();
+
+        // io
+        let io = Rc::clone(&self.io);
+        events.add_source(io.clone()).unwrap();
+        events.set_enabled(io, EventState::On).unwrap();
+    }
+
+    pub fn defer_enable(&self, enable: bool) -> i32 {
+        self.io.defer_enable(enable)
+    }
+
+    fn register(&self) {
+        let events = self.data.comm.um().events();
+
+        // defer
+        let defer = Rc::clone(&self.defer);
+        events.add_source(defer).unwrap();
+    }
+}
+
+struct SwapMonitorIo {
+    // associated objects
+    data: Rc<SwapMonitorData>,
+    defer: Rc<SwapMonitorDefer>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl SwapMonitorIo {
+    pub fn new(datar: &Rc<SwapMonitorData>, deferr: &Rc<SwapMonitorDefer>) -> SwapMonitorIo {
+        SwapMonitorIo {
+            data: Rc::clone(datar),
+            defer: Rc::clone(deferr),
+        }
+    }
+
+    pub fn defer_enable(&self, enable: bool) -> i32 {
+        let source = Rc::clone(&self.defer);
+        let state = match enable {
+            true => EventState::OneShot,
+            false => EventState::Off,
+        };
+        let events = self.data.comm.um().events();
+        match events.set_enabled(source, state) {
+            Ok(_) => 0,
+            Err(_) => -1,
+        }
+    }
+
+    fn reli(&self) -> Rc<Reliability> {
+        self.data.comm.reli()
+    }
+
+    fn rentry(&self) -> Rc<SwapRe> {
+        self.data.comm.rentry()
+    }
+}
+
+impl Source for SwapMonitorIo {
+    fn event_type(&self) -> EventType {
+        EventType::Io
+    }
+
+    /* /proc/swaps reports changes with POLLPRI, and the event is reset by the poll itself */
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLPRI) as u32
+    }
+
+    fn dispatch(&self, _e: &Events) -> i32 {
+        self.reli()
+            .set_last_frame2(ReliLastFrame::SubManager as u32, UnitType::UnitSwap as u32);
+        self.rentry().set_last_frame(SwapReFrame::Monitor);
+        self.data.dispatch_proc_swaps();
+        self.rentry().clear_last_frame();
+        self.reli().clear_last_frame();
+
+        self.defer_enable(false)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn fd(&self) -> RawFd {
+        self.data.fd()
+    }
+
+    fn priority(&self) -> i8 {
+        -10i8
+    }
+}
+
+struct SwapMonitorDefer {
+    // associated objects
+    data: Rc<SwapMonitorData>,
+}
+
+impl SwapMonitorDefer {
+    pub fn new(datar: &Rc<SwapMonitorData>) -> SwapMonitorDefer {
+        SwapMonitorDefer {
+            data: Rc::clone(datar),
+        }
+    }
+
+    fn reli(&self) -> Rc<Reliability> {
+        self.data.comm.reli()
+    }
+
+    fn rentry(&self) -> Rc<SwapRe> {
+        self.data.comm.rentry()
+    }
+}
+
+impl Source for SwapMonitorDefer {
+    fn event_type(&self) -> EventType {
+        EventType::Defer
+    }
+
+    fn epoll_event(&self) -> u32 {
+        0
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn dispatch(&self, _event: &Events) -> i32 {
+        self.reli()
+            .set_last_frame2(ReliLastFrame::SubManager as u32, UnitType::UnitSwap as u32);
+        self.rentry().set_last_frame(SwapReFrame::Monitor);
+        self.data.dispatch_proc_swaps();
+        self.rentry().clear_last_frame();
+        self.reli().clear_last_frame();
+        0
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+}
+
+struct SwapMonitorData {
+    // associated objects
+    comm: Arc<SwapUmComm>,
+
+    // owned objects
+    file: Option<File>,
+}
+
+impl SwapMonitorData {
+    pub fn new(commr: &Arc<SwapUmComm>) -> Self {
+        /* kernels built without swap support don't have /proc/swaps */
+        let file = match File::open(PROC_SWAPS) {
+            Ok(f) => Some(f),
+            Err(e) => {
+                log::warn!(
+                    "Failed to open {}, swap is not monitored: {}",
+                    PROC_SWAPS,
+                    e
+                );
+                None
+            }
+        };
+        SwapMonitorData {
+            comm: Arc::clone(commr),
+            file,
+        }
+    }
+
+    fn fd(&self) -> RawFd {
+        self.file.as_ref().map_or(-1, |f| f.as_raw_fd())
+    }
+
+    pub fn dispatch_proc_swaps(&self) {
+        let unit_type = Some(UnitType::UnitSwap);
+        for unit in self.comm.um().units_get_all(unit_type).iter() {
+            self.comm.um().update_swap_state_by_proc_swaps(unit);
+        }
+    }
+}
+
+impl UnitMngUtil for SwapManager {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+        self.register();
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+impl Default for SwapManager {
+    fn default() -> Self {
+        SwapManager::new()
+    }
+}
+
+use core::declare_umobj_plugin;
+declare_umobj_plugin!(SwapManager, SwapManager::default);
@@ -0,0 +1,619 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//!  The core logic of the swap subclass
+use basic::mount::{filter_options, is_swap};
+use basic::time::USEC_PER_SEC;
+use basic::{SWAPOFF_BIN, SWAP_BIN};
+use event::{EventState, Events};
+use event::{EventType, Source};
+use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
+
+use crate::config::SwapConfig;
+use crate::rentry::SwapResult;
+use crate::spawn::SwapSpawn;
+
+use super::comm::SwapUnitComm;
+use super::rentry::SwapState;
+use core::error::*;
+use core::exec::{ExecCommand, ExecContext};
+use core::rel::ReStation;
+use core::unit::{UnitActiveState, UnitNotifyFlags};
+use std::os::unix::prelude::RawFd;
+use std::path::Path;
+use std::rc::Weak;
+use std::{cell::RefCell, rc::Rc};
+
+impl SwapState {
+    fn swap_state_to_unit_state(&self) -> UnitActiveState {
+        match *self {
+            SwapState::Dead => UnitActiveState::InActive,
+            SwapState::Activating | SwapState::ActivatingDone => UnitActiveState::Activating,
+            SwapState::Active => UnitActiveState::Active,
+            SwapState::Deactivating
+            | SwapState::DeactivatingSigterm
+            | SwapState::DeactivatingSigkill => UnitActiveState::DeActivating,
+            SwapState::Failed => UnitActiveState::Failed,
+        }
+    }
+
+    fn has_control_process(&self) -> bool {
+        matches!(
+            *self,
+            SwapState::Activating
+                | SwapState::ActivatingDone
+                | SwapState::Deactivating
+                | SwapState::DeactivatingSigterm
+                | SwapState::DeactivatingSigkill
+        )
+    }
+}
+
+pub(super) struct SwapMng {
+    comm: Rc<SwapUnitComm>,
+    state: RefCell<SwapState>,
+
+    config: Rc<SwapConfig>,
+    control_command: RefCell<Option<ExecCommand>>,
+    spawn: Rc<SwapSpawn>,
+    timer: Rc<SwapTimer>,
+
+    result: RefCell<SwapResult>,
+    find_in_proc_swaps: RefCell<bool>,
+    pid: RefCell<Option<Pid>>,
+    timeout_usec: RefCell<u64>,
+}
+
+impl ReStation for SwapMng {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, _reload: bool) {
+        if let Some(state) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_mng_insert(self.state());
+    }
+
+    fn entry_coldplug(&self) {
+        // nothing to do, the state is synchronized with /proc/swaps by the manager.
+    }
+
+    fn entry_clear(&self) {
+        if let Err(e) = self.disable_timer() {
+            log::error!("Failed to disable timer: {}", e);
+        }
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl SwapMng {
+    pub(super) fn new(
+        commr: &Rc<SwapUnitComm>,
+        configr: &Rc<SwapConfig>,
+        exec_ctx: &Rc<ExecContext>,
+    ) -> Self {
+        SwapMng {
+            comm: Rc::clone(commr),
+            state: RefCell::new(SwapState::Dead),
+            config: Rc::clone(configr),
+            control_command: RefCell::new(None),
+            spawn: Rc::new(SwapSpawn::new(commr, exec_ctx)),
+            timer: Rc::new(SwapTimer::new(u64::MAX)),
+            result: RefCell::new(SwapResult::Success),
+            find_in_proc_swaps: RefCell::new(false),
+            pid: RefCell::new(None),
+            timeout_usec: RefCell::new(90 * USEC_PER_SEC),
+        }
+    }
+
+    pub(super) fn enter_activating(&self) {
+        let what = self.config.swap_what();
+
+        let mut swapon_command = ExecCommand::empty();
+        if let Err(e) = swapon_command.set_path(SWAP_BIN) {
+            log::error!("Failed to set swapon command: {}", e);
+            self.enter_dead_or_active(SwapResult::FailureResources);
+            return;
+        }
+        let priority = self.config.priority();
+        if priority >= 0 {
+            swapon_command.append_many_argv(vec!["-p", &priority.to_string()]);
+        }
+        let options = filter_options(
+            &self.config.swap_options(),
+            vec!["nofail", "noauto", "auto", "defaults"],
+        );
+        if !options.is_empty() {
+            swapon_command.append_many_argv(vec!["-o", &options]);
+        }
+        swapon_command.append_many_argv(vec![&what]);
+
+        *self.control_command.borrow_mut() = Some(swapon_command.clone());
+        self.unwatch_control_pid();
+        let control_pid = match self.spawn.spawn_cmd(&swapon_command) {
+            Err(e) => {
+                log::error!("Failed to swapon {}: {}", what, e);
+                self.enter_dead_or_active(SwapResult::FailureResources);
+                return;
+            }
+            Ok(v) => v,
+        };
+        self.set_control_pid(Some(control_pid));
+        self.watch_control_pid();
+        self.set_state(SwapState::Activating);
+    }
+
+    pub(super) fn enter_deactivating(&self) {
+        let what = self.config.swap_what();
+
+        let mut swapoff_command = ExecCommand::empty();
+        if let Err(e) = swapoff_command.set_path(SWAPOFF_BIN) {
+            log::error!("Failed to set swapoff command: {}", e);
+            self.enter_dead_or_active(SwapResult::FailureResources);
+            return;
+        }
+        swapoff_command.append_many_argv(vec![&what]);
+
+        *self.control_command.borrow_mut() = Some(swapoff_command.clone());
+        self.unwatch_control_pid();
+        let control_pid = match self.spawn.spawn_cmd(&swapoff_command) {
+            Err(e) => {
+                log::error!("Failed to swapoff {}: {}", what, e);
+                self.enter_dead_or_active(SwapResult::FailureResources);
+                return;
+            }
+            Ok(v) => v,
+        };
+        self.set_control_pid(Some(control_pid));
+        self.watch_control_pid();
+        self.set_state(SwapState::Deactivating);
+    }
+
+    pub(super) fn enter_signal(&self, state: SwapState, res: SwapResult) {
+        if self.result() == SwapResult::Success {
+            self.set_result(res);
+        }
+        let unit = match self.comm.owner() {
+            None => {
+                log::error!(
+                    "Failed to determine the owner unit of {}, ignoring",
+                    self.comm.get_owner_id()
+                );
+                return;
+            }
+            Some(v) => v,
+        };
+        let ret = unit.kill_context(
+            self.config.kill_context(),
+            None,
+            self.control_pid(),
+            state.to_kill_option(),
+            false,
+        );
+        if let Err(e) = ret {
+            log::warn!(
+                "Failed to kill process of {}: {}",
+                self.comm.get_owner_id(),
+                e
+            );
+            self.enter_dead_or_active(SwapResult::FailureResources);
+            return;
+        }
+
+        if ret.unwrap() {
+            self.set_state(state);
+        } else if state == SwapState::DeactivatingSigterm {
+            self.enter_signal(SwapState::DeactivatingSigkill, SwapResult::Success);
+        } else {
+            self.enter_dead_or_active(SwapResult::Success);
+        }
+    }
+
+    pub(super) fn enter_dead_or_active(&self, res: SwapResult) {
+        if self.find_in_proc_swaps() {
+            self.enter_active(res);
+        } else {
+            self.enter_dead(res);
+        }
+    }
+
+    pub(super) fn enter_dead(&self, res: SwapResult) {
+        if self.result() == SwapResult::Success {
+            self.set_result(res);
+        }
+        if self.result() == SwapResult::Success {
+            self.set_state(SwapState::Dead);
+        } else {
+            self.set_state(SwapState::Failed);
+        }
+    }
+
+    pub(super) fn enter_active(&self, res: SwapResult) {
+        if self.result() == SwapResult::Success {
+            self.set_result(res);
+        }
+        self.set_state(SwapState::Active);
+    }
+
+    pub(super) fn cycle_clear(&self) {
+        self.set_result(SwapResult::Success);
+        *self.control_command.borrow_mut() = None;
+    }
+
+    pub(super) fn timeout_usec(&self) -> u64 {
+        *self.timeout_usec.borrow()
+    }
+
+    pub(super) fn attach_spawn(&self, mng: &Rc<SwapMng>) {
+        self.spawn.attach_mng(mng)
+    }
+
+    pub(super) fn timer(&self) -> Rc<SwapTimer> {
+        self.timer.clone()
+    }
+
+    pub(super) fn attach_timer(&self, mng: &Rc<SwapMng>) {
+        self.timer.attach_mng(mng)
+    }
+
+    pub(super) fn enable_timer(&self, usec: u64) -> Result<i32> {
+        let events = self.comm.um().events();
+        if usec == u64::MAX {
+            events.del_source(self.timer())?;
+            return Ok(0);
+        }
+        log::debug!("Enable a timer: {}us", usec);
+
+        let timer = self.timer();
+        events.del_source(timer.clone())?;
+
+        timer.set_time(usec);
+        events.add_source(timer.clone())?;
+        events.set_enabled(timer, EventState::OneShot)?;
+
+        Ok(0)
+    }
+
+    pub(super) fn disable_timer(&self) -> Result<i32> {
+        let events = self.comm.um().events();
+        events.del_source(self.timer())?;
+        Ok(0)
+    }
+
+    pub(super) fn dispatch_timer(&self) -> i32 {
+        log::info!("Dispatch timeout event for {}", self.comm.get_owner_id());
+        match self.state() {
+            SwapState::Activating | SwapState::ActivatingDone | SwapState::Deactivating => {
+                self.enter_signal(SwapState::DeactivatingSigterm, SwapResult::FailureTimeout);
+            }
+            SwapState::DeactivatingSigterm => {
+                self.enter_signal(SwapState::DeactivatingSigkill, SwapResult::FailureTimeout);
+            }
+            SwapState::DeactivatingSigkill => {
+                self.enter_dead_or_active(SwapResult::FailureTimeout);
+            }
+            // Dead, Active, Failed should be impossible here.
+            _ => return -1,
+        }
+        0
+    }
+
+    pub(super) fn start_check(&self) -> Result<bool> {
+        let ret = self.comm.owner().map_or(false, |u| u.test_start_limit());
+        if !ret {
+            self.enter_dead(SwapResult::Success);
+            return Err(Error::UnitActionECanceled);
+        }
+
+        Ok(false)
+    }
+
+    pub(super) fn start_action(&self) -> Result<()> {
+        if [
+            SwapState::Deactivating,
+            SwapState::DeactivatingSigterm,
+            SwapState::DeactivatingSigkill,
+        ]
+        .contains(&self.state())
+        {
+            return Err(Error::UnitActionEAgain);
+        }
+
+        if [SwapState::Activating, SwapState::ActivatingDone].contains(&self.state()) {
+            return Ok(());
+        }
+        self.cycle_clear();
+        self.enter_activating();
+        Ok(())
+    }
+
+    pub(super) fn stop_action(&self) -> Result<i32> {
+        let state = self.state();
+        if [
+            SwapState::Deactivating,
+            SwapState::DeactivatingSigterm,
+            SwapState::DeactivatingSigkill,
+        ]
+        .contains(&state)
+        {
+            return Ok(0);
+        }
+        if [SwapState::Activating, SwapState::ActivatingDone].contains(&state) {
+            self.enter_signal(SwapState::DeactivatingSigterm, SwapResult::Success);
+            return Ok(0);
+        }
+        if state == SwapState::Active {
+            self.enter_deactivating();
+            return Ok(1);
+        }
+        Ok(0)
+    }
+
+    /// synchronize the state with /proc/swaps, the swap may be turned on or off by others.
+    pub(super) fn update_swap_state_by_proc_swaps(&self) {
+        let what = self.config.swap_what();
+        let found = !what.is_empty() && is_swap(Path::new(&what));
+        let changed = found != self.find_in_proc_swaps();
+        self.set_find_in_proc_swaps(found);
+        if !changed {
+            return;
+        }
+
+        if found {
+            match self.state() {
+                SwapState::Dead | SwapState::Failed => {
+                    self.cycle_clear();
+                    self.enter_active(SwapResult::Success);
+                }
+                SwapState::Activating => self.set_state(SwapState::ActivatingDone),
+                _ => {}
+            }
+        } else {
+            match self.state() {
+                SwapState::Active => self.enter_dead(SwapResult::Success),
+                SwapState::ActivatingDone => self.set_state(SwapState::Activating),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn reset_failed(&self) {
+        if self.state() == SwapState::Failed {
+            self.set_state(SwapState::Dead);
+        }
+        self.set_result(SwapResult::Success);
+    }
+
+    pub fn get_state(&self) -> String {
+        let state = *self.state.borrow();
+        state.to_string()
+    }
+
+    fn set_state(&self, new_state: SwapState) {
+        let old_state = self.state();
+        self.state.replace(new_state);
+
+        if !new_state.has_control_process() {
+            self.unwatch_control_pid();
+            if let Err(e) = self.disable_timer() {
+                log::debug!(
+                    "Failed to disable the timer of {}: {}",
+                    self.comm.get_owner_id(),
+                    e
+                );
+            }
+        }
+
+        if new_state != old_state {
+            log::debug!(
+                "{} original state[{:?}] -> new state[{:?}]",
+                self.comm.get_owner_id(),
+                old_state,
+                new_state,
+            );
+        }
+
+        if let Some(u) = self.comm.owner() {
+            u.notify(
+                old_state.swap_state_to_unit_state(),
+                new_state.swap_state_to_unit_state(),
+                UnitNotifyFlags::EMPTY,
+            )
+        }
+
+        self.db_update();
+    }
+
+    pub fn state(&self) -> SwapState {
+        *self.state.borrow()
+    }
+
+    pub(super) fn swap_state_to_unit_state(&self) -> UnitActiveState {
+        self.state().swap_state_to_unit_state()
+    }
+
+    fn result(&self) -> SwapResult {
+        *self.result.borrow()
+    }
+
+    fn set_result(&self, r: SwapResult) {
+        *self.result.borrow_mut() = r
+    }
+
+    fn find_in_proc_swaps(&self) -> bool {
+        *self.find_in_proc_swaps.borrow()
+    }
+
+    fn set_find_in_proc_swaps(&self, find: bool) {
+        *self.find_in_proc_swaps.borrow_mut() = find
+    }
+
+    pub fn set_control_pid(&self, control_pid: Option<Pid>) {
+        *self.pid.borrow_mut() = control_pid;
+    }
+
+    pub fn control_pid(&self) -> Option<Pid> {
+        *self.pid.borrow()
+    }
+
+    pub fn watch_control_pid(&self) {
+        if let Some(pid) = self.control_pid() {
+            if let Some(u) = self.comm.owner() {
+                self.comm.um().child_watch_pid(&u.id(), pid);
+            }
+        }
+    }
+
+    pub fn unwatch_control_pid(&self) {
+        if let Some(pid) = self.control_pid() {
+            if let Some(u) = self.comm.owner() {
+                self.comm.um().child_unwatch_pid(&u.id(), pid)
+            }
+            self.set_control_pid(None);
+        }
+    }
+}
+
+impl SwapMng {
+    pub(super) fn sigchld_event(&self, wait_status: WaitStatus) {
+        if self.control_pid() != wait_status.pid() {
+            return;
+        }
+        self.do_sigchld_event(wait_status);
+        self.db_update();
+    }
+
+    fn do_sigchld_event(&self, wait_status: WaitStatus) {
+        self.set_control_pid(None);
+        log::debug!("Got a swap process sigchld, status: {:?}", wait_status);
+        let f = self.sigchld_result(wait_status);
+        if self.result() == SwapResult::Success {
+            self.set_result(f);
+        }
+        *self.control_command.borrow_mut() = None;
+
+        /* the /proc/swaps event may arrive after the process exits, check it by ourselves */
+        let what = self.config.swap_what();
+        self.set_find_in_proc_swaps(is_swap(Path::new(&what)));
+
+        match self.state() {
+            SwapState::Activating | SwapState::ActivatingDone => {
+                if f == SwapResult::Success && !self.find_in_proc_swaps() {
+                    log::warn!(
+                        "Swapon process of {} has exited, but there is no swap.",
+                        self.comm.get_owner_id()
+                    );
+                    self.enter_dead(SwapResult::FailureProtocol);
+                    return;
+                }
+                self.enter_dead_or_active(f);
+            }
+            SwapState::Deactivating
+            | SwapState::DeactivatingSigterm
+            | SwapState::DeactivatingSigkill => {
+                self.enter_dead_or_active(f);
+            }
+            _ => {}
+        }
+    }
+
+    fn sigchld_result(&self, wait_status: WaitStatus) -> SwapResult {
+        match wait_status {
+            WaitStatus::Exited(_, status) => {
+                if status == 0 {
+                    SwapResult::Success
+                } else {
+                    SwapResult::FailureExitCode
+                }
+            }
+            WaitStatus::Signaled(_pid, _sig, core_dump) => {
+                if core_dump {
+                    SwapResult::FailureCoreDump
+                } else {
+                    SwapResult::FailureSignal
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub(super) struct SwapTimer {
+    time: RefCell<u64>,
+    mng: RefCell<Weak<SwapMng>>,
+}
+
+impl SwapTimer {
+    pub fn new(usec: u64) -> Self {
+        SwapTimer {
+            time: RefCell::new(usec),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(super) fn attach_mng(&self, mng: &Rc<SwapMng>) {
+        *self.mng.borrow_mut() = Rc::downgrade(mng)
+    }
+
+    pub(super) fn set_time(&self, usec: u64) {
+        *self.time.borrow_mut() = usec
+    }
+
+    pub(self) fn mng(&self) -> Rc<SwapMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+
+    fn do_dispatch(&self) -> i32 {
+        self.mng().dispatch_timer()
+    }
+}
+
+impl Source for SwapTimer {
+    fn fd(&self) -> RawFd {
+        0
+    }
+
+    fn event_type(&self) -> EventType {
+        EventType::TimerMonotonic
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn time_relative(&self) -> u64 {
+        *self.time.borrow()
+    }
+
+    fn dispatch(&self, _: &Events) -> i32 {
+        self.do_dispatch()
+    }
+
+    fn priority(&self) -> i8 {
+        0
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn description(&self) -> String {
+        String::from("SwapTimer")
+    }
+}
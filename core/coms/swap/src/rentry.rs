@@ -0,0 +1,252 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+//
+#![allow(non_snake_case)]
+use core::error::*;
+use core::rel::{ReDb, ReDbRwTxn, ReDbTable, ReliSwitch, Reliability};
+use core::unit::{KillMode, KillOperation};
+use macros::{EnumDisplay, UnitSection};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+const RELI_DB_HSWAP_MNG: &str = "swapmng";
+const RELI_DB_HSWAPM_FRAME: &str = "swapm-frame";
+const RELI_DB_HSWAP_CONF: &str = "swapconf";
+const RELI_LAST_KEY: u32 = 0; // singleton
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, EnumDisplay)]
+pub(super) enum SwapState {
+    Dead,
+    Activating,
+    ActivatingDone,
+    Active,
+    Deactivating,
+    DeactivatingSigterm,
+    DeactivatingSigkill,
+    Failed,
+}
+
+impl SwapState {
+    pub fn to_kill_option(self) -> KillOperation {
+        match self {
+            SwapState::DeactivatingSigterm => KillOperation::KillTerminate,
+            SwapState::DeactivatingSigkill => KillOperation::KillKill,
+            _ => KillOperation::KillInvalid,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub(super) enum SwapResult {
+    Success,
+    FailureResources,
+    FailureTimeout,
+    FailureExitCode,
+    FailureSignal,
+    FailureCoreDump,
+    FailureProtocol,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SwapReConf {
+    swap: SectionSwap,
+}
+
+impl SwapReConf {
+    fn new(swap: &SectionSwap) -> SwapReConf {
+        SwapReConf { swap: swap.clone() }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SwapReMng {
+    state: SwapState,
+}
+
+impl SwapReMng {
+    fn new(state: SwapState) -> SwapReMng {
+        SwapReMng { state }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) enum SwapReFrame {
+    Monitor,
+}
+
+struct SwapReDb<K, V>(ReDb<K, V>);
+
+pub(super) struct SwapRe {
+    conf: Rc<SwapReDb<String, SwapReConf>>, // RELI_DB_HSWAP_CONF; key: unit_id, data: config;
+
+    // database: multi-instance(N)
+    mng: Rc<SwapReDb<String, SwapReMng>>, // RELI_DB_HSWAP_MNG; key: unit_id, data: state;
+
+    // database: singleton(1)
+    frame: Rc<SwapReDb<u32, SwapReFrame>>, // RELI_DB_HSWAPM_FRAME; key: RELI_LAST_KEY, data: SwapReFrame;
+}
+
+impl SwapRe {
+    pub(super) fn new(relir: &Rc<Reliability>) -> SwapRe {
+        let mng = Rc::new(SwapReDb(ReDb::new(relir, RELI_DB_HSWAP_MNG)));
+        let frame = Rc::new(SwapReDb(ReDb::new(relir, RELI_DB_HSWAPM_FRAME)));
+        let conf = Rc::new(SwapReDb(ReDb::new(relir, RELI_DB_HSWAP_CONF)));
+        let rentry = SwapRe { conf, mng, frame };
+        rentry.register(relir);
+        rentry
+    }
+
+    pub(super) fn conf_insert(&self, unit_id: &str, swap: &SectionSwap) {
+        let conf = SwapReConf::new(swap);
+        self.conf.0.insert(unit_id.to_string(), conf);
+    }
+
+    pub(super) fn conf_get(&self, unit_id: &str) -> Option<SectionSwap> {
+        let conf = self.conf.0.get(&unit_id.to_string());
+        conf.map(|c| c.swap)
+    }
+
+    pub(super) fn mng_insert(&self, unit_id: &str, state: SwapState) {
+        let mng = SwapReMng::new(state);
+        self.mng.0.insert(unit_id.to_string(), mng);
+    }
+
+    pub(super) fn mng_get(&self, unit_id: &str) -> Option<SwapState> {
+        let mng = self.mng.0.get(&unit_id.to_string());
+        mng.map(|m| m.state)
+    }
+
+    pub(super) fn set_last_frame(&self, frame: SwapReFrame) {
+        self.frame.0.insert(RELI_LAST_KEY, frame);
+    }
+
+    pub(super) fn clear_last_frame(&self) {
+        self.frame.0.remove(&RELI_LAST_KEY);
+    }
+
+    pub(super) fn last_frame(&self) -> Option<SwapReFrame> {
+        self.frame.0.get(&RELI_LAST_KEY)
+    }
+
+    fn register(&self, relir: &Reliability) {
+        // rel-db: RELI_DB_HSWAP_CONF
+        let db = Rc::clone(&self.conf);
+        relir.history_db_register(RELI_DB_HSWAP_CONF, db);
+
+        // rel-db: RELI_DB_HSWAP_MNG
+        let db = Rc::clone(&self.mng);
+        relir.history_db_register(RELI_DB_HSWAP_MNG, db);
+
+        // rel-db: RELI_DB_HSWAPM_FRAME
+        let db = Rc::clone(&self.frame);
+        relir.history_db_register(RELI_DB_HSWAPM_FRAME, db);
+    }
+}
+
+impl ReDbTable for SwapReDb<String, SwapReConf> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
+
+impl ReDbTable for SwapReDb<String, SwapReMng> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
+
+impl ReDbTable for SwapReDb<u32, SwapReFrame> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn flush(&self, db_wtxn: &mut ReDbRwTxn, switch: ReliSwitch) {
+        self.0.data_2_db(db_wtxn, switch);
+    }
+
+    fn import<'a>(&self) {
+        self.0.db_2_cache();
+    }
+
+    fn switch_set(&self, switch: ReliSwitch) {
+        self.0.switch_buffer(switch);
+    }
+}
+
+#[derive(UnitSection, Default, Clone, Serialize, Deserialize, Debug)]
+pub struct SectionSwap {
+    #[entry(default = String::new(), parser = parse_what)]
+    pub What: String,
+    /// -1 means the priority is chosen by the kernel
+    #[entry(default = -1, parser = parse_priority)]
+    pub Priority: i32,
+    #[entry(default = String::new())]
+    pub Options: String,
+
+    // Kill
+    #[entry(default = KillMode::ControlGroup)]
+    pub KillMode: KillMode,
+    #[entry(default = String::from("SIGTERM"))]
+    pub KillSignal: String,
+}
+
+fn parse_what(s: &str) -> Result<String> {
+    basic::fs::parse_absolute_path(s).map_err(|_| Error::ConfigureError {
+        msg: format!("Invalid What: {}", s),
+    })
+}
+
+fn parse_priority(s: &str) -> Result<i32> {
+    /* the range accepted by swapon(2) */
+    match s.parse::<i32>() {
+        Ok(v) if (-1..=32767).contains(&v) => Ok(v),
+        _ => Err(Error::ConfigureError {
+            msg: format!("Invalid Priority: {}", s),
+        }),
+    }
+}
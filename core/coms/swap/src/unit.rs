@@ -0,0 +1,262 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! swap unit is entry of swap type of unit，need impl
+//! UnitObj,UnitMngUtil, UnitSubClass trait
+
+use super::comm::SwapUnitComm;
+use super::config::SwapConfig;
+use super::mng::SwapMng;
+use basic::mount::mount_point_to_unit_name;
+use basic::unit_name::unit_name_from_path;
+use basic::SHUTDOWN_TARGET;
+use core::error::*;
+use core::exec::ExecContext;
+use core::rel::{ReStation, Reliability};
+use core::unit::{
+    section_properties, SubUnit, UmIf, UnitActiveState, UnitBase, UnitDependencyMask, UnitMngUtil,
+    UnitRelations, UnitType,
+};
+use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use unit_parser::error::LocatedError;
+
+struct SwapUnit {
+    comm: Rc<SwapUnitComm>,
+    config: Rc<SwapConfig>,
+    mng: Rc<SwapMng>,
+}
+
+impl ReStation for SwapUnit {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self, reload: bool) {
+        self.config.db_map(reload);
+        self.mng.db_map(reload);
+    }
+
+    fn db_insert(&self) {
+        self.config.db_insert();
+        self.mng.db_insert();
+    }
+
+    // reload: no external connections, entry-only
+    fn entry_coldplug(&self) {
+        self.mng.entry_coldplug();
+    }
+
+    fn entry_clear(&self) {
+        self.mng.entry_clear();
+    }
+}
+
+impl SwapUnit {
+    fn new(_um: Rc<dyn UmIf>) -> SwapUnit {
+        let comm = Rc::new(SwapUnitComm::new());
+        let config = Rc::new(SwapConfig::new(&comm));
+        let exec_ctx = Rc::new(ExecContext::new());
+        let mng = Rc::new(SwapMng::new(&comm, &config, &exec_ctx));
+        mng.attach_spawn(&mng);
+        mng.attach_timer(&mng);
+        SwapUnit { comm, config, mng }
+    }
+
+    fn verify(&self) -> Result<()> {
+        let what = self.config.swap_what();
+        if what.is_empty() {
+            log::error!("Swap unit lacks What setting. Refusing.");
+            return Err(Error::ConfigureError {
+                msg: "What is not set".to_string(),
+            });
+        }
+
+        /* the swap unit must be named after the swap device or file it manages */
+        let unit_name = self.comm.get_owner_id();
+        if unit_name_from_path(&what, ".swap") != unit_name {
+            log::error!(
+                "What={} doesn't match the unit name {}. Refusing.",
+                what,
+                unit_name
+            );
+            return Err(Error::ConfigureError {
+                msg: "unit name doesn't match What".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn add_extras(&self) -> Result<()> {
+        let um = self.comm.um();
+        let u = match self.comm.owner() {
+            None => {
+                return Ok(());
+            }
+            Some(v) => v,
+        };
+
+        self.add_device_dependencies(&um, &u)?;
+
+        self.add_mount_dependencies(&um, &u)?;
+
+        self.add_default_dependencies(&um, &u)
+    }
+
+    /* a swap device is only usable while the device unit of it is plugged */
+    fn add_device_dependencies(&self, um: &Rc<dyn UmIf>, u: &Rc<dyn UnitBase>) -> Result<()> {
+        let what = self.config.swap_what();
+        if !what.starts_with("/dev/") {
+            return Ok(());
+        }
+
+        um.unit_add_two_dependency(
+            &u.id(),
+            UnitRelations::UnitBindsTo,
+            UnitRelations::UnitAfter,
+            &unit_name_from_path(&what, ".device"),
+            true,
+            UnitDependencyMask::Implicit,
+        )
+    }
+
+    /* a swap file can only be activated after the file system holding it is mounted */
+    fn add_mount_dependencies(&self, um: &Rc<dyn UmIf>, u: &Rc<dyn UnitBase>) -> Result<()> {
+        let what = self.config.swap_what();
+        if what.starts_with("/dev/") {
+            return Ok(());
+        }
+
+        let mounts = um.units_get_all(Some(UnitType::UnitMount));
+        for dir in Path::new(&what).ancestors().skip(1) {
+            let mount_name = mount_point_to_unit_name(&dir.to_string_lossy());
+            if !mounts.contains(&mount_name) {
+                continue;
+            }
+            um.unit_add_two_dependency(
+                &u.id(),
+                UnitRelations::UnitAfter,
+                UnitRelations::UnitRequires,
+                &mount_name,
+                true,
+                UnitDependencyMask::Implicit,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn add_default_dependencies(&self, um: &Rc<dyn UmIf>, u: &Rc<dyn UnitBase>) -> Result<()> {
+        if !u.default_dependencies() {
+            return Ok(());
+        }
+
+        um.unit_add_two_dependency(
+            &u.id(),
+            UnitRelations::UnitBefore,
+            UnitRelations::UnitConflicts,
+            SHUTDOWN_TARGET,
+            true,
+            UnitDependencyMask::Default,
+        )
+    }
+}
+
+impl SubUnit for SwapUnit {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn load(&self, paths: Vec<PathBuf>) -> Result<()> {
+        let unit_name = self.comm.get_owner_id();
+        self.config.load(paths, &unit_name, true)?;
+
+        self.verify()?;
+
+        self.add_extras()
+    }
+
+    fn current_active_state(&self) -> UnitActiveState {
+        self.mng.swap_state_to_unit_state()
+    }
+
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Swap)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        SwapConfig::verify(paths, name)
+    }
+
+    fn control_pid(&self) -> Option<Pid> {
+        self.mng.control_pid()
+    }
+
+    fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
+        self.comm.attach_unit(unit);
+        self.db_insert();
+    }
+
+    fn init(&self) {}
+
+    fn done(&self) {}
+
+    fn dump(&self) {}
+
+    fn start(&self) -> Result<()> {
+        log::info!("Activating swap {}", self.comm.get_owner_id());
+        let started = self.mng.start_check()?;
+        if started {
+            log::debug!("{} is being activated, skipping.", self.comm.get_owner_id());
+            return Ok(());
+        }
+
+        self.mng.start_action()
+    }
+
+    fn stop(&self, _force: bool) -> Result<()> {
+        self.mng.stop_action()?;
+        Ok(())
+    }
+
+    fn release_resources(&self) {}
+
+    fn sigchld_events(&self, wait_status: WaitStatus) {
+        self.mng.sigchld_event(wait_status);
+    }
+
+    fn reset_failed(&self) {
+        self.mng.reset_failed()
+    }
+
+    fn update_swap_state_by_proc_swaps(&self) {
+        self.mng.update_swap_state_by_proc_swaps()
+    }
+}
+
+impl UnitMngUtil for SwapUnit {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+use core::declare_unitobj_plugin_with_param;
+declare_unitobj_plugin_with_param!(SwapUnit, SwapUnit::new);
@@ -18,6 +18,7 @@ use basic::time::USEC_INFINITY;
 use core::error::*;
 use core::rel::ReStation;
 use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
 use unit_parser::prelude::UnitConfig;
 
 #[derive(Default, Clone)]
@@ -220,6 +221,11 @@ impl TimerConfig {
         self.data.clone()
     }
 
+    /// check the configuration files strictly without loading them
+    pub(super) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        TimerConfigData::verify_config_located(paths, name)
+    }
+
     pub(super) fn set_unit_ref(&self, unit: String) {
         self.set_ref(unit);
         self.db_update();
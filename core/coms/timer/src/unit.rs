@@ -20,10 +20,11 @@ use crate::{
 use core::{
     error::*,
     rel::{ReStation, Reliability},
-    unit::{SubUnit, UnitActiveState, UnitBase, UnitMngUtil},
+    unit::{section_properties, SubUnit, UnitActiveState, UnitBase, UnitMngUtil},
     UmIf,
 };
 use std::{any::Any, path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
 
 struct TimerUnit {
     comm: Rc<TimerUnitComm>,
@@ -104,6 +105,14 @@ impl SubUnit for TimerUnit {
         self.mng.state().to_string()
     }
 
+    fn get_properties(&self) -> Vec<(String, String)> {
+        section_properties(&self.config.config_data().borrow().Timer)
+    }
+
+    fn verify_config(&self, paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        TimerConfig::verify(paths, name)
+    }
+
     fn attach_unit(&self, unit: Rc<dyn UnitBase>) {
         self.comm.attach_unit(unit);
         self.db_insert();
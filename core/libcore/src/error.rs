@@ -123,6 +123,10 @@ pub enum Error {
     Internal,
     NotSupported,
     BadRequest,
+    #[snafu(display("Transaction order is cyclic: {}", cycle))]
+    OrderingCycle {
+        cycle: String,
+    },
 
     /// events error
     #[snafu(display("event error; '{}'.", msg))]
@@ -209,6 +213,7 @@ impl From<Error> for nix::Error {
             Error::Internal => nix::Error::EIO,
             Error::NotSupported => nix::Error::ENOTSUP,
             Error::BadRequest => nix::Error::EBADR,
+            Error::OrderingCycle { cycle: _ } => nix::Error::EDEADLK,
             Error::Timeout => nix::Error::ETIMEDOUT,
             Error::NoCmdFound => nix::Error::ENOENT,
             Error::SpawnError => nix::Error::EIO,
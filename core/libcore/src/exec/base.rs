@@ -10,6 +10,10 @@
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
 
+use super::privilege::Privileges;
+use super::sandbox::Sandbox;
+use super::seccomp::Seccomp;
+use super::stdio::ExecStdio;
 use crate::error::*;
 use basic::fs::{path_is_abosolute, path_length_is_valid, path_name_is_safe, path_simplify};
 use basic::rlimit;
@@ -317,6 +321,10 @@ pub struct ExecContext {
     group: RefCell<Option<Group>>,
     umask: RefCell<Option<Mode>>,
     selinux_context: RefCell<Option<String>>,
+    sandbox: RefCell<Sandbox>,
+    privileges: RefCell<Privileges>,
+    seccomp: RefCell<Seccomp>,
+    stdio: RefCell<ExecStdio>,
 }
 
 impl Default for ExecContext {
@@ -448,6 +456,10 @@ impl ExecContext {
             group: RefCell::new(None),
             umask: RefCell::new(None),
             selinux_context: RefCell::new(None),
+            sandbox: RefCell::new(Sandbox::default()),
+            privileges: RefCell::new(Privileges::default()),
+            seccomp: RefCell::new(Seccomp::default()),
+            stdio: RefCell::new(ExecStdio::default()),
         }
     }
 
@@ -662,6 +674,46 @@ impl ExecContext {
     pub fn selinux_context(&self) -> Option<String> {
         self.selinux_context.borrow().clone()
     }
+
+    /// set the mount namespace sandbox of the exec context
+    pub fn set_sandbox(&self, sandbox: Sandbox) {
+        *self.sandbox.borrow_mut() = sandbox;
+    }
+
+    /// the mount namespace sandbox of the exec context
+    pub fn sandbox(&self) -> Sandbox {
+        self.sandbox.borrow().clone()
+    }
+
+    /// set the privilege restrictions of the exec context
+    pub fn set_privileges(&self, privileges: Privileges) {
+        *self.privileges.borrow_mut() = privileges;
+    }
+
+    /// the privilege restrictions of the exec context
+    pub fn privileges(&self) -> Privileges {
+        self.privileges.borrow().clone()
+    }
+
+    /// set the system call filter of the exec context
+    pub fn set_seccomp(&self, seccomp: Seccomp) {
+        *self.seccomp.borrow_mut() = seccomp;
+    }
+
+    /// the system call filter of the exec context
+    pub fn seccomp(&self) -> Seccomp {
+        self.seccomp.borrow().clone()
+    }
+
+    /// set the standard input and output of the exec context
+    pub fn set_stdio(&self, stdio: ExecStdio) {
+        *self.stdio.borrow_mut() = stdio;
+    }
+
+    /// the standard input and output of the exec context
+    pub fn stdio(&self) -> ExecStdio {
+        self.stdio.borrow().clone()
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
@@ -683,11 +735,14 @@ pub enum ExecDirectoryType {
 pub struct ExecParameters {
     environment: Rc<EnvData>,
     fds: Vec<i32>,
+    fd_names: Vec<String>,
     notify_sock: Option<PathBuf>,
     cgroup_path: Option<PathBuf>,
     watchdog_usec: u64,
     flags: ExecFlags,
     nonblock: bool,
+    stdio_fd: Option<i32>,
+    private_tmp: Option<PathBuf>,
 }
 
 struct EnvData {
@@ -732,11 +787,14 @@ impl ExecParameters {
         ExecParameters {
             environment: Rc::new(EnvData::new()),
             fds: Vec::new(),
+            fd_names: Vec::new(),
             notify_sock: None,
             cgroup_path: None,
             watchdog_usec: 0,
             flags: ExecFlags::CONTROL,
             nonblock: false,
+            stdio_fd: None,
+            private_tmp: None,
         }
     }
 
@@ -775,6 +833,16 @@ impl ExecParameters {
         self.fds.to_vec()
     }
 
+    /// insert the names of the fds that will be passed to child, in the same order as the fds
+    pub fn insert_fd_names(&mut self, fd_names: Vec<String>) {
+        self.fd_names = fd_names
+    }
+
+    /// return the names of the fds that will be passed to child
+    pub fn fd_names(&self) -> Vec<String> {
+        self.fd_names.to_vec()
+    }
+
     /// set the NOTIFY_SOCKET value
     pub fn set_notify_sock(&mut self, notify_sock: PathBuf) {
         self.notify_sock = Some(notify_sock)
@@ -809,6 +877,26 @@ impl ExecParameters {
     pub fn cgroup_path(&self) -> Option<PathBuf> {
         self.cgroup_path.clone()
     }
+
+    /// set the socket fd used by StandardInput=socket and StandardOutput=socket
+    pub fn set_stdio_fd(&mut self, fd: i32) {
+        self.stdio_fd = Some(fd);
+    }
+
+    /// get the socket fd used by StandardInput=socket and StandardOutput=socket
+    pub fn stdio_fd(&self) -> Option<i32> {
+        self.stdio_fd
+    }
+
+    /// set the private tmp directory created by PrivateTmp=yes
+    pub fn set_private_tmp(&mut self, private_tmp: PathBuf) {
+        self.private_tmp = Some(private_tmp);
+    }
+
+    /// get the private tmp directory created by PrivateTmp=yes
+    pub fn private_tmp(&self) -> Option<PathBuf> {
+        self.private_tmp.clone()
+    }
 }
 
 bitflags! {
@@ -13,6 +13,10 @@
 //! execute module
 mod base;
 mod cmd;
+mod privilege;
+mod sandbox;
+mod seccomp;
+mod stdio;
 pub use base::{
     parse_environment, parse_mode, parse_runtime_directory, parse_state_directory,
     parse_working_directory,
@@ -24,3 +28,15 @@ pub use base::{
 pub use cmd::parse_exec_command;
 pub use cmd::ExecCommand;
 pub use cmd::ExecFlag;
+pub use privilege::{
+    capabilities_mask, parse_capabilities, CapabilityList, Privileges, SecureBits,
+};
+pub use sandbox::{
+    is_private_tmp, parse_bind_paths, parse_sandbox_paths, private_tmp_mounts, private_var_tmp,
+    setup_private_tmp, BindPath, ProtectHome, ProtectSystem, Sandbox, SandboxPath,
+};
+pub use seccomp::{
+    parse_syscall_architectures, parse_syscall_error_number, parse_syscall_filter,
+    syscall_filter_merge, Seccomp, SyscallFilter, SyscallFilterList,
+};
+pub use stdio::{ExecInput, ExecOutput, ExecStdio, DEFAULT_TTY_PATH};
@@ -0,0 +1,199 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use crate::error::*;
+use basic::capability::{all_capabilities, Capability};
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use unit_parser::prelude::UnitEntry;
+
+/// one assignment of CapabilityBoundingSet or AmbientCapabilities
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CapabilityList {
+    mask: u64,
+    invert: bool,
+}
+
+impl CapabilityList {
+    /// the capabilities listed in the assignment
+    pub fn mask(&self) -> u64 {
+        self.mask
+    }
+
+    /// the assignment is prefixed with "~", the listed capabilities are removed
+    pub fn invert(&self) -> bool {
+        self.invert
+    }
+}
+
+fn parse_capability(s: &str) -> Result<Capability> {
+    let name = s.to_uppercase();
+    let name = name.strip_prefix("CAP_").unwrap_or(&name);
+    Capability::from_str(name).map_err(|_| Error::ConfigureError {
+        msg: format!("invalid capability: {}", s),
+    })
+}
+
+/// parse the space separated capability names, the list can be prefixed with "~"
+pub fn parse_capabilities(s: &str) -> Result<Vec<CapabilityList>> {
+    let s = s.trim();
+    let (list, invert) = match s.strip_prefix('~') {
+        None => (s, false),
+        Some(v) => (v, true),
+    };
+
+    let mut mask = 0;
+    for word in list.split_whitespace() {
+        mask |= parse_capability(word)?.bitmask();
+    }
+    Ok(vec![CapabilityList { mask, invert }])
+}
+
+/// merge the assignments into a capability bitmask, return None if nothing is configured
+///
+/// The assignments are merged in order: a normal assignment adds the capabilities,
+/// an inverted one removes them, and an empty assignment resets the set to empty.
+pub fn capabilities_mask(lists: &[CapabilityList]) -> Option<u64> {
+    let mut res: Option<u64> = None;
+    for list in lists {
+        res = if list.invert {
+            Some(res.unwrap_or_else(all_capabilities) & !list.mask)
+        } else if list.mask == 0 {
+            Some(0)
+        } else {
+            Some(res.unwrap_or(0) | list.mask)
+        };
+    }
+    res
+}
+
+bitflags! {
+    /// the securebits of the executed processes, see capabilities(7)
+    #[derive(Serialize, Deserialize)]
+    pub struct SecureBits: u32 {
+        /// SECBIT_NOROOT
+        const NOROOT = 1 << 0;
+        /// SECBIT_NOROOT_LOCKED
+        const NOROOT_LOCKED = 1 << 1;
+        /// SECBIT_NO_SETUID_FIXUP
+        const NO_SETUID_FIXUP = 1 << 2;
+        /// SECBIT_NO_SETUID_FIXUP_LOCKED
+        const NO_SETUID_FIXUP_LOCKED = 1 << 3;
+        /// SECBIT_KEEP_CAPS
+        const KEEP_CAPS = 1 << 4;
+        /// SECBIT_KEEP_CAPS_LOCKED
+        const KEEP_CAPS_LOCKED = 1 << 5;
+    }
+}
+
+impl Default for SecureBits {
+    fn default() -> Self {
+        SecureBits::empty()
+    }
+}
+
+impl UnitEntry for SecureBits {
+    type Error = Error;
+
+    fn parse_from_str<S: AsRef<str>>(input: S) -> std::result::Result<Self, Self::Error> {
+        let mut res = SecureBits::empty();
+        for word in input.as_ref().split_whitespace() {
+            res |= match word {
+                "noroot" => SecureBits::NOROOT,
+                "noroot-locked" => SecureBits::NOROOT_LOCKED,
+                "no-setuid-fixup" => SecureBits::NO_SETUID_FIXUP,
+                "no-setuid-fixup-locked" => SecureBits::NO_SETUID_FIXUP_LOCKED,
+                "keep-caps" => SecureBits::KEEP_CAPS,
+                "keep-caps-locked" => SecureBits::KEEP_CAPS_LOCKED,
+                _ => {
+                    return Err(Error::ConfigureError {
+                        msg: format!("invalid secure bit: {}", word),
+                    })
+                }
+            };
+        }
+        Ok(res)
+    }
+}
+
+/// the privileges of the executed processes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Privileges {
+    /// the capabilities kept in the bounding set, None means not limited
+    pub capability_bounding_set: Option<u64>,
+    /// the capabilities raised to the ambient set
+    pub ambient_capabilities: u64,
+    /// set PR_SET_NO_NEW_PRIVS before executing the command
+    pub no_new_privileges: bool,
+    /// the securebits set before executing the command
+    pub secure_bits: SecureBits,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_capabilities() {
+        let res = parse_capabilities("CAP_NET_BIND_SERVICE cap_net_raw").unwrap();
+        assert_eq!(res.len(), 1);
+        assert!(!res[0].invert());
+        assert_eq!(
+            res[0].mask(),
+            Capability::NET_BIND_SERVICE.bitmask() | Capability::NET_RAW.bitmask()
+        );
+
+        let res = parse_capabilities("~CAP_SYS_ADMIN").unwrap();
+        assert!(res[0].invert());
+        assert_eq!(res[0].mask(), Capability::SYS_ADMIN.bitmask());
+
+        assert!(parse_capabilities("CAP_FOO").is_err());
+    }
+
+    #[test]
+    fn test_capabilities_mask() {
+        assert_eq!(capabilities_mask(&[]), None);
+
+        let mut lists = parse_capabilities("CAP_NET_BIND_SERVICE").unwrap();
+        lists.append(&mut parse_capabilities("CAP_NET_RAW").unwrap());
+        assert_eq!(
+            capabilities_mask(&lists),
+            Some(Capability::NET_BIND_SERVICE.bitmask() | Capability::NET_RAW.bitmask())
+        );
+
+        lists.append(&mut parse_capabilities("~CAP_NET_RAW").unwrap());
+        assert_eq!(
+            capabilities_mask(&lists),
+            Some(Capability::NET_BIND_SERVICE.bitmask())
+        );
+
+        lists.append(&mut parse_capabilities("").unwrap());
+        assert_eq!(capabilities_mask(&lists), Some(0));
+
+        let lists = parse_capabilities("~CAP_SYS_ADMIN").unwrap();
+        assert_eq!(
+            capabilities_mask(&lists),
+            Some(all_capabilities() & !Capability::SYS_ADMIN.bitmask())
+        );
+    }
+
+    #[test]
+    fn test_parse_secure_bits() {
+        assert_eq!(
+            SecureBits::parse_from_str("keep-caps noroot-locked").unwrap(),
+            SecureBits::KEEP_CAPS | SecureBits::NOROOT_LOCKED
+        );
+        assert_eq!(SecureBits::parse_from_str("").unwrap(), SecureBits::empty());
+        assert!(SecureBits::parse_from_str("keep").is_err());
+    }
+}
@@ -0,0 +1,380 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use crate::error::*;
+use basic::fs::{path_is_abosolute, path_simplify};
+use nix::fcntl::OFlag;
+use nix::sys::stat::{fchmod, fstat, Mode, SFlag};
+use serde::{Deserialize, Serialize};
+use std::ffi::{CString, OsString};
+use std::fs::{self, DirBuilder, File, Permissions};
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::prelude::{FromRawFd, OsStringExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use unit_parser::prelude::UnitEntry;
+
+/// the root-owned directory which the private /tmp of the units are created in
+const PRIVATE_TMP_ROOT: &str = "/run/sysmaster/private-tmp";
+
+/// the private /var/tmp of the units are created in /var/tmp, so they are kept on the disk
+const PRIVATE_VAR_TMP_ROOT: &str = "/var/tmp";
+
+/// the prefix of the private /var/tmp directories in PRIVATE_VAR_TMP_ROOT
+const PRIVATE_VAR_TMP_PREFIX: &str = "sysmaster-private-";
+
+/// make the os directories read-only for the executed processes
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ProtectSystem {
+    /// no protection
+    No,
+    /// /usr, /boot and /efi are read-only
+    Yes,
+    /// /etc is read-only too
+    Full,
+    /// the whole file system is read-only, except the API file systems
+    Strict,
+}
+
+impl Default for ProtectSystem {
+    fn default() -> Self {
+        ProtectSystem::No
+    }
+}
+
+impl UnitEntry for ProtectSystem {
+    type Error = Error;
+
+    fn parse_from_str<S: AsRef<str>>(input: S) -> std::result::Result<Self, Self::Error> {
+        match input.as_ref() {
+            "full" => Ok(ProtectSystem::Full),
+            "strict" => Ok(ProtectSystem::Strict),
+            s => match basic::config::parse_boolean(s)? {
+                true => Ok(ProtectSystem::Yes),
+                false => Ok(ProtectSystem::No),
+            },
+        }
+    }
+}
+
+/// protect the home directories from the executed processes
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ProtectHome {
+    /// no protection
+    No,
+    /// /home, /root and /run/user are inaccessible
+    Yes,
+    /// /home, /root and /run/user are read-only
+    ReadOnly,
+    /// an empty read-only tmpfs is mounted on /home, /root and /run/user
+    Tmpfs,
+}
+
+impl Default for ProtectHome {
+    fn default() -> Self {
+        ProtectHome::No
+    }
+}
+
+impl UnitEntry for ProtectHome {
+    type Error = Error;
+
+    fn parse_from_str<S: AsRef<str>>(input: S) -> std::result::Result<Self, Self::Error> {
+        match input.as_ref() {
+            "read-only" => Ok(ProtectHome::ReadOnly),
+            "tmpfs" => Ok(ProtectHome::Tmpfs),
+            s => match basic::config::parse_boolean(s)? {
+                true => Ok(ProtectHome::Yes),
+                false => Ok(ProtectHome::No),
+            },
+        }
+    }
+}
+
+/// the path configured in ReadOnlyPaths and InaccessiblePaths
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SandboxPath {
+    path: PathBuf,
+    ignore_missing: bool,
+}
+
+impl SandboxPath {
+    /// the absolute path
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// the path is prefixed with "-", don't fail if it doesn't exist
+    pub fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+}
+
+pub(super) fn parse_absolute_path(s: &str) -> Result<PathBuf> {
+    if !path_is_abosolute(s) {
+        return Err(Error::ConfigureError {
+            msg: format!("path is not absolute: {}", s),
+        });
+    }
+
+    match path_simplify(s) {
+        None => Err(Error::ConfigureError {
+            msg: format!("invalid path: {}", s),
+        }),
+        Some(v) => Ok(PathBuf::from(v)),
+    }
+}
+
+/// parse the space separated absolute paths, the path can be prefixed with "-"
+pub fn parse_sandbox_paths(s: &str) -> Result<Vec<SandboxPath>> {
+    let mut res = Vec::new();
+    for word in s.split_whitespace() {
+        let (path, ignore_missing) = match word.strip_prefix('-') {
+            None => (word, false),
+            Some(v) => (v, true),
+        };
+        res.push(SandboxPath {
+            path: parse_absolute_path(path)?,
+            ignore_missing,
+        });
+    }
+    Ok(res)
+}
+
+/// the path configured in BindPaths and BindReadOnlyPaths
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BindPath {
+    source: PathBuf,
+    destination: PathBuf,
+    ignore_missing: bool,
+}
+
+impl BindPath {
+    /// the path on the host
+    pub fn source(&self) -> PathBuf {
+        self.source.clone()
+    }
+
+    /// the path which the source is mounted on
+    pub fn destination(&self) -> PathBuf {
+        self.destination.clone()
+    }
+
+    /// the source is prefixed with "-", don't fail if it doesn't exist
+    pub fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+}
+
+/// parse the space separated "SOURCE[:DESTINATION]" pairs, the source can be prefixed with "-"
+pub fn parse_bind_paths(s: &str) -> Result<Vec<BindPath>> {
+    let mut res = Vec::new();
+    for word in s.split_whitespace() {
+        let (word, ignore_missing) = match word.strip_prefix('-') {
+            None => (word, false),
+            Some(v) => (v, true),
+        };
+        let (source, destination) = match word.split_once(':') {
+            None => (word, word),
+            Some(v) => v,
+        };
+        res.push(BindPath {
+            source: parse_absolute_path(source)?,
+            destination: parse_absolute_path(destination)?,
+            ignore_missing,
+        });
+    }
+    Ok(res)
+}
+
+/// the mount namespace sandbox of the executed processes
+#[derive(Clone, Debug, Default)]
+pub struct Sandbox {
+    /// ProtectSystem=
+    pub protect_system: ProtectSystem,
+    /// ProtectHome=
+    pub protect_home: ProtectHome,
+    /// PrivateTmp=
+    pub private_tmp: bool,
+    /// ReadOnlyPaths=
+    pub read_only_paths: Vec<SandboxPath>,
+    /// InaccessiblePaths=
+    pub inaccessible_paths: Vec<SandboxPath>,
+    /// BindPaths=
+    pub bind_paths: Vec<BindPath>,
+    /// BindReadOnlyPaths=
+    pub bind_read_only_paths: Vec<BindPath>,
+}
+
+impl Sandbox {
+    /// whether a private mount namespace is needed to apply the sandbox
+    pub fn needs_mount_namespace(&self) -> bool {
+        self.protect_system != ProtectSystem::No
+            || self.protect_home != ProtectHome::No
+            || self.private_tmp
+            || !self.read_only_paths.is_empty()
+            || !self.inaccessible_paths.is_empty()
+            || !self.bind_paths.is_empty()
+            || !self.bind_read_only_paths.is_empty()
+    }
+}
+
+/// create the private /tmp and /var/tmp of the unit with a random name, the returned
+/// directory is shared by all the processes of the unit and removed when the unit stops,
+/// together with the private /var/tmp named after it, see private_var_tmp().
+pub fn setup_private_tmp(unit_id: &str) -> Result<PathBuf> {
+    let root = Path::new(PRIVATE_TMP_ROOT);
+    fs::create_dir_all(root).context(IoSnafu)?;
+
+    /* Never follow a symlink planted in place of the root directory, and make sure
+     * nobody but root can get into it before creating the unit directory. */
+    let fd = nix::fcntl::open(
+        root,
+        OFlag::O_DIRECTORY | OFlag::O_CLOEXEC | OFlag::O_NOFOLLOW,
+        Mode::empty(),
+    )
+    .context(NixSnafu)?;
+    /* closed when leaving the scope */
+    let _root_dir = unsafe { File::from_raw_fd(fd) };
+    let st = fstat(fd).context(NixSnafu)?;
+    if st.st_uid != 0 || SFlag::from_bits_truncate(st.st_mode) & SFlag::S_IFMT != SFlag::S_IFDIR {
+        return Err(Error::Other {
+            msg: format!("{} is not a directory owned by root", PRIVATE_TMP_ROOT),
+        });
+    }
+    fchmod(fd, Mode::from_bits_truncate(0o700)).context(NixSnafu)?;
+
+    /* mkdtemp() creates the directory with 0700 and fails if the name exists. */
+    let template =
+        CString::new(format!("{}/{}-XXXXXX", PRIVATE_TMP_ROOT, unit_id)).map_err(|_| {
+            Error::InvalidName {
+                what: unit_id.to_string(),
+            }
+        })?;
+    let raw = template.into_raw();
+    let ret = unsafe { libc::mkdtemp(raw) };
+    let template = unsafe { CString::from_raw(raw) };
+    if ret.is_null() {
+        return Err(Error::Nix {
+            source: nix::Error::last(),
+        });
+    }
+    let dir = PathBuf::from(OsString::from_vec(template.into_bytes()));
+
+    /* The name is random and only known to root, creating the directory in the world
+     * writable /var/tmp fails if somebody planted it in advance. */
+    let var_dir = private_var_tmp(&dir);
+    if let Err(e) = DirBuilder::new().mode(0o700).create(&var_dir) {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(Error::Io { source: e });
+    }
+
+    for (source, _) in private_tmp_mounts(&dir) {
+        if let Err(e) = fs::create_dir(&source)
+            .and_then(|_| fs::set_permissions(&source, Permissions::from_mode(0o1777)))
+        {
+            let _ = fs::remove_dir_all(&dir);
+            let _ = fs::remove_dir_all(&var_dir);
+            return Err(Error::Io { source: e });
+        }
+    }
+    Ok(dir)
+}
+
+/// return the private /var/tmp of the private tmp directory, it's in /var/tmp rather than
+/// in the tmpfs, i.e. /var/tmp/sysmaster-private-foo.service-a1b2c3
+pub fn private_var_tmp(dir: &Path) -> PathBuf {
+    let name = dir.file_name().unwrap_or_default().to_string_lossy();
+    Path::new(PRIVATE_VAR_TMP_ROOT).join(format!("{}{}", PRIVATE_VAR_TMP_PREFIX, name))
+}
+
+/// return the directories in the private tmp directories and the paths they are mounted on
+pub fn private_tmp_mounts(dir: &Path) -> Vec<(PathBuf, PathBuf)> {
+    vec![
+        (dir.join("tmp"), PathBuf::from("/tmp")),
+        (private_var_tmp(dir).join("tmp"), PathBuf::from("/var/tmp")),
+    ]
+}
+
+/// whether the directory is created by setup_private_tmp, only these are removed
+pub fn is_private_tmp(dir: &Path) -> bool {
+    dir.parent() == Some(Path::new(PRIVATE_TMP_ROOT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_protect() {
+        assert_eq!(
+            ProtectSystem::parse_from_str("strict").unwrap(),
+            ProtectSystem::Strict
+        );
+        assert_eq!(
+            ProtectSystem::parse_from_str("yes").unwrap(),
+            ProtectSystem::Yes
+        );
+        assert_eq!(
+            ProtectSystem::parse_from_str("false").unwrap(),
+            ProtectSystem::No
+        );
+        assert!(ProtectSystem::parse_from_str("foo").is_err());
+        assert_eq!(
+            ProtectHome::parse_from_str("read-only").unwrap(),
+            ProtectHome::ReadOnly
+        );
+        assert_eq!(
+            ProtectHome::parse_from_str("tmpfs").unwrap(),
+            ProtectHome::Tmpfs
+        );
+    }
+
+    #[test]
+    fn test_parse_sandbox_paths() {
+        let paths = parse_sandbox_paths("/var/lib -/opt//foo/").unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].path(), PathBuf::from("/var/lib"));
+        assert!(!paths[0].ignore_missing());
+        assert_eq!(paths[1].path(), PathBuf::from("/opt/foo"));
+        assert!(paths[1].ignore_missing());
+        assert!(parse_sandbox_paths("var/lib").is_err());
+    }
+
+    #[test]
+    fn test_parse_bind_paths() {
+        let paths = parse_bind_paths("/srv/data:/data -/run/foo").unwrap();
+        assert_eq!(paths[0].source(), PathBuf::from("/srv/data"));
+        assert_eq!(paths[0].destination(), PathBuf::from("/data"));
+        assert_eq!(paths[1].destination(), PathBuf::from("/run/foo"));
+        assert!(paths[1].ignore_missing());
+        assert!(parse_bind_paths("/srv:data").is_err());
+    }
+
+    #[test]
+    fn test_private_tmp_mounts() {
+        let dir = Path::new(PRIVATE_TMP_ROOT).join("foo.service-a1b2c3");
+        let mounts = private_tmp_mounts(&dir);
+        assert_eq!(mounts[0], (dir.join("tmp"), PathBuf::from("/tmp")));
+        assert_eq!(
+            mounts[1],
+            (
+                PathBuf::from("/var/tmp/sysmaster-private-foo.service-a1b2c3/tmp"),
+                PathBuf::from("/var/tmp")
+            )
+        );
+        assert!(is_private_tmp(&dir));
+        assert!(!is_private_tmp(Path::new(
+            "/tmp/sysmaster-private-foo.service"
+        )));
+        assert!(!is_private_tmp(&dir.join("tmp")));
+    }
+}
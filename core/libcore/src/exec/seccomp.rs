@@ -0,0 +1,259 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use crate::error::*;
+use basic::seccomp::{
+    parse_errno, syscall_expand, syscall_name_is_valid, SeccompAction, SeccompFilter, NATIVE_ARCH,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// one assignment of SystemCallFilter
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyscallFilterList {
+    names: Vec<String>,
+    invert: bool,
+}
+
+impl SyscallFilterList {
+    /// the system calls and groups listed in the assignment
+    pub fn names(&self) -> Vec<String> {
+        self.names.clone()
+    }
+
+    /// the assignment is prefixed with "~", the listed system calls are denied
+    pub fn invert(&self) -> bool {
+        self.invert
+    }
+}
+
+/// parse the space separated system call names and groups, the list can be prefixed with "~"
+pub fn parse_syscall_filter(s: &str) -> Result<Vec<SyscallFilterList>> {
+    let s = s.trim();
+    let (list, invert) = match s.strip_prefix('~') {
+        None => (s, false),
+        Some(v) => (v, true),
+    };
+
+    let mut names = Vec::new();
+    for word in list.split_whitespace() {
+        if !syscall_name_is_valid(word) {
+            return Err(Error::ConfigureError {
+                msg: format!("invalid system call: {}", word),
+            });
+        }
+        names.push(word.to_string());
+    }
+    Ok(vec![SyscallFilterList { names, invert }])
+}
+
+/// parse the errno returned by the denied system calls, the name or the number is accepted
+pub fn parse_syscall_error_number(s: &str) -> Result<i32> {
+    match parse_errno(s.trim()) {
+        None => Err(Error::ConfigureError {
+            msg: format!("invalid errno: {}", s),
+        }),
+        Some(v) => Ok(v),
+    }
+}
+
+/// parse the architectures the system calls are allowed from, only the native one is supported
+pub fn parse_syscall_architectures(s: &str) -> Result<Vec<String>> {
+    let mut res = Vec::new();
+    for word in s.split_whitespace() {
+        if word != "native" && word != NATIVE_ARCH {
+            return Err(Error::ConfigureError {
+                msg: format!("unsupported architecture: {}", word),
+            });
+        }
+        res.push(word.to_string());
+    }
+    Ok(res)
+}
+
+/// the merged SystemCallFilter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyscallFilter {
+    /// only the listed system calls are allowed, or the listed ones are denied
+    pub allow_list: bool,
+    /// the system calls and groups
+    pub names: BTreeSet<String>,
+}
+
+/// merge the assignments of SystemCallFilter, return None if nothing is configured
+///
+/// The first assignment decides whether this is an allow list or a deny list, the
+/// later assignments in the same sense add the system calls, the ones in the other
+/// sense remove them. An empty assignment resets the filter.
+pub fn syscall_filter_merge(lists: &[SyscallFilterList]) -> Option<SyscallFilter> {
+    let mut res: Option<SyscallFilter> = None;
+    for list in lists {
+        if list.names.is_empty() {
+            res = None;
+            continue;
+        }
+        match res.as_mut() {
+            None => {
+                res = Some(SyscallFilter {
+                    allow_list: !list.invert,
+                    names: list.names.iter().cloned().collect(),
+                })
+            }
+            Some(filter) => {
+                if filter.allow_list != list.invert {
+                    filter.names.extend(list.names.iter().cloned());
+                } else {
+                    for name in &list.names {
+                        filter.names.remove(name);
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+/// the seccomp settings of the executed processes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Seccomp {
+    /// SystemCallFilter
+    pub filter: Option<SyscallFilter>,
+    /// SystemCallErrorNumber, the denied system calls kill the process if not set
+    pub error_number: Option<i32>,
+    /// SystemCallArchitectures=native, kill the process calling the non-native system calls
+    pub native_arch_only: bool,
+}
+
+impl Seccomp {
+    /// build the seccomp filter, return None if nothing needs to be filtered
+    pub fn build(&self) -> Option<SeccompFilter> {
+        if self.filter.is_none() && !self.native_arch_only {
+            return None;
+        }
+
+        let deny_action = match self.error_number {
+            None => SeccompAction::KillProcess,
+            Some(e) => SeccompAction::Errno(e),
+        };
+
+        let (match_action, default_action, names) = match &self.filter {
+            None => (SeccompAction::Allow, SeccompAction::Allow, BTreeSet::new()),
+            Some(filter) if filter.allow_list => {
+                /* The basic system calls are needed to execute the command at all. */
+                let mut names = filter.names.clone();
+                names.insert("@default".to_string());
+                (SeccompAction::Allow, deny_action, names)
+            }
+            Some(filter) => (deny_action, SeccompAction::Allow, filter.names.clone()),
+        };
+
+        /* The non-native system calls can't be matched by the numbers, they would
+         * bypass a deny list if they were allowed, so deny them for both lists. */
+        let arch_action = match (&self.filter, self.native_arch_only) {
+            (_, true) => SeccompAction::KillProcess,
+            (Some(_), false) => deny_action,
+            (None, false) => SeccompAction::Allow,
+        };
+
+        let mut seccomp_filter = SeccompFilter::new(match_action, default_action, arch_action);
+        seccomp_filter.add_syscalls(&syscall_expand(&names));
+        Some(seccomp_filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_syscall_filter() {
+        let res = parse_syscall_filter("@system-service ioctl").unwrap();
+        assert_eq!(res.len(), 1);
+        assert!(!res[0].invert());
+        assert_eq!(
+            res[0].names(),
+            vec!["@system-service".to_string(), "ioctl".to_string()]
+        );
+
+        let res = parse_syscall_filter("~@mount reboot").unwrap();
+        assert!(res[0].invert());
+
+        assert!(parse_syscall_filter("foo").is_err());
+        assert!(parse_syscall_filter("@foo").is_err());
+    }
+
+    #[test]
+    fn test_syscall_filter_merge() {
+        assert_eq!(syscall_filter_merge(&[]), None);
+
+        let mut lists = parse_syscall_filter("@system-service").unwrap();
+        lists.append(&mut parse_syscall_filter("~ioctl").unwrap());
+        lists.append(&mut parse_syscall_filter("ioctl mount").unwrap());
+        lists.append(&mut parse_syscall_filter("~mount").unwrap());
+        let res = syscall_filter_merge(&lists).unwrap();
+        assert!(res.allow_list);
+        assert_eq!(
+            res.names,
+            ["@system-service", "ioctl"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect()
+        );
+
+        lists.append(&mut parse_syscall_filter("").unwrap());
+        lists.append(&mut parse_syscall_filter("~reboot").unwrap());
+        let res = syscall_filter_merge(&lists).unwrap();
+        assert!(!res.allow_list);
+        assert_eq!(res.names, ["reboot".to_string()].into_iter().collect());
+    }
+
+    #[test]
+    fn test_parse_syscall_error_number() {
+        assert_eq!(parse_syscall_error_number("EPERM").unwrap(), libc::EPERM);
+        assert_eq!(parse_syscall_error_number("1").unwrap(), 1);
+        assert!(parse_syscall_error_number("EFOO").is_err());
+    }
+
+    #[test]
+    fn test_parse_syscall_architectures() {
+        assert_eq!(
+            parse_syscall_architectures("native").unwrap(),
+            vec!["native".to_string()]
+        );
+        assert!(parse_syscall_architectures("foo").is_err());
+    }
+
+    #[test]
+    fn test_seccomp_build() {
+        assert!(Seccomp::default().build().is_none());
+
+        let seccomp = Seccomp {
+            filter: None,
+            error_number: None,
+            native_arch_only: true,
+        };
+        assert!(seccomp.build().is_some());
+
+        /* the non-native system calls are denied for the deny list too */
+        let mut seccomp = Seccomp {
+            filter: syscall_filter_merge(&parse_syscall_filter("~reboot").unwrap()),
+            error_number: None,
+            native_arch_only: false,
+        };
+        let filter = seccomp.build().unwrap();
+        assert_eq!(filter.arch_action(), SeccompAction::KillProcess);
+
+        seccomp.error_number = Some(libc::EPERM);
+        let filter = seccomp.build().unwrap();
+        assert_eq!(filter.arch_action(), SeccompAction::Errno(libc::EPERM));
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use super::sandbox::parse_absolute_path;
+use crate::error::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use unit_parser::prelude::UnitEntry;
+
+/// the default terminal used by StandardInput=tty and StandardOutput=tty
+pub const DEFAULT_TTY_PATH: &str = "/dev/console";
+
+/// where the standard input of the executed processes is connected to
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ExecInput {
+    /// /dev/null
+    Null,
+    /// the terminal configured by TTYPath, become its controlling process
+    Tty,
+    /// like Tty, but steal the terminal from other processes
+    TtyForce,
+    /// the connection socket of the socket unit with Accept=yes
+    Socket,
+    /// the file opened for reading
+    File(PathBuf),
+    /// the text followed by a newline
+    Data(String),
+}
+
+impl Default for ExecInput {
+    fn default() -> Self {
+        ExecInput::Null
+    }
+}
+
+impl ExecInput {
+    /// the input is connected to the terminal
+    pub fn is_tty(&self) -> bool {
+        matches!(self, ExecInput::Tty | ExecInput::TtyForce)
+    }
+}
+
+impl UnitEntry for ExecInput {
+    type Error = Error;
+
+    fn parse_from_str<S: AsRef<str>>(input: S) -> std::result::Result<Self, Self::Error> {
+        let s = input.as_ref();
+        if let Some(path) = s.strip_prefix("file:") {
+            return Ok(ExecInput::File(parse_absolute_path(path)?));
+        }
+        if let Some(data) = s.strip_prefix("data:") {
+            return Ok(ExecInput::Data(data.to_string()));
+        }
+        match s {
+            "null" => Ok(ExecInput::Null),
+            "tty" => Ok(ExecInput::Tty),
+            "tty-force" => Ok(ExecInput::TtyForce),
+            "socket" => Ok(ExecInput::Socket),
+            _ => Err(Error::ConfigureError {
+                msg: format!("invalid StandardInput: {}", s),
+            }),
+        }
+    }
+}
+
+/// where the standard output or error of the executed processes is connected to
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ExecOutput {
+    /// the terminal if the input is a terminal, otherwise what sysmaster passes,
+    /// for the standard error, the same as the standard output
+    Inherit,
+    /// /dev/null
+    Null,
+    /// the terminal configured by TTYPath
+    Tty,
+    /// the kernel log buffer
+    Kmsg,
+    /// the log collector, fall back to kmsg if it is not running
+    Log,
+    /// the connection socket of the socket unit with Accept=yes
+    Socket,
+    /// the file opened for writing from the beginning, without truncating
+    File(PathBuf),
+    /// the file opened for appending
+    Append(PathBuf),
+    /// the file truncated before writing
+    Truncate(PathBuf),
+}
+
+impl Default for ExecOutput {
+    fn default() -> Self {
+        ExecOutput::Inherit
+    }
+}
+
+impl UnitEntry for ExecOutput {
+    type Error = Error;
+
+    fn parse_from_str<S: AsRef<str>>(input: S) -> std::result::Result<Self, Self::Error> {
+        let s = input.as_ref();
+        if let Some(path) = s.strip_prefix("file:") {
+            return Ok(ExecOutput::File(parse_absolute_path(path)?));
+        }
+        if let Some(path) = s.strip_prefix("append:") {
+            return Ok(ExecOutput::Append(parse_absolute_path(path)?));
+        }
+        if let Some(path) = s.strip_prefix("truncate:") {
+            return Ok(ExecOutput::Truncate(parse_absolute_path(path)?));
+        }
+        match s {
+            "inherit" => Ok(ExecOutput::Inherit),
+            "null" => Ok(ExecOutput::Null),
+            "tty" => Ok(ExecOutput::Tty),
+            "kmsg" => Ok(ExecOutput::Kmsg),
+            "log" => Ok(ExecOutput::Log),
+            "socket" => Ok(ExecOutput::Socket),
+            _ => Err(Error::ConfigureError {
+                msg: format!("invalid StandardOutput or StandardError: {}", s),
+            }),
+        }
+    }
+}
+
+/// the standard input, output and error of the executed processes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecStdio {
+    /// StandardInput
+    pub input: ExecInput,
+    /// StandardOutput
+    pub output: ExecOutput,
+    /// StandardError
+    pub error: ExecOutput,
+    /// TTYPath
+    pub tty_path: PathBuf,
+}
+
+impl Default for ExecStdio {
+    fn default() -> Self {
+        ExecStdio {
+            input: ExecInput::default(),
+            output: ExecOutput::default(),
+            error: ExecOutput::default(),
+            tty_path: PathBuf::from(DEFAULT_TTY_PATH),
+        }
+    }
+}
+
+impl ExecStdio {
+    /// any of the standard input, output and error is connected to the socket
+    pub fn uses_socket(&self) -> bool {
+        self.input == ExecInput::Socket
+            || self.output == ExecOutput::Socket
+            || self.error == ExecOutput::Socket
+    }
+
+    /// any of the standard input, output and error is connected to the terminal
+    pub fn uses_tty(&self) -> bool {
+        self.input.is_tty() || self.output == ExecOutput::Tty || self.error == ExecOutput::Tty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exec_input() {
+        assert_eq!(ExecInput::parse_from_str("null").unwrap(), ExecInput::Null);
+        assert_eq!(ExecInput::parse_from_str("tty").unwrap(), ExecInput::Tty);
+        assert_eq!(
+            ExecInput::parse_from_str("socket").unwrap(),
+            ExecInput::Socket
+        );
+        assert_eq!(
+            ExecInput::parse_from_str("data:hello world").unwrap(),
+            ExecInput::Data("hello world".to_string())
+        );
+        assert_eq!(
+            ExecInput::parse_from_str("file:/etc/foo").unwrap(),
+            ExecInput::File(PathBuf::from("/etc/foo"))
+        );
+        assert!(ExecInput::parse_from_str("file:foo").is_err());
+        assert!(ExecInput::parse_from_str("kmsg").is_err());
+    }
+
+    #[test]
+    fn test_parse_exec_output() {
+        assert_eq!(
+            ExecOutput::parse_from_str("inherit").unwrap(),
+            ExecOutput::Inherit
+        );
+        assert_eq!(
+            ExecOutput::parse_from_str("kmsg").unwrap(),
+            ExecOutput::Kmsg
+        );
+        assert_eq!(ExecOutput::parse_from_str("log").unwrap(), ExecOutput::Log);
+        assert_eq!(
+            ExecOutput::parse_from_str("append:/var/log/foo.log").unwrap(),
+            ExecOutput::Append(PathBuf::from("/var/log/foo.log"))
+        );
+        assert_eq!(
+            ExecOutput::parse_from_str("truncate:/var/log//foo.log").unwrap(),
+            ExecOutput::Truncate(PathBuf::from("/var/log/foo.log"))
+        );
+        assert!(ExecOutput::parse_from_str("append:foo.log").is_err());
+        assert!(ExecOutput::parse_from_str("data:foo").is_err());
+    }
+
+    #[test]
+    fn test_exec_stdio() {
+        let stdio = ExecStdio::default();
+        assert!(!stdio.uses_socket());
+        assert!(!stdio.uses_tty());
+        assert_eq!(stdio.tty_path, PathBuf::from(DEFAULT_TTY_PATH));
+
+        let stdio = ExecStdio {
+            input: ExecInput::Socket,
+            output: ExecOutput::Socket,
+            ..Default::default()
+        };
+        assert!(stdio.uses_socket());
+    }
+}
@@ -14,6 +14,7 @@ use super::Reliability;
 use crate::error::*;
 use heed::types::SerdeBincode;
 use heed::{Database, Env, RoTxn, RwTxn};
+use lazy_static::lazy_static;
 use nix::sys::stat::{self, Mode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -26,6 +27,7 @@ use std::fs;
 use std::hash::Hash;
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::RwLock;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 /// the switch of the reliability database, which control the caching behavior.
@@ -361,6 +363,26 @@ static RELI_INTERNAL_DB_NAME: [&str; RELI_INTERNAL_MAX_DBS as usize] = [
 
 const RELI_PATH_DIR: &str = "/run/sysmaster/reliability";
 
+lazy_static! {
+    /// the running directory set by reli_dir_set, RELI_PATH_DIR is used if it's empty
+    static ref RELI_RUN_DIR: RwLock<String> = RwLock::new(String::new());
+}
+
+/// set the running directory for reliability, i.e. the one in $XDG_RUNTIME_DIR of a user manager.
+/// it must be called before reli_dir_prepare.
+pub fn reli_dir_set(dir: &str) {
+    *RELI_RUN_DIR.write().unwrap() = dir.to_string();
+}
+
+fn reli_dir_run() -> String {
+    let dir = RELI_RUN_DIR.read().unwrap();
+    if dir.is_empty() {
+        String::from(RELI_PATH_DIR)
+    } else {
+        dir.clone()
+    }
+}
+
 /// get the directory for reliability.
 pub fn reli_dir_get() -> Result<String> {
     #[cfg(debug)]
@@ -402,7 +424,7 @@ fn reli_dir_get_debug() -> Result<String> {
 
 /// prepare the directory for reliability.
 /// the reliability path is prepared and searched according to the following priority, from high to low:
-/// 1. /run/sysmaster/reliability/: the real running directory.
+/// 1. /run/sysmaster/reliability/ or the one set by reli_dir_set: the real running directory.
 /// 2. [debug-only]OUT_DIR/../reliability/: make CI happy, which is target/debug/reliability/ or target/release/reliability/ usually.
 /// 3. [debug-only]ROCESS_RELI_PATH: the path customized.
 pub fn reli_dir_prepare() -> Result<()> {
@@ -455,26 +477,25 @@ fn reli_dir_prepare_debug() -> Result<()> {
 }
 
 fn reli_dir_prepare_run() -> Result<()> {
-    let dir = Path::new(RELI_PATH_DIR);
+    let run_dir = reli_dir_run();
+    let dir = Path::new(&run_dir);
     if !dir.exists() {
         fs::create_dir_all(dir).context(IoSnafu)?;
     }
 
     log::info!(
         "prepare reliability running directory successfully: {}.",
-        RELI_PATH_DIR
+        run_dir
     );
     Ok(())
 }
 
 fn reli_dir_get_run() -> Result<String> {
-    let dir = Path::new(RELI_PATH_DIR);
+    let run_dir = reli_dir_run();
+    let dir = Path::new(&run_dir);
     if dir.exists() {
-        log::info!(
-            "get reliability run directory successfully: {}.",
-            RELI_PATH_DIR
-        );
-        Ok(String::from(RELI_PATH_DIR))
+        log::info!("get reliability run directory successfully: {}.", run_dir);
+        Ok(run_dir)
     } else {
         Err(Error::NotFound {
             what: dir.to_string_lossy().to_string(),
@@ -16,7 +16,7 @@ pub use api_comm::{reli_debug_get_switch, ReliConf};
 pub use api_norecov::Reliability;
 #[cfg(not(feature = "norecovery"))]
 pub use api_recov::Reliability;
-pub use base::{reli_dir_prepare, ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, ReliSwitch};
+pub use base::{reli_dir_prepare, reli_dir_set, ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, ReliSwitch};
 use serde::{Deserialize, Serialize};
 pub use station::{ReStation, ReStationKind};
 use std::convert::TryFrom;
@@ -18,6 +18,7 @@ use super::umif::UnitMngUtil;
 use crate::error::*;
 use basic::time::UnitTimeStamp;
 use bitflags::bitflags;
+use nix::sys::signal::Signal;
 use nix::sys::wait::WaitStatus;
 use nix::{sys::socket::UnixCredentials, unistd::Pid};
 use std::any::Any;
@@ -25,6 +26,7 @@ use std::cell::RefCell;
 use std::num::ParseIntError;
 use std::str::FromStr;
 use std::{collections::HashMap, path::PathBuf, rc::Rc};
+use unit_parser::error::LocatedError;
 
 ///The trait Defining Shared Behavior from Base Unit  to SUB unit
 ///
@@ -92,6 +94,9 @@ pub trait UnitBase {
 
     ///
     fn get_unit_timestamp(&self) -> Rc<RefCell<UnitTimeStamp>>;
+
+    /// the directory the configuration of the unit is written to, the runtime one is lost after reboot
+    fn config_dir(&self, runtime: bool) -> PathBuf;
 }
 
 ///The trait Defining Shared Behavior of sub unit
@@ -136,8 +141,10 @@ pub trait SubUnit: ReStation + UnitMngUtil {
         false
     }
 
-    ///
-    fn kill(&self) {}
+    /// send the signal to the processes of the unit, return UnitActionEOpNotSupp for default
+    fn kill(&self, _signal: Signal) -> Result<()> {
+        Err(Error::UnitActionEOpNotSupp)
+    }
 
     ///
     fn release_resources(&self) {}
@@ -190,6 +197,22 @@ pub trait SubUnit: ReStation + UnitMngUtil {
         })
     }
 
+    /// the parsed configuration of the sub unit type section, i.e. [Service], in key=value pairs
+    fn get_properties(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// check the configuration files of the sub unit type section strictly, used to validate
+    /// a drop-in before installing it and to verify unit files offline
+    fn verify_config(&self, _paths: Vec<PathBuf>, _name: &str) -> Vec<LocatedError> {
+        Vec::new()
+    }
+
+    /// the process running the control commands, i.e. ExecStartPre or ExecStop
+    fn control_pid(&self) -> Option<Pid> {
+        None
+    }
+
     // ================ ONLY VALID FOR SERVICE ================
     ///
     fn set_socket_fd(&self, _fd: i32) {}
@@ -197,6 +220,21 @@ pub trait SubUnit: ReStation + UnitMngUtil {
     ///
     fn release_socket_fd(&self, _fd: i32) {}
 
+    /// the main process of the service
+    fn main_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    /// the wait status of the last exited main process
+    fn exit_status(&self) -> Option<WaitStatus> {
+        None
+    }
+
+    /// the status sent by the service with STATUS=
+    fn status_text(&self) -> Option<String> {
+        None
+    }
+
     // ================ ONLY VALID FOR MOUNT ================
     ///
     fn setup_existing_mount(&self, _what: &str, _mount_where: &str, _options: &str, _fstype: &str) {
@@ -210,6 +248,14 @@ pub trait SubUnit: ReStation + UnitMngUtil {
 
     ///
     fn trigger_notify(&self) {}
+
+    // ================ ONLY VALID FOR SWAP ================
+    /// update the swap state after /proc/swaps changed
+    fn update_swap_state_by_proc_swaps(&self) {}
+
+    // ================ ONLY VALID FOR DEVICE ================
+    /// the device with the sysfs path is found or lost, wants are the units pulled in by it
+    fn setup_device(&self, _sysfs: &str, _wants: &[String], _found: bool) {}
 }
 
 /// the macro for create a sub unit instance with dyn ref of UmIf,
@@ -0,0 +1,435 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! cgroup resource control of the unit, the values are written to the
+//! cgroup v2 interface files of the unit's cgroup.
+use crate::error::*;
+use basic::config::{parse_size, Base};
+use nix::sys::stat::{major, minor, stat, SFlag};
+use nix::unistd::{chown, Gid, Uid};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+/// the value of the limit means no limit, i.e. "max" in the cgroup files
+pub const CGROUP_LIMIT_MAX: u64 = u64::MAX;
+
+const CGROUP_WEIGHT_MIN: u64 = 1;
+const CGROUP_WEIGHT_MAX: u64 = 10000;
+const CGROUP_WEIGHT_DEFAULT: u64 = 100;
+const CPU_QUOTA_PERIOD_USEC: u64 = 100000;
+const USEC_PER_SEC: u64 = 1000000;
+
+/// the bandwidth limit of one block device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IODeviceLimit {
+    /// the device node or a file on the device
+    pub path: PathBuf,
+    /// bytes per second
+    pub limit: u64,
+}
+
+fn parse_limit(s: &str, base: Base) -> Result<u64> {
+    let s = s.trim();
+    if s == "infinity" {
+        return Ok(CGROUP_LIMIT_MAX);
+    }
+
+    parse_size(s, base).map_err(|_| Error::ConfigureError {
+        msg: format!("invalid limit: {}", s),
+    })
+}
+
+/// parse MemoryMax=, MemoryHigh=, the value is in bytes, K, M, G, T suffix
+/// are binary based, "infinity" means no limit
+pub fn parse_memory_limit(s: &str) -> Result<u64> {
+    parse_limit(s, Base::Binary)
+}
+
+/// parse TasksMax=, the value is a number or "infinity"
+pub fn parse_tasks_max(s: &str) -> Result<u64> {
+    let s = s.trim();
+    if s == "infinity" {
+        return Ok(CGROUP_LIMIT_MAX);
+    }
+
+    s.parse::<u64>().map_err(|_| Error::ConfigureError {
+        msg: format!("invalid TasksMax: {}", s),
+    })
+}
+
+/// parse CPUWeight=, IOWeight=, the value range is 1~10000
+pub fn parse_cgroup_weight(s: &str) -> Result<u64> {
+    let weight = s.trim().parse::<u64>().map_err(|_| Error::ConfigureError {
+        msg: format!("invalid weight: {}", s),
+    })?;
+
+    if !(CGROUP_WEIGHT_MIN..=CGROUP_WEIGHT_MAX).contains(&weight) {
+        return Err(Error::ConfigureError {
+            msg: format!(
+                "weight {} is out of range {}~{}",
+                weight, CGROUP_WEIGHT_MIN, CGROUP_WEIGHT_MAX
+            ),
+        });
+    }
+    Ok(weight)
+}
+
+/// parse CPUQuota=, the value is a percentage of one cpu, i.e. 20%, 200%,
+/// return the cpu time in microseconds per second
+pub fn parse_cpu_quota(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let usec = s
+        .strip_suffix('%')
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .and_then(|v| v.checked_mul(USEC_PER_SEC))
+        .ok_or_else(|| Error::ConfigureError {
+            msg: format!("invalid CPUQuota: {}", s),
+        })?;
+
+    Ok(usec / 100)
+}
+
+/// parse IODeviceReadBandwidthMax=, the value is a device path and the
+/// bandwidth separated by space, i.e. "/dev/sda 10M"
+pub fn parse_io_device_limit(s: &str) -> Result<Vec<IODeviceLimit>> {
+    let (path, limit) = match s.trim().split_once(char::is_whitespace) {
+        Some(v) => v,
+        None => {
+            return Err(Error::ConfigureError {
+                msg: format!("invalid device bandwidth: {}", s),
+            })
+        }
+    };
+
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(Error::ConfigureError {
+            msg: format!("device path {:?} is not absolute", path),
+        });
+    }
+
+    Ok(vec![IODeviceLimit {
+        path,
+        limit: parse_limit(limit, Base::Binary)?,
+    }])
+}
+
+fn limit_to_string(v: u64) -> String {
+    if v == CGROUP_LIMIT_MAX {
+        "max".to_string()
+    } else {
+        v.to_string()
+    }
+}
+
+fn device_number(path: &Path) -> Result<(u64, u64)> {
+    let st = stat(path).context(NixSnafu)?;
+    let kind = SFlag::from_bits_truncate(st.st_mode) & SFlag::S_IFMT;
+    /* for a regular file, limit the device which the file lives on */
+    let dev = if kind == SFlag::S_IFBLK {
+        st.st_rdev
+    } else {
+        st.st_dev
+    };
+    Ok((major(dev), minor(dev)))
+}
+
+/// cgroup resource control context of the unit
+#[derive(Default)]
+pub struct CgroupContext {
+    memory_max: RefCell<Option<u64>>,
+    memory_high: RefCell<Option<u64>>,
+    cpu_weight: RefCell<Option<u64>>,
+    cpu_quota_per_sec_usec: RefCell<Option<u64>>,
+    tasks_max: RefCell<Option<u64>>,
+    io_weight: RefCell<Option<u64>>,
+    io_device_read_bandwidth: RefCell<Vec<IODeviceLimit>>,
+    delegate: RefCell<bool>,
+}
+
+impl CgroupContext {
+    /// set MemoryMax=
+    pub fn set_memory_max(&self, v: Option<u64>) {
+        *self.memory_max.borrow_mut() = v;
+    }
+
+    /// set MemoryHigh=
+    pub fn set_memory_high(&self, v: Option<u64>) {
+        *self.memory_high.borrow_mut() = v;
+    }
+
+    /// set CPUWeight=
+    pub fn set_cpu_weight(&self, v: Option<u64>) {
+        *self.cpu_weight.borrow_mut() = v;
+    }
+
+    /// set CPUQuota=, in microseconds per second
+    pub fn set_cpu_quota(&self, v: Option<u64>) {
+        *self.cpu_quota_per_sec_usec.borrow_mut() = v;
+    }
+
+    /// set TasksMax=
+    pub fn set_tasks_max(&self, v: Option<u64>) {
+        *self.tasks_max.borrow_mut() = v;
+    }
+
+    /// set IOWeight=
+    pub fn set_io_weight(&self, v: Option<u64>) {
+        *self.io_weight.borrow_mut() = v;
+    }
+
+    /// set IODeviceReadBandwidthMax=
+    pub fn set_io_device_read_bandwidth(&self, v: Vec<IODeviceLimit>) {
+        *self.io_device_read_bandwidth.borrow_mut() = v;
+    }
+
+    /// set Delegate=
+    pub fn set_delegate(&self, v: bool) {
+        *self.delegate.borrow_mut() = v;
+    }
+
+    /// whether the cgroup subtree of the unit is delegated to its processes
+    pub fn delegate(&self) -> bool {
+        *self.delegate.borrow()
+    }
+
+    fn memory_configured(&self) -> bool {
+        self.memory_max.borrow().is_some() || self.memory_high.borrow().is_some()
+    }
+
+    fn cpu_configured(&self) -> bool {
+        self.cpu_weight.borrow().is_some() || self.cpu_quota_per_sec_usec.borrow().is_some()
+    }
+
+    fn io_configured(&self) -> bool {
+        self.io_weight.borrow().is_some() || !self.io_device_read_bandwidth.borrow().is_empty()
+    }
+
+    /// the cgroup attributes and their values, the attributes which are not
+    /// configured are reset to the kernel default, so that the limits removed
+    /// from the configuration take effect after reload.
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attrs = vec![
+            (
+                "memory.max",
+                limit_to_string(self.memory_max.borrow().unwrap_or(CGROUP_LIMIT_MAX)),
+            ),
+            (
+                "memory.high",
+                limit_to_string(self.memory_high.borrow().unwrap_or(CGROUP_LIMIT_MAX)),
+            ),
+            (
+                "cpu.weight",
+                self.cpu_weight
+                    .borrow()
+                    .unwrap_or(CGROUP_WEIGHT_DEFAULT)
+                    .to_string(),
+            ),
+            (
+                "pids.max",
+                limit_to_string(self.tasks_max.borrow().unwrap_or(CGROUP_LIMIT_MAX)),
+            ),
+            (
+                "io.weight",
+                format!(
+                    "default {}",
+                    self.io_weight.borrow().unwrap_or(CGROUP_WEIGHT_DEFAULT)
+                ),
+            ),
+        ];
+
+        let cpu_max = match *self.cpu_quota_per_sec_usec.borrow() {
+            None => format!("max {}", CPU_QUOTA_PERIOD_USEC),
+            Some(quota) => format!(
+                "{} {}",
+                quota / (USEC_PER_SEC / CPU_QUOTA_PERIOD_USEC),
+                CPU_QUOTA_PERIOD_USEC
+            ),
+        };
+        attrs.push(("cpu.max", cpu_max));
+        attrs
+    }
+
+    /// write the resource limits to the cgroup of the unit
+    pub fn apply(&self, cg_path: &Path) -> Result<()> {
+        let mut controllers = Vec::new();
+        if self.memory_configured() {
+            controllers.push("memory");
+        }
+        if self.cpu_configured() {
+            controllers.push("cpu");
+        }
+        if self.tasks_max.borrow().is_some() {
+            controllers.push("pids");
+        }
+        if self.io_configured() {
+            controllers.push("io");
+        }
+
+        if !controllers.is_empty() {
+            cgroup::cg_enable_controllers(cg_path, &controllers).context(CgroupSnafu)?;
+        }
+
+        for (attr, value) in self.attributes() {
+            let configured = controllers.iter().any(|c| attr.starts_with(c));
+            match cgroup::cg_set_attribute(cg_path, attr, &value) {
+                Ok(_) => {}
+                /* the controller is not enabled, nothing to reset */
+                Err(_) if !configured => {}
+                Err(e) => return Err(Error::Cgroup { source: e }),
+            }
+        }
+
+        let mut devices = Vec::new();
+        for device in self.io_device_read_bandwidth.borrow().iter() {
+            let (maj, min) = device_number(&device.path)?;
+            devices.push((format!("{}:{}", maj, min), device.limit));
+        }
+
+        /* the devices removed from the configuration keep their limits in
+         * io.max until they are reset explicitly */
+        if let Ok(io_max) = cgroup::cg_get_attribute(cg_path, "io.max") {
+            for dev in io_max_stale_devices(&io_max, &devices) {
+                let value = format!("{} rbps=max", dev);
+                cgroup::cg_set_attribute(cg_path, "io.max", &value).context(CgroupSnafu)?;
+            }
+        }
+
+        for (dev, limit) in devices.iter() {
+            let value = format!("{} rbps={}", dev, limit_to_string(*limit));
+            cgroup::cg_set_attribute(cg_path, "io.max", &value).context(CgroupSnafu)?;
+        }
+
+        Ok(())
+    }
+
+    /// change the owner of the cgroup and the files needed to manage its subtree,
+    /// so that the processes running as the user can create the sub cgroups and
+    /// move the processes between them, nothing is done if not delegated.
+    pub fn delegate_to(&self, cg_path: &Path, uid: Uid, gid: Gid) -> Result<()> {
+        if !self.delegate() {
+            return Ok(());
+        }
+
+        let procs = cgroup::cg_attribute_path(cg_path, "cgroup.procs").context(CgroupSnafu)?;
+        if let Some(dir) = procs.parent() {
+            chown(dir, Some(uid), Some(gid)).context(NixSnafu)?;
+        }
+        for attr in ["cgroup.procs", "cgroup.subtree_control", "cgroup.threads"] {
+            let path = cgroup::cg_attribute_path(cg_path, attr).context(CgroupSnafu)?;
+            if !path.exists() {
+                continue;
+            }
+            chown(&path, Some(uid), Some(gid)).context(NixSnafu)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// the devices which have a read bandwidth limit in io.max, but are not
+/// configured any more, the lines of io.max are like "8:0 rbps=1024 wbps=max ..."
+fn io_max_stale_devices(io_max: &str, devices: &[(String, u64)]) -> Vec<String> {
+    io_max
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let dev = fields.next()?;
+            let limited = fields.any(|f| f.starts_with("rbps=") && f != "rbps=max");
+            if limited && !devices.iter().any(|(d, _)| d == dev) {
+                Some(dev.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// the memory used by the cgroup in bytes, read from memory.current
+pub fn cgroup_memory_current(cg_path: &Path) -> Option<u64> {
+    cgroup::cg_get_attribute(cg_path, "memory.current")
+        .ok()?
+        .parse::<u64>()
+        .ok()
+}
+
+/// the CPU time consumed by the cgroup in nanoseconds, read from cpu.stat
+pub fn cgroup_cpu_usage(cg_path: &Path) -> Option<u64> {
+    let stat = cgroup::cg_get_attribute(cg_path, "cpu.stat").ok()?;
+    parse_cpu_stat_usage(&stat)
+}
+
+fn parse_cpu_stat_usage(stat: &str) -> Option<u64> {
+    for line in stat.lines() {
+        let mut fields = line.split_whitespace();
+        if fields.next() == Some("usage_usec") {
+            let usec = fields.next()?.parse::<u64>().ok()?;
+            return Some(usec.saturating_mul(1000));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cgroup_values() {
+        assert_eq!(parse_memory_limit("infinity").unwrap(), CGROUP_LIMIT_MAX);
+        assert_eq!(parse_memory_limit("512M").unwrap(), 512 * 1024 * 1024);
+        assert_eq!(parse_tasks_max("100").unwrap(), 100);
+        assert!(parse_tasks_max("abc").is_err());
+        assert_eq!(parse_cgroup_weight("100").unwrap(), 100);
+        assert!(parse_cgroup_weight("0").is_err());
+        assert!(parse_cgroup_weight("10001").is_err());
+        assert_eq!(parse_cpu_quota("20%").unwrap(), 200000);
+        assert!(parse_cpu_quota("20").is_err());
+        assert!(parse_cpu_quota("0%").is_err());
+        assert!(parse_cpu_quota("18446744073709551615%").is_err());
+
+        let devs = parse_io_device_limit("/dev/sda 10M").unwrap();
+        assert_eq!(devs[0].path, PathBuf::from("/dev/sda"));
+        assert_eq!(devs[0].limit, 10 * 1024 * 1024);
+        assert!(parse_io_device_limit("/dev/sda").is_err());
+    }
+
+    #[test]
+    fn test_cgroup_attributes() {
+        let ctx = CgroupContext::default();
+        ctx.set_memory_max(Some(1024));
+        ctx.set_cpu_quota(Some(200000));
+        let attrs = ctx.attributes();
+        assert!(attrs.contains(&("memory.max", "1024".to_string())));
+        assert!(attrs.contains(&("memory.high", "max".to_string())));
+        assert!(attrs.contains(&("cpu.max", "20000 100000".to_string())));
+    }
+
+    #[test]
+    fn test_io_max_stale_devices() {
+        let io_max = "8:0 rbps=1024 wbps=max riops=max wiops=max\n\
+                      8:16 rbps=2048 wbps=max riops=max wiops=max\n\
+                      8:32 rbps=max wbps=4096 riops=max wiops=max";
+        let devices = vec![("8:16".to_string(), 4096)];
+        assert_eq!(io_max_stale_devices(io_max, &devices), vec!["8:0"]);
+        assert!(io_max_stale_devices("", &devices).is_empty());
+    }
+
+    #[test]
+    fn test_parse_cpu_stat_usage() {
+        let stat = "usage_usec 1500\nuser_usec 1000\nsystem_usec 500";
+        assert_eq!(parse_cpu_stat_usage(stat), Some(1500000));
+        assert_eq!(parse_cpu_stat_usage("user_usec 1000"), None);
+    }
+}
@@ -117,6 +117,11 @@ pub enum UnitType {
     UnitMount,
     UnitTimer,
     UnitPath,
+    UnitSlice,
+    UnitScope,
+    UnitAutomount,
+    UnitSwap,
+    UnitDevice,
     UnitTypeMax,
     UnitTypeInvalid,
     UnitTypeErrnoMax,
@@ -132,6 +137,11 @@ impl UnitType {
             UnitType::UnitMount,
             UnitType::UnitTimer,
             UnitType::UnitPath,
+            UnitType::UnitSlice,
+            UnitType::UnitScope,
+            UnitType::UnitAutomount,
+            UnitType::UnitSwap,
+            UnitType::UnitDevice,
         ]
         .iter()
         .copied()
@@ -149,6 +159,11 @@ impl FromStr for UnitType {
             "mount" => UnitType::UnitMount,
             "timer" => UnitType::UnitTimer,
             "path" => UnitType::UnitPath,
+            "slice" => UnitType::UnitSlice,
+            "scope" => UnitType::UnitScope,
+            "automount" => UnitType::UnitAutomount,
+            "swap" => UnitType::UnitSwap,
+            "device" => UnitType::UnitDevice,
             _ => UnitType::UnitTypeInvalid,
         };
         Ok(ret)
@@ -164,6 +179,11 @@ impl From<UnitType> for String {
             UnitType::UnitMount => "mount".into(),
             UnitType::UnitTimer => "timer".into(),
             UnitType::UnitPath => "path".into(),
+            UnitType::UnitSlice => "slice".into(),
+            UnitType::UnitScope => "scope".into(),
+            UnitType::UnitAutomount => "automount".into(),
+            UnitType::UnitSwap => "swap".into(),
+            UnitType::UnitDevice => "device".into(),
             UnitType::UnitTypeMax => null_str!(""),
             UnitType::UnitTypeInvalid => null_str!(""),
             UnitType::UnitTypeErrnoMax => null_str!(""),
@@ -181,6 +201,11 @@ impl TryFrom<u32> for UnitType {
             3 => Ok(UnitType::UnitMount),
             4 => Ok(UnitType::UnitTimer),
             5 => Ok(UnitType::UnitPath),
+            6 => Ok(UnitType::UnitSlice),
+            7 => Ok(UnitType::UnitScope),
+            8 => Ok(UnitType::UnitAutomount),
+            9 => Ok(UnitType::UnitSwap),
+            10 => Ok(UnitType::UnitDevice),
             v => Err(format!("input {} is invalid", v)),
         }
     }
@@ -25,6 +25,7 @@ pub enum KillOperation {
     KillRestart,
     KillKill,
     KillWatchdog,
+    KillSignal(Signal),
     KillInvalid,
 }
 
@@ -37,6 +38,7 @@ impl KillOperation {
             | KillOperation::KillRestart => kill_context.kill_signal(),
             KillOperation::KillKill => Signal::SIGKILL,
             KillOperation::KillWatchdog => Signal::SIGABRT,
+            KillOperation::KillSignal(sig) => sig,
             _ => Signal::SIGTERM,
         }
     }
@@ -125,3 +127,26 @@ impl KillContext {
         *self.kill_signal.borrow()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kill_operation_to_signal() {
+        let kill_context = Rc::new(KillContext::default());
+        kill_context.set_kill_signal(Signal::SIGINT);
+        assert_eq!(
+            KillOperation::KillTerminate.to_signal(kill_context.clone()),
+            Signal::SIGINT
+        );
+        assert_eq!(
+            KillOperation::KillKill.to_signal(kill_context.clone()),
+            Signal::SIGKILL
+        );
+        assert_eq!(
+            KillOperation::KillSignal(Signal::SIGUSR1).to_signal(kill_context),
+            Signal::SIGUSR1
+        );
+    }
+}
@@ -12,20 +12,28 @@
 
 //!
 pub use base::{unit_name_is_valid, SubUnit, UnitBase, UnitNameFlags};
+pub use cgroup::{
+    cgroup_cpu_usage, cgroup_memory_current, parse_cgroup_weight, parse_cpu_quota,
+    parse_io_device_limit, parse_memory_limit, parse_tasks_max, CgroupContext, IODeviceLimit,
+    CGROUP_LIMIT_MAX,
+};
 pub use deps::{
     unit_name_to_type, unit_write_flags_is_noop, UnitDependencyMask, UnitRelationAtom,
     UnitRelations, UnitType, UnitWriteFlags,
 };
 pub use kill::{KillContext, KillMode, KillOperation};
 pub use path_spec::{PathSpec, PathType};
-pub use state::{UnitActiveState, UnitNotifyFlags, UnitStatus};
+pub use property::section_properties;
+pub use state::{UnitActiveState, UnitNotifyFlags};
 pub use umif::{UmIf, UnitManagerObj, UnitMngUtil};
 pub use write::{unit_write_setting, unit_write_settingf};
 
 mod base;
+mod cgroup;
 mod deps;
 mod kill;
 mod path_spec;
+mod property;
 mod state;
 mod umif;
 mod write;
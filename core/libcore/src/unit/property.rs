@@ -0,0 +1,87 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Turn the parsed configuration sections into key=value pairs, used by sctl show.
+use serde::Serialize;
+use serde_json::Value;
+
+fn property_value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => String::from(if *b { "yes" } else { "no" }),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(a) => a
+            .iter()
+            .map(property_value_to_string)
+            .collect::<Vec<String>>()
+            .join(" "),
+        Value::Object(o) => {
+            let fields: Vec<String> = o
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, property_value_to_string(v)))
+                .collect();
+            format!("{{ {} }}", fields.join(" ; "))
+        }
+    }
+}
+
+/// Serialize every field of the section, i.e. SectionService, the field name is
+/// the key and the value is rendered as a string. Booleans are yes/no, lists are
+/// separated by spaces, and unset values are empty.
+pub fn section_properties<T: Serialize>(section: &T) -> Vec<(String, String)> {
+    let value = match serde_json::to_value(section) {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!("Failed to serialize the configuration section: {}", e);
+            return Vec::new();
+        }
+    };
+
+    match value {
+        Value::Object(o) => o
+            .iter()
+            .map(|(k, v)| (k.clone(), property_value_to_string(v)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[allow(non_snake_case)]
+    #[derive(Serialize)]
+    struct SectionTest {
+        Description: String,
+        RemainAfterExit: bool,
+        MemoryMax: Option<u64>,
+        After: Vec<String>,
+    }
+
+    #[test]
+    fn test_section_properties() {
+        let section = SectionTest {
+            Description: "test".to_string(),
+            RemainAfterExit: true,
+            MemoryMax: None,
+            After: vec!["a.service".to_string(), "b.service".to_string()],
+        };
+        let props = section_properties(&section);
+        assert!(props.contains(&("Description".to_string(), "test".to_string())));
+        assert!(props.contains(&("RemainAfterExit".to_string(), "yes".to_string())));
+        assert!(props.contains(&("MemoryMax".to_string(), String::new())));
+        assert!(props.contains(&("After".to_string(), "a.service b.service".to_string())));
+    }
+}
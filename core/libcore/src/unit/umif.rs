@@ -123,6 +123,11 @@ pub trait UmIf {
         Ok(())
     }
 
+    /// stopping a unit by pushing it to job queue
+    fn unit_stop_by_job(&self, _name: &str) -> Result<()> {
+        Ok(())
+    }
+
     /// starting a unit by calling unit.start()
     fn unit_start_directly(&self, _name: &str) -> Result<()> {
         Ok(())
@@ -186,8 +191,8 @@ pub trait UmIf {
         false
     }
 
-    /// return the fds that trigger the unit {name};
-    fn collect_socket_fds(&self, _name: &str) -> Vec<i32> {
+    /// return the fds that trigger the unit {name}, each one is named after its socket unit;
+    fn collect_socket_fds(&self, _name: &str) -> Vec<(i32, String)> {
         Vec::new()
     }
 
@@ -211,6 +216,11 @@ pub trait UmIf {
         Ok(())
     }
 
+    /// put the unit into the slice, the cgroup of the unit will be created under the slice
+    fn unit_set_slice(&self, _unit_name: &str, _slice: &str) -> Result<()> {
+        Ok(())
+    }
+
     ///
     fn private_section(&self, _unit_type: UnitType) -> String {
         null_str!("")
@@ -249,6 +259,14 @@ pub trait UmIf {
     /// update mount state
     fn update_mount_state_by_mountinfo(&self, _unit_name: &str) {}
 
+    /* ========== ONLY VALID IN SWAP ========== */
+    /// update swap state
+    fn update_swap_state_by_proc_swaps(&self, _unit_name: &str) {}
+
+    /* ========== ONLY VALID IN DEVICE ========== */
+    /// create the device unit if needed when the device is found, and update its state
+    fn setup_device(&self, _unit_name: &str, _sysfs: &str, _wants: &[String], _found: bool) {}
+
     /// get unit timestamp data
     fn get_unit_timestamp(&self, _unit_name: &str) -> Rc<RefCell<UnitTimeStamp>> {
         Rc::new(RefCell::new(UnitTimeStamp::default()))
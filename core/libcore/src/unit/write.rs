@@ -15,16 +15,19 @@ use super::deps::{self, UnitWriteFlags};
 use crate::error::*;
 use std::fmt::Write as _;
 use std::fmt::{self, Arguments};
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 use std::io::Write as _;
+use std::path::Path;
 use std::rc::Rc;
 
-///
+/// Write the setting to the transient file of the unit, or to the drop-in
+/// "{unit}.d/50-{name}.conf" in the runtime configuration directory, i.e. /run,
+/// if RUNTIME is set, otherwise in the persistent one, i.e. /etc.
 pub fn unit_write_setting(
     unit: Rc<dyn UnitBase>,
     ps: &str,
     flags: UnitWriteFlags,
-    _name: &str,
+    name: &str,
     data: &str,
 ) -> Result<()> {
     if deps::unit_write_flags_is_noop(flags) {
@@ -74,8 +77,26 @@ pub fn unit_write_setting(
         return Ok(());
     }
 
-    // not supported now
-    Err(Error::NotSupported)
+    let dir = unit.config_dir(flags.contains(UnitWriteFlags::RUNTIME));
+    write_drop_in(&dir, &unit.id(), name, &format!("{}{}", prefix, data))
+}
+
+fn write_drop_in(dir: &Path, unit_name: &str, name: &str, data: &str) -> Result<()> {
+    let dropin_dir = dir.join(format!("{}.d", unit_name));
+    fs::create_dir_all(&dropin_dir).context(IoSnafu)?;
+
+    /* One file for each setting, so setting it again overrides the previous value. */
+    let path = dropin_dir.join(format!("50-{}.conf", name));
+    let mut content = String::from(
+        "# This is a drop-in unit file extension, created via \"sctl set-property\". Do not edit.\n",
+    );
+    content += data;
+    if !data.ends_with('\n') {
+        content += "\n";
+    }
+    fs::write(&path, content).context(IoSnafu)?;
+    log::info!("write drop-in successfully: {:?}.", path);
+    Ok(())
 }
 
 ///
@@ -0,0 +1,103 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Render the dependency tree returned by sysmaster.
+
+use basic::show_table::CellColor;
+use cmdproto::proto::UnitDependency;
+
+/// the colored marker of the active state
+fn state_marker(active_state: &str) -> String {
+    let color = match active_state {
+        "active" | "reloading" => CellColor::Green,
+        "failed" => CellColor::Red,
+        _ => CellColor::Empty,
+    };
+    "\x1b".to_string() + &String::from(color) + "●" + "\x1b[0m"
+}
+
+/// whether no sibling follows the dependency at index in the depth-first list
+fn is_last_sibling(dependencies: &[UnitDependency], index: usize) -> bool {
+    let depth = dependencies[index].depth;
+    for dep in &dependencies[index + 1..] {
+        if dep.depth <= depth {
+            return dep.depth < depth;
+        }
+    }
+    true
+}
+
+/// the tree of list-dependencies, one unit each line with its active state marker
+pub(crate) fn format_dependencies(dependencies: &[UnitDependency]) -> String {
+    let mut lines = Vec::new();
+    /* Whether the ancestor at each depth has more siblings, a vertical line is drawn if so. */
+    let mut more_siblings: Vec<bool> = Vec::new();
+    for (i, dep) in dependencies.iter().enumerate() {
+        if dep.depth == 0 {
+            lines.push(dep.name.clone());
+            continue;
+        }
+
+        let depth = dep.depth as usize;
+        more_siblings.truncate(depth - 1);
+        let mut prefix = String::new();
+        for more in &more_siblings {
+            prefix += if *more { "│ " } else { "  " };
+        }
+        let last = is_last_sibling(dependencies, i);
+        prefix += if last { "└─" } else { "├─" };
+        more_siblings.push(!last);
+
+        lines.push(format!(
+            "{} {}{}",
+            state_marker(&dep.active_state),
+            prefix,
+            dep.name
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(depth: u32, name: &str) -> UnitDependency {
+        UnitDependency {
+            depth,
+            name: name.to_string(),
+            active_state: "active".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_dependencies() {
+        let deps = vec![
+            dep(0, "multi-user.target"),
+            dep(1, "a.service"),
+            dep(1, "basic.target"),
+            dep(2, "sysinit.target"),
+            dep(3, "b.service"),
+            dep(1, "c.service"),
+        ];
+        let marker = state_marker("active");
+        let expected = [
+            "multi-user.target".to_string(),
+            format!("{} ├─a.service", marker),
+            format!("{} ├─basic.target", marker),
+            format!("{} │ └─sysinit.target", marker),
+            format!("{} │   └─b.service", marker),
+            format!("{} └─c.service", marker),
+        ];
+        assert_eq!(format_dependencies(&deps), expected.join("\n"));
+    }
+}
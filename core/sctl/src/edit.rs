@@ -0,0 +1,121 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Edit the override drop-in of a unit in the editor of the user, the edited
+//! content is sent to sysmaster, which checks and installs it.
+
+use basic::fs::{user_config_dir, user_runtime_dir, ETC_SYSTEM_PATH, RUN_SYSTEM_PATH};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// the override drop-in of the unit, in /run if runtime is true, otherwise in /etc,
+/// or in $XDG_RUNTIME_DIR and $XDG_CONFIG_HOME for the user manager
+pub(crate) fn override_path(unit: &str, runtime: bool, user: bool) -> PathBuf {
+    let dir = match (user, runtime) {
+        (false, true) => RUN_SYSTEM_PATH.to_string(),
+        (false, false) => ETC_SYSTEM_PATH.to_string(),
+        (true, true) => format!("{}/user", user_runtime_dir().unwrap_or_default()),
+        (true, false) => format!("{}/user", user_config_dir().unwrap_or_default()),
+    };
+    Path::new(&dir)
+        .join(format!("{}.d", unit))
+        .join("override.conf")
+}
+
+/// the initial content shown in the editor if the unit has no override yet
+fn override_template(unit: &str, path: &Path) -> String {
+    format!(
+        "# Override of {}, installed as {}.\n\
+         # Put the settings under the section they belong to, i.e. [Service].\n\
+         # An empty file removes the override.\n",
+        unit,
+        path.to_string_lossy()
+    )
+}
+
+/// $EDITOR, $VISUAL or vi, split into the program and its arguments
+fn editor() -> Vec<String> {
+    for var in ["EDITOR", "VISUAL"] {
+        if let Ok(v) = std::env::var(var) {
+            let editor: Vec<String> = v.split_whitespace().map(|s| s.to_string()).collect();
+            if !editor.is_empty() {
+                return editor;
+            }
+        }
+    }
+    vec!["vi".to_string()]
+}
+
+/// Open the override of the unit in the editor, return the new content, or
+/// None if it is not changed.
+pub(crate) fn edit_override(unit: &str, runtime: bool, user: bool) -> io::Result<Option<String>> {
+    let path = override_path(unit, runtime, user);
+    let old = match fs::read_to_string(&path) {
+        Ok(v) => v,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => override_template(unit, &path),
+        Err(e) => return Err(e),
+    };
+
+    let tmp_path =
+        std::env::temp_dir().join(format!("sctl-edit-{}-{}.conf", std::process::id(), unit));
+    let mut tmp = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&tmp_path)?;
+    let res = tmp
+        .write_all(old.as_bytes())
+        .and_then(|_| run_editor(&tmp_path))
+        .and_then(|_| fs::read_to_string(&tmp_path));
+    let _ = fs::remove_file(&tmp_path);
+
+    let new = res?;
+    if new == old {
+        return Ok(None);
+    }
+    Ok(Some(new))
+}
+
+fn run_editor(path: &Path) -> io::Result<()> {
+    let editor = editor();
+    let status = Command::new(&editor[0])
+        .args(&editor[1..])
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("{} exited with {}", editor[0], status),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_path() {
+        assert_eq!(
+            override_path("foo.service", false, false),
+            Path::new("/etc/sysmaster/system/foo.service.d/override.conf")
+        );
+        assert_eq!(
+            override_path("foo.service", true, false),
+            Path::new("/run/sysmaster/system/foo.service.d/override.conf")
+        );
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Convert the typed responses into json for `--output json`.
+//!
+//! The field names are the same as the ones in abi.proto, the timestamps are
+//! realtime in microseconds and the unset values are null.
+
+use cmdproto::proto::{
+    transient_unit_comm::UnitProperty, JobEntry, OrderingCycle, UnitDependency, UnitListEntry,
+    UnitStatus,
+};
+use logproto::record::Record;
+use serde_json::{json, Value};
+
+/// one unit of list-units
+pub(crate) fn unit_list_entry_to_json(unit: &UnitListEntry) -> Value {
+    json!({
+        "name": unit.name,
+        "load_state": unit.load_state,
+        "active_state": unit.active_state,
+        "sub_state": unit.sub_state,
+        "description": unit.description,
+    })
+}
+
+/// one job of list-jobs
+pub(crate) fn job_entry_to_json(job: &JobEntry) -> Value {
+    json!({
+        "id": job.id,
+        "unit": job.unit,
+        "job_type": job.job_type,
+        "state": job.state,
+    })
+}
+
+/// one ordering cycle of list-cycles, dropped is null if the transaction was refused
+pub(crate) fn ordering_cycle_to_json(cycle: &OrderingCycle) -> Value {
+    let dropped = if cycle.dropped.is_empty() {
+        Value::Null
+    } else {
+        Value::String(cycle.dropped.clone())
+    };
+    json!({
+        "timestamp": cycle.timestamp,
+        "anchor": cycle.anchor,
+        "jobs": cycle.jobs,
+        "dropped": dropped,
+    })
+}
+
+/// one node of list-dependencies, in depth-first order
+pub(crate) fn unit_dependency_to_json(dep: &UnitDependency) -> Value {
+    json!({
+        "depth": dep.depth,
+        "name": dep.name,
+        "active_state": dep.active_state,
+    })
+}
+
+/// the properties of show, the keys are the property names
+pub(crate) fn unit_properties_to_json(properties: &[&UnitProperty]) -> Value {
+    properties
+        .iter()
+        .map(|p| (p.key.clone(), Value::String(p.value.clone())))
+        .collect::<serde_json::Map<String, Value>>()
+        .into()
+}
+
+/// the status of one unit with its recent log lines
+pub(crate) fn unit_status_to_json(status: &UnitStatus, logs: &[Record]) -> Value {
+    let processes: Vec<Value> = status
+        .processes
+        .iter()
+        .map(|p| json!({"pid": p.pid, "cmdline": p.cmdline}))
+        .collect();
+    let logs: Vec<Value> = logs
+        .iter()
+        .map(|r| {
+            json!({
+                "timestamp": r.timestamp,
+                "identifier": r.identifier,
+                "pid": r.pid,
+                "priority": r.priority,
+                "message": r.message,
+            })
+        })
+        .collect();
+
+    json!({
+        "name": status.name,
+        "description": status.description,
+        "documentation": status.documentation,
+        "load_state": status.load_state,
+        "active_state": status.active_state,
+        "sub_state": status.sub_state,
+        "state_change_timestamp": status.state_change_timestamp,
+        "active_enter_timestamp": status.active_enter_timestamp,
+        "active_exit_timestamp": status.active_exit_timestamp,
+        "inactive_enter_timestamp": status.inactive_enter_timestamp,
+        "inactive_exit_timestamp": status.inactive_exit_timestamp,
+        "main_pid": status.main_pid,
+        "control_pid": status.control_pid,
+        "cgroup_path": status.cgroup_path,
+        "processes": processes,
+        "memory_current": status.memory_current,
+        "cpu_usage_nsec": status.cpu_usage_nsec,
+        "exit_code": status.exit_code,
+        "exit_signal": status.exit_signal,
+        "status_text": status.status_text,
+        "logs": logs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_status_to_json() {
+        let status = UnitStatus {
+            name: "foo.service".to_string(),
+            active_state: "active".to_string(),
+            main_pid: 100,
+            memory_current: Some(4096),
+            ..Default::default()
+        };
+        let value = unit_status_to_json(&status, &[]);
+        assert_eq!(value["name"], "foo.service");
+        assert_eq!(value["main_pid"], 100);
+        assert_eq!(value["memory_current"], 4096);
+        assert!(value["cpu_usage_nsec"].is_null());
+        assert_eq!(value["logs"], json!([]));
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Parse the signal of kill.
+
+use nix::sys::signal::Signal;
+use std::str::FromStr;
+
+/// parse the signal by the number or the name, with or without the "SIG" prefix,
+/// i.e. 9, SIGKILL, KILL
+pub(crate) fn parse_signal(s: &str) -> Result<i32, String> {
+    let s = s.trim();
+    let signal = match s.parse::<i32>() {
+        Ok(v) => Signal::try_from(v).ok(),
+        Err(_) if s.starts_with("SIG") => Signal::from_str(s).ok(),
+        Err(_) => Signal::from_str(&format!("SIG{}", s)).ok(),
+    };
+
+    match signal {
+        Some(v) => Ok(v as i32),
+        None => Err(format!("Invalid signal: {}", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_signal() {
+        assert_eq!(parse_signal("9"), Ok(Signal::SIGKILL as i32));
+        assert_eq!(parse_signal("SIGTERM"), Ok(Signal::SIGTERM as i32));
+        assert_eq!(parse_signal("HUP"), Ok(Signal::SIGHUP as i32));
+        assert!(parse_signal("0").is_err());
+        assert!(parse_signal("SIGFOO").is_err());
+        assert!(parse_signal("").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Render the unit list and the job list returned by sysmaster as tables.
+
+use crate::status::format_timestamp;
+use basic::show_table::{CellColor, ShowTable};
+use cmdproto::proto::{JobEntry, OrderingCycle, UnitListEntry};
+
+/// the table of list-units, the units of different types are separated by an underline
+pub(crate) fn format_unit_list(units: &[UnitListEntry]) -> String {
+    let mut list_units_table = ShowTable::new();
+    list_units_table.add_line(vec!["UNIT", "LOAD", "ACTIVE", "SUB", "DESCRIPTION"]);
+    let mut last_type = None;
+    for unit in units {
+        let unit_type = unit.name.rsplit('.').next();
+        if last_type != unit_type {
+            list_units_table.set_current_row_underline(true);
+            last_type = unit_type;
+        }
+        list_units_table.add_line(vec![
+            &unit.name,
+            &unit.load_state,
+            &unit.active_state,
+            &unit.sub_state,
+            &unit.description,
+        ]);
+        if unit.active_state == "failed" {
+            list_units_table.set_current_row_color(CellColor::Red);
+        }
+    }
+    list_units_table.to_string()
+}
+
+/// the table of list-jobs, with a summary line
+pub(crate) fn format_job_list(jobs: &[JobEntry]) -> String {
+    if jobs.is_empty() {
+        return "No jobs running.".to_string();
+    }
+
+    let mut list_jobs_table = ShowTable::new();
+    list_jobs_table.add_line(vec!["JOB", "UNIT", "TYPE", "STATE"]);
+    list_jobs_table.set_current_row_underline(true);
+    for job in jobs {
+        list_jobs_table.add_line(vec![
+            &job.id.to_string(),
+            &job.unit,
+            &job.job_type,
+            &job.state,
+        ]);
+    }
+    format!("{}\n\n{} jobs listed.", list_jobs_table, jobs.len())
+}
+
+/// the ordering cycles of list-cycles, the oldest first, one cycle each line
+pub(crate) fn format_cycle_list(cycles: &[OrderingCycle]) -> String {
+    if cycles.is_empty() {
+        return "No ordering cycles found.".to_string();
+    }
+
+    let mut lines = Vec::new();
+    for cycle in cycles {
+        let mut path = cycle.jobs.clone();
+        if let Some(first) = cycle.jobs.first() {
+            path.push(first.clone());
+        }
+        let result = if cycle.dropped.is_empty() {
+            "transaction refused".to_string()
+        } else {
+            format!("dropped {}", cycle.dropped)
+        };
+        lines.push(format!(
+            "{} {}: {}, {}",
+            format_timestamp(cycle.timestamp),
+            cycle.anchor,
+            path.join(" after "),
+            result
+        ));
+    }
+    format!("{}\n\n{} cycles listed.", lines.join("\n"), cycles.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_job_list() {
+        assert_eq!(format_job_list(&[]), "No jobs running.");
+
+        let jobs = vec![JobEntry {
+            id: 3,
+            unit: "foo.service".to_string(),
+            job_type: "start".to_string(),
+            state: "running".to_string(),
+        }];
+        let res = format_job_list(&jobs);
+        assert!(res.contains("foo.service"));
+        assert!(res.ends_with("1 jobs listed."));
+    }
+
+    #[test]
+    fn test_format_cycle_list() {
+        assert_eq!(format_cycle_list(&[]), "No ordering cycles found.");
+
+        let cycles = vec![
+            OrderingCycle {
+                timestamp: 0,
+                anchor: "multi-user.target/start".to_string(),
+                jobs: vec!["a.service/start".to_string(), "b.service/start".to_string()],
+                dropped: "b.service/start".to_string(),
+            },
+            OrderingCycle {
+                timestamp: 0,
+                anchor: "c.service/start".to_string(),
+                jobs: vec!["c.service/start".to_string(), "d.service/start".to_string()],
+                dropped: String::new(),
+            },
+        ];
+        let res = format_cycle_list(&cycles);
+        let lines: Vec<&str> = res.lines().collect();
+        assert!(lines[0].ends_with(
+            " multi-user.target/start: a.service/start after b.service/start after a.service/start, dropped b.service/start"
+        ));
+        assert!(lines[1].ends_with(
+            " c.service/start: c.service/start after d.service/start after c.service/start, transaction refused"
+        ));
+        assert!(res.ends_with("2 cycles listed."));
+    }
+}
@@ -17,14 +17,25 @@ use clap::Parser;
 use cmdproto::{
     error::ERROR_CODE_MASK_PRINT_STDOUT,
     proto::{
-        abi::{sys_comm, unit_comm, CommandRequest},
-        mngr_comm, unit_file, ProstClientStream,
+        abi::{sys_comm, unit_comm, CommandRequest, CommandResponse},
+        job_comm, mngr_comm, unit_dependency_comm, unit_file, ProstClientStream,
     },
 };
-use constants::PRIVATE_SOCKET;
-use std::process::exit;
+use constants::{LOG_COLLECTOR_DIR, PRIVATE_SOCKET, SYSMASTER_BIN};
+use logproto::{record::Record, storage::read_records};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{exit, Command};
 use std::{io::Write, os::unix::net::UnixStream};
 
+mod deps;
+mod edit;
+mod json;
+mod kill;
+mod list;
+mod property;
+mod status;
+
 /// parse program arguments
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -37,6 +48,14 @@ struct Args {
     /// Number of times
     #[clap(short, long, default_value_t = 1)]
     count: u8,
+
+    /// Print the status and the lists as text tables or json
+    #[clap(short, long, global = true, default_value = "text", possible_values(&["text", "json", "json-pretty"]))]
+    output: String,
+
+    /// Talk to the service manager of the calling user, rather than the system manager
+    #[clap(long, global = true)]
+    user: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -74,6 +93,10 @@ enum SubCmd {
     Status {
         #[clap(required = true)]
         units: Vec<String>,
+
+        /// Number of the recent log lines to show
+        #[clap(short = 'n', long, default_value_t = 10)]
+        lines: usize,
     },
 
     /// `[unit]` Start one or more units
@@ -164,18 +187,142 @@ enum SubCmd {
         #[clap(required = true)]
         unit_file: Vec<String>,
     },
+
+    /// `[unit]` Show the output of a unit collected by the log collector
+    #[clap(display_order = 20)]
+    Log {
+        #[clap(required = true)]
+        unit: String,
+
+        /// Show the most recent lines only
+        #[clap(short = 'n', long)]
+        lines: Option<usize>,
+    },
+
+    /// `[job]` List the jobs in the job queue
+    #[clap(display_order = 21)]
+    ListJobs {},
+
+    /// `[job]` Cancel a waiting job
+    #[clap(display_order = 22)]
+    CancelJob {
+        #[clap(required = true)]
+        job_id: String,
+    },
+
+    /// `[job]` List the recent ordering cycles found in transactions and how they were broken
+    #[clap(display_order = 29)]
+    ListCycles {},
+
+    /// `[unit]` Show the properties of a unit
+    #[clap(display_order = 23)]
+    Show {
+        #[clap(required = true)]
+        unit: String,
+
+        /// Show only the given properties, separated by commas
+        #[clap(short, long, use_value_delimiter = true)]
+        property: Vec<String>,
+    },
+
+    /// `[unit]` Set the properties of a unit at runtime, i.e. MemoryMax=1G
+    #[clap(display_order = 24)]
+    SetProperty {
+        #[clap(required = true)]
+        unit: String,
+
+        #[clap(required = true)]
+        assignments: Vec<String>,
+
+        /// Write the changes to /run, they are lost after reboot
+        #[clap(long)]
+        runtime: bool,
+    },
+
+    /// `[unit]` Show the units pulled in by a unit as a tree
+    #[clap(display_order = 27)]
+    ListDependencies {
+        #[clap(required = true)]
+        unit: String,
+
+        /// Show the units pulling in the unit instead
+        #[clap(long, conflicts_with_all = &["after", "before"])]
+        reverse: bool,
+
+        /// Show the units ordered before the unit, i.e. After=
+        #[clap(long, conflicts_with = "before")]
+        after: bool,
+
+        /// Show the units ordered after the unit, i.e. Before=
+        #[clap(long)]
+        before: bool,
+
+        /// Expand the dependencies of all units, not only of the targets
+        #[clap(short, long)]
+        all: bool,
+    },
+
+    /// `[unit-file]` Edit the override drop-in of a unit in $EDITOR
+    #[clap(display_order = 25)]
+    Edit {
+        #[clap(required = true)]
+        unit: String,
+
+        /// Edit the override in /run, it is lost after reboot
+        #[clap(long)]
+        runtime: bool,
+    },
+
+    /// `[unit-file]` Remove the overrides and the masks of one or more units
+    #[clap(display_order = 26)]
+    Revert {
+        #[clap(required = true)]
+        unit_file: Vec<String>,
+    },
+
+    /// `[unit-file]` Verify unit files and their drop-ins offline, exit non-zero if any problem is found
+    #[clap(display_order = 28)]
+    Verify {
+        #[clap(required = true)]
+        files: Vec<String>,
+    },
+
+    /// `[unit]` Send a signal to the processes of one or more units
+    #[clap(display_order = 30)]
+    Kill {
+        #[clap(required = true)]
+        units: Vec<String>,
+
+        /// The signal to send, by the number or the name, i.e. 9, SIGKILL or KILL
+        #[clap(short, long, default_value = "SIGTERM")]
+        signal: String,
+    },
+}
+
+/// The typed content of the response, printed as a table or json
+enum Content {
+    UnitStatus(usize),
+    UnitList,
+    JobList,
+    CycleList,
+    Properties(Vec<String>),
+    Dependencies,
+    Message,
 }
 
 /// Generate CommandRequest based on parsed args
 /// clap Args => protobuf based CommandRequest
 fn generate_command_request(args: Args) -> Option<CommandRequest> {
+    let user = args.user;
     let command_request = match args.subcmd {
         SubCmd::Start { units } => CommandRequest::new_unitcomm(unit_comm::Action::Start, units),
         SubCmd::Stop { units } => CommandRequest::new_unitcomm(unit_comm::Action::Stop, units),
         SubCmd::Restart { units } => {
             CommandRequest::new_unitcomm(unit_comm::Action::Restart, units)
         }
-        SubCmd::Status { units } => CommandRequest::new_unitcomm(unit_comm::Action::Status, units),
+        SubCmd::Status { units, .. } => {
+            CommandRequest::new_unitcomm(unit_comm::Action::Status, units)
+        }
 
         SubCmd::Reload { units } => CommandRequest::new_unitcomm(unit_comm::Action::Reload, units),
         SubCmd::Isolate { units } => {
@@ -184,6 +331,13 @@ fn generate_command_request(args: Args) -> Option<CommandRequest> {
         SubCmd::ResetFailed { units } => {
             CommandRequest::new_unitcomm(unit_comm::Action::Resetfailed, units)
         }
+        SubCmd::Kill { units, signal } => match kill::parse_signal(&signal) {
+            Ok(signal) => CommandRequest::new_killcomm(units, signal),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(nix::Error::EINVAL as i32);
+            }
+        },
 
         SubCmd::Mask { unit_file } => {
             CommandRequest::new_unitfile(unit_file::Action::Mask, unit_file)
@@ -197,6 +351,9 @@ fn generate_command_request(args: Args) -> Option<CommandRequest> {
         SubCmd::Disable { unit_file } => {
             CommandRequest::new_unitfile(unit_file::Action::Disable, unit_file)
         }
+        SubCmd::Revert { unit_file } => {
+            CommandRequest::new_unitfile(unit_file::Action::Revert, unit_file)
+        }
 
         SubCmd::Shutdown { force } => {
             CommandRequest::new_syscomm(sys_comm::Action::Poweroff, force)
@@ -215,11 +372,202 @@ fn generate_command_request(args: Args) -> Option<CommandRequest> {
         SubCmd::DaemonReexec {} => CommandRequest::new_mngrcomm(mngr_comm::Action::Reexec),
 
         SubCmd::ListUnits {} => CommandRequest::new_mngrcomm(mngr_comm::Action::Listunits),
+        SubCmd::ListJobs {} => CommandRequest::new_jobcomm(job_comm::Action::List, String::new()),
+        SubCmd::ListCycles {} => {
+            CommandRequest::new_jobcomm(job_comm::Action::Cycles, String::new())
+        }
+        SubCmd::CancelJob { job_id } => {
+            CommandRequest::new_jobcomm(job_comm::Action::Cancel, job_id)
+        }
         SubCmd::SwitchRoot { init } => CommandRequest::new_switch_root_comm(init),
+        SubCmd::Show { unit, .. } => CommandRequest::new_show_property_comm(unit),
+        SubCmd::ListDependencies {
+            unit,
+            reverse,
+            after,
+            before,
+            all,
+        } => {
+            let mode = if reverse {
+                unit_dependency_comm::Mode::Reverse
+            } else if after {
+                unit_dependency_comm::Mode::After
+            } else if before {
+                unit_dependency_comm::Mode::Before
+            } else {
+                unit_dependency_comm::Mode::Forward
+            };
+            CommandRequest::new_dependency_comm(unit, mode, all)
+        }
+        SubCmd::SetProperty {
+            unit,
+            assignments,
+            runtime,
+        } => match property::parse_assignments(&assignments) {
+            Ok(properties) => CommandRequest::new_set_property_comm(unit, properties, runtime),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(nix::Error::EINVAL as i32);
+            }
+        },
+        SubCmd::Edit { unit, runtime } => {
+            let unit = unit_name_with_suffix(&unit);
+            match edit::edit_override(&unit, runtime, user) {
+                Ok(Some(content)) => CommandRequest::new_edit_comm(unit, content, runtime),
+                Ok(None) => {
+                    println!("The override of {} is not changed.", unit);
+                    exit(0);
+                }
+                Err(e) => {
+                    eprintln!("Failed to edit {}: {}", unit, e);
+                    exit(e.raw_os_error().unwrap_or(1));
+                }
+            }
+        }
+
+        /* The log files are read by sctl itself, see print_log(). */
+        SubCmd::Log { .. } => return None,
+        /* The unit files are verified by sysmaster offline, see verify(). */
+        SubCmd::Verify { .. } => return None,
     };
     Some(command_request)
 }
 
+/// The most recent log lines of the unit, nothing if the log collector is not used.
+fn recent_log(unit: &str, lines: usize) -> Vec<Record> {
+    if lines == 0 {
+        return Vec::new();
+    }
+    let mut records = read_records(Path::new(LOG_COLLECTOR_DIR), Some(unit)).unwrap_or_default();
+    let skip = records.len().saturating_sub(lines);
+    records.split_off(skip)
+}
+
+/// Treat the unit as a service if the type is not given, the same as sysmaster does.
+fn unit_name_with_suffix(unit: &str) -> String {
+    if unit.contains('.') {
+        unit.to_string()
+    } else {
+        format!("{}.service", unit)
+    }
+}
+
+/// Print the records of the unit from the log files, return the exit code.
+fn print_log(unit: &str, lines: Option<usize>) -> i32 {
+    let unit = unit_name_with_suffix(unit);
+
+    let records = match read_records(Path::new(LOG_COLLECTOR_DIR), Some(&unit)) {
+        Err(e) => {
+            eprintln!("Failed to read the log of {}: {}", unit, e);
+            return e.raw_os_error().unwrap_or(1);
+        }
+        Ok(v) => v,
+    };
+
+    let skip = match lines {
+        None => 0,
+        Some(n) => records.len().saturating_sub(n),
+    };
+    let mut stdout = std::io::stdout();
+    for record in &records[skip..] {
+        /* Stop quietly if the reader goes away, e.g. sctl log foo | head */
+        if writeln!(stdout, "{}", record).is_err() {
+            break;
+        }
+    }
+    0
+}
+
+/// Verify the unit files with sysmaster offline, return the exit code.
+fn verify(files: &[String]) -> i32 {
+    let e = Command::new(SYSMASTER_BIN)
+        .arg("--verify")
+        .args(files)
+        .exec();
+    eprintln!("Failed to run {}: {}", SYSMASTER_BIN, e);
+    e.raw_os_error().unwrap_or(1)
+}
+
+/// Print the typed content of the response as text tables or json.
+fn print_content(content: &Content, data: &CommandResponse, output: &str) {
+    let text = match content {
+        Content::Message => return,
+        Content::UnitList if output == "text" => list::format_unit_list(&data.unit_list),
+        Content::JobList if output == "text" => list::format_job_list(&data.jobs),
+        Content::CycleList if output == "text" => list::format_cycle_list(&data.ordering_cycles),
+        Content::Dependencies if output == "text" => {
+            if data.dependencies.is_empty() {
+                return;
+            }
+            deps::format_dependencies(&data.dependencies)
+        }
+        Content::Properties(filter) if output == "text" => {
+            let properties = property::filter_properties(&data.unit_properties, filter);
+            if properties.is_empty() {
+                return;
+            }
+            property::format_properties(&properties)
+        }
+        Content::UnitStatus(lines) if output == "text" => {
+            let mut text = String::new();
+            for unit_status in &data.unit_status {
+                if !text.is_empty() {
+                    text += "\n\n";
+                }
+                let logs = recent_log(&unit_status.name, *lines);
+                text += &status::format_unit_status(unit_status, &logs);
+            }
+            if text.is_empty() {
+                return;
+            }
+            text
+        }
+        _ => {
+            let value: serde_json::Value = match content {
+                Content::UnitList => data
+                    .unit_list
+                    .iter()
+                    .map(json::unit_list_entry_to_json)
+                    .collect(),
+                Content::JobList => data.jobs.iter().map(json::job_entry_to_json).collect(),
+                Content::CycleList => data
+                    .ordering_cycles
+                    .iter()
+                    .map(json::ordering_cycle_to_json)
+                    .collect(),
+                Content::Dependencies => data
+                    .dependencies
+                    .iter()
+                    .map(json::unit_dependency_to_json)
+                    .collect(),
+                Content::Properties(filter) => json::unit_properties_to_json(
+                    &property::filter_properties(&data.unit_properties, filter),
+                ),
+                Content::UnitStatus(lines) => data
+                    .unit_status
+                    .iter()
+                    .map(|s| json::unit_status_to_json(s, &recent_log(&s.name, *lines)))
+                    .collect(),
+                Content::Message => return,
+            };
+            let res = if output == "json-pretty" {
+                serde_json::to_string_pretty(&value)
+            } else {
+                serde_json::to_string(&value)
+            };
+            match res {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("Failed to format the output as json: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+    /* Don't care if we fail to write the content out. */
+    let _ = writeln!(std::io::stdout(), "{}", text);
+}
+
 fn parse_args() -> Args {
     let mut str_args: Vec<String> = std::env::args().collect();
 
@@ -250,6 +598,34 @@ fn parse_args() -> Args {
 fn main() {
     let args = parse_args();
 
+    if let SubCmd::Log { unit, lines } = &args.subcmd {
+        exit(print_log(unit, *lines));
+    }
+    if let SubCmd::Verify { files } = &args.subcmd {
+        exit(verify(files));
+    }
+    let content = match &args.subcmd {
+        SubCmd::Status { lines, .. } => Content::UnitStatus(*lines),
+        SubCmd::ListUnits {} => Content::UnitList,
+        SubCmd::ListJobs {} => Content::JobList,
+        SubCmd::ListCycles {} => Content::CycleList,
+        SubCmd::Show { property, .. } => Content::Properties(property.clone()),
+        SubCmd::ListDependencies { .. } => Content::Dependencies,
+        _ => Content::Message,
+    };
+    let output = args.output.clone();
+    let socket_path = if args.user {
+        match basic::fs::user_runtime_dir() {
+            None => {
+                eprintln!("Failed to connect to the user manager: XDG_RUNTIME_DIR is not set.");
+                exit(nix::Error::EINVAL as i32);
+            }
+            Some(dir) => format!("{}/private", dir),
+        }
+    } else {
+        PRIVATE_SOCKET.to_string()
+    };
+
     let command_request = match generate_command_request(args) {
         None => {
             eprintln!("This command is currently not supported");
@@ -258,7 +634,7 @@ fn main() {
         Some(v) => v,
     };
 
-    let stream = match UnixStream::connect(PRIVATE_SOCKET) {
+    let stream = match UnixStream::connect(socket_path) {
         Err(e) => {
             eprintln!("Failed to connect to sysmaster: {}", e);
             exit(e.raw_os_error().unwrap());
@@ -276,9 +652,11 @@ fn main() {
         Ok(v) => v,
     };
 
+    print_content(&content, &data, &output);
+
     /* We should always print the error message if the returned error code is not 0. */
     if data.message.is_empty() {
-        exit(0);
+        exit((data.error_code & !ERROR_CODE_MASK_PRINT_STDOUT) as i32);
     }
 
     if data.error_code == 0 || (data.error_code & ERROR_CODE_MASK_PRINT_STDOUT != 0) {
@@ -0,0 +1,78 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Parse the assignments of set-property and print the properties of show.
+
+use cmdproto::proto::transient_unit_comm::UnitProperty;
+
+/// parse "Key=Value" of set-property
+pub(crate) fn parse_assignments(assignments: &[String]) -> Result<Vec<UnitProperty>, String> {
+    let mut properties = Vec::new();
+    for assignment in assignments {
+        let (key, value) = match assignment.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => (key.trim(), value.trim()),
+            _ => return Err(format!("Invalid assignment: {}", assignment)),
+        };
+        properties.push(UnitProperty {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+    Ok(properties)
+}
+
+/// the properties in the filter, all of them if the filter is empty
+pub(crate) fn filter_properties<'a>(
+    properties: &'a [UnitProperty],
+    filter: &[String],
+) -> Vec<&'a UnitProperty> {
+    properties
+        .iter()
+        .filter(|p| filter.is_empty() || filter.contains(&p.key))
+        .collect()
+}
+
+/// one "Key=Value" line for each property
+pub(crate) fn format_properties(properties: &[&UnitProperty]) -> String {
+    properties
+        .iter()
+        .map(|p| format!("{}={}", p.key, p.value))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assignments() {
+        let props =
+            parse_assignments(&["MemoryMax=1G".to_string(), "RestartSec=5".to_string()]).unwrap();
+        assert_eq!(props.len(), 2);
+        assert_eq!(props[0].key, "MemoryMax");
+        assert_eq!(props[0].value, "1G");
+
+        assert!(parse_assignments(&["MemoryMax".to_string()]).is_err());
+        assert!(parse_assignments(&["=1G".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_filter_properties() {
+        let props =
+            parse_assignments(&["MainPID=1".to_string(), "MemoryMax=".to_string()]).unwrap();
+        let res = filter_properties(&props, &["MainPID".to_string()]);
+        assert_eq!(format_properties(&res), "MainPID=1");
+        let res = filter_properties(&props, &[]);
+        assert_eq!(format_properties(&res), "MainPID=1\nMemoryMax=");
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Render the unit status returned by sysmaster, with the recent log lines.
+
+use basic::show_table::{CellAlign, CellColor, ShowTable};
+use cmdproto::proto::UnitStatus;
+use logproto::record::Record;
+use nix::sys::signal::Signal;
+use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USEC_PER_SEC: u64 = 1_000_000;
+
+/// Format the status of one unit, the log lines are printed after a blank line.
+pub(crate) fn format_unit_status(status: &UnitStatus, logs: &[Record]) -> String {
+    let mut status_table = ShowTable::new();
+
+    let color = match status.active_state.as_str() {
+        "active" | "reloading" => CellColor::Green,
+        "failed" => CellColor::Red,
+        _ => CellColor::Empty,
+    };
+
+    let mut active = format!("{} ({})", status.active_state, status.sub_state);
+    let since = match status.active_state.as_str() {
+        "active" | "reloading" => status.active_enter_timestamp,
+        "activating" => status.inactive_exit_timestamp,
+        "deactivating" => status.active_exit_timestamp,
+        _ => status.inactive_enter_timestamp,
+    };
+    if since > 0 {
+        active += &format!(
+            " since {}; {} ago",
+            format_timestamp(since),
+            format_timespan(realtime_usec().saturating_sub(since))
+        );
+    }
+
+    status_table.add_line(vec!["Loaded:", &status.load_state]);
+    status_table.add_line(vec!["Active:", &active]);
+    if !status.documentation.is_empty() {
+        status_table.add_line(vec!["Docs:", &status.documentation]);
+    }
+    if status.main_pid != 0 {
+        status_table.add_line(vec!["Main PID:", &status.main_pid.to_string()]);
+    }
+    if let Some(text) = &status.status_text {
+        status_table.add_line(vec!["Status:", &format!("\"{}\"", text)]);
+    }
+    if let Some(exit) = format_exit_status(status) {
+        status_table.add_line(vec!["Last Exit:", &exit]);
+    }
+    if status.control_pid != 0 {
+        status_table.add_line(vec!["Control PID:", &status.control_pid.to_string()]);
+    }
+    if let Some(memory) = status.memory_current {
+        status_table.add_line(vec!["Memory:", &format_bytes(memory)]);
+    }
+    if let Some(cpu) = status.cpu_usage_nsec {
+        status_table.add_line(vec!["CPU:", &format_timespan(cpu / 1000)]);
+    }
+    status_table.add_line(vec!["CGroup:", &format_cgroup(status)]);
+
+    status_table.set_one_col_align(0, CellAlign::Right);
+    /* The first column: keep the left space, delete the right space. */
+    status_table.set_one_col_space(0, true, false);
+    /* Cell (1, 1) is used to show the unit state, make it colored. */
+    status_table.set_one_cell_color(1, 1, color);
+
+    let mut res = "\x1b".to_string() + &String::from(color) + "● " + "\x1b[0m" + &status.name;
+    if !status.description.is_empty() {
+        res = res + " - " + &status.description;
+    }
+    res = res + "\n" + &status_table.to_string();
+
+    if !logs.is_empty() {
+        res += "\n";
+        for record in logs {
+            res = res + "\n" + &record.to_string();
+        }
+    }
+    res
+}
+
+/// the cgroup path followed by the processes in it
+fn format_cgroup(status: &UnitStatus) -> String {
+    let mut res = if status.cgroup_path.is_empty() {
+        "Empty cgroup path".to_string()
+    } else {
+        status.cgroup_path.clone()
+    };
+    for (i, process) in status.processes.iter().enumerate() {
+        let branch = if i + 1 == status.processes.len() {
+            "└─"
+        } else {
+            "├─"
+        };
+        res += &format!("\n{}{} {}", branch, process.pid, process.cmdline);
+    }
+    res
+}
+
+fn format_exit_status(status: &UnitStatus) -> Option<String> {
+    if let Some(code) = status.exit_code {
+        return Some(format!("code=exited, status={}", code));
+    }
+    let signal = status.exit_signal?;
+    let name = match Signal::try_from(signal) {
+        Ok(v) => v.as_str().to_string(),
+        Err(_) => signal.to_string(),
+    };
+    Some(format!("code=killed, signal={}", name))
+}
+
+/// "2023-01-01 08:00:00" in the local time
+pub(crate) fn format_timestamp(usec: u64) -> String {
+    let time = (usec / USEC_PER_SEC) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&time, &mut tm) };
+    format!(
+        "{:0>4}-{:0>2}-{:0>2} {:0>2}:{:0>2}:{:0>2}",
+        tm.tm_year + 1900, /* tm_year is years since 1900 */
+        tm.tm_mon + 1,     /* tm_mon is months since Jan: [0, 11] */
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// "1d 2h 3min 4s", the milliseconds are shown if it is shorter than one minute
+fn format_timespan(usec: u64) -> String {
+    let units = [
+        ("d", 24 * 3600 * USEC_PER_SEC),
+        ("h", 3600 * USEC_PER_SEC),
+        ("min", 60 * USEC_PER_SEC),
+    ];
+    let mut res = Vec::new();
+    let mut rest = usec;
+    for (suffix, len) in units {
+        if rest >= len {
+            res.push(format!("{}{}", rest / len, suffix));
+            rest %= len;
+        }
+    }
+    if res.is_empty() {
+        let msec = rest / 1000;
+        res.push(format!("{}.{:0>3}s", msec / 1000, msec % 1000));
+    } else if rest >= USEC_PER_SEC {
+        res.push(format!("{}s", rest / USEC_PER_SEC));
+    }
+    res.join(" ")
+}
+
+/// "12.5M", in the base of 1024
+fn format_bytes(bytes: u64) -> String {
+    let suffixes = ["K", "M", "G", "T"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    for suffix in &suffixes[..suffixes.len() - 1] {
+        if value < 1024.0 {
+            return format!("{:.1}{}", value, suffix);
+        }
+        value /= 1024.0;
+    }
+    format!("{:.1}{}", value, suffixes[suffixes.len() - 1])
+}
+
+fn realtime_usec() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_micros() as u64,
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cmdproto::proto::UnitProcess;
+
+    #[test]
+    fn test_format_timespan() {
+        assert_eq!(format_timespan(1500), "0.001s");
+        assert_eq!(format_timespan(12 * USEC_PER_SEC + 345000), "12.345s");
+        assert_eq!(format_timespan(3661 * USEC_PER_SEC), "1h 1min 1s");
+        assert_eq!(format_timespan(2 * 24 * 3600 * USEC_PER_SEC), "2d");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5K");
+        assert_eq!(format_bytes(12 * 1024 * 1024 + 512 * 1024), "12.5M");
+    }
+
+    #[test]
+    fn test_format_unit_status() {
+        let status = UnitStatus {
+            name: "foo.service".to_string(),
+            load_state: "loaded".to_string(),
+            active_state: "failed".to_string(),
+            sub_state: "failed".to_string(),
+            cgroup_path: "system.slice/foo.service".to_string(),
+            processes: vec![
+                UnitProcess {
+                    pid: 1,
+                    cmdline: "/usr/bin/foo".to_string(),
+                },
+                UnitProcess {
+                    pid: 2,
+                    cmdline: "/usr/bin/bar".to_string(),
+                },
+            ],
+            exit_signal: Some(libc::SIGTERM),
+            status_text: Some("Serving 3 requests".to_string()),
+            ..Default::default()
+        };
+        let res = format_unit_status(&status, &[]);
+        assert!(res.contains("foo.service"));
+        assert!(res.contains("code=killed, signal=SIGTERM"));
+        assert!(res.contains("├─1 /usr/bin/foo"));
+        assert!(res.contains("└─2 /usr/bin/bar"));
+        assert!(res.contains("Status: \"Serving 3 requests\""));
+        assert!(!res.contains("Main PID:"));
+    }
+}
@@ -48,6 +48,17 @@ pub(crate) enum JobStage {
     End(JobResult),
 }
 
+impl fmt::Display for JobStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage = match self {
+            JobStage::Init | JobStage::Wait => "waiting",
+            JobStage::Running => "running",
+            JobStage::End(_) => "finished",
+        };
+        write!(f, "{}", stage)
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct JobConf {
     unit: Rc<UnitX>,
@@ -15,6 +15,7 @@ use super::entry::{Job, JobConf, JobInfo, JobResult};
 use super::rentry::{JobAttr, JobKind, JobRe};
 use super::stat::JobStat;
 use super::table::JobTable;
+use super::transaction::OrderingCycle;
 use super::{entry, junit, notify, table, transaction};
 use crate::unit::{DataManager, JobMode, UnitDb, UnitX};
 use crate::utils::table::{TableOp, TableSubscribe};
@@ -25,6 +26,9 @@ use event::{EventState, EventType, Events, Source};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+/// the number of the most recent ordering cycles kept for sctl
+const ORDERING_CYCLES_MAX: usize = 32;
+
 #[derive(Debug)]
 pub(crate) struct JobAffect {
     // data
@@ -191,7 +195,6 @@ impl JobManager {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub(crate) fn remove(&self, id: u128) -> Result<()> {
         self.data.remove(id)?;
         self.try_enable();
@@ -203,6 +206,14 @@ impl JobManager {
         self.data.get_jobinfo(id)
     }
 
+    pub(crate) fn get_all_jobinfos(&self) -> Vec<JobInfo> {
+        self.data.jobs.get_all()
+    }
+
+    pub(crate) fn get_ordering_cycles(&self) -> Vec<OrderingCycle> {
+        self.data.cycles.borrow().clone()
+    }
+
     pub(crate) fn has_job(&self, unit: &Rc<UnitX>) -> bool {
         let trigger = self.data.jobs.get_trigger_info(unit).is_some();
         let suspend = !self.data.jobs.get_suspends(unit).is_empty();
@@ -317,6 +328,7 @@ struct JobManagerData {
 
     // statistics
     stat: JobStat,
+    cycles: RefCell<Vec<OrderingCycle>>, // the most recent ordering cycles found in transactions
 }
 
 // the declaration "pub(self)" is for identification only.
@@ -342,6 +354,7 @@ impl JobManagerData {
             text: RefCell::new(None),
 
             stat: JobStat::new(),
+            cycles: RefCell::new(Vec::new()),
         }
     }
 
@@ -352,6 +365,7 @@ impl JobManagerData {
         *self.running.borrow_mut() = false;
         *self.text.borrow_mut() = None;
         self.stat.clear();
+        self.cycles.borrow_mut().clear();
     }
 
     pub(self) fn rentry_trigger_merge(&self, unit_id: &str, force: bool) {
@@ -421,7 +435,17 @@ impl JobManagerData {
         // build changes in stage
         transaction::job_trans_expand(&self.stage, &self.ja, &self.db, config, mode)?;
         transaction::job_trans_affect(&self.stage, &self.ja, &self.db, config, mode)?;
-        transaction::job_trans_verify(&self.stage, &self.jobs, mode)?;
+        let mut cycles = Vec::new();
+        let ret = transaction::job_trans_verify(
+            &self.stage,
+            &self.jobs,
+            &self.db,
+            config,
+            mode,
+            &mut cycles,
+        );
+        self.record_cycles(cycles);
+        ret?;
 
         // commit stage to jobs
         let (add_jobs, del_jobs, update_jobs) = self.jobs.commit(&self.stage, mode)?;
@@ -519,7 +543,6 @@ impl JobManagerData {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub(self) fn remove(&self, id: u128) -> Result<()> {
         assert!(!*self.running.borrow());
 
@@ -565,6 +588,15 @@ impl JobManagerData {
         self.jobs.calc_ready()
     }
 
+    fn record_cycles(&self, mut cycles: Vec<OrderingCycle>) {
+        let mut records = self.cycles.borrow_mut();
+        records.append(&mut cycles);
+        let len = records.len();
+        if len > ORDERING_CYCLES_MAX {
+            records.drain(..len - ORDERING_CYCLES_MAX);
+        }
+    }
+
     fn remove_unit(&self, unit: &UnitX) {
         // delete related jobs
         let (del_trigger, del_suspends) = self.jobs.remove_unit(unit);
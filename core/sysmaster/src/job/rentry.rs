@@ -13,6 +13,7 @@
 use crate::manager::rentry::{RELI_DB_HJOB_SUSPENDS, RELI_DB_HJOB_TRIGGER};
 use core::rel::{ReDb, Reliability};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::hash::Hash;
 use std::rc::Rc;
 
@@ -36,6 +37,23 @@ pub(crate) enum JobKind {
     ReloadOrStart,
 }
 
+impl fmt::Display for JobKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            JobKind::Start => "start",
+            JobKind::Stop => "stop",
+            JobKind::Reload => "reload",
+            JobKind::Restart => "restart",
+            JobKind::Verify => "verify-active",
+            JobKind::Nop => "nop",
+            JobKind::TryReload => "try-reload",
+            JobKind::TryRestart => "try-restart",
+            JobKind::ReloadOrStart => "reload-or-start",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
 pub(super) fn job_is_basic_op(kind: JobKind) -> bool {
     match kind {
         JobKind::Start | JobKind::Stop | JobKind::Reload | JobKind::Restart => true,
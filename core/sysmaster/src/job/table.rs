@@ -268,6 +268,17 @@ impl JobTable {
         self.t_id.borrow().get(&id).map(|job| JobInfo::map(job))
     }
 
+    pub(super) fn get_all(&self) -> Vec<JobInfo> {
+        let mut jobs: Vec<JobInfo> = self
+            .t_id
+            .borrow()
+            .values()
+            .map(|job| JobInfo::map(job))
+            .collect();
+        jobs.sort_by_key(|job| job.id);
+        jobs
+    }
+
     pub(super) fn get_suspends(&self, unit: &UnitX) -> Vec<JobInfo> {
         self.t_unit
             .borrow()
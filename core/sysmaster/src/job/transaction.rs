@@ -17,10 +17,27 @@ use super::table::JobTable;
 use crate::unit::JobMode;
 use crate::unit::UnitDb;
 use crate::unit::UnitX;
+use basic::time::now_clockid;
 use core::error::*;
 use core::unit::UnitRelationAtom;
+use libc::CLOCK_REALTIME;
+use std::collections::HashSet;
 use std::rc::Rc;
 
+/// An ordering cycle found in a transaction, and how it is broken
+#[derive(Clone, Debug)]
+pub(crate) struct OrderingCycle {
+    /// CLOCK_REALTIME in microseconds
+    pub(crate) timestamp: u64,
+    /// the job the transaction is built for, i.e. "multi-user.target/start"
+    pub(crate) anchor: String,
+    /// the jobs in the cycle, each one is ordered after the next one, and the last one is
+    /// ordered after the first one
+    pub(crate) jobs: Vec<String>,
+    /// the job deleted to break the cycle, None if the transaction is refused
+    pub(crate) dropped: Option<String>,
+}
+
 pub(super) fn job_trans_expand(
     stage: &JobTable,
     ja: &JobAlloc,
@@ -68,10 +85,18 @@ pub(super) fn job_trans_affect(
     }
 }
 
-pub(super) fn job_trans_verify(stage: &JobTable, jobs: &JobTable, mode: JobMode) -> Result<()> {
+pub(super) fn job_trans_verify(
+    stage: &JobTable,
+    jobs: &JobTable,
+    db: &UnitDb,
+    config: &JobConf,
+    mode: JobMode,
+    cycles: &mut Vec<OrderingCycle>,
+) -> Result<()> {
     // job-list + unit-list(from db) -> job-list' => stage
     // todo!(); transaction_activate: the other parts is waiting for future support
 
+    trans_verify_order(stage, db, config, cycles)?;
     trans_verify_is_conflict(stage)?;
     trans_verify_is_destructive(stage, jobs, mode)?;
 
@@ -302,6 +327,189 @@ fn trans_affect_trigger(
     // the jobs expanded do not need to be reverted separately, which are reverted in the up-level caller 'JobManagerData->exec()' uniformly.
 }
 
+fn trans_verify_order(
+    stage: &JobTable,
+    db: &UnitDb,
+    config: &JobConf,
+    cycles: &mut Vec<OrderingCycle>,
+) -> Result<()> {
+    let anchor = format!("{}/{}", config.get_unit().id(), config.get_kind());
+    let essentials = trans_essential_units(stage, db, config.get_unit());
+
+    // break the cycles one by one, deleting a job may break several cycles at once
+    while let Some(cycle) = trans_find_order_cycle(stage, db) {
+        let jobs: Vec<String> = cycle.iter().map(|u| trans_job_name(stage, u)).collect();
+        log::warn!(
+            "Found ordering cycle on {}: {} after {}",
+            anchor,
+            jobs.join(" after "),
+            jobs[0]
+        );
+
+        // only the start jobs pulled in by Wants= are not essential to the anchor
+        let victim = cycle.iter().find(|u| {
+            !essentials.contains(&u.id()) && stage.get_suspend(u, JobKind::Start).is_some()
+        });
+        let dropped = victim.map(|u| trans_job_name(stage, u));
+        cycles.push(OrderingCycle {
+            timestamp: now_clockid(CLOCK_REALTIME),
+            anchor: anchor.clone(),
+            jobs: jobs.clone(),
+            dropped: dropped.clone(),
+        });
+
+        match (victim, dropped) {
+            (Some(unit), Some(name)) => {
+                log::warn!("Deleted job {} to break the ordering cycle.", name);
+                let pulled = trans_pulled_units(stage, db, unit, &TRANS_PULL_IN_ATOMS);
+                let (_, del_jobs) = stage.remove_unit(unit);
+                for job in del_jobs.iter() {
+                    job.clear();
+                }
+                trans_collect_garbage(stage, db, config.get_unit(), &pulled);
+            }
+            _ => {
+                log::error!(
+                    "Unable to break the ordering cycle on {}, every job in it is essential.",
+                    anchor
+                );
+                return Err(Error::OrderingCycle {
+                    cycle: jobs.join(" after "),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// the dependencies through which the jobs pull in the jobs of other units
+const TRANS_PULL_IN_ATOMS: [UnitRelationAtom; 7] = [
+    UnitRelationAtom::UnitAtomPullInStart,
+    UnitRelationAtom::UnitAtomPullInStartIgnored,
+    UnitRelationAtom::UnitAtomPullInVerify,
+    UnitRelationAtom::UnitAtomPullInStop,
+    UnitRelationAtom::UnitAtomPullInStopIgnored,
+    UnitRelationAtom::UnitAtomPropagateStop,
+    UnitRelationAtom::UnitAtomPropagateRestart,
+];
+
+/// the units of the anchor itself and the ones pulled in by it through the requirement
+/// dependencies, their jobs can not be deleted without failing the anchor
+fn trans_essential_units(stage: &JobTable, db: &UnitDb, anchor: &Rc<UnitX>) -> HashSet<String> {
+    let atoms = [
+        UnitRelationAtom::UnitAtomPullInStart,
+        UnitRelationAtom::UnitAtomPullInVerify,
+        UnitRelationAtom::UnitAtomPullInStop,
+        UnitRelationAtom::UnitAtomPropagateStop,
+        UnitRelationAtom::UnitAtomPropagateRestart,
+    ];
+    trans_pulled_units(stage, db, anchor, &atoms)
+}
+
+/// the unit itself and the units with jobs in the stage reachable from it through the atoms
+fn trans_pulled_units(
+    stage: &JobTable,
+    db: &UnitDb,
+    unit: &Rc<UnitX>,
+    atoms: &[UnitRelationAtom],
+) -> HashSet<String> {
+    let mut pulled = HashSet::from([unit.id()]);
+    let mut pending = vec![Rc::clone(unit)];
+    while let Some(unit) = pending.pop() {
+        for atom in atoms {
+            for other in db.dep_gets_atom(&unit, *atom) {
+                if stage.is_unit_empty(&other) || pulled.contains(&other.id()) {
+                    continue;
+                }
+                pulled.insert(other.id());
+                pending.push(other);
+            }
+        }
+    }
+    pulled
+}
+
+/// delete the jobs which were pulled in by a deleted job and are not pulled in by the
+/// anchor any more, like the jobs pulled in by the Wants= of the deleted one
+fn trans_collect_garbage(
+    stage: &JobTable,
+    db: &UnitDb,
+    anchor: &Rc<UnitX>,
+    candidates: &HashSet<String>,
+) {
+    let anchored = trans_pulled_units(stage, db, anchor, &TRANS_PULL_IN_ATOMS);
+    for job in stage.get_all() {
+        let id = job.unit.id();
+        if !candidates.contains(&id) || anchored.contains(&id) || stage.is_unit_empty(&job.unit) {
+            continue;
+        }
+        log::debug!(
+            "Deleted job {}, nothing pulls it in any more.",
+            trans_job_name(stage, &job.unit)
+        );
+        let (_, del_jobs) = stage.remove_unit(&job.unit);
+        for job in del_jobs.iter() {
+            job.clear();
+        }
+    }
+}
+
+/// one cycle of the After= ordering among the units with jobs in the stage
+fn trans_find_order_cycle(stage: &JobTable, db: &UnitDb) -> Option<Vec<Rc<UnitX>>> {
+    let mut units: Vec<Rc<UnitX>> = Vec::new();
+    for job in stage.get_all() {
+        if !units.iter().any(|u| u.id() == job.unit.id()) {
+            units.push(Rc::clone(&job.unit));
+        }
+    }
+    units.sort_by_key(|u| u.id());
+
+    let mut done = HashSet::new();
+    let mut path = Vec::new();
+    units
+        .iter()
+        .find_map(|unit| trans_order_dfs(stage, db, unit, &mut path, &mut done))
+}
+
+fn trans_order_dfs(
+    stage: &JobTable,
+    db: &UnitDb,
+    unit: &Rc<UnitX>,
+    path: &mut Vec<Rc<UnitX>>,
+    done: &mut HashSet<String>,
+) -> Option<Vec<Rc<UnitX>>> {
+    if done.contains(&unit.id()) {
+        return None;
+    }
+    if let Some(pos) = path.iter().position(|u| u.id() == unit.id()) {
+        return Some(path[pos..].to_vec());
+    }
+
+    path.push(Rc::clone(unit));
+    let mut afters = db.dep_gets_atom(unit, UnitRelationAtom::UnitAtomAfter);
+    afters.retain(|other| !stage.is_unit_empty(other));
+    afters.sort_by_key(|other| other.id());
+    for other in afters.iter() {
+        if let Some(cycle) = trans_order_dfs(stage, db, other, path, done) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    done.insert(unit.id());
+    None
+}
+
+/// the jobs of the unit in the stage, i.e. "foo.service/start"
+fn trans_job_name(stage: &JobTable, unit: &UnitX) -> String {
+    let kinds: Vec<String> = stage
+        .get_suspends(unit)
+        .iter()
+        .map(|job| job.kind.to_string())
+        .collect();
+    format!("{}/{}", unit.id(), kinds.join("|"))
+}
+
 fn trans_verify_is_conflict(stage: &JobTable) -> Result<()> {
     if stage.is_suspends_conflict() {
         return Err(Error::Conflict);
@@ -678,6 +886,122 @@ mod tests {
         assert_eq!(ret.len(), 0);
     }
 
+    #[test]
+    fn jt_api_verify_order_cycle_wants() {
+        let (reli, db, unit_test1, unit_test2) = prepare_unit_multi(UnitRelations::UnitWants);
+        prepare_order_cycle(&db, &unit_test1, &unit_test2);
+        let rentry = Rc::new(JobRe::new(&reli));
+        let stage = JobTable::new(&db);
+        let jobs = JobTable::new(&db);
+        let ja = JobAlloc::new(
+            &reli,
+            &rentry,
+            &Rc::new(Events::new().unwrap()),
+            &Rc::new(DataManager::new()),
+        );
+
+        let mode = JobMode::Replace;
+        let conf = JobConf::new(&unit_test1, JobKind::Start);
+        job_trans_expand(&stage, &ja, &db, &conf, mode).unwrap();
+        assert_eq!(stage.len(), 2);
+
+        // the start job of test2 is only wanted, it is deleted to break the cycle
+        let mut cycles = Vec::new();
+        let ret = job_trans_verify(&stage, &jobs, &db, &conf, mode, &mut cycles);
+        assert!(ret.is_ok());
+        assert_eq!(stage.len(), 1);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].anchor, "test1.service/start");
+        assert_eq!(cycles[0].jobs.len(), 2);
+        assert_eq!(cycles[0].dropped.as_deref(), Some("test2.service/start"));
+    }
+
+    #[test]
+    fn jt_api_verify_order_cycle_garbage() {
+        let (reli, db, unit_test1, unit_test2) = prepare_unit_multi(UnitRelations::UnitWants);
+        prepare_order_cycle(&db, &unit_test1, &unit_test2);
+        let rentry = Rc::new(UnitRe::new(&reli));
+        let name_test3 = String::from("shutdown.target");
+        let unit_test3 = create_unit(&Rc::new(DataManager::new()), &reli, &rentry, &name_test3);
+        db.units_insert(name_test3, Rc::clone(&unit_test3));
+        db.dep_insert(
+            Rc::clone(&unit_test2),
+            UnitRelations::UnitRequires,
+            Rc::clone(&unit_test3),
+            true,
+            0,
+        )
+        .unwrap();
+        let rentry = Rc::new(JobRe::new(&reli));
+        let stage = JobTable::new(&db);
+        let jobs = JobTable::new(&db);
+        let ja = JobAlloc::new(
+            &reli,
+            &rentry,
+            &Rc::new(Events::new().unwrap()),
+            &Rc::new(DataManager::new()),
+        );
+
+        let mode = JobMode::Replace;
+        let conf = JobConf::new(&unit_test1, JobKind::Start);
+        job_trans_expand(&stage, &ja, &db, &conf, mode).unwrap();
+        assert_eq!(stage.len(), 3);
+
+        // the start job of shutdown.target is only pulled in by the deleted one, it is deleted too
+        let mut cycles = Vec::new();
+        let ret = job_trans_verify(&stage, &jobs, &db, &conf, mode, &mut cycles);
+        assert!(ret.is_ok());
+        assert_eq!(stage.len(), 1);
+        assert!(stage.is_unit_empty(&unit_test3));
+        assert_eq!(cycles.len(), 1);
+    }
+
+    #[test]
+    fn jt_api_verify_order_cycle_requires() {
+        let (reli, db, unit_test1, unit_test2) = prepare_unit_multi(UnitRelations::UnitRequires);
+        prepare_order_cycle(&db, &unit_test1, &unit_test2);
+        let rentry = Rc::new(JobRe::new(&reli));
+        let stage = JobTable::new(&db);
+        let jobs = JobTable::new(&db);
+        let ja = JobAlloc::new(
+            &reli,
+            &rentry,
+            &Rc::new(Events::new().unwrap()),
+            &Rc::new(DataManager::new()),
+        );
+
+        let mode = JobMode::Replace;
+        let conf = JobConf::new(&unit_test1, JobKind::Start);
+        job_trans_expand(&stage, &ja, &db, &conf, mode).unwrap();
+
+        // every job in the cycle is essential, the transaction is refused
+        let mut cycles = Vec::new();
+        let ret = job_trans_verify(&stage, &jobs, &db, &conf, mode, &mut cycles);
+        assert!(ret.is_err());
+        assert_eq!(cycles.len(), 1);
+        assert!(cycles[0].dropped.is_none());
+    }
+
+    fn prepare_order_cycle(db: &Rc<UnitDb>, unit_test1: &Rc<UnitX>, unit_test2: &Rc<UnitX>) {
+        let relation = UnitRelations::UnitAfter;
+        db.dep_insert(
+            Rc::clone(unit_test1),
+            relation,
+            Rc::clone(unit_test2),
+            true,
+            0,
+        )
+        .unwrap();
+        db.dep_insert(
+            Rc::clone(unit_test2),
+            relation,
+            Rc::clone(unit_test1),
+            true,
+            0,
+        )
+        .unwrap();
+    }
+
     fn prepare_unit_multi(
         relation: UnitRelations,
     ) -> (Rc<Reliability>, Rc<UnitDb>, Rc<UnitX>, Rc<UnitX>) {
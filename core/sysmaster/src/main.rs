@@ -42,7 +42,6 @@ use crate::manager::{Action, Manager, Mode, MANAGER_ARGS_SIZE_MAX};
 use crate::mount::setup;
 use basic::{machine, machine::Machine, mount::read_lines, FSTAB_PATH, MOUNT_BIN};
 use clap::Parser;
-use constants::LOG_FILE_PATH;
 use core::error::*;
 use core::rel;
 use libc::{c_int, PR_SET_CHILD_SUBREAPER};
@@ -64,10 +63,22 @@ struct Args {
     #[clap(long)]
     /// Reload the configuration.
     deserialize: bool,
+
+    #[clap(long, value_name = "FILE", multiple_values = true)]
+    /// Verify the unit files and their drop-ins offline, and exit non-zero if any problem is found.
+    verify: Vec<String>,
+
+    #[clap(long)]
+    /// Run as the service manager of the calling user.
+    user: bool,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    /* Verifying the unit files needs no manager, and must not touch the system. */
+    if !args.verify.is_empty() {
+        exit(unit::verify_units(&args.verify));
+    }
     //------------------------Code placed at the top-----------------------------
 
     /* systemd use reset_all_signal_handlers, we can't because sysmaster is not PID1.
@@ -81,11 +92,15 @@ fn main() -> Result<()> {
 
     //---------------------------------------------------------------------------
 
-    //remount / to rw permission, as log will create file if user want flush to to file
-    remount_sysroot();
+    let mode = if args.user { Mode::User } else { Mode::System };
+    if mode == Mode::User {
+        prepare_user_runtime()?;
+    } else {
+        //remount / to rw permission, as log will create file if user want flush to to file
+        remount_sysroot();
+    }
 
-    let system = Mode::System;
-    let manager_config = Rc::new(RefCell::new(ManagerConfig::new(&system)));
+    let manager_config = Rc::new(RefCell::new(ManagerConfig::new(&mode)));
     log::init_log(
         "sysmaster",
         Level::from_str(&manager_config.borrow().LogLevel).unwrap(),
@@ -94,12 +109,12 @@ fn main() -> Result<()> {
             .LogTarget
             .split(&[' ', '-'][..])
             .collect(),
-        LOG_FILE_PATH,
+        &mode.log_file_path().to_string_lossy(),
         manager_config.borrow().LogFileSize,
         manager_config.borrow().LogFileNumber,
         false,
     );
-    log::info!("sysmaster running in system mode.");
+    log::info!("sysmaster running in {:?} mode.", mode);
 
     // temporary annotation for repeat mount
 
@@ -108,15 +123,17 @@ fn main() -> Result<()> {
     //     format!("failed to mount early mount point, errno: {}", e)
     // })?;
 
-    setup::mount_setup()?;
+    if mode == Mode::System {
+        setup::mount_setup()?;
+    }
 
     rel::reli_dir_prepare()?;
     let self_recovery_enable = rel::reli_debug_get_switch();
     log::info!("sysmaster self_recovery_enable: {}.", self_recovery_enable);
 
-    initialize_runtime(self_recovery_enable)?;
+    initialize_runtime(self_recovery_enable, mode)?;
 
-    let manager = Manager::new(system, Action::Run, manager_config);
+    let manager = Manager::new(mode, Action::Run, manager_config);
 
     // enable clear
     if !self_recovery_enable && !args.deserialize {
@@ -145,7 +162,23 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn initialize_runtime(self_recovery_enable: bool) -> Result<()> {
+/* The user manager keeps its sockets and runtime data in $XDG_RUNTIME_DIR/sysmaster,
+ * which is private to the user. */
+fn prepare_user_runtime() -> Result<()> {
+    let runtime_dir = match basic::fs::user_runtime_dir() {
+        None => {
+            return Err(Error::Other {
+                msg: "XDG_RUNTIME_DIR is not set, refusing to run the user manager".to_string(),
+            })
+        }
+        Some(v) => v,
+    };
+    std::fs::create_dir_all(&runtime_dir)?;
+    rel::reli_dir_set(&format!("{}/reliability", runtime_dir));
+    Ok(())
+}
+
+fn initialize_runtime(self_recovery_enable: bool, mode: Mode) -> Result<()> {
     if self_recovery_enable {
         install_crash_handler();
         log::info!("install crash handler.");
@@ -154,6 +187,12 @@ fn initialize_runtime(self_recovery_enable: bool) -> Result<()> {
         register_reexec_signal(false);
     }
 
+    /* the user manager runs on the system set up by the system manager */
+    if mode == Mode::User {
+        set_child_reaper();
+        return Ok(());
+    }
+
     #[cfg(feature = "linux")]
     setup::mount_cgroup_controllers().map_err(|e| Error::Other {
         msg: format!("mount cgroup controllers failed: {}", e),
@@ -10,6 +10,7 @@
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
 
+use super::SYSTEM_RUNTIME_DIR;
 use basic::do_entry_log;
 use basic::fs::{self, is_symlink};
 use cmdproto::proto::execute::ExecuterAction;
@@ -21,8 +22,6 @@ use std::os::unix::io::RawFd;
 use std::path::Path;
 use std::{os::unix::prelude::AsRawFd, rc::Rc};
 
-use constants::PRIVATE_SOCKET;
-
 pub(super) struct Commands<T> {
     // associated objects
     reli: Rc<Reliability>,
@@ -36,18 +35,19 @@ impl<T> Commands<T>
 where
     T: ExecuterAction,
 {
-    pub(super) fn new(relir: &Rc<Reliability>, comm_action: T) -> Self {
+    pub(super) fn new(relir: &Rc<Reliability>, comm_action: T, socket_path: &Path) -> Self {
         /* The socket is used to communicate with sctl, panic if any of the following steps fail. */
-        let sctl_socket_path = Path::new(PRIVATE_SOCKET);
+        let sctl_socket_path = socket_path;
+        /* the runtime directory of the user manager is private to the user */
         let run_sysmaster = sctl_socket_path.parent().unwrap();
-        if run_sysmaster.exists() {
-            let _ = fs::chmod("/run/sysmaster", 0o755);
+        if run_sysmaster == Path::new(SYSTEM_RUNTIME_DIR) && run_sysmaster.exists() {
+            let _ = fs::chmod(SYSTEM_RUNTIME_DIR, 0o755);
         }
         /* remove the old socket if it exists */
         if sctl_socket_path.exists() && !is_symlink(sctl_socket_path) {
             do_entry_log!(std::fs::remove_file, sctl_socket_path, "remove");
         }
-        let sctl_socket_addr = socket::UnixAddr::new(Path::new(PRIVATE_SOCKET)).unwrap();
+        let sctl_socket_addr = socket::UnixAddr::new(sctl_socket_path).unwrap();
         let socket_fd = socket::socket(
             socket::AddressFamily::Unix,
             socket::SockType::Stream,
@@ -143,13 +143,17 @@ mod tests {
     use std::fs::File;
     use std::io::{Read, Write};
     use std::os::unix::prelude::FromRawFd;
+    use std::path::Path;
     use std::process::exit;
     use std::sync::{Arc, Mutex};
     use std::{os::unix::net::UnixStream, rc::Rc};
 
     use cmdproto::error::Result;
-    use cmdproto::proto::{execute::ExecuterAction, unit_comm};
-    use cmdproto::proto::{CommandRequest, ProstClientStream};
+    use cmdproto::proto::{execute::ExecuterAction, transient_unit_comm::UnitProperty, unit_comm};
+    use cmdproto::proto::{
+        unit_dependency_comm, CommandRequest, JobEntry, OrderingCycle, ProstClientStream,
+    };
+    use cmdproto::proto::{BootTimes, UnitDependency, UnitListEntry, UnitStatus};
     use constants::PRIVATE_SOCKET;
     use core::rel::{ReliConf, Reliability};
     use event::{EventState, Events};
@@ -163,7 +167,6 @@ mod tests {
 
     impl ExecuterAction for TestExecAction {
         type Error = nix::Error;
-        type Status = nix::Error;
 
         fn start(&self, _unit_name: &str) -> Result<(), Self::Error> {
             Err(nix::Error::ENOENT)
@@ -189,12 +192,67 @@ mod tests {
             Ok(())
         }
 
-        fn status(&self, _unit_name: &str) -> Result<Self::Status, Self::Error> {
-            Ok(nix::Error::EINVAL)
+        fn kill(&self, _unit_name: &str, _signal: i32) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn status(&self, unit_name: &str) -> Result<UnitStatus, Self::Error> {
+            Ok(UnitStatus {
+                name: unit_name.to_string(),
+                active_state: "inactive".to_string(),
+                ..Default::default()
+            })
+        }
+
+        fn show(&self, _unit_name: &str) -> Result<Vec<UnitProperty>, Self::Error> {
+            Ok(Vec::new())
+        }
+
+        fn set_property(
+            &self,
+            _unit_name: &str,
+            _properties: &[UnitProperty],
+            _runtime: bool,
+        ) -> Result<(), Self::Error> {
+            Ok(())
         }
 
-        fn list_units(&self) -> Result<String, Self::Error> {
-            Ok(String::new())
+        fn edit(
+            &self,
+            _unit_name: &str,
+            _content: &str,
+            _runtime: bool,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn list_dependencies(
+            &self,
+            _unit_name: &str,
+            _mode: unit_dependency_comm::Mode,
+            _all: bool,
+        ) -> Result<Vec<UnitDependency>, Self::Error> {
+            Ok(Vec::new())
+        }
+
+        fn list_units(&self) -> Result<Vec<UnitListEntry>, Self::Error> {
+            Ok(Vec::new())
+        }
+
+        fn boot_times(&self) -> Result<BootTimes, Self::Error> {
+            Ok(BootTimes::default())
+        }
+
+        fn list_jobs(&self) -> Result<Vec<JobEntry>, Self::Error> {
+            Ok(Vec::new())
+        }
+
+        fn cancel_job(&self, _job_id: u64) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn list_ordering_cycles(&self) -> Result<Vec<OrderingCycle>, Self::Error> {
+            Ok(Vec::new())
         }
 
         fn suspend(&self) -> Result<i32, Self::Error> {
@@ -229,6 +287,10 @@ mod tests {
             Ok(())
         }
 
+        fn revert(&self, _unit_name: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
         fn daemon_reload(&self) {}
 
         fn daemon_reexec(&self) {}
@@ -269,7 +331,7 @@ mod tests {
                 ReliConf::new().set_max_dbs(RELI_HISTORY_MAX_DBS),
             ));
             /* This will remove the /run/sysmaster/sctl on the compiling environment. */
-            let command = Rc::new(Commands::new(&reli, exec_action));
+            let command = Rc::new(Commands::new(&reli, exec_action, Path::new(PRIVATE_SOCKET)));
             let e = Events::new().unwrap();
             e.add_source(command.clone()).unwrap();
             e.set_enabled(command, EventState::On).unwrap();
@@ -383,7 +445,7 @@ mod tests {
             ReliConf::new().set_max_dbs(RELI_HISTORY_MAX_DBS),
         ));
         /* This will remove the /run/sysmaster/sctl on the compiling environment. */
-        let command = Rc::new(Commands::new(&reli, exec_action));
+        let command = Rc::new(Commands::new(&reli, exec_action, Path::new(PRIVATE_SOCKET)));
         let e = Events::new().unwrap();
         e.add_source(command.clone()).unwrap();
         e.set_enabled(command.clone(), EventState::On).unwrap();
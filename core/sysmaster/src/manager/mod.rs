@@ -11,7 +11,6 @@
 // See the Mulan PSL v2 for more details.
 
 use constants::LOG_FILE_PATH;
-use core::unit::UnitStatus;
 use nix::sys::signalfd::siginfo;
 #[cfg(test)]
 pub(crate) use rentry::RELI_HISTORY_MAX_DBS;
@@ -27,11 +26,15 @@ use crate::unit::UnitManagerX;
 use alive_timer::AliveTimer;
 use basic::fs::LookupPaths;
 use basic::process::{self};
-use basic::{CGROUP_SYSMASTER, MULTI_USER_TARGET};
+use basic::{CGROUP_SYSMASTER, DEFAULT_TARGET, EXIT_TARGET, MULTI_USER_TARGET};
 use cgroup::CgController;
 use cgroup::{cg_create_and_attach, CgFlags};
 use cmdproto::proto::execute::ExecuterAction;
-use cmdproto::proto::transient_unit_comm::UnitConfig;
+use cmdproto::proto::transient_unit_comm::{UnitConfig, UnitProperty};
+use cmdproto::proto::{
+    unit_dependency_comm, BootTimes, JobEntry, OrderingCycle, UnitDependency, UnitListEntry,
+    UnitStatus,
+};
 use commands::Commands;
 use core::error::*;
 use core::rel::{ReliConf, ReliLastFrame, Reliability};
@@ -44,26 +47,46 @@ use pre_install::{Install, PresetMode};
 use signals::{SignalDispatcher, Signals};
 use std::cell::RefCell;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::str::FromStr;
 
 /// maximal size of process's arguments
 pub const MANAGER_ARGS_SIZE_MAX: usize = 5; // 6 - 1
 
+/// the runtime directory of the system manager
+const SYSTEM_RUNTIME_DIR: &str = "/run/sysmaster";
+
 struct SignalMgr {
     um: Rc<UnitManagerX>,
+    mode: Mode,
 }
 
 impl SignalMgr {
-    fn new(um: Rc<UnitManagerX>) -> Self {
-        SignalMgr { um: Rc::clone(&um) }
+    fn new(um: Rc<UnitManagerX>, mode: Mode) -> Self {
+        SignalMgr {
+            um: Rc::clone(&um),
+            mode,
+        }
     }
     fn reexec(&self) -> Result<i32> {
         self.um.set_state(State::ReExecute);
         Ok(1)
     }
 
+    /* the user manager is stopped by SIGTERM when the user logs out, it stops all
+     * the units by starting exit.target and exits once the target is reached */
+    fn exit(&self) -> Result<i32> {
+        if let Err(e) = self
+            .um
+            .start_unit(EXIT_TARGET, false, "replace_irreversible")
+        {
+            log::error!("Failed to start {}, exiting now: {}", EXIT_TARGET, e);
+            self.um.set_state(State::Exit);
+        }
+        Ok(1)
+    }
+
     fn reload(&self) -> Result<i32> {
         self.um.set_state(State::ReLoad);
         Ok(1)
@@ -75,6 +98,7 @@ impl SignalDispatcher for SignalMgr {
         /* Received signal should be in the set defined in EVENT_SIGNALS */
         match signal.ssi_signo as libc::c_int {
             libc::SIGHUP => self.reload(),
+            libc::SIGTERM if self.mode == Mode::User => self.exit(),
             libc::SIGTERM => self.reexec(),
             libc::SIGCHLD => Ok(self.um.child_sigchld_enable(true)),
             /* Kernel will send SIGINT to PID1 when users press ctrl-alt-del,
@@ -107,7 +131,6 @@ impl CommandActionMgr {
 
 impl ExecuterAction for CommandActionMgr {
     type Error = core::error::Error;
-    type Status = core::unit::UnitStatus;
     // type Result<T, Error> = Result<T, E>;
     fn start(&self, unit_name: &str) -> Result<(), Self::Error> {
         self.um.start_unit(unit_name, true, "replace")
@@ -133,14 +156,62 @@ impl ExecuterAction for CommandActionMgr {
         self.um.reset_failed(unit_name)
     }
 
+    fn kill(&self, unit_name: &str, signal: i32) -> Result<(), Self::Error> {
+        self.um.kill_unit(unit_name, signal)
+    }
+
     fn status(&self, unit_name: &str) -> Result<UnitStatus, Self::Error> {
         self.um.get_unit_status(unit_name)
     }
 
-    fn list_units(&self) -> Result<String, Self::Error> {
+    fn show(&self, unit_name: &str) -> Result<Vec<UnitProperty>, Self::Error> {
+        self.um.get_unit_properties(unit_name)
+    }
+
+    fn set_property(
+        &self,
+        unit_name: &str,
+        properties: &[UnitProperty],
+        runtime: bool,
+    ) -> Result<(), Self::Error> {
+        self.um.set_unit_properties(unit_name, properties, runtime)
+    }
+
+    fn edit(&self, unit_name: &str, content: &str, runtime: bool) -> Result<(), Self::Error> {
+        self.um
+            .edit_unit(unit_name, content, runtime)
+            .map(|_| self.daemon_reload())
+    }
+
+    fn list_dependencies(
+        &self,
+        unit_name: &str,
+        mode: unit_dependency_comm::Mode,
+        all: bool,
+    ) -> Result<Vec<UnitDependency>, Self::Error> {
+        self.um.get_unit_dependencies(unit_name, mode, all)
+    }
+
+    fn list_units(&self) -> Result<Vec<UnitListEntry>, Self::Error> {
         self.um.get_all_units()
     }
 
+    fn boot_times(&self) -> Result<BootTimes, Self::Error> {
+        self.um.get_boot_times()
+    }
+
+    fn list_jobs(&self) -> Result<Vec<JobEntry>, Self::Error> {
+        Ok(self.um.get_all_jobs())
+    }
+
+    fn cancel_job(&self, job_id: u64) -> Result<(), Self::Error> {
+        self.um.cancel_job(job_id)
+    }
+
+    fn list_ordering_cycles(&self) -> Result<Vec<OrderingCycle>, Self::Error> {
+        Ok(self.um.get_ordering_cycles())
+    }
+
     fn suspend(&self) -> Result<i32, Self::Error> {
         self.set_state(State::Suspend);
         Ok(0)
@@ -179,6 +250,10 @@ impl ExecuterAction for CommandActionMgr {
         self.um.unmask_unit(unit_file).map(|_| self.daemon_reload())
     }
 
+    fn revert(&self, unit_file: &str) -> Result<(), Self::Error> {
+        self.um.revert_unit(unit_file).map(|_| self.daemon_reload())
+    }
+
     fn daemon_reload(&self) {
         self.set_state(State::ReLoad);
     }
@@ -237,13 +312,20 @@ impl Manager {
                 .set_max_dbs(rentry::RELI_HISTORY_MAX_DBS),
         ));
         let mut l_path = LookupPaths::new();
-        l_path.init_lookup_paths();
+        match mode {
+            Mode::System => l_path.init_lookup_paths(),
+            Mode::User => l_path.init_user_lookup_paths(
+                &mode.runtime_dir().to_string_lossy(),
+                &basic::fs::user_config_dir().unwrap_or_default(),
+            ),
+        }
         let lookup_path = Rc::new(l_path);
         let state = Rc::new(RefCell::new(State::Init));
         let um = Rc::new(UnitManagerX::new(
             &event,
             &reli,
             &lookup_path,
+            mode,
             Rc::clone(&state),
             manager_config.clone(),
         ));
@@ -255,8 +337,9 @@ impl Manager {
             commands: Rc::new(Commands::new(
                 &reli,
                 CommandActionMgr::new(Rc::clone(&um), Rc::clone(&state)),
+                &mode.runtime_dir().join("private"),
             )),
-            signal: Rc::new(Signals::new(&reli, SignalMgr::new(Rc::clone(&um)))),
+            signal: Rc::new(Signals::new(&reli, SignalMgr::new(Rc::clone(&um), mode))),
             reli,
             mode,
             _action: action,
@@ -271,8 +354,9 @@ impl Manager {
     fn add_default_job(&self) -> Result<i32> {
         self.reli.set_last_frame1(ReliLastFrame::ManagerOp as u32);
         // add target "SPECIAL_DEFAULT_TARGET"
-        if let Err(e) = self.um.start_unit(MULTI_USER_TARGET, false, "replace") {
-            log::error!("Failed to start multi-user.target: {:?}", e);
+        let target = self.mode.default_target();
+        if let Err(e) = self.um.start_unit(target, false, "replace") {
+            log::error!("Failed to start {}: {:?}", target, e);
         }
         self.reli.clear_last_frame();
         Ok(0)
@@ -345,9 +429,28 @@ impl Manager {
     pub fn main_loop(&self) -> Result<bool> {
         loop {
             let state = self.rloop()?;
+            if self.mode == Mode::User
+                && [
+                    State::Reboot,
+                    State::PowerOff,
+                    State::Halt,
+                    State::KExec,
+                    State::Suspend,
+                    State::SwitchRoot,
+                ]
+                .contains(&state)
+            {
+                log::warn!(
+                    "{:?} is not supported by the user manager, ignoring.",
+                    state
+                );
+                self.set_state(State::Ok);
+                continue;
+            }
             match state {
                 State::ReLoad => self.reload(),
                 State::ReExecute => return self.reexec(),
+                State::Exit => return self.exit_user(),
                 State::Reboot => self.reboot(RebootMode::RB_AUTOBOOT),
                 State::PowerOff => self.reboot(RebootMode::RB_POWER_OFF),
                 State::Halt => self.reboot(RebootMode::RB_HALT_SYSTEM),
@@ -366,6 +469,10 @@ impl Manager {
 
     /// create cgroup and attach self to it
     pub fn setup_cgroup(&self) -> Result<()> {
+        if self.mode == Mode::User {
+            self.setup_user_cgroup()?;
+        }
+
         let cg_init = PathBuf::from(CGROUP_SYSMASTER);
 
         if let Err(e) = cg_create_and_attach(&cg_init, Pid::from_raw(0)) {
@@ -386,6 +493,48 @@ impl Manager {
         Ok(())
     }
 
+    /* The user manager can only manage the cgroup subtree delegated to it, which is the
+     * cgroup it is started in, all the cgroup paths of the units are relative to it. The
+     * manager moves itself to the "sysmaster" child later, so the controllers can be
+     * enabled for the units in the root of the subtree. */
+    fn setup_user_cgroup(&self) -> Result<()> {
+        let mut root = cgroup::cg_pid_get_path(Pid::from_raw(0)).map_err(|e| Error::Other {
+            msg: format!("failed to get the cgroup of the user manager: {}", e),
+        })?;
+        /* already moved by the previous run, i.e. daemon-reexec */
+        if root.ends_with(CGROUP_SYSMASTER) {
+            root.pop();
+        }
+        let procs = Path::new(cgroup::CG_BASE_DIR)
+            .join(root.strip_prefix("/").unwrap_or(&root))
+            .join("cgroup.procs");
+        let delegated = std::fs::OpenOptions::new().write(true).open(&procs);
+        if delegated.is_err() {
+            return Err(Error::Other {
+                msg: format!("the cgroup {:?} is not delegated to the user manager", root),
+            });
+        }
+
+        log::info!("Using the delegated cgroup {:?}.", root);
+        cgroup::cg_set_root(&root);
+        Ok(())
+    }
+
+    fn exit_user(&self) -> Result<bool> {
+        /* the processes left by the units, or all of them if exit.target timed out */
+        log::info!("Exiting, killing the remaining processes of the user manager.");
+        if let Err(e) = cgroup::cg_kill_recursive(
+            Path::new(""),
+            Signal::SIGTERM,
+            CgFlags::IGNORE_SELF | CgFlags::SIGCONT | CgFlags::RECURSIVE,
+            HashSet::new(),
+        ) {
+            log::warn!("Failed to kill the processes of the user manager: {}", e);
+        }
+        self.reli.data_clear();
+        Ok(false)
+    }
+
     fn reload(&self) {
         self.config.borrow_mut().reload(&self.mode);
         log::init_log(
@@ -396,7 +545,7 @@ impl Manager {
                 .LogTarget
                 .split(&[' ', '-'][..])
                 .collect(),
-            LOG_FILE_PATH,
+            &self.mode.log_file_path().to_string_lossy(),
             self.config.borrow().LogFileSize,
             self.config.borrow().LogFileNumber,
             false,
@@ -491,15 +640,43 @@ impl Manager {
         let signal = Rc::clone(&self.signal);
         self.event.set_enabled(signal, EventState::On).unwrap();
 
-        // time
-        let timer = Rc::clone(&self.alive_timer);
-        timer.enable(true);
+        // time, the user manager is not watched by init
+        if self.mode == Mode::System {
+            let timer = Rc::clone(&self.alive_timer);
+            timer.enable(true);
+        }
     }
 
     fn run_generators(&self) {
+        /* the generators are only run by the system manager */
+        if self.mode == Mode::User {
+            return;
+        }
         let paths = vec!["/usr/lib/sysmaster/system-generators"];
 
-        if let Err(err) = basic::exec::execute_directories(paths) {
+        /* Clear the output of the last run, so the removed entries of the
+         * generators' configuration don't leave stale units behind. */
+        let output = &self.lookup_path.generator;
+        if let Err(err) = std::fs::remove_dir_all(output) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                log::error!(
+                    "Failed to clear the generator directory {}: {}",
+                    output,
+                    err
+                );
+                return;
+            }
+        }
+        if let Err(err) = std::fs::create_dir_all(output) {
+            log::error!(
+                "Failed to create the generator directory {}: {}",
+                output,
+                err
+            );
+            return;
+        }
+
+        if let Err(err) = basic::exec::execute_directories(paths, output) {
             log::error!("run generators err: {}", err);
         }
     }
@@ -508,12 +685,40 @@ impl Manager {
 /// manager running mode
 #[allow(missing_docs)]
 #[allow(dead_code)]
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Mode {
     System,
     User,
 }
 
+impl Mode {
+    /// the target started when the manager boots up
+    pub(crate) fn default_target(&self) -> &'static str {
+        match self {
+            Mode::System => MULTI_USER_TARGET,
+            Mode::User => DEFAULT_TARGET,
+        }
+    }
+
+    /// the directory the sockets and the runtime data of the manager are in,
+    /// $XDG_RUNTIME_DIR/sysmaster for the user manager
+    pub(crate) fn runtime_dir(&self) -> PathBuf {
+        match self {
+            Mode::System => PathBuf::from(SYSTEM_RUNTIME_DIR),
+            /* XDG_RUNTIME_DIR is checked before the user manager starts */
+            Mode::User => PathBuf::from(basic::fs::user_runtime_dir().unwrap_or_default()),
+        }
+    }
+
+    /// the log file when LogTarget is configured to "file"
+    pub(crate) fn log_file_path(&self) -> PathBuf {
+        match self {
+            Mode::System => PathBuf::from(LOG_FILE_PATH),
+            Mode::User => self.runtime_dir().join("sysmaster.log"),
+        }
+    }
+}
+
 /// manager action mode
 #[allow(missing_docs)]
 #[allow(dead_code)]
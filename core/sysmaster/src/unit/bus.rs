@@ -15,14 +15,14 @@ use super::entry::UnitX;
 use super::rentry::{self, UnitLoadState};
 use super::submanager::UnitSubManagers;
 use super::uload::UnitLoad;
-use basic::fs::RUN_TRANSIENT_PATH;
+use basic::fs::LookupPaths;
 use cmdproto::proto::transient_unit_comm::UnitProperty;
 use core::error::*;
 use core::rel::Reliability;
 use core::unit::{self, UnitType, UnitWriteFlags};
 use nix::sys::stat::{self, Mode};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::rc::Rc;
 
 pub struct UnitBus {
@@ -31,6 +31,7 @@ pub struct UnitBus {
     load: Rc<UnitLoad>,
     jm: Rc<JobManager>,
     sms: Rc<UnitSubManagers>,
+    lookup_path: Rc<LookupPaths>,
     // owned objects
 }
 
@@ -40,12 +41,14 @@ impl UnitBus {
         loadr: &Rc<UnitLoad>,
         jmr: &Rc<JobManager>,
         smsr: &Rc<UnitSubManagers>,
+        lookup_path: &Rc<LookupPaths>,
     ) -> UnitBus {
         UnitBus {
             reli: Rc::clone(relir),
             load: Rc::clone(loadr),
             jm: Rc::clone(jmr),
             sms: Rc::clone(smsr),
+            lookup_path: Rc::clone(lookup_path),
         }
     }
 
@@ -110,17 +113,17 @@ impl UnitBus {
         unit: &Rc<UnitX>,
         properties: &[UnitProperty],
     ) -> Result<()> {
-        let name = unit.id();
-        let path = get_transient_file_path(&name);
+        let dir = Path::new(&self.lookup_path.transient);
+        let path = dir.join(unit.id());
 
-        unit.make_transient(Some(path)); // record first
-        create_transient_file(&name)?; // create file
+        unit.make_transient(Some(path.clone())); // record first
+        create_transient_file(dir, &path)?; // create file
         self.unit_set_properties(unit, properties, UnitWriteFlags::RUNTIME)?; // write file
 
         Ok(())
     }
 
-    fn unit_set_properties(
+    pub(super) fn unit_set_properties(
         &self,
         unit: &Rc<UnitX>,
         properties: &[UnitProperty],
@@ -240,10 +243,10 @@ impl UnitBus {
     }
 }
 
-fn create_transient_file(name: &str) -> Result<()> {
+fn create_transient_file(dir: &Path, path: &Path) -> Result<()> {
     // create '/run/sysmaster/transient' with mode 750
     let old_mask = stat::umask(Mode::from_bits_truncate(!0o750));
-    let ret = create_transient_dir_body();
+    let ret = create_transient_dir_body(dir);
     let _ = stat::umask(old_mask);
     if let Err(e) = ret {
         log::error!("create transient directory failed: {}", e);
@@ -252,36 +255,27 @@ fn create_transient_file(name: &str) -> Result<()> {
 
     // create '/run/sysmaster/transient/unit.service' with mode 640
     let old_mask = stat::umask(Mode::from_bits_truncate(!0o640));
-    let ret = create_transient_file_body(name);
+    let ret = create_transient_file_body(path);
     let _ = stat::umask(old_mask);
     if let Err(e) = ret {
-        log::error!("create transient file failed:dir{:?}, {}", name, e);
+        log::error!("create transient file failed:path{:?}, {}", path, e);
         return Err(e);
     }
 
     Ok(())
 }
 
-fn create_transient_dir_body() -> Result<()> {
-    let dir = Path::new(RUN_TRANSIENT_PATH);
+fn create_transient_dir_body(dir: &Path) -> Result<()> {
     if !dir.exists() {
         fs::create_dir_all(dir).context(IoSnafu)?;
-        log::info!(
-            "create transient directory successfully: {}.",
-            RUN_TRANSIENT_PATH
-        );
+        log::info!("create transient directory successfully: {:?}.", dir);
     }
 
     Ok(())
 }
 
-fn create_transient_file_body(name: &str) -> Result<()> {
-    let path = get_transient_file_path(name);
-    fs::write(path.clone(), "# This is a transient unit file, created programmatically via the sysmaster API. Do not edit.\n")?;
+fn create_transient_file_body(path: &Path) -> Result<()> {
+    fs::write(path, "# This is a transient unit file, created programmatically via the sysmaster API. Do not edit.\n")?;
     log::info!("create transient file successfully: {:?}.", path);
     Ok(())
 }
-
-fn get_transient_file_path(name: &str) -> PathBuf {
-    Path::new(RUN_TRANSIENT_PATH).join(name)
-}
@@ -13,6 +13,7 @@
 use super::base::UeBase;
 use core::error::*;
 use core::rel::ReStation;
+use core::unit::UnitType;
 use nix::NixPath;
 use std::rc::Rc;
 use std::{cell::RefCell, path::PathBuf};
@@ -59,6 +60,11 @@ impl UeCgroup {
 
         cg_path
     }
+
+    pub(super) fn set_slice(&self, slice: &str) {
+        self.data.borrow_mut().set_slice(slice);
+        self.db_update();
+    }
 }
 
 struct UnitCgroupData {
@@ -67,6 +73,7 @@ struct UnitCgroupData {
 
     // owned objects
     cg_path: PathBuf,
+    slice: String,
 }
 
 impl UnitCgroupData {
@@ -74,6 +81,7 @@ impl UnitCgroupData {
         UnitCgroupData {
             base: Rc::clone(baser),
             cg_path: PathBuf::from(""),
+            slice: String::new(),
         }
     }
 
@@ -92,9 +100,37 @@ impl UnitCgroupData {
     }
 
     fn set_default_cg_path(&mut self) {
-        let cg_tree_name = PathBuf::from(cgroup::cg_escape(&self.base.id()));
+        let id = self.base.id();
+        let cg_tree_name = if self.base.unit_type() == UnitType::UnitSlice {
+            /* the slice itself is a node of the cgroup tree, "a-b.slice" lives in "a.slice/a-b.slice" */
+            cgroup::cg_slice_to_path(&id)
+        } else if !self.slice.is_empty() {
+            cgroup::cg_slice_to_path(&self.slice).map(|p| p.join(cgroup::cg_escape(&id)))
+        } else {
+            Ok(PathBuf::from(cgroup::cg_escape(&id)))
+        };
 
-        self.cg_path = cg_tree_name;
+        self.cg_path = match cg_tree_name {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Failed to build the cgroup path of {}: {}", id, e);
+                PathBuf::from(cgroup::cg_escape(&id))
+            }
+        };
+    }
+
+    pub(self) fn set_slice(&mut self, slice: &str) {
+        if self.slice == slice {
+            return;
+        }
+        self.slice = slice.to_string();
+
+        /* The cgroup path is calculated again on the next start, unless there are
+         * processes still running in the old one. */
+        if !self.cg_path.is_empty() && cgroup::cg_is_empty_recursive(&self.cg_path).unwrap_or(true)
+        {
+            self.cg_path = PathBuf::from("");
+        }
     }
 
     pub(self) fn prepare_cg_exec(&mut self) -> Result<()> {
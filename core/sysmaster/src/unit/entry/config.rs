@@ -22,9 +22,12 @@ use core::specifier::{
     unit_string_specifier_escape, unit_strings_specifier_escape, UnitSpecifierData, LONG_LINE_MAX,
     PATH_MAX, UNIT_NAME_MAX,
 };
+use core::unit::{unit_name_to_type, UnitType};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
+use unit_parser::error::LocatedError;
 use unit_parser::prelude::{UnitConfig, UnitEntry};
 
 pub(crate) struct UeConfig {
@@ -157,7 +160,8 @@ impl UeConfig {
 
     pub(super) fn load_fragment_and_dropin(&self, files: &UnitFile, name: &str) -> Result<()> {
         let unit_conf_frag = files.get_unit_id_fragment_pathbuf(name);
-        if unit_conf_frag.is_empty() {
+        /* device units are created from uevents, the config file is optional */
+        if unit_conf_frag.is_empty() && unit_name_to_type(name) != UnitType::UnitDevice {
             return Err(format!("{} doesn't have corresponding config file", name).into());
         }
 
@@ -196,6 +200,11 @@ impl UeConfig {
         self.data.clone()
     }
 
+    /// check [Unit] and [Install] of the configuration files strictly without loading them
+    pub(crate) fn verify(paths: Vec<PathBuf>, name: &str) -> Vec<LocatedError> {
+        UeConfigData::verify_config_located(paths, name)
+    }
+
     pub(crate) fn set_property(&self, key: &str, value: &str) -> Result<()> {
         let ret = self.data.borrow_mut().set_property(key, value);
         self.db_update();
@@ -192,6 +192,10 @@ impl UeLoad {
         self.transient_file.borrow().clone()
     }
 
+    pub(super) fn config_dir(&self, runtime: bool) -> PathBuf {
+        self.file.config_dir(runtime)
+    }
+
     pub(super) fn last_section_private(&self) -> i8 {
         *self.last_section_private.borrow()
     }
@@ -38,7 +38,7 @@
 //!  uu_cgroup: cgroup related configurations
 //!  uu_config is the configuration of unit
 //!
-pub(crate) use config::UnitEmergencyAction;
+pub(crate) use config::{UeConfig, UnitEmergencyAction};
 pub(crate) use ratelimit::StartLimitResult;
 pub use uentry::Unit;
 pub(crate) use unitx::UnitX;
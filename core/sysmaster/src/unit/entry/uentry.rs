@@ -24,12 +24,14 @@ use crate::unit::rentry::{UnitLoadState, UnitRe};
 use crate::unit::util::UnitFile;
 use basic::process::{self, my_child};
 use basic::time::{now_clockid, UnitTimeStamp};
+use basic::IN_SET;
 use cgroup::{self, CgFlags};
 use core::error::*;
 use core::rel::ReStation;
+use core::unit::{section_properties, SubUnit, UnitActiveState, UnitBase, UnitType};
 use core::unit::{KillContext, KillMode, KillOperation, UnitNotifyFlags, UnitWriteFlags};
-use core::unit::{SubUnit, UnitActiveState, UnitBase, UnitType};
 use libc::{CLOCK_MONOTONIC, CLOCK_REALTIME};
+use nix::sys::signal::Signal;
 use nix::sys::socket::UnixCredentials;
 use nix::sys::wait::WaitStatus;
 use nix::unistd::Pid;
@@ -218,6 +220,10 @@ impl UnitBase for Unit {
     fn set_last_section_private(&self, lsp: i8) {
         self.load.set_last_section_private(lsp);
     }
+
+    fn config_dir(&self, runtime: bool) -> PathBuf {
+        self.load.config_dir(runtime)
+    }
 }
 
 impl Unit {
@@ -414,6 +420,10 @@ impl Unit {
         self.cgroup.cg_path()
     }
 
+    pub(super) fn set_slice(&self, slice: &str) {
+        self.cgroup.set_slice(slice)
+    }
+
     /// kill the process belongs to the unit
     pub fn kill_context(
         &self,
@@ -580,6 +590,48 @@ impl Unit {
         self.sub.get_subunit_state()
     }
 
+    /// the main process of the service
+    pub fn main_pid(&self) -> Option<Pid> {
+        self.sub.main_pid()
+    }
+
+    /// the process running the control commands
+    pub fn control_pid(&self) -> Option<Pid> {
+        self.sub.control_pid()
+    }
+
+    /// the wait status of the last exited main process
+    pub fn exit_status(&self) -> Option<WaitStatus> {
+        self.sub.exit_status()
+    }
+
+    /// the status sent by the service with STATUS=
+    pub fn status_text(&self) -> Option<String> {
+        self.sub.status_text()
+    }
+
+    /// the parsed configuration of [Unit], [Install] and the sub unit type section
+    pub fn get_properties(&self) -> Vec<(String, String)> {
+        let config_data = self.config.config_data();
+        let mut properties = section_properties(&config_data.borrow().Unit);
+        properties.append(&mut section_properties(&config_data.borrow().Install));
+        properties.append(&mut self.sub.get_properties());
+        properties
+    }
+
+    /// check the configuration files strictly, both [Unit], [Install] and the sub unit type
+    /// section, the files are not loaded
+    pub(crate) fn verify_config(&self, paths: Vec<PathBuf>) -> Result<()> {
+        let mut errors = UeConfig::verify(paths.clone(), &self.id());
+        errors.append(&mut self.sub.verify_config(paths, &self.id()));
+        match errors.first() {
+            None => Ok(()),
+            Some(e) => Err(Error::ConfigureError {
+                msg: format!("Invalid Configuration: {}", e.error),
+            }),
+        }
+    }
+
     /// test start rate, if start more than burst times in interval time, return error
     fn test_start_limit(&self) -> bool {
         if self.config.config_data().borrow().Unit.StartLimitInterval > 0
@@ -809,6 +861,24 @@ impl Unit {
         self.sub.reset_failed()
     }
 
+    /// send the signal to the processes of the unit
+    pub(crate) fn kill(&self, signal: Signal) -> Result<()> {
+        let active_state = self.current_active_state();
+        if IN_SET!(
+            active_state,
+            UnitActiveState::InActive,
+            UnitActiveState::Failed
+        ) {
+            log::info!("Unit {} is not active, no process to kill", self.id());
+            return Err(Error::Nix {
+                source: nix::Error::ESRCH,
+            });
+        }
+
+        log::info!("Sending signal {} to {}", signal, self.id());
+        self.sub.kill(signal)
+    }
+
     pub(super) fn sigchld_events(&self, wait_status: WaitStatus) {
         self.sub.sigchld_events(wait_status)
     }
@@ -886,6 +956,14 @@ impl Unit {
         self.sub.update_mount_state_by_mountinfo();
     }
 
+    pub(crate) fn update_swap_state_by_proc_swaps(&self) {
+        self.sub.update_swap_state_by_proc_swaps();
+    }
+
+    pub(crate) fn setup_device(&self, sysfs: &str, wants: &[String], found: bool) {
+        self.sub.setup_device(sysfs, wants, found);
+    }
+
     pub(crate) fn notify_message(
         &self,
         ucred: &UnixCredentials,
@@ -21,6 +21,7 @@ use basic::IN_SET;
 use core::error::*;
 use core::rel::ReStation;
 use core::unit::{self, SubUnit, UnitActiveState, UnitRelations, UnitType, UnitWriteFlags};
+use nix::sys::signal::Signal;
 use nix::sys::wait::WaitStatus;
 use nix::unistd::Pid;
 use std::fmt::Arguments;
@@ -116,7 +117,9 @@ impl UnitX {
     }
 
     #[allow(dead_code)]
-    pub(crate) fn kill(&self) {}
+    pub(crate) fn kill(&self, signal: Signal) -> Result<()> {
+        self.0.kill(signal)
+    }
     #[allow(dead_code)]
     pub(crate) fn release_resources(&self) {}
     pub(crate) fn sigchld_events(&self, wait_status: WaitStatus) {
@@ -243,6 +246,10 @@ impl UnitX {
         self.0.cg_path()
     }
 
+    pub(crate) fn set_slice(&self, slice: &str) {
+        self.0.set_slice(slice)
+    }
+
     pub(crate) fn load_state(&self) -> UnitLoadState {
         self.0.load_state()
     }
@@ -304,6 +311,10 @@ impl UnitX {
         self.0.set_property(key, value)
     }
 
+    pub(crate) fn verify_config(&self, paths: Vec<PathBuf>) -> Result<()> {
+        self.0.verify_config(paths)
+    }
+
     pub(crate) fn write_settingf(
         &self,
         ps: &str,
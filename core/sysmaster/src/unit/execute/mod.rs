@@ -11,4 +11,6 @@
 // See the Mulan PSL v2 for more details.
 
 pub(super) use spawn::ExecSpawn;
+mod sandbox;
 mod spawn;
+mod stdio;
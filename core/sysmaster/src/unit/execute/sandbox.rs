@@ -0,0 +1,243 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Set up the private mount namespace of the spawned process, which is configured by
+//! ProtectSystem, ProtectHome, PrivateTmp, ReadOnlyPaths, InaccessiblePaths and BindPaths.
+
+use basic::mount::{is_mount_point, MountInfoParser};
+use core::error::*;
+use core::exec::{private_tmp_mounts, BindPath, ProtectHome, ProtectSystem, Sandbox};
+use nix::mount::{mount, MsFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// the API file systems stay writable even if the whole file system is read-only.
+const API_FILE_SYSTEMS: [&str; 3] = ["/dev", "/proc", "/sys"];
+const HOME_DIRECTORIES: [&str; 3] = ["/home", "/root", "/run/user"];
+
+/// the private tmp directory is created on the host by the unit, so it is shared by all
+/// the processes of the unit.
+pub(super) fn setup_sandbox(sandbox: &Sandbox, private_tmp: Option<&Path>) -> Result<()> {
+    if !sandbox.needs_mount_namespace() {
+        return Ok(());
+    }
+
+    let private_tmp = match (sandbox.private_tmp, private_tmp) {
+        (false, _) => Vec::new(),
+        (true, Some(dir)) => private_tmp_mounts(dir),
+        (true, None) => {
+            return Err(Error::ConfigureError {
+                msg: "PrivateTmp is enabled, but the private tmp directory is not created"
+                    .to_string(),
+            })
+        }
+    };
+
+    basic::namespace::detach_mount_namespace().context(NixSnafu)?;
+
+    /* Bind mounts go first, so they can be protected by the following options. */
+    for bind in &sandbox.bind_paths {
+        bind_mount(bind, false)?;
+    }
+    for bind in &sandbox.bind_read_only_paths {
+        bind_mount(bind, true)?;
+    }
+
+    for (source, target) in &private_tmp {
+        mount_bind(source, target)?;
+    }
+
+    match sandbox.protect_system {
+        ProtectSystem::No => {}
+        ProtectSystem::Yes => make_read_only_paths(&["/usr", "/boot", "/efi"])?,
+        ProtectSystem::Full => make_read_only_paths(&["/usr", "/boot", "/efi", "/etc"])?,
+        ProtectSystem::Strict => {
+            /* The writable mounts configured by the unit itself are kept writable. */
+            let mut skip: Vec<PathBuf> = API_FILE_SYSTEMS.iter().map(PathBuf::from).collect();
+            skip.extend(private_tmp.iter().map(|(_, t)| t.clone()));
+            skip.extend(sandbox.bind_paths.iter().map(|b| b.destination()));
+            make_read_only(Path::new("/"), &skip)?
+        }
+    }
+
+    match sandbox.protect_home {
+        ProtectHome::No => {}
+        ProtectHome::Yes => make_inaccessible_paths(&HOME_DIRECTORIES)?,
+        ProtectHome::ReadOnly => make_read_only_paths(&HOME_DIRECTORIES)?,
+        ProtectHome::Tmpfs => {
+            for dir in HOME_DIRECTORIES {
+                let path = Path::new(dir);
+                if path.is_dir() {
+                    mount_tmpfs(path, "mode=0755")?;
+                }
+            }
+        }
+    }
+
+    for p in &sandbox.read_only_paths {
+        let path = p.path();
+        if !path.exists() {
+            if p.ignore_missing() {
+                continue;
+            }
+            return Err(missing_path(&path));
+        }
+        make_read_only(&path, &[])?;
+    }
+
+    for p in &sandbox.inaccessible_paths {
+        let path = p.path();
+        if !path.exists() {
+            if p.ignore_missing() {
+                continue;
+            }
+            return Err(missing_path(&path));
+        }
+        make_inaccessible(&path)?;
+    }
+
+    Ok(())
+}
+
+fn missing_path(path: &Path) -> Error {
+    Error::ConfigureError {
+        msg: format!("sandbox path {:?} doesn't exist", path),
+    }
+}
+
+fn mount_bind(source: &Path, target: &Path) -> Result<()> {
+    log::debug!("Bind mounting {:?} to {:?}", source, target);
+    mount(
+        Some(source),
+        target,
+        None::<&str>,
+        MsFlags::MS_BIND | MsFlags::MS_REC,
+        None::<&str>,
+    )
+    .context(NixSnafu)
+}
+
+fn mount_tmpfs(target: &Path, options: &str) -> Result<()> {
+    log::debug!("Mounting read-only tmpfs on {:?}", target);
+    mount(
+        Some("tmpfs"),
+        target,
+        Some("tmpfs"),
+        MsFlags::MS_RDONLY | MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+        Some(options),
+    )
+    .context(NixSnafu)
+}
+
+fn bind_mount(bind: &BindPath, read_only: bool) -> Result<()> {
+    let source = bind.source();
+    let destination = bind.destination();
+    if !source.exists() {
+        if bind.ignore_missing() {
+            return Ok(());
+        }
+        return Err(missing_path(&source));
+    }
+
+    if !destination.exists() {
+        if source.is_dir() {
+            fs::create_dir_all(&destination).context(IoSnafu)?;
+        } else {
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent).context(IoSnafu)?;
+            }
+            fs::File::create(&destination).context(IoSnafu)?;
+        }
+    }
+
+    mount_bind(&source, &destination)?;
+    if read_only {
+        remount_read_only(&destination, &[])?;
+    }
+    Ok(())
+}
+
+fn make_read_only_paths(paths: &[&str]) -> Result<()> {
+    for p in paths {
+        let path = Path::new(p);
+        if path.exists() {
+            make_read_only(path, &[])?;
+        }
+    }
+    Ok(())
+}
+
+fn make_read_only(path: &Path, skip: &[PathBuf]) -> Result<()> {
+    /* Make the path a mount point first, the flags of a bind mount can be changed alone. */
+    if !is_mount_point(path) {
+        mount_bind(path, path)?;
+    }
+    remount_read_only(path, skip)
+}
+
+/// remount the mount point of the path and all the mount points under it read-only,
+/// except the ones in the skip list.
+fn remount_read_only(path: &Path, skip: &[PathBuf]) -> Result<()> {
+    for mount_point in sub_mount_points(path)? {
+        if skip.iter().any(|s| mount_point.starts_with(s)) {
+            continue;
+        }
+
+        log::debug!("Remounting {:?} read-only", mount_point);
+        if let Err(e) = mount(
+            None::<&str>,
+            &mount_point,
+            None::<&str>,
+            MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
+            None::<&str>,
+        ) {
+            /* The top mount point must be read-only, the others are best effort. */
+            if mount_point == path {
+                return Err(Error::Nix { source: e });
+            }
+            log::warn!("Failed to remount {:?} read-only: {}", mount_point, e);
+        }
+    }
+    Ok(())
+}
+
+fn sub_mount_points(path: &Path) -> Result<Vec<PathBuf>> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").context(IoSnafu)?;
+    let mut res = vec![path.to_path_buf()];
+    for info in MountInfoParser::new(mountinfo) {
+        let mount_point = PathBuf::from(info.mount_point);
+        if mount_point != path && mount_point.starts_with(path) && !res.contains(&mount_point) {
+            res.push(mount_point);
+        }
+    }
+    Ok(res)
+}
+
+fn make_inaccessible_paths(paths: &[&str]) -> Result<()> {
+    for p in paths {
+        let path = Path::new(p);
+        if path.exists() {
+            make_inaccessible(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn make_inaccessible(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        return mount_tmpfs(path, "mode=0000");
+    }
+
+    /* A file is covered by a read-only /dev/null. */
+    mount_bind(Path::new("/dev/null"), path)?;
+    remount_read_only(path, &[])
+}
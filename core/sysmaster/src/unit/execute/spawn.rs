@@ -11,9 +11,14 @@
 // See the Mulan PSL v2 for more details.
 
 use super::super::entry::Unit;
+use super::sandbox;
+use super::stdio;
 use basic::fd;
 use core::error::*;
-use core::exec::{ExecCommand, ExecContext, ExecFlags, ExecParameters, WorkingDirectory};
+use core::exec::{
+    ExecCommand, ExecContext, ExecFlags, ExecParameters, Privileges, Seccomp, SecureBits,
+    WorkingDirectory,
+};
 use nix::fcntl::FcntlArg;
 use nix::sys::signal::{pthread_sigmask, SigmaskHow};
 use nix::sys::signalfd::SigSet;
@@ -22,7 +27,7 @@ use nix::unistd::{self, chroot, setresgid, setresuid, ForkResult, Gid, Pid, Uid}
 use regex::Regex;
 use std::fs::Permissions;
 use std::os::unix::prelude::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::rc::Rc;
 use walkdir::DirEntry;
@@ -92,6 +97,56 @@ fn apply_user_and_group(exec_ctx: Rc<ExecContext>, params: &ExecParameters) -> R
     setresuid(user.uid, user.uid, user.uid).context(NixSnafu)
 }
 
+fn apply_capability_bounding_set(privileges: &Privileges) -> Result<()> {
+    let keep = match privileges.capability_bounding_set {
+        None => return Ok(()),
+        Some(v) => v,
+    };
+    basic::capability::capability_bounding_set_drop(keep)?;
+    Ok(())
+}
+
+fn apply_secure_bits(privileges: &Privileges) -> Result<()> {
+    let mut secure_bits = privileges.secure_bits;
+    /* Keep the permitted capabilities when changing to the non-root user,
+     * so that they can be raised to the ambient set later. */
+    if privileges.ambient_capabilities != 0 {
+        secure_bits |= SecureBits::KEEP_CAPS;
+    }
+    if secure_bits.is_empty() {
+        return Ok(());
+    }
+    let current = basic::capability::securebits()?;
+    if current & secure_bits.bits() == secure_bits.bits() {
+        return Ok(());
+    }
+    basic::capability::set_securebits(current | secure_bits.bits())?;
+    Ok(())
+}
+
+fn apply_ambient_capabilities(privileges: &Privileges) -> Result<()> {
+    basic::capability::capability_ambient_set_apply(privileges.ambient_capabilities)?;
+    Ok(())
+}
+
+fn apply_no_new_privileges(privileges: &Privileges) -> Result<()> {
+    if !privileges.no_new_privileges {
+        return Ok(());
+    }
+    basic::capability::set_no_new_privs()?;
+    Ok(())
+}
+
+/// install the seccomp filter, return whether a filter is installed
+fn apply_seccomp(seccomp: &Seccomp) -> Result<bool> {
+    let filter = match seccomp.build() {
+        None => return Ok(false),
+        Some(v) => v,
+    };
+    filter.load()?;
+    Ok(true)
+}
+
 fn apply_root_directory(root_directory: Option<PathBuf>) -> Result<()> {
     let root_directory = match root_directory {
         None => return Ok(()),
@@ -179,17 +234,51 @@ fn exec_child(unit: &Unit, cmdline: &ExecCommand, params: &ExecParameters, ctx:
         return;
     }
 
+    /* Set up the stdio before the sandbox and the root directory, the paths
+     * are on the host. */
+    let identifier = Path::new(cmdline.path())
+        .file_name()
+        .map_or(String::new(), |v| v.to_string_lossy().to_string());
+    if let Err(e) = stdio::setup_stdio(&ctx.stdio(), params, &unit.id(), &identifier) {
+        log::error!("Failed to set up the stdio: {}", e);
+        return;
+    }
+
+    if let Err(e) = sandbox::setup_sandbox(&ctx.sandbox(), params.private_tmp().as_deref()) {
+        log::error!("Failed to set up the sandbox: {}", e);
+        return;
+    }
+
     if let Err(e) = apply_root_directory(ctx.root_directory()) {
         log::error!("Failed to apply root directory: {}", e);
         return;
     }
 
+    let privileges = ctx.privileges();
+    let seccomp = ctx.seccomp();
+    /* Dropping the bounding set and setting the securebits need CAP_SETPCAP,
+     * do them before changing the user. */
+    if let Err(e) = apply_capability_bounding_set(&privileges) {
+        log::error!("Failed to apply the capability bounding set: {}", e);
+        return;
+    }
+
+    if let Err(e) = apply_secure_bits(&privileges) {
+        log::error!("Failed to apply the secure bits: {}", e);
+        return;
+    }
+
     #[cfg(feature = "linux")]
     if let Err(e) = apply_user_and_group(ctx.clone(), params) {
         log::error!("Failed to apply user or group: {}", e);
         return;
     }
 
+    if let Err(e) = apply_ambient_capabilities(&privileges) {
+        log::error!("Failed to apply the ambient capabilities: {}", e);
+        return;
+    }
+
     if let Err(e) = apply_working_directory(ctx.working_directory()) {
         log::error!("Failed to apply working directory: {}", e);
         return;
@@ -237,6 +326,11 @@ fn exec_child(unit: &Unit, cmdline: &ExecCommand, params: &ExecParameters, ctx:
         return;
     }
 
+    if let Err(e) = apply_no_new_privileges(&privileges) {
+        log::error!("Failed to set no new privileges: {}", e);
+        return;
+    }
+
     let envs_cstr = envs.iter().map(|v| v.as_c_str()).collect::<Vec<_>>();
     let mut keep_fds = params.fds();
 
@@ -263,8 +357,22 @@ fn exec_child(unit: &Unit, cmdline: &ExecCommand, params: &ExecParameters, ctx:
     }
 
     log::debug!("Begin to execute {:?}, args: {:?}", cmd, cstr_args);
+
+    /* Install the seccomp filter as late as possible, the system calls used by
+     * sysmaster itself may be denied. Don't log once it's installed, the logger
+     * opens its files when needed. */
+    let filtered = match apply_seccomp(&seccomp) {
+        Err(e) => {
+            log::error!("Failed to apply the seccomp filter: {}", e);
+            return;
+        }
+        Ok(v) => v,
+    };
+
     if unistd::execve(&cmd, &cstr_args, &envs_cstr).is_err() {
-        log::error!("Failed to execute cmd: {:?}", cmd);
+        if !filtered {
+            log::error!("Failed to execute cmd: {:?}", cmd);
+        }
         std::process::exit(1);
     }
 }
@@ -317,6 +425,13 @@ fn build_environment(_unit: &Unit, ep: &ExecParameters) -> Vec<std::ffi::CString
         envs.push(std::ffi::CString::new(format!("LISTEN_PID={}", nix::unistd::getpid())).unwrap());
 
         envs.push(std::ffi::CString::new(format!("LISTEN_FDS={}", fds)).unwrap());
+
+        let fd_names = ep.fd_names();
+        if fd_names.len() == fds {
+            envs.push(
+                std::ffi::CString::new(format!("LISTEN_FDNAMES={}", fd_names.join(":"))).unwrap(),
+            );
+        }
     }
 
     if ep.exec_flags().contains(ExecFlags::SOFT_WATCHDOG) && ep.watchdog_usec() > 0 {
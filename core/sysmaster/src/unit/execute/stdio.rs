@@ -0,0 +1,228 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Connect the standard input, output and error of the spawned process, which is
+//! configured by StandardInput, StandardOutput, StandardError and TTYPath.
+
+use basic::fd;
+use constants::LOG_STDOUT_SOCKET;
+use core::error::*;
+use core::exec::{ExecInput, ExecOutput, ExecParameters, ExecStdio};
+use std::ffi::CStr;
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+const STDIN_FILENO: RawFd = 0;
+const STDOUT_FILENO: RawFd = 1;
+const STDERR_FILENO: RawFd = 2;
+
+/// the syslog priorities of the output sent to the log collector
+const LOG_PRIORITY_INFO: u8 = 6;
+const LOG_PRIORITY_ERR: u8 = 3;
+
+pub(super) fn setup_stdio(
+    stdio: &ExecStdio,
+    params: &ExecParameters,
+    unit_id: &str,
+    identifier: &str,
+) -> Result<()> {
+    setup_input(stdio, params)?;
+    setup_output(stdio, params, STDOUT_FILENO, unit_id, identifier)?;
+    setup_output(stdio, params, STDERR_FILENO, unit_id, identifier)
+}
+
+fn setup_input(stdio: &ExecStdio, params: &ExecParameters) -> Result<()> {
+    match &stdio.input {
+        ExecInput::Null => move_fd(open_file(Path::new("/dev/null"), true, 0)?, STDIN_FILENO),
+        ExecInput::Tty | ExecInput::TtyForce => {
+            let fd = acquire_terminal(&stdio.tty_path, stdio.input == ExecInput::TtyForce)?;
+            move_fd(fd, STDIN_FILENO)
+        }
+        ExecInput::Socket => dup_fd(socket_fd(params)?, STDIN_FILENO),
+        ExecInput::File(path) => move_fd(open_file(path, true, 0)?, STDIN_FILENO),
+        ExecInput::Data(data) => move_fd(open_data(data)?, STDIN_FILENO),
+    }
+}
+
+fn setup_output(
+    stdio: &ExecStdio,
+    params: &ExecParameters,
+    target: RawFd,
+    unit_id: &str,
+    identifier: &str,
+) -> Result<()> {
+    let output = if target == STDOUT_FILENO {
+        &stdio.output
+    } else {
+        &stdio.error
+    };
+
+    match output {
+        ExecOutput::Inherit => {
+            /* Follow the terminal of the input, and the standard error follows the
+             * standard output. Otherwise keep what sysmaster passes to us. */
+            if stdio.input.is_tty() {
+                return dup_fd(STDIN_FILENO, target);
+            }
+            if target == STDERR_FILENO && stdio.output != ExecOutput::Inherit {
+                return dup_fd(STDOUT_FILENO, target);
+            }
+            Ok(())
+        }
+        ExecOutput::Null => move_fd(
+            open_file(Path::new("/dev/null"), false, libc::O_NOCTTY)?,
+            target,
+        ),
+        ExecOutput::Tty => {
+            if stdio.input.is_tty() {
+                return dup_fd(STDIN_FILENO, target);
+            }
+            move_fd(open_file(&stdio.tty_path, false, libc::O_NOCTTY)?, target)
+        }
+        ExecOutput::Kmsg => move_fd(
+            open_file(Path::new("/dev/kmsg"), false, libc::O_NOCTTY)?,
+            target,
+        ),
+        ExecOutput::Log => {
+            let priority = if target == STDOUT_FILENO {
+                LOG_PRIORITY_INFO
+            } else {
+                LOG_PRIORITY_ERR
+            };
+            let fd = match connect_log_collector(unit_id, identifier, priority) {
+                Ok(v) => v,
+                Err(e) => {
+                    log::debug!(
+                        "Failed to connect to the log collector: {}, falling back to kmsg.",
+                        e
+                    );
+                    open_file(Path::new("/dev/kmsg"), false, libc::O_NOCTTY)?
+                }
+            };
+            move_fd(fd, target)
+        }
+        ExecOutput::Socket => dup_fd(socket_fd(params)?, target),
+        ExecOutput::File(path) => move_fd(open_file(path, false, libc::O_CREAT)?, target),
+        ExecOutput::Append(path) => move_fd(
+            open_file(path, false, libc::O_CREAT | libc::O_APPEND)?,
+            target,
+        ),
+        ExecOutput::Truncate(path) => move_fd(
+            open_file(path, false, libc::O_CREAT | libc::O_TRUNC)?,
+            target,
+        ),
+    }
+}
+
+fn socket_fd(params: &ExecParameters) -> Result<RawFd> {
+    match params.stdio_fd() {
+        None => {
+            log::error!("No socket is passed for the stdio.");
+            Err(Error::InvalidData)
+        }
+        Some(v) => Ok(v),
+    }
+}
+
+/// Move fd to target, the original fd is closed.
+fn move_fd(fd: RawFd, target: RawFd) -> Result<()> {
+    if fd == target {
+        return fd::fd_cloexec(fd, false).map_err(Error::from);
+    }
+    nix::unistd::dup2(fd, target).context(NixSnafu)?;
+    fd::close(fd);
+    Ok(())
+}
+
+/// Duplicate fd to target, the original fd is kept.
+fn dup_fd(fd: RawFd, target: RawFd) -> Result<()> {
+    if fd == target {
+        return Ok(());
+    }
+    nix::unistd::dup2(fd, target).context(NixSnafu)?;
+    Ok(())
+}
+
+fn open_file(path: &Path, read: bool, flags: libc::c_int) -> Result<RawFd> {
+    let mut options = OpenOptions::new();
+    if read {
+        options.read(true);
+    } else {
+        options.write(true);
+    }
+    let file = options
+        .custom_flags(flags)
+        .mode(0o644)
+        .open(path)
+        .map_err(|e| {
+            log::error!("Failed to open {:?} for the stdio: {}", path, e);
+            Error::Io { source: e }
+        })?;
+    Ok(file.into_raw_fd())
+}
+
+/// Open the terminal and make it the controlling terminal of a new session.
+fn acquire_terminal(path: &Path, force: bool) -> Result<RawFd> {
+    /* We may be the session leader already, ignore the error. */
+    unsafe { libc::setsid() };
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| {
+            log::error!("Failed to open the terminal {:?}: {}", path, e);
+            Error::Io { source: e }
+        })?;
+    let fd = file.into_raw_fd();
+
+    if unsafe { libc::ioctl(fd, libc::TIOCSCTTY, force as libc::c_int) } < 0 {
+        let e = nix::errno::Errno::last();
+        log::error!("Failed to acquire the terminal {:?}: {}", path, e);
+        fd::close(fd);
+        return Err(Error::Nix { source: e });
+    }
+    Ok(fd)
+}
+
+/// Put the data into a memfd, it's read from the beginning.
+fn open_data(data: &str) -> Result<RawFd> {
+    let name = CStr::from_bytes_with_nul(b"stdin-data\0").unwrap();
+    let fd = unsafe { libc::memfd_create(name.as_ptr(), 0) };
+    if fd < 0 {
+        return Err(Error::Nix {
+            source: nix::errno::Errno::last(),
+        });
+    }
+
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    file.write_all(data.as_bytes())
+        .and_then(|_| file.write_all(b"\n"))
+        .and_then(|_| file.seek(SeekFrom::Start(0)))
+        .map_err(|e| Error::Io { source: e })?;
+    Ok(file.into_raw_fd())
+}
+
+/// Connect to the log collector, the header tells it where the output comes from.
+fn connect_log_collector(unit_id: &str, identifier: &str, priority: u8) -> Result<RawFd> {
+    let mut stream = UnixStream::connect(LOG_STDOUT_SOCKET).map_err(|e| Error::Io { source: e })?;
+    let header = format!("{}\n{}\n{}\n", unit_id, identifier, priority);
+    stream
+        .write_all(header.as_bytes())
+        .and_then(|_| stream.shutdown(std::net::Shutdown::Read))
+        .map_err(|e| Error::Io { source: e })?;
+    Ok(stream.into_raw_fd())
+}
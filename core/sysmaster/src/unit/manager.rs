@@ -33,26 +33,31 @@ use super::uload::UnitLoad;
 use crate::job::JobResult;
 use crate::manager::config::ManagerConfig;
 use crate::manager::pre_install::{Install, PresetMode};
-use crate::manager::State;
+use crate::manager::{Mode, State};
 use crate::unit::data::{DataManager, UnitState};
 use crate::utils::table::{TableOp, TableSubscribe};
 use basic::fs::LookupPaths;
-use basic::show_table::{CellColor, ShowTable};
 use basic::time::UnitTimeStamp;
-use basic::{machine, process, rlimit, signal};
-use cmdproto::proto::transient_unit_comm::UnitConfig;
+use basic::{machine, process, rlimit, signal, EXIT_TARGET};
+use cmdproto::proto::transient_unit_comm::{UnitConfig, UnitProperty};
+use cmdproto::proto::{
+    unit_dependency_comm, BootTimes, JobEntry, OrderingCycle, UnitDependency, UnitListEntry,
+    UnitProcess, UnitStatus, UnitTimes,
+};
 use constants::SIG_SWITCH_ROOT_OFFSET;
 use core::error::*;
 use core::exec::ExecParameters;
 use core::exec::{ExecCommand, ExecContext};
 use core::rel::{ReStation, ReStationKind, ReliLastFrame, Reliability};
 use core::unit::{
-    unit_name_is_valid, UmIf, UnitActiveState, UnitDependencyMask, UnitNameFlags, UnitStatus,
-    UnitType,
+    cgroup_cpu_usage, cgroup_memory_current, unit_name_is_valid, unit_name_to_type, UmIf,
+    UnitActiveState, UnitDependencyMask, UnitNameFlags, UnitType, UnitWriteFlags,
 };
 use core::unit::{UnitRelationAtom, UnitRelations};
 use event::Events;
 use libc::getppid;
+use nix::sys::signal::Signal;
+use nix::sys::wait::WaitStatus;
 use nix::unistd::Pid;
 use std::cell::RefCell;
 use std::convert::TryFrom;
@@ -87,6 +92,7 @@ impl UnitManagerX {
         eventr: &Rc<Events>,
         relir: &Rc<Reliability>,
         lookup_path: &Rc<LookupPaths>,
+        mode: Mode,
         state: Rc<RefCell<State>>,
         manager_config: Rc<RefCell<ManagerConfig>>,
     ) -> UnitManagerX {
@@ -99,6 +105,7 @@ impl UnitManagerX {
                 relir,
                 &_dm,
                 lookup_path,
+                mode,
                 Rc::clone(&state),
                 manager_config.clone(),
             ),
@@ -149,6 +156,10 @@ impl UnitManagerX {
         self.data.reset_failed(name)
     }
 
+    pub(crate) fn kill_unit(&self, name: &str, signal: i32) -> Result<()> {
+        self.data.kill_unit(name, signal)
+    }
+
     pub(crate) fn restart_unit(&self, name: &str, is_manual: bool) -> Result<()> {
         self.data.restart_unit(name, is_manual)
     }
@@ -157,10 +168,48 @@ impl UnitManagerX {
         self.data.get_unit_status(name)
     }
 
-    pub(crate) fn get_all_units(&self) -> Result<String> {
+    pub(crate) fn get_unit_properties(&self, name: &str) -> Result<Vec<UnitProperty>> {
+        self.data.get_unit_properties(name)
+    }
+
+    pub(crate) fn set_unit_properties(
+        &self,
+        name: &str,
+        properties: &[UnitProperty],
+        runtime: bool,
+    ) -> Result<()> {
+        self.data.set_unit_properties(name, properties, runtime)
+    }
+
+    pub(crate) fn get_unit_dependencies(
+        &self,
+        name: &str,
+        mode: unit_dependency_comm::Mode,
+        all: bool,
+    ) -> Result<Vec<UnitDependency>> {
+        self.data.get_unit_dependencies(name, mode, all)
+    }
+
+    pub(crate) fn get_all_units(&self) -> Result<Vec<UnitListEntry>> {
         self.data.get_all_units()
     }
 
+    pub(crate) fn get_boot_times(&self) -> Result<BootTimes> {
+        self.data.get_boot_times()
+    }
+
+    pub(crate) fn get_all_jobs(&self) -> Vec<JobEntry> {
+        self.data.get_all_jobs()
+    }
+
+    pub(crate) fn cancel_job(&self, job_id: u64) -> Result<()> {
+        self.data.jm.remove(job_id as u128)
+    }
+
+    pub(crate) fn get_ordering_cycles(&self) -> Vec<OrderingCycle> {
+        self.data.get_ordering_cycles()
+    }
+
     pub(crate) fn child_sigchld_enable(&self, enable: bool) -> i32 {
         self.data.sigchld.enable(enable)
     }
@@ -210,7 +259,8 @@ impl UnitManagerX {
 
     pub(crate) fn mask_unit(&self, unit_file: &str) -> Result<()> {
         log::info!("Masking unit {}.", unit_file);
-        let link_name_path = std::path::Path::new(basic::fs::ETC_SYSTEM_PATH).join(unit_file);
+        let link_name_path =
+            std::path::Path::new(&self.lookup_path.persistent_path).join(unit_file);
         let target_path = std::path::Path::new("/dev/null");
         basic::fs::symlink(
             target_path.to_str().unwrap(),
@@ -222,7 +272,8 @@ impl UnitManagerX {
 
     pub(crate) fn unmask_unit(&self, unit_file: &str) -> Result<()> {
         log::info!("Unmasking unit {}.", unit_file);
-        let link_name_path = std::path::Path::new(basic::fs::ETC_SYSTEM_PATH).join(unit_file);
+        let link_name_path =
+            std::path::Path::new(&self.lookup_path.persistent_path).join(unit_file);
         if !link_name_path.exists() {
             return Ok(());
         }
@@ -255,6 +306,53 @@ impl UnitManagerX {
         Ok(())
     }
 
+    pub(crate) fn revert_unit(&self, unit_file: &str) -> Result<()> {
+        check_unit_file_name(unit_file)?;
+        log::info!("Reverting unit {}.", unit_file);
+        let local = [
+            &self.lookup_path.transient,
+            &self.lookup_path.persistent_path,
+            &self.lookup_path.runtime_path,
+        ];
+        let vendor = self
+            .lookup_path
+            .search_path
+            .iter()
+            .filter(|dir| !local.contains(dir))
+            .any(|dir| std::path::Path::new(dir).join(unit_file).exists());
+        for dir in [
+            &self.lookup_path.persistent_path,
+            &self.lookup_path.runtime_path,
+        ] {
+            let dropin_dir = std::path::Path::new(dir).join(format!("{}.d", unit_file));
+            if dropin_dir.is_dir() {
+                log::info!("Removing {}", dropin_dir.to_string_lossy());
+                std::fs::remove_dir_all(&dropin_dir)?;
+            }
+
+            // the mask symlink, or a local copy of a vendor unit file
+            let path = std::path::Path::new(dir).join(unit_file);
+            let masked = match path.read_link() {
+                Ok(target) => target.ends_with("/dev/null"),
+                Err(_) => false,
+            };
+            if masked || (vendor && path.is_file()) {
+                log::info!("Removing {}", path.to_string_lossy());
+                std::fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn edit_unit(&self, name: &str, content: &str, runtime: bool) -> Result<()> {
+        let dir = if runtime {
+            &self.lookup_path.runtime_path
+        } else {
+            &self.lookup_path.persistent_path
+        };
+        self.data.edit_unit(name, content, dir)
+    }
+
     pub(crate) fn switch_root(&self, init: &[String]) -> Result<()> {
         if machine::Machine::in_initrd(None) {
             let mut str_paras = String::new();
@@ -324,6 +422,7 @@ pub struct UnitManager {
     notify: NotifyManager,
     sms: Rc<UnitSubManagers>,
     bus: UnitBus,
+    mode: Mode,
 }
 
 impl UmIf for UnitManager {
@@ -476,6 +575,10 @@ impl UmIf for UnitManager {
         self.start_unit(name, false, "replace")
     }
 
+    fn unit_stop_by_job(&self, name: &str) -> Result<()> {
+        self.stop_unit(name, false)
+    }
+
     ///
     fn events(&self) -> Rc<Events> {
         Rc::clone(&self.events)
@@ -579,7 +682,7 @@ impl UmIf for UnitManager {
         false
     }
 
-    fn collect_socket_fds(&self, name: &str) -> Vec<i32> {
+    fn collect_socket_fds(&self, name: &str) -> Vec<(i32, String)> {
         let deps = self.db.dep_gets(name, UnitRelations::UnitTriggeredBy);
         let mut fds = Vec::new();
         for dep in deps.iter() {
@@ -587,7 +690,7 @@ impl UmIf for UnitManager {
                 continue;
             }
 
-            fds.extend(dep.collect_fds())
+            fds.extend(dep.collect_fds().into_iter().map(|fd| (fd, dep.id())))
         }
 
         fds
@@ -702,6 +805,46 @@ impl UmIf for UnitManager {
         Ok(())
     }
 
+    fn unit_set_slice(&self, unit_name: &str, slice: &str) -> Result<()> {
+        if unit_name_to_type(slice) != UnitType::UnitSlice {
+            log::error!(
+                "{} is not a slice unit, refusing to put {} into it.",
+                slice,
+                unit_name
+            );
+            return Err(Error::InvalidData);
+        }
+
+        if unit_name_to_type(unit_name) == UnitType::UnitSlice {
+            log::error!("The parent of slice {} is decided by its name.", unit_name);
+            return Err(Error::InvalidData);
+        }
+
+        let unit = match self.load_unitx(unit_name) {
+            None => return Err(Error::UnitActionENoent),
+            Some(v) => v,
+        };
+
+        self.unit_add_dependency(
+            unit_name,
+            UnitRelations::UnitInSlice,
+            slice,
+            true,
+            UnitDependencyMask::File,
+        )?;
+        self.unit_add_two_dependency(
+            unit_name,
+            UnitRelations::UnitAfter,
+            UnitRelations::UnitRequires,
+            slice,
+            true,
+            UnitDependencyMask::Implicit,
+        )?;
+
+        unit.set_slice(slice);
+        Ok(())
+    }
+
     fn private_section(&self, unit_type: UnitType) -> String {
         self.sms.private_section(unit_type)
     }
@@ -779,6 +922,36 @@ impl UmIf for UnitManager {
         mount.update_mount_state_by_mountinfo();
     }
 
+    /// update swap state
+    fn update_swap_state_by_proc_swaps(&self, unit_name: &str) {
+        let swap = match self.units_get(unit_name) {
+            None => {
+                log::error!("Failed to update the swap state of {}", unit_name);
+                return;
+            }
+            Some(v) => v,
+        };
+        swap.update_swap_state_by_proc_swaps();
+    }
+
+    /// setup device
+    fn setup_device(&self, unit_name: &str, sysfs: &str, wants: &[String], found: bool) {
+        /* only a found device creates the unit, a lost device only updates the existing one */
+        let device = if found {
+            self.load_unitx(unit_name).map(|u| u.unit())
+        } else {
+            self.units_get(unit_name)
+        };
+        let device = match device {
+            None => {
+                log::debug!("Failed to get the device unit {}, ignoring.", unit_name);
+                return;
+            }
+            Some(v) => v,
+        };
+        device.setup_device(sysfs, wants, found);
+    }
+
     fn trigger_notify(&self, name: &str) {
         let deps = self.db.dep_gets(name, UnitRelations::UnitTriggeredBy);
         for dep in deps.iter() {
@@ -875,8 +1048,8 @@ impl UnitManager {
             }
             UnitEmergencyAction::Exit => {
                 log::info!("Exiting by starting exit.target caused by {}", reason);
-                if self.unit_start_by_job("exit.target").is_err() {
-                    log::error!("Failed to start exit.target.");
+                if self.unit_start_by_job(EXIT_TARGET).is_err() {
+                    log::error!("Failed to start {}.", EXIT_TARGET);
                 }
             }
             UnitEmergencyAction::ExitForce => {
@@ -992,6 +1165,20 @@ impl UnitManager {
         }
     }
 
+    pub(self) fn kill_unit(&self, name: &str, signal: i32) -> Result<()> {
+        let signal = Signal::try_from(signal).map_err(|_| Error::Nix {
+            source: nix::Error::EINVAL,
+        })?;
+
+        if let Some(unit) = self.units_get(name) {
+            unit.kill(signal)
+        } else {
+            Err(Error::LoadError {
+                msg: format!("Failed to load {}", name),
+            })
+        }
+    }
+
     pub(self) fn start_transient_unit(
         &self,
         job_mode_str: &str,
@@ -1030,32 +1217,19 @@ impl UnitManager {
         Ok(())
     }
 
-    fn get_unit_cgroup_path(&self, unit: Rc<Unit>) -> String {
-        let res = match unit.cg_path().to_str() {
-            Some(res) => res.to_string(),
-            None => String::new(),
+    fn get_unit_status_processes(&self, unit: &Unit) -> Vec<UnitProcess> {
+        let cg_path = unit.cg_path();
+        let pids = if cg_path.as_os_str().is_empty() {
+            unit.get_pids()
+        } else {
+            cgroup::cg_get_pids(&cg_path)
         };
-        if res.is_empty() {
-            return "Empty cgroup path".to_string();
-        }
-        res
-    }
-
-    fn get_unit_status_pids(&self, unit: Rc<Unit>) -> String {
-        let pids = unit.get_pids();
-        if pids.is_empty() {
-            return "No process".to_string();
-        }
-        let mut res = String::new();
-        for pid in pids.iter() {
-            if !res.is_empty() {
-                res += "\n";
-            }
-            res += &pid.to_string();
-            res += " ";
-            res += &basic::cmdline::Cmdline::new(*pid).get_cmdline();
-        }
-        res
+        pids.iter()
+            .map(|pid| UnitProcess {
+                pid: pid.as_raw() as u32,
+                cmdline: basic::cmdline::Cmdline::new(*pid).get_cmdline(),
+            })
+            .collect()
     }
 
     pub(self) fn get_unit_status(&self, name: &str) -> Result<UnitStatus> {
@@ -1070,29 +1244,233 @@ impl UnitManager {
                 return Err(Error::NotExisted);
             }
         };
-        let error_code = match self.current_active_state(name) {
-            // systemd will return 3 if the unit's state is failed or inactive.
-            UnitActiveState::Failed | UnitActiveState::InActive => 3,
-            _ => 0,
+
+        let timestamp = *unit.get_unit_timestamp().borrow();
+        let cg_path = unit.cg_path();
+        let (exit_code, exit_signal) = match unit.exit_status() {
+            Some(WaitStatus::Exited(_, code)) => (Some(code), None),
+            Some(WaitStatus::Signaled(_, signal, _)) => (None, Some(signal as i32)),
+            _ => (None, None),
+        };
+        let (memory_current, cpu_usage_nsec) = if cg_path.as_os_str().is_empty() {
+            (None, None)
+        } else {
+            (cgroup_memory_current(&cg_path), cgroup_cpu_usage(&cg_path))
         };
-        Ok(UnitStatus::new(
-            name.to_string(),
-            unit.get_description(),
-            unit.get_documentation(),
-            self.load_unit_success(name).to_string(),
-            self.get_subunit_state(name),
-            self.current_active_state(name).to_string(),
-            self.get_unit_cgroup_path(unit.clone()),
-            self.get_unit_status_pids(unit.clone()),
-            error_code,
-        ))
-    }
-
-    pub(self) fn get_all_units(&self) -> Result<String> {
-        let mut list_units_table = ShowTable::new();
-        list_units_table.add_line(vec!["UNIT", "LOAD", "ACTIVE", "SUB", "DESCRIPTION"]);
+
+        Ok(UnitStatus {
+            name: name.to_string(),
+            description: unit.get_description().unwrap_or_default(),
+            documentation: unit.get_documentation().unwrap_or_default(),
+            load_state: unit.load_state().to_string(),
+            active_state: self.current_active_state(name).to_string(),
+            sub_state: self.get_subunit_state(name),
+            state_change_timestamp: timestamp.state_change_timestamp.realtime,
+            active_enter_timestamp: timestamp.active_enter_timestamp.realtime,
+            active_exit_timestamp: timestamp.active_exit_timestamp.realtime,
+            inactive_enter_timestamp: timestamp.inactive_enter_timestamp.realtime,
+            inactive_exit_timestamp: timestamp.inactive_exit_timestamp.realtime,
+            main_pid: unit.main_pid().map_or(0, |p| p.as_raw() as u32),
+            control_pid: unit.control_pid().map_or(0, |p| p.as_raw() as u32),
+            cgroup_path: cg_path.to_string_lossy().to_string(),
+            processes: self.get_unit_status_processes(&unit),
+            memory_current,
+            cpu_usage_nsec,
+            exit_code,
+            exit_signal,
+            status_text: unit.status_text(),
+        })
+    }
+
+    pub(self) fn get_unit_properties(&self, name: &str) -> Result<Vec<UnitProperty>> {
+        let status = self.get_unit_status(name)?;
+        let unit = match self.units_get(name) {
+            Some(unit) => unit,
+            None => return Err(Error::NotExisted),
+        };
+
+        let optional = |v: Option<u64>| v.map_or(String::new(), |v| v.to_string());
+        let mut properties = vec![
+            ("Id".to_string(), status.name),
+            ("LoadState".to_string(), status.load_state),
+            ("ActiveState".to_string(), status.active_state),
+            ("SubState".to_string(), status.sub_state),
+            (
+                "StateChangeTimestamp".to_string(),
+                status.state_change_timestamp.to_string(),
+            ),
+            (
+                "ActiveEnterTimestamp".to_string(),
+                status.active_enter_timestamp.to_string(),
+            ),
+            (
+                "ActiveExitTimestamp".to_string(),
+                status.active_exit_timestamp.to_string(),
+            ),
+            (
+                "InactiveEnterTimestamp".to_string(),
+                status.inactive_enter_timestamp.to_string(),
+            ),
+            (
+                "InactiveExitTimestamp".to_string(),
+                status.inactive_exit_timestamp.to_string(),
+            ),
+            ("MainPID".to_string(), status.main_pid.to_string()),
+            ("ControlPID".to_string(), status.control_pid.to_string()),
+            ("ControlGroup".to_string(), status.cgroup_path),
+            ("MemoryCurrent".to_string(), optional(status.memory_current)),
+            ("CPUUsageNSec".to_string(), optional(status.cpu_usage_nsec)),
+            (
+                "StatusText".to_string(),
+                status.status_text.unwrap_or_default(),
+            ),
+        ];
+        properties.append(&mut unit.get_properties());
+
+        Ok(properties
+            .into_iter()
+            .map(|(key, value)| UnitProperty { key, value })
+            .collect())
+    }
+
+    pub(self) fn set_unit_properties(
+        &self,
+        name: &str,
+        properties: &[UnitProperty],
+        runtime: bool,
+    ) -> Result<()> {
+        let unit = match self.load_unitx(name) {
+            None => {
+                return Err(Error::UnitActionENoent);
+            }
+            Some(v) => v,
+        };
+        if unit.load_state() != UnitLoadState::Loaded {
+            return Err(Error::UnitActionENoent);
+        }
+
+        let flags = if runtime {
+            UnitWriteFlags::RUNTIME
+        } else {
+            UnitWriteFlags::PERSISTENT
+        };
+        self.bus.unit_set_properties(&unit, properties, flags)
+    }
+
+    pub(self) fn edit_unit(&self, name: &str, content: &str, dir: &str) -> Result<()> {
+        let unit = match self.load_unitx(name) {
+            None => {
+                return Err(Error::UnitActionENoent);
+            }
+            Some(v) => v,
+        };
+        if [UnitLoadState::NotFound, UnitLoadState::Masked].contains(&unit.load_state()) {
+            return Err(Error::UnitActionENoent);
+        }
+
+        let dropin_dir = PathBuf::from(dir).join(format!("{}.d", unit.id()));
+        let override_path = dropin_dir.join("override.conf");
+        if content.trim().is_empty() {
+            if override_path.exists() {
+                log::info!("Removing {}", override_path.to_string_lossy());
+                std::fs::remove_file(&override_path)?;
+            }
+            return Ok(());
+        }
+
+        // check the new content before it takes the place of the old override
+        std::fs::create_dir_all(&dropin_dir)?;
+        let tmp_path = dropin_dir.join(".override.conf.tmp");
+        std::fs::write(&tmp_path, content)?;
+        if let Err(e) = unit.verify_config(vec![tmp_path.clone()]) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+        std::fs::rename(&tmp_path, &override_path)?;
+        log::info!("Installed {}", override_path.to_string_lossy());
+        Ok(())
+    }
+
+    pub(self) fn get_unit_dependencies(
+        &self,
+        name: &str,
+        mode: unit_dependency_comm::Mode,
+        all: bool,
+    ) -> Result<Vec<UnitDependency>> {
+        let unit = match self.load_unitx(name) {
+            None => {
+                return Err(Error::UnitActionENoent);
+            }
+            Some(v) => v,
+        };
+        let relations = match mode {
+            unit_dependency_comm::Mode::Forward => vec![
+                UnitRelations::UnitRequires,
+                UnitRelations::UnitRequisite,
+                UnitRelations::UnitBindsTo,
+                UnitRelations::UnitWants,
+                UnitRelations::UnitConsistsOf,
+                UnitRelations::UnitUpHolds,
+            ],
+            unit_dependency_comm::Mode::Reverse => vec![
+                UnitRelations::UnitRequiresBy,
+                UnitRelations::UnitRequisiteOf,
+                UnitRelations::UnitBoundBy,
+                UnitRelations::UnitWantsBy,
+                UnitRelations::UnitPartOf,
+                UnitRelations::UnitUpHeldBy,
+            ],
+            unit_dependency_comm::Mode::After => vec![UnitRelations::UnitAfter],
+            unit_dependency_comm::Mode::Before => vec![UnitRelations::UnitBefore],
+        };
+
+        let id = unit.id();
+        let mut dependencies = vec![UnitDependency {
+            depth: 0,
+            name: id.clone(),
+            active_state: self.current_active_state(&id).to_string(),
+        }];
+        let mut path = vec![id];
+        self.add_unit_dependencies(&relations, all, &mut path, &mut dependencies);
+        Ok(dependencies)
+    }
+
+    /// add the dependencies of the last unit in path, the units already in path are not
+    /// expanded again to break the cycles
+    fn add_unit_dependencies(
+        &self,
+        relations: &[UnitRelations],
+        all: bool,
+        path: &mut Vec<String>,
+        dependencies: &mut Vec<UnitDependency>,
+    ) {
+        let source = path.last().unwrap().clone();
+        let mut dests: Vec<String> = relations
+            .iter()
+            .flat_map(|relation| self.db.dep_gets(&source, *relation))
+            .map(|unit| unit.id())
+            .collect();
+        dests.sort();
+        dests.dedup();
+
+        for dest in dests {
+            dependencies.push(UnitDependency {
+                depth: path.len() as u32,
+                name: dest.clone(),
+                active_state: self.current_active_state(&dest).to_string(),
+            });
+            if path.contains(&dest) || (!all && !dest.ends_with(".target")) {
+                continue;
+            }
+            path.push(dest);
+            self.add_unit_dependencies(relations, all, path, dependencies);
+            path.pop();
+        }
+    }
+
+    pub(self) fn get_all_units(&self) -> Result<Vec<UnitListEntry>> {
+        let mut unit_list = Vec::new();
         for unit_type in UnitType::iterator() {
-            list_units_table.set_current_row_underline(true);
             for unit_name in self.units_get_all(Some(unit_type)) {
                 let unit = match self.units_get(&unit_name) {
                     Some(unit) => unit,
@@ -1101,26 +1479,100 @@ impl UnitManager {
                         continue;
                     }
                 };
-                let load_state = self.load_unit_success(&unit_name).to_string();
-                let active_state = self.current_active_state(&unit_name).to_string();
-                let sub_state = self.get_subunit_state(&unit_name);
-                let description = match unit.get_description() {
-                    None => String::from(&unit_name),
-                    Some(str) => str,
-                };
-                list_units_table.add_line(vec![
-                    &unit_name,
-                    &load_state,
-                    &active_state,
-                    &sub_state,
-                    &description,
-                ]);
-                if active_state == "failed" {
-                    list_units_table.set_current_row_color(CellColor::Red);
-                }
+                unit_list.push(UnitListEntry {
+                    load_state: unit.load_state().to_string(),
+                    active_state: self.current_active_state(&unit_name).to_string(),
+                    sub_state: self.get_subunit_state(&unit_name),
+                    description: unit.get_description().unwrap_or_else(|| unit_name.clone()),
+                    name: unit_name,
+                });
             }
         }
-        Ok(list_units_table.to_string())
+        Ok(unit_list)
+    }
+
+    pub(self) fn get_boot_times(&self) -> Result<BootTimes> {
+        let mut units = Vec::new();
+        for unit_name in self.units_get_all(None) {
+            let unit = match self.units_get(&unit_name) {
+                Some(unit) => unit,
+                None => continue,
+            };
+            if unit.load_state() != UnitLoadState::Loaded {
+                continue;
+            }
+
+            let timestamp = *unit.get_unit_timestamp().borrow();
+            let mut after: Vec<String> = self
+                .db
+                .dep_gets(&unit_name, UnitRelations::UnitAfter)
+                .iter()
+                .map(|u| u.id())
+                .collect();
+            after.sort();
+            units.push(UnitTimes {
+                active_state: self.current_active_state(&unit_name).to_string(),
+                inactive_exit: timestamp.inactive_exit_timestamp.monotonic,
+                active_enter: timestamp.active_enter_timestamp.monotonic,
+                active_exit: timestamp.active_exit_timestamp.monotonic,
+                inactive_enter: timestamp.inactive_enter_timestamp.monotonic,
+                after,
+                name: unit_name,
+            });
+        }
+        units.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // the kernel boots before sysmaster, whose start time is where the userspace begins
+        let userspace = process::process_start_usec(nix::unistd::getpid()).unwrap_or_else(|e| {
+            log::warn!("Failed to get the start time of sysmaster: {}", e);
+            0
+        });
+        let finish_target = self.mode.default_target();
+        let finish = match self.units_get(finish_target) {
+            Some(unit) => {
+                unit.get_unit_timestamp()
+                    .borrow()
+                    .active_enter_timestamp
+                    .monotonic
+            }
+            None => 0,
+        };
+        let realtime_base = basic::time::now_clockid(libc::CLOCK_REALTIME)
+            .saturating_sub(basic::time::now_clockid(libc::CLOCK_MONOTONIC));
+
+        Ok(BootTimes {
+            userspace,
+            finish,
+            realtime_base,
+            units,
+            finish_target: finish_target.to_string(),
+        })
+    }
+
+    pub(self) fn get_all_jobs(&self) -> Vec<JobEntry> {
+        self.jm
+            .get_all_jobinfos()
+            .iter()
+            .map(|job| JobEntry {
+                id: job.id as u64,
+                unit: job.unit.id(),
+                job_type: job.kind.to_string(),
+                state: job.stage.to_string(),
+            })
+            .collect()
+    }
+
+    pub(self) fn get_ordering_cycles(&self) -> Vec<OrderingCycle> {
+        self.jm
+            .get_ordering_cycles()
+            .into_iter()
+            .map(|cycle| OrderingCycle {
+                timestamp: cycle.timestamp,
+                anchor: cycle.anchor,
+                jobs: cycle.jobs,
+                dropped: cycle.dropped.unwrap_or_default(),
+            })
+            .collect()
     }
 
     pub(self) fn new(
@@ -1128,6 +1580,7 @@ impl UnitManager {
         relir: &Rc<Reliability>,
         dmr: &Rc<DataManager>,
         lookup_path: &Rc<LookupPaths>,
+        mode: Mode,
         state: Rc<RefCell<State>>,
         manager_config: Rc<RefCell<ManagerConfig>>,
     ) -> Rc<UnitManager> {
@@ -1157,9 +1610,17 @@ impl UnitManager {
             jm: Rc::clone(&_jm),
             exec: ExecSpawn::new(),
             sigchld: Sigchld::new(eventr, relir, &_db, &_jm),
-            notify: NotifyManager::new(eventr, relir, &_rentry, &_db, &_jm),
+            notify: NotifyManager::new(
+                eventr,
+                relir,
+                &_rentry,
+                &_db,
+                &_jm,
+                mode.runtime_dir().join("notify"),
+            ),
             sms: Rc::clone(&_sms),
-            bus: UnitBus::new(relir, &_load, &_jm, &_sms),
+            bus: UnitBus::new(relir, &_load, &_jm, &_sms, lookup_path),
+            mode,
         });
         um.load.set_um(&um);
         let umif = Rc::clone(&um);
@@ -1226,6 +1687,11 @@ impl UnitManager {
                 "unit ".to_string() + source + " succeeded",
             );
         }
+        /* all the units are stopped once exit.target is reached */
+        if self.mode == Mode::User && source == EXIT_TARGET && state.ns == UnitActiveState::Active {
+            log::info!("{} is reached, exiting.", EXIT_TARGET);
+            self.set_state(State::Exit);
+        }
 
         if let Err(_e) = self.jm.try_finish(&unitx, state.os, state.ns, state.flags) {
             // debug
@@ -1446,6 +1912,21 @@ impl ReStation for UnitManager {
     fn into_unitobj(self: Box<Self>) -> Box<dyn SubUnit>;
 }*/
 
+/// the name is joined to the lookup paths, so it must be a unit name, not a path
+fn check_unit_file_name(unit_file: &str) -> Result<()> {
+    if unit_file.contains('/')
+        || !unit_name_is_valid(
+            unit_file,
+            UnitNameFlags::PLAIN | UnitNameFlags::INSTANCE | UnitNameFlags::TEMPLATE,
+        )
+    {
+        return Err(Error::InvalidName {
+            what: unit_file.to_string(),
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1480,6 +1961,7 @@ mod tests {
             &reli,
             &dm,
             &lookup_path,
+            Mode::System,
             state,
             Rc::new(RefCell::new(ManagerConfig::new(&Mode::System))),
         );
@@ -1612,4 +2094,15 @@ mod tests {
             };
         }
     }
+
+    #[test]
+    fn test_check_unit_file_name() {
+        assert!(check_unit_file_name("foo.service").is_ok());
+        assert!(check_unit_file_name("foo@bar.service").is_ok());
+        assert!(check_unit_file_name("foo@.service").is_ok());
+        assert!(check_unit_file_name("../foo.service").is_err());
+        assert!(check_unit_file_name("etc/foo.service").is_err());
+        assert!(check_unit_file_name("foo").is_err());
+        assert!(check_unit_file_name("").is_err());
+    }
 }
@@ -35,6 +35,7 @@ pub(super) use entry::UnitX;
 pub(super) use manager::UnitManagerX;
 pub use rentry::UeConfigInstall;
 pub(super) use rentry::{unit_name_to_type, JobMode};
+pub(super) use verify::verify_units;
 
 #[cfg(test)]
 pub(super) use rentry::UnitRe;
@@ -76,3 +77,4 @@ mod submanager;
 mod test;
 mod uload;
 mod util;
+mod verify;
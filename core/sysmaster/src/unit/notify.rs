@@ -30,8 +30,6 @@ use std::{
     path::PathBuf, rc::Rc,
 };
 
-const NOTIFY_SOCKET: &str = "/run/sysmaster/notify";
-
 pub(super) struct NotifyManager {
     // associated objects
     events: Rc<Events>,
@@ -90,9 +88,10 @@ impl NotifyManager {
         rentryr: &Rc<UnitRe>,
         dbr: &Rc<UnitDb>,
         jmr: &Rc<JobManager>,
+        notify_sock: PathBuf,
     ) -> NotifyManager {
         let notify_config = Rc::new(NotifyConfig::new());
-        notify_config.set_notify_sock(PathBuf::from(NOTIFY_SOCKET));
+        notify_config.set_notify_sock(notify_sock);
         let _notify = Rc::new(Notify::new(relir, rentryr, dbr, &notify_config));
         let nm = NotifyManager {
             events: Rc::clone(eventr),
@@ -112,6 +111,8 @@ impl NotifyManager {
 
 use constants::INVALID_FD;
 const NOTIFY_INVALID_PID: libc::pid_t = -1;
+/// the most fds one notify message can carry, messages with more fds are refused
+const NOTIFY_FD_MAX: usize = 768;
 
 struct Notify {
     // associated objects
@@ -218,22 +219,25 @@ impl Notify {
     ) -> Result<i32> {
         let mut buffer = [0u8; 4096];
         let mut iov = [IoSliceMut::new(&mut buffer)];
-        let mut space = cmsg_space!(libc::ucred, RawFd);
+        let mut space = cmsg_space!(libc::ucred, [RawFd; NOTIFY_FD_MAX]);
 
         // pop
         let msgs = socket::recvmsg::<()>(self.rawfd(), &mut iov, Some(&mut space), flags)
             .context(NixSnafu)?;
+        let (received_cred, received_fds) = notify_trans_recvmsg(&msgs);
+        let bytes = msgs.bytes;
 
         if msgs.flags.contains(MsgFlags::MSG_CTRUNC) {
+            close_fds(&received_fds);
             return Err(Error::Nix {
                 source: nix::Error::EXFULL,
             });
         }
 
         // check: peek == pop
-        let (received_cred, received_fds) = notify_trans_recvmsg(&msgs);
         if get_pid_from_cred(&received_cred) != pid {
             log::error!("the received notify message has been destroyed");
+            close_fds(&received_fds);
             return Err(Error::Other {
                 msg: "the received notify message has been destroyed".to_string(),
             });
@@ -241,25 +245,29 @@ impl Notify {
 
         // build input
         let ucred = received_cred.unwrap();
-        let contents = String::from_utf8(buffer.to_vec()).unwrap();
+        let contents = String::from_utf8_lossy(&buffer[..bytes.min(buffer.len())]);
         let mut messages = HashMap::new();
         for line in contents.lines() {
-            let content: Vec<&str> = line
-                .split('=')
-                .map(|s| s.trim_end_matches(char::from(0)))
-                .collect();
-            if content.len() != 2 {
-                continue;
+            /* the value may contain '=', e.g. STATUS=a=b */
+            let line = line.trim_end_matches(char::from(0));
+            if let Some((key, value)) = line.split_once('=') {
+                messages.insert(key, value.trim_end());
             }
-
-            messages.insert(content[0], content[1].trim_end());
         }
-        log::debug!("[notify] ucred: {:?}, messages: {:?}", &ucred, messages,);
-
-        // action
-        if let Some(u) = unit {
-            log::debug!("[notify] unit: {:?}", u.id());
-            let _ = u.notify_message(&ucred, &messages, received_fds);
+        log::debug!(
+            "[notify] ucred: {:?}, messages: {:?}, fds: {:?}",
+            &ucred,
+            messages,
+            received_fds
+        );
+
+        // action: the unit takes over the fds, otherwise they are closed here
+        match unit {
+            Some(u) => {
+                log::debug!("[notify] unit: {:?}", u.id());
+                let _ = u.notify_message(&ucred, &messages, received_fds);
+            }
+            None => close_fds(&received_fds),
         }
 
         Ok(0)
@@ -278,25 +286,23 @@ impl Notify {
 fn notify_peek_pid(fd: RawFd, flags: MsgFlags) -> Result<libc::pid_t> {
     let mut buffer = [0u8; 4096];
     let mut iov = [IoSliceMut::new(&mut buffer)];
-    let mut space = cmsg_space!(libc::ucred, RawFd);
+    let mut space = cmsg_space!(libc::ucred, [RawFd; NOTIFY_FD_MAX]);
 
     // peek
     let peek_flags = flags | MsgFlags::MSG_PEEK;
     let msgs =
         socket::recvmsg::<()>(fd, &mut iov, Some(&mut space), peek_flags).context(NixSnafu)?;
 
+    // get message information
+    let (received_cred, received_fds) = notify_trans_recvmsg(&msgs);
+    close_fds(&received_fds);
+
     if msgs.flags.contains(MsgFlags::MSG_CTRUNC) {
         return Err(Error::Nix {
             source: nix::Error::EXFULL,
         });
     }
 
-    // get message information
-    let (received_cred, received_fds) = notify_trans_recvmsg(&msgs);
-    for fd in received_fds.iter() {
-        fd::close(*fd);
-    }
-
     // check
     let pid = get_pid_from_cred(&received_cred);
     if pid < 0 {
@@ -309,6 +315,12 @@ fn notify_peek_pid(fd: RawFd, flags: MsgFlags) -> Result<libc::pid_t> {
     Ok(pid)
 }
 
+fn close_fds(fds: &[RawFd]) {
+    for fd in fds.iter() {
+        fd::close(*fd);
+    }
+}
+
 fn notify_trans_recvmsg(msgs: &RecvMsg<()>) -> (Option<UnixCredentials>, Vec<i32>) {
     let mut received_fds = Vec::new();
     let mut received_cred = None;
@@ -60,6 +60,20 @@ pub enum UnitLoadState {
     Masked,
 }
 
+impl std::fmt::Display for UnitLoadState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitLoadState::Stub => write!(f, "stub"),
+            UnitLoadState::Loaded => write!(f, "loaded"),
+            UnitLoadState::NotFound => write!(f, "not-found"),
+            UnitLoadState::Error => write!(f, "error"),
+            UnitLoadState::BadSetting => write!(f, "bad-setting"),
+            UnitLoadState::Merged => write!(f, "merged"),
+            UnitLoadState::Masked => write!(f, "masked"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct UnitReLoad {
     load_state: UnitLoadState,
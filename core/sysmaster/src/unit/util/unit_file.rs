@@ -50,6 +50,15 @@ impl UnitFile {
         self.data.borrow().get_all_names()
     }
 
+    pub fn config_dir(&self, runtime: bool) -> PathBuf {
+        let lookup_path = &self.data.borrow().lookup_path;
+        if runtime {
+            PathBuf::from(&lookup_path.runtime_path)
+        } else {
+            PathBuf::from(&lookup_path.persistent_path)
+        }
+    }
+
     pub fn get_unit_wants_symlink_units(&self, name: &str) -> Vec<PathBuf> {
         self.data.borrow().get_unit_wants_symlink_units(name)
     }
@@ -152,6 +161,23 @@ impl UnitFileData {
         res
     }
 
+    /* Collect the drop-ins of all search paths, a drop-in overrides the one with the same
+     * file name in the lower priority search path, and they are ordered by the file name. */
+    fn search_dropin_fragments(&mut self, name: &str) -> Vec<PathBuf> {
+        let mut dropins: HashMap<String, PathBuf> = HashMap::new();
+        let search_path_list = self.lookup_path.search_path.clone();
+        for search_path in &search_path_list {
+            for v in self.search_dropin_fragment(search_path, name) {
+                let file_name = v.file_name().unwrap().to_string_lossy().to_string();
+                dropins.entry(file_name).or_insert(v);
+            }
+        }
+
+        let mut file_names: Vec<&String> = dropins.keys().collect();
+        file_names.sort();
+        file_names.iter().map(|v| dropins[*v].clone()).collect()
+    }
+
     fn build_id_fragment_by_name(&mut self, path: &str, name: &str) -> Option<Vec<PathBuf>> {
         let mut res: Vec<PathBuf> = Vec::new();
         if fs::metadata(path).is_err() {
@@ -238,14 +264,7 @@ impl UnitFileData {
         }
 
         if !pathbuf_fragment.is_empty() || !name.contains('@') {
-            for search_path in &search_path_list {
-                let mut v = self.search_dropin_fragment(search_path, name);
-                if v.is_empty() {
-                    continue;
-                }
-                pathbuf_fragment.append(&mut v);
-                break;
-            }
+            pathbuf_fragment.append(&mut self.search_dropin_fragments(name));
 
             self.unit_id_fragment
                 .insert(name.to_string(), pathbuf_fragment);
@@ -268,14 +287,7 @@ impl UnitFileData {
             break;
         }
 
-        for search_path in &search_path_list {
-            let mut v = self.search_dropin_fragment(search_path, &template_name);
-            if v.is_empty() {
-                continue;
-            }
-            pathbuf_fragment.append(&mut v);
-            break;
-        }
+        pathbuf_fragment.append(&mut self.search_dropin_fragments(&template_name));
 
         self.unit_id_fragment
             .insert(name.to_string(), pathbuf_fragment);
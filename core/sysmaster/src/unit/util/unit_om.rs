@@ -54,18 +54,28 @@ mod plugin {
 
 #[cfg(feature = "noplugin")]
 mod noplugin {
+    #[cfg(feature = "automount")]
+    use automount::{self};
     use core::error::*;
     use core::unit::UmIf;
     use core::unit::{SubUnit, UnitManagerObj, UnitType};
+    #[cfg(feature = "device")]
+    use device::{self};
     #[cfg(feature = "mount")]
     use mount::{self};
     #[cfg(feature = "path")]
     use path::{self};
+    #[cfg(feature = "scope")]
+    use scope::{self};
     #[cfg(feature = "service")]
     use service::{self};
+    #[cfg(feature = "slice")]
+    use slice::{self};
     #[cfg(feature = "socket")]
     use socket::{self};
     use std::rc::Rc;
+    #[cfg(feature = "swap")]
+    use swap::{self};
     #[cfg(feature = "target")]
     use target::{self};
     #[cfg(feature = "timer")]
@@ -90,6 +100,16 @@ mod noplugin {
             UnitType::UnitTimer => timer::__um_obj_create,
             #[cfg(feature = "path")]
             UnitType::UnitPath => path::__um_obj_create,
+            #[cfg(feature = "slice")]
+            UnitType::UnitSlice => slice::__um_obj_create,
+            #[cfg(feature = "scope")]
+            UnitType::UnitScope => scope::__um_obj_create,
+            #[cfg(feature = "automount")]
+            UnitType::UnitAutomount => automount::__um_obj_create,
+            #[cfg(feature = "swap")]
+            UnitType::UnitSwap => swap::__um_obj_create,
+            #[cfg(feature = "device")]
+            UnitType::UnitDevice => device::__um_obj_create,
             _ => {
                 return Err(Error::Other {
                     msg: "Component unsupported!".to_string(),
@@ -120,6 +140,16 @@ mod noplugin {
             UnitType::UnitTimer => timer::__subunit_create_with_params,
             #[cfg(feature = "path")]
             UnitType::UnitPath => path::__subunit_create_with_params,
+            #[cfg(feature = "slice")]
+            UnitType::UnitSlice => slice::__subunit_create_with_params,
+            #[cfg(feature = "scope")]
+            UnitType::UnitScope => scope::__subunit_create_with_params,
+            #[cfg(feature = "automount")]
+            UnitType::UnitAutomount => automount::__subunit_create_with_params,
+            #[cfg(feature = "swap")]
+            UnitType::UnitSwap => swap::__subunit_create_with_params,
+            #[cfg(feature = "device")]
+            UnitType::UnitDevice => device::__subunit_create_with_params,
             _ => {
                 return Err(Error::Other {
                     msg: "Component unsupported!".to_string(),
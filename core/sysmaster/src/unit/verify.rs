@@ -0,0 +1,452 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Verify the unit files offline, without a running manager. The unit files are checked
+//! together with their drop-ins for the unknown keys, the bad values, the missing
+//! executables, the dangling dependencies and the ordering cycles, and every problem is
+//! reported with the file and the line where it is found.
+
+use super::entry::UeConfig;
+use super::rentry::unit_name_to_type;
+use super::util::create_subunit_with_um;
+use basic::fs::LookupPaths;
+use core::exec::parse_exec_command;
+use core::unit::{unit_name_is_valid, UmIf, UnitNameFlags, UnitType};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use unit_parser::internal::UnitParser;
+
+/// The keys of [Unit] and [Install] whose values are unit names
+const DEPENDENCY_KEYS: [&str; 12] = [
+    "Wants",
+    "Requires",
+    "BindsTo",
+    "Requisite",
+    "PartOf",
+    "OnFailure",
+    "OnSuccess",
+    "Before",
+    "After",
+    "Conflicts",
+    "WantedBy",
+    "RequiredBy",
+];
+
+/// The sub unit is created without a manager, nothing is asked from it.
+struct OfflineUm;
+
+impl UmIf for OfflineUm {}
+
+/// A problem found in a unit file
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Diagnostic {
+    path: PathBuf,
+    /// 0 if the problem is about the whole file
+    line: usize,
+    msg: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.path.display(), self.msg)
+        } else {
+            write!(f, "{}:{}: {}", self.path.display(), self.line, self.msg)
+        }
+    }
+}
+
+/// One assignment of a unit file, the specifiers are resolved
+struct Assignment {
+    path: PathBuf,
+    line: usize,
+    section: String,
+    key: String,
+    value: String,
+}
+
+/// An ordering edge, the unit `from` is started before the unit `to`
+struct OrderingEdge {
+    from: String,
+    to: String,
+    path: PathBuf,
+    line: usize,
+}
+
+/// Verify the unit files, print the problems and return the exit code, 0 if no problem
+/// is found.
+pub(crate) fn verify_units(files: &[String]) -> i32 {
+    let mut lookup_path = LookupPaths::new();
+    lookup_path.init_lookup_paths();
+
+    let files: Vec<PathBuf> = files.iter().map(PathBuf::from).collect();
+    let diagnostics = verify_files(&files, &lookup_path.search_path);
+    for d in diagnostics.iter() {
+        println!("{}", d);
+    }
+    if diagnostics.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+fn verify_files(files: &[PathBuf], search_path: &[String]) -> Vec<Diagnostic> {
+    /* the units given on the command line are known even if they are not installed yet */
+    let given: HashSet<String> = files.iter().map(|f| file_name(f)).collect();
+
+    let mut diagnostics = Vec::new();
+    let mut edges = Vec::new();
+    for file in files {
+        let name = file_name(file);
+        if !unit_name_is_valid(&name, UnitNameFlags::ANY) {
+            diagnostics.push(Diagnostic {
+                path: file.clone(),
+                line: 0,
+                msg: format!("Invalid unit name {}.", name),
+            });
+            continue;
+        }
+
+        let mut paths = vec![file.clone()];
+        paths.append(&mut search_dropins(file, &name, search_path));
+
+        let mut unit_diagnostics = verify_config(&paths, &name);
+        for assignment in paths.iter().flat_map(|p| read_assignments(p, &name)) {
+            if let Some(d) = verify_exec(&assignment) {
+                unit_diagnostics.push(d);
+            }
+            if let Some(d) = verify_dependency(&assignment, &given, search_path) {
+                unit_diagnostics.push(d);
+            }
+            edges.append(&mut ordering_edges(&assignment, &name));
+        }
+        unit_diagnostics.sort();
+        unit_diagnostics.dedup();
+        diagnostics.append(&mut unit_diagnostics);
+    }
+
+    diagnostics.append(&mut ordering_cycles(&edges));
+    diagnostics
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// the drop-ins beside the unit file and in the search paths, a drop-in overrides the one
+/// with the same file name found later, and they are ordered by the file name
+fn search_dropins(file: &Path, name: &str, search_path: &[String]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(parent) = file.parent() {
+        dirs.push(parent.join(format!("{}.d", name)));
+    }
+    for path in search_path {
+        dirs.push(Path::new(path).join(format!("{}.d", name)));
+    }
+
+    let mut dropins: HashMap<String, PathBuf> = HashMap::new();
+    for dir in dirs {
+        let entries = match dir.read_dir() {
+            Err(_) => continue,
+            Ok(v) => v,
+        };
+        for entry in entries.flatten() {
+            let dropin = entry.path();
+            let dropin_name = file_name(&dropin);
+            if dropin.is_file() && dropin_name.ends_with(".conf") {
+                dropins.entry(dropin_name).or_insert(dropin);
+            }
+        }
+    }
+
+    let mut file_names: Vec<&String> = dropins.keys().collect();
+    file_names.sort();
+    file_names.iter().map(|v| dropins[*v].clone()).collect()
+}
+
+/// the unknown keys and the bad values of [Unit], [Install] and the sub unit type section
+fn verify_config(paths: &[PathBuf], name: &str) -> Vec<Diagnostic> {
+    let mut errors = UeConfig::verify(paths.to_vec(), name);
+    let unit_type = unit_name_to_type(name);
+    match create_subunit_with_um(unit_type, Rc::new(OfflineUm)) {
+        Ok(sub) => errors.append(&mut sub.verify_config(paths.to_vec(), name)),
+        Err(e) => log::debug!("Failed to create the sub unit of {}: {}", name, e),
+    }
+    errors
+        .into_iter()
+        .map(|e| Diagnostic {
+            path: e.path,
+            line: e.line,
+            msg: e.error.to_string(),
+        })
+        .collect()
+}
+
+/// all assignments of the unit file, the file is not readable is reported by verify_config()
+fn read_assignments(path: &Path, name: &str) -> Vec<Assignment> {
+    let content = match fs::read_to_string(path) {
+        Err(_) => return Vec::new(),
+        Ok(v) => v,
+    };
+
+    let mut assignments = Vec::new();
+    let mut parser = UnitParser::new(&content, (name,));
+    while let Some(mut section) = parser.next() {
+        while let Some((key, value)) = section.next() {
+            assignments.push(Assignment {
+                path: path.to_path_buf(),
+                line: section.line(),
+                section: section.name.to_string(),
+                key: key.to_string(),
+                value,
+            });
+        }
+        parser.progress(section.finish());
+    }
+    assignments
+}
+
+/// the executables of Exec*= must exist and be executable
+fn verify_exec(assignment: &Assignment) -> Option<Diagnostic> {
+    if ["Unit", "Install"].contains(&assignment.section.as_str())
+        || !assignment.key.starts_with("Exec")
+        || assignment.value.trim().is_empty()
+    {
+        return None;
+    }
+    /* the bad command lines are reported as bad values by verify_config() */
+    let commands = parse_exec_command(&assignment.value).ok()?;
+    let msg = commands.iter().find_map(|cmd| {
+        let path = Path::new(cmd.path());
+        match fs::metadata(path) {
+            Err(_) => Some(format!(
+                "Command {} of {}= is not found.",
+                cmd.path(),
+                assignment.key
+            )),
+            Ok(m) if !m.is_file() || m.permissions().mode() & 0o111 == 0 => Some(format!(
+                "Command {} of {}= is not executable.",
+                cmd.path(),
+                assignment.key
+            )),
+            Ok(_) => None,
+        }
+    })?;
+    Some(Diagnostic {
+        path: assignment.path.clone(),
+        line: assignment.line,
+        msg,
+    })
+}
+
+/// whether the unit file of the unit or its template is installed in the search paths
+fn unit_file_exists(name: &str, search_path: &[String]) -> bool {
+    let mut names = vec![name.to_string()];
+    if let (Some((prefix, _)), Some((_, suffix))) = (name.split_once('@'), name.rsplit_once('.')) {
+        names.push(format!("{}@.{}", prefix, suffix));
+    }
+    search_path
+        .iter()
+        .any(|p| names.iter().any(|n| Path::new(p).join(n).exists()))
+}
+
+/// the units named by the dependency keys must exist, the units generated at runtime, such
+/// as the mounts and the slices, may have no unit file and are not checked
+fn verify_dependency(
+    assignment: &Assignment,
+    given: &HashSet<String>,
+    search_path: &[String],
+) -> Option<Diagnostic> {
+    if !["Unit", "Install"].contains(&assignment.section.as_str())
+        || !DEPENDENCY_KEYS.contains(&assignment.key.as_str())
+    {
+        return None;
+    }
+    let msg = assignment.value.split_whitespace().find_map(|name| {
+        if !unit_name_is_valid(name, UnitNameFlags::PLAIN | UnitNameFlags::INSTANCE) {
+            return Some(format!(
+                "Invalid unit name {} of {}=.",
+                name, assignment.key
+            ));
+        }
+        let checked = matches!(
+            unit_name_to_type(name),
+            UnitType::UnitService
+                | UnitType::UnitSocket
+                | UnitType::UnitTarget
+                | UnitType::UnitTimer
+                | UnitType::UnitPath
+                | UnitType::UnitAutomount
+                | UnitType::UnitSwap
+        );
+        if checked && !given.contains(name) && !unit_file_exists(name, search_path) {
+            return Some(format!(
+                "Unit {} of {}= is not found.",
+                name, assignment.key
+            ));
+        }
+        None
+    })?;
+    Some(Diagnostic {
+        path: assignment.path.clone(),
+        line: assignment.line,
+        msg,
+    })
+}
+
+fn ordering_edges(assignment: &Assignment, name: &str) -> Vec<OrderingEdge> {
+    if assignment.section != "Unit" {
+        return Vec::new();
+    }
+    let before = match assignment.key.as_str() {
+        "After" => false,
+        "Before" => true,
+        _ => return Vec::new(),
+    };
+    assignment
+        .value
+        .split_whitespace()
+        .filter(|other| *other != name)
+        .map(|other| {
+            let (from, to) = if before { (name, other) } else { (other, name) };
+            OrderingEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                path: assignment.path.clone(),
+                line: assignment.line,
+            }
+        })
+        .collect()
+}
+
+/// the cycles of the ordering edges, each reported at the edge closing it
+fn ordering_cycles(edges: &[OrderingEdge]) -> Vec<Diagnostic> {
+    let mut graph: HashMap<&str, Vec<&OrderingEdge>> = HashMap::new();
+    for edge in edges {
+        graph.entry(edge.from.as_str()).or_default().push(edge);
+    }
+    let mut nodes: Vec<&str> = graph.keys().copied().collect();
+    nodes.sort_unstable();
+
+    let mut diagnostics = Vec::new();
+    let mut done: HashSet<&str> = HashSet::new();
+    for node in nodes {
+        let mut stack = Vec::new();
+        find_cycles(&graph, node, &mut stack, &mut done, &mut diagnostics);
+    }
+    diagnostics
+}
+
+fn find_cycles<'a>(
+    graph: &HashMap<&'a str, Vec<&'a OrderingEdge>>,
+    node: &'a str,
+    stack: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if done.contains(node) {
+        return;
+    }
+    stack.push(node);
+    for edge in graph.get(node).into_iter().flatten() {
+        let to = edge.to.as_str();
+        if let Some(pos) = stack.iter().position(|n| *n == to) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(to);
+            diagnostics.push(Diagnostic {
+                path: edge.path.clone(),
+                line: edge.line,
+                msg: format!("Ordering cycle found: {}.", cycle.join(" -> ")),
+            });
+            continue;
+        }
+        find_cycles(graph, to, stack, done, diagnostics);
+    }
+    stack.pop();
+    done.insert(node);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_unit(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_verify_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write_unit(
+            dir.path(),
+            "a.service",
+            "[Unit]\nDescription=a\nBefore=b.service\nWants=none.service\n\n\
+             [Service]\nExecStart=/none/bin/a\nUnknownKey=1\n",
+        );
+        let b = write_unit(
+            dir.path(),
+            "b.service",
+            "[Unit]\nBefore=c.service\n[Service]\nExecStart=/bin/sh -c true\n",
+        );
+        let c = write_unit(
+            dir.path(),
+            "c.service",
+            "[Unit]\nBefore=a.service\n[Service]\nExecStart=/bin/sh -c true\n",
+        );
+
+        let diagnostics: Vec<String> = verify_files(&[a, b, c], &[])
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        let a = dir.path().join("a.service").to_string_lossy().to_string();
+        let c = dir.path().join("c.service").to_string_lossy().to_string();
+        assert_eq!(
+            diagnostics,
+            vec![
+                format!("{}:4: Unit none.service of Wants= is not found.", a),
+                format!("{}:7: Command /none/bin/a of ExecStart= is not found.", a),
+                format!("{}:8: Unknown entry with key UnknownKey.", a),
+                format!(
+                    "{}:2: Ordering cycle found: a.service -> b.service -> c.service -> a.service.",
+                    c
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_dropins() {
+        let dir = tempfile::tempdir().unwrap();
+        let unit = write_unit(dir.path(), "a.service", "[Unit]\n");
+        fs::create_dir(dir.path().join("a.service.d")).unwrap();
+        write_unit(&dir.path().join("a.service.d"), "20-b.conf", "[Unit]\n");
+        write_unit(&dir.path().join("a.service.d"), "10-a.conf", "[Unit]\n");
+        write_unit(&dir.path().join("a.service.d"), "ignored", "[Unit]\n");
+
+        let dropins: Vec<String> = search_dropins(&unit, "a.service", &[])
+            .iter()
+            .map(|p| file_name(p))
+            .collect();
+        assert_eq!(dropins, vec!["10-a.conf", "20-b.conf"]);
+    }
+}
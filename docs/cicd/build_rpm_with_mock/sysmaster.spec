@@ -69,8 +69,11 @@ install -Dm0750 -t %{sysmaster_install_target} %{sysmaster_install_source}/sysmo
 install -Dm0750 -t %{sysmaster_install_target} %{sysmaster_install_source}/random_seed
 install -Dm0750 -t %{sysmaster_install_target} %{sysmaster_install_source}/rc-local-generator
 install -Dm0750 -t %{sysmaster_install_target} %{sysmaster_install_source}/hostname_setup
+install -Dm0750 -t %{sysmaster_install_target} %{sysmaster_install_source}/logcollector
 install -Dm0750 -t %{sysmaster_install_target} %{sysmaster_install_source}/sysmaster-run
+install -Dm0750 -t %{sysmaster_install_target} %{sysmaster_install_source}/sysmaster-analyze
 install -Dm0750 -t %{sysmaster_install_target}/system-generators %{sysmaster_install_source}/getty-generator
+install -Dm0750 %{sysmaster_install_source}/fstab %{sysmaster_install_target}/system-generators/fstab-generator
 
 cp -a %{factory_install_source}/* %{factory_install_target}
 
@@ -82,7 +85,7 @@ for unit in NetworkManager.service dbus.service fstab.service hostname-setup.ser
     ln -s /usr/lib/sysmaster/system/$unit %{buildroot}/etc/sysmaster/system/multi-user.target.wants/$unit
 done
 
-for unit in udevd.service udev-trigger.service devmaster.service; do
+for unit in udevd.service udev-trigger.service devmaster.service logcollector.socket; do
     ln -s /usr/lib/sysmaster/system/$unit %{buildroot}/etc/sysmaster/system/sysinit.target.wants/$unit
 done
 
@@ -94,14 +97,19 @@ sed -i 's/\"\/lib\/devmaster\/rules.d\"/&, \"\/etc\/udev\/rules.d\", \"\/run\/ud
 %dir %attr(0550,-,-) /usr/lib/sysmaster
 %dir %attr(0750,-,-) /usr/lib/sysmaster/system
 %attr(0640,-,-) /usr/lib/sysmaster/system/*
+%dir %attr(0755,-,-) /usr/lib/sysmaster/user
+%attr(0644,-,-) /usr/lib/sysmaster/user/*
 %attr(0550,-,-) /usr/lib/sysmaster/init
 %attr(0550,-,-) /usr/lib/sysmaster/fstab
 %attr(0550,-,-) /usr/lib/sysmaster/sysmonitor
 %attr(0550,-,-) /usr/lib/sysmaster/random_seed
 %attr(0550,-,-) /usr/lib/sysmaster/rc-local-generator
 %attr(0550,-,-) /usr/lib/sysmaster/system-generators/getty-generator
+%attr(0550,-,-) /usr/lib/sysmaster/system-generators/fstab-generator
 %attr(0550,-,-) /usr/lib/sysmaster/hostname_setup
+%attr(0550,-,-) /usr/lib/sysmaster/logcollector
 %attr(0550,-,-) /usr/lib/sysmaster/sysmaster-run
+%attr(0550,-,-) /usr/lib/sysmaster/sysmaster-analyze
 %attr(0550,-,-) /usr/lib/sysmaster/sysmaster
 %dir %attr(0750,-,-) /etc/sysmaster
 %dir %attr(0750,-,-) /etc/sysmaster/system
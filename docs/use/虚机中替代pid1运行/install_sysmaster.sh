@@ -25,7 +25,10 @@ install -Dm0550 -t ${sysmaster_install_target} ${target_dir}/sysmonitor || exit
 install -Dm0550 -t ${sysmaster_install_target} ${target_dir}/random_seed || exit 1
 install -Dm0550 -t ${sysmaster_install_target} ${target_dir}/rc-local-generator || exit 1
 install -Dm0550 -t ${sysmaster_install_target} ${target_dir}/hostname_setup || exit 1
+install -Dm0550 -t ${sysmaster_install_target} ${target_dir}/logcollector || exit 1
+install -Dm0550 -t ${sysmaster_install_target} ${target_dir}/sysmaster-analyze || exit 1
 install -Dm0550 -t ${sysmaster_install_target}/system-generators ${target_dir}/getty-generator || exit 1
+install -Dm0550 ${target_dir}/fstab ${sysmaster_install_target}/system-generators/fstab-generator || exit 1
 
 # Install '.service', '.socket', and '.target' units.
 install -Dm0640 -t ${sysmaster_install_target}/system ${units_dir}/* || exit 1
@@ -0,0 +1,80 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The units ordered by the time they took to start.
+
+use crate::time::format_timespan;
+use cmdproto::proto::UnitTimes;
+
+/// the time the unit started, activating or active directly
+pub(crate) fn started(unit: &UnitTimes) -> u64 {
+    if unit.inactive_exit > 0 {
+        unit.inactive_exit
+    } else {
+        unit.active_enter
+    }
+}
+
+/// the time from leaving inactive to entering active, None if the unit never started
+pub(crate) fn activating_time(unit: &UnitTimes) -> Option<u64> {
+    if unit.inactive_exit == 0 || unit.active_enter < unit.inactive_exit {
+        return None;
+    }
+    Some(unit.active_enter - unit.inactive_exit)
+}
+
+/// one unit each line, the slowest first
+pub(crate) fn format_blame(units: &[UnitTimes]) -> String {
+    let mut times: Vec<(u64, &str)> = units
+        .iter()
+        .filter_map(|u| activating_time(u).map(|t| (t, u.name.as_str())))
+        .filter(|(t, _)| *t > 0)
+        .collect();
+    times.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    let times: Vec<(String, &str)> = times
+        .into_iter()
+        .map(|(t, name)| (format_timespan(t), name))
+        .collect();
+    let width = times.iter().map(|(t, _)| t.len()).max().unwrap_or(0);
+    times
+        .iter()
+        .map(|(t, name)| format!("{:>width$} {}", t, name, width = width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(name: &str, inactive_exit: u64, active_enter: u64) -> UnitTimes {
+        UnitTimes {
+            name: name.to_string(),
+            inactive_exit,
+            active_enter,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_blame() {
+        let units = vec![
+            unit("a.service", 1_000_000, 1_050_000),
+            unit("b.service", 1_000_000, 2_500_000),
+            unit("c.target", 2_000_000, 2_000_000),
+            unit("d.service", 0, 0),
+            unit("e.service", 3_000_000, 0),
+        ];
+        assert_eq!(format_blame(&units), "1.5s b.service\n50ms a.service");
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The time-critical chain of units, following the After= dependencies.
+
+use crate::blame::{activating_time, started};
+use crate::time::format_timespan;
+use cmdproto::proto::{BootTimes, UnitTimes};
+use std::collections::{HashMap, HashSet};
+
+const CHAIN_HEADER: &str =
+    "The time when unit became active or started is printed after the \"@\" character.\n\
+The time the unit took to start is printed after the \"+\" character.\n";
+
+/// the After= dependency which became active the last before the unit started, the unit
+/// waited for it the longest
+fn latest_after<'a>(
+    units: &HashMap<&str, &'a UnitTimes>,
+    unit: &UnitTimes,
+) -> Option<&'a UnitTimes> {
+    let started = started(unit);
+    unit.after
+        .iter()
+        .filter_map(|name| units.get(name.as_str()))
+        .filter(|dep| dep.active_enter > 0 && dep.active_enter <= started)
+        .max_by(|a, b| {
+            a.active_enter
+                .cmp(&b.active_enter)
+                .then(b.name.cmp(&a.name))
+        })
+        .copied()
+}
+
+/// the chain from the unit back to the start of the boot, one unit each line
+pub(crate) fn format_critical_chain(boot: &BootTimes, name: &str) -> Result<String, String> {
+    let units: HashMap<&str, &UnitTimes> =
+        boot.units.iter().map(|u| (u.name.as_str(), u)).collect();
+    let mut unit = match units.get(name) {
+        Some(u) => *u,
+        None => return Err(format!("Unit {} not found.", name)),
+    };
+    if unit.active_enter == 0 {
+        return Err(format!("Unit {} has not become active yet.", name));
+    }
+
+    let mut lines = vec![CHAIN_HEADER.to_string()];
+    let mut visited = HashSet::new();
+    loop {
+        let mut line = if visited.is_empty() {
+            String::new()
+        } else {
+            "  ".repeat(visited.len() - 1) + "└─"
+        };
+        line += &format!(
+            "{} @{}",
+            unit.name,
+            format_timespan(unit.active_enter.saturating_sub(boot.userspace))
+        );
+        if let Some(t) = activating_time(unit).filter(|t| *t > 0) {
+            line += &format!(" +{}", format_timespan(t));
+        }
+        lines.push(line);
+
+        visited.insert(unit.name.as_str());
+        unit = match latest_after(&units, unit) {
+            Some(u) if !visited.contains(u.name.as_str()) => u,
+            _ => break,
+        };
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(name: &str, inactive_exit: u64, active_enter: u64, after: &[&str]) -> UnitTimes {
+        UnitTimes {
+            name: name.to_string(),
+            inactive_exit,
+            active_enter,
+            after: after.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_critical_chain() {
+        let boot = BootTimes {
+            userspace: 1_000_000,
+            finish: 4_000_000,
+            units: vec![
+                unit("basic.target", 2_000_000, 2_000_000, &["sysinit.target"]),
+                unit("sysinit.target", 1_500_000, 1_800_000, &[]),
+                unit("a.service", 2_100_000, 3_500_000, &["basic.target"]),
+                unit("b.service", 2_100_000, 2_200_000, &["basic.target"]),
+                unit("late.service", 3_900_000, 3_950_000, &[]),
+                unit(
+                    "multi-user.target",
+                    3_600_000,
+                    4_000_000,
+                    &["a.service", "b.service", "late.service"],
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let chain = format_critical_chain(&boot, "multi-user.target").unwrap();
+        let expected = [
+            CHAIN_HEADER,
+            "multi-user.target @3s +400ms",
+            "└─a.service @2.5s +1.4s",
+            "  └─basic.target @1s",
+            "    └─sysinit.target @800ms +300ms",
+        ];
+        assert_eq!(chain, expected.join("\n"));
+
+        assert!(format_critical_chain(&boot, "none.target").is_err());
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! sysmaster-analyze, analyze the boot performance with the timestamps recorded by sysmaster
+
+#![allow(deprecated)]
+use clap::Parser;
+use cmdproto::error::ERROR_CODE_MASK_PRINT_STDOUT;
+use cmdproto::proto::{mngr_comm, BootTimes, CommandRequest, ProstClientStream};
+use constants::{PRIVATE_SOCKET, SYSMASTER_BIN};
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::process::{exit, Command};
+
+mod blame;
+mod critical_chain;
+mod plot;
+mod time;
+
+/// parse program arguments
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(subcommand)]
+    subcmd: Option<SubCmd>,
+}
+
+#[derive(Parser, Debug)]
+enum SubCmd {
+    /// Print the time spent in the kernel and the userspace during the boot
+    #[clap(display_order = 1)]
+    Time {},
+
+    /// List the units ordered by the time they took to start
+    #[clap(display_order = 2)]
+    Blame {},
+
+    /// `[unit]` Print the time-critical chain of units, the default target if no unit is given
+    #[clap(display_order = 3)]
+    CriticalChain { unit: Option<String> },
+
+    /// Print an SVG graphic of the boot on the stdout
+    #[clap(display_order = 4)]
+    Plot {},
+
+    /// Verify unit files and their drop-ins offline, exit non-zero if any problem is found
+    #[clap(display_order = 5)]
+    Verify {
+        #[clap(required = true)]
+        files: Vec<String>,
+    },
+}
+
+/// get the boot timestamps from sysmaster
+fn get_boot_times() -> Result<BootTimes, (String, i32)> {
+    let stream = match UnixStream::connect(PRIVATE_SOCKET) {
+        Err(e) => {
+            return Err((
+                format!("Failed to connect to sysmaster: {}", e),
+                e.raw_os_error().unwrap_or(1),
+            ))
+        }
+        Ok(v) => v,
+    };
+
+    let mut client = ProstClientStream::new(stream);
+    let request = CommandRequest::new_mngrcomm(mngr_comm::Action::Boottimes);
+    let data = match client.execute(request) {
+        Err(e) => {
+            return Err((format!("Failed to execute the given command: {}", e), 1));
+        }
+        Ok(v) => v,
+    };
+
+    let error_code = (data.error_code & !ERROR_CODE_MASK_PRINT_STDOUT) as i32;
+    if error_code != 0 {
+        return Err((data.message, error_code));
+    }
+    Ok(data.boot_times.unwrap_or_default())
+}
+
+fn main() {
+    let args = Args::parse();
+
+    /* The unit files are verified by sysmaster offline, no running sysmaster is needed. */
+    if let Some(SubCmd::Verify { files }) = &args.subcmd {
+        let e = Command::new(SYSMASTER_BIN)
+            .arg("--verify")
+            .args(files)
+            .exec();
+        eprintln!("Failed to run {}: {}", SYSMASTER_BIN, e);
+        exit(e.raw_os_error().unwrap_or(1));
+    }
+
+    let boot_times = match get_boot_times() {
+        Err((message, error_code)) => {
+            eprintln!("{}", message);
+            exit(error_code);
+        }
+        Ok(v) => v,
+    };
+
+    let output = match args.subcmd.unwrap_or(SubCmd::Time {}) {
+        SubCmd::Time {} => time::format_time(&boot_times),
+        SubCmd::Blame {} => Ok(blame::format_blame(&boot_times.units)),
+        SubCmd::CriticalChain { unit } => {
+            let unit = unit.unwrap_or_else(|| boot_times.finish_target.clone());
+            critical_chain::format_critical_chain(&boot_times, &unit)
+        }
+        SubCmd::Plot {} => Ok(plot::plot_svg(&boot_times)),
+        SubCmd::Verify { .. } => unreachable!(),
+    };
+
+    match output {
+        Ok(v) => {
+            /* Don't care if we fail to write the output, e.g. to a closed pipe. */
+            let _ = writeln!(std::io::stdout(), "{}", v);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
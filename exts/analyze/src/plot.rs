@@ -0,0 +1,218 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The SVG chart of the boot, one bar each unit along the time axis.
+
+use crate::blame::{activating_time, started};
+use crate::time::{format_time, format_timespan};
+use cmdproto::proto::{BootTimes, UnitTimes};
+use std::fmt::Write;
+
+/// pixels per second
+const SCALE_X: f64 = 100.0;
+/// pixels per bar
+const SCALE_Y: f64 = 20.0;
+const MARGIN: f64 = 20.0;
+/// the space of the summary above the bars
+const HEADER: f64 = 60.0;
+
+const STYLE: &str = "  <style type=\"text/css\">
+    rect { stroke-width: 1; stroke-opacity: 0; }
+    rect.background { fill: rgb(255,255,255); }
+    rect.kernel { fill: rgb(150,150,150); fill-opacity: 0.7; }
+    rect.userspace { fill: rgb(150,150,200); fill-opacity: 0.7; }
+    rect.activating { fill: rgb(255,0,0); fill-opacity: 0.7; }
+    rect.active { fill: rgb(200,150,150); fill-opacity: 0.7; }
+    line.sec { stroke: rgb(200,200,200); stroke-width: 1; }
+    text { font-family: Verdana, Helvetica; font-size: 10px; }
+    text.title { font-size: 14px; }
+  </style>
+";
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn x(usec: u64) -> f64 {
+    MARGIN + usec as f64 / 1_000_000.0 * SCALE_X
+}
+
+fn y(row: usize) -> f64 {
+    MARGIN + HEADER + row as f64 * SCALE_Y
+}
+
+/// a bar from start to end in the row with the class of its style
+fn bar(svg: &mut String, class: &str, row: usize, start: u64, end: u64) {
+    let _ = writeln!(
+        svg,
+        "  <rect class=\"{}\" x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\" />",
+        class,
+        x(start),
+        y(row),
+        x(end.max(start)) - x(start),
+        SCALE_Y - 1.0
+    );
+}
+
+/// the label on the right of the start of the bar
+fn label(svg: &mut String, row: usize, start: u64, text: &str) {
+    let _ = writeln!(
+        svg,
+        "  <text x=\"{:.3}\" y=\"{:.3}\">{}</text>",
+        x(start) + 5.0,
+        y(row) + SCALE_Y - 6.0,
+        escape(text)
+    );
+}
+
+/// the whole SVG document
+pub(crate) fn plot_svg(boot: &BootTimes) -> String {
+    let mut units: Vec<&UnitTimes> = boot
+        .units
+        .iter()
+        .filter(|u| u.inactive_exit > 0 || u.active_enter > 0)
+        .collect();
+    units.sort_by(|a, b| {
+        started(a)
+            .cmp(&started(b))
+            .then(a.active_enter.cmp(&b.active_enter))
+            .then(a.name.cmp(&b.name))
+    });
+
+    let end = boot
+        .units
+        .iter()
+        .flat_map(|u| [u.inactive_exit, u.active_enter, u.active_exit])
+        .chain([boot.userspace, boot.finish])
+        .max()
+        .unwrap_or(0);
+    /* round the time axis up to the next second */
+    let seconds = end / 1_000_000 + u64::from(end % 1_000_000 != 0);
+    let width = x(seconds * 1_000_000) + MARGIN + 300.0;
+    let rows = units.len() + 2;
+    let height = y(rows) + MARGIN;
+
+    let mut svg = String::new();
+    svg += "<?xml version=\"1.0\" standalone=\"no\"?>\n";
+    let _ = writeln!(
+        svg,
+        "<svg width=\"{:.0}\" height=\"{:.0}\" version=\"1.1\" xmlns=\"http://www.w3.org/2000/svg\">",
+        width, height
+    );
+    svg += STYLE;
+    let _ = writeln!(
+        svg,
+        "  <rect class=\"background\" width=\"100%\" height=\"100%\" />"
+    );
+    let summary = format_time(boot).unwrap_or_else(|e| e);
+    for (i, line) in summary.lines().enumerate() {
+        let _ = writeln!(
+            svg,
+            "  <text class=\"title\" x=\"{:.3}\" y=\"{:.3}\">{}</text>",
+            MARGIN,
+            MARGIN + 14.0 + i as f64 * 18.0,
+            escape(line)
+        );
+    }
+
+    for sec in 0..=seconds {
+        let _ = writeln!(
+            svg,
+            "  <line class=\"sec\" x1=\"{0:.3}\" y1=\"{1:.3}\" x2=\"{0:.3}\" y2=\"{2:.3}\" />",
+            x(sec * 1_000_000),
+            y(0),
+            y(rows)
+        );
+        let _ = writeln!(
+            svg,
+            "  <text x=\"{:.3}\" y=\"{:.3}\">{}s</text>",
+            x(sec * 1_000_000),
+            y(0) - 4.0,
+            sec
+        );
+    }
+
+    bar(&mut svg, "kernel", 0, 0, boot.userspace);
+    label(&mut svg, 0, 0, "kernel");
+    let userspace_end = if boot.finish > 0 { boot.finish } else { end };
+    bar(&mut svg, "userspace", 1, boot.userspace, userspace_end);
+    label(&mut svg, 1, boot.userspace, "sysmaster");
+
+    for (i, unit) in units.iter().enumerate() {
+        let row = i + 2;
+        let start = started(unit);
+        if unit.active_enter > start {
+            bar(&mut svg, "activating", row, start, unit.active_enter);
+        }
+        if unit.active_enter > 0 {
+            let active_end = if unit.active_exit > unit.active_enter {
+                unit.active_exit
+            } else {
+                end
+            };
+            bar(&mut svg, "active", row, unit.active_enter, active_end);
+        }
+        let text = match activating_time(unit).filter(|t| *t > 0) {
+            Some(t) => format!("{} ({})", unit.name, format_timespan(t)),
+            None => unit.name.clone(),
+        };
+        label(&mut svg, row, start, &text);
+    }
+
+    svg += "</svg>";
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plot_svg() {
+        let boot = BootTimes {
+            userspace: 1_000_000,
+            finish: 2_500_000,
+            units: vec![
+                UnitTimes {
+                    name: "b<&>.service".to_string(),
+                    inactive_exit: 1_200_000,
+                    active_enter: 1_700_000,
+                    ..Default::default()
+                },
+                UnitTimes {
+                    name: "a.service".to_string(),
+                    inactive_exit: 1_100_000,
+                    active_enter: 1_100_000,
+                    ..Default::default()
+                },
+                UnitTimes {
+                    name: "never.service".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let svg = plot_svg(&boot);
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(">b&lt;&amp;&gt;.service (500ms)</text>"));
+        assert!(!svg.contains("never.service"));
+        assert_eq!(svg.matches("class=\"activating\"").count(), 1);
+        assert_eq!(svg.matches("class=\"active\"").count(), 2);
+        /* the units are sorted by the time they started */
+        assert!(svg.find("a.service").unwrap() < svg.find("b&lt;").unwrap());
+    }
+}
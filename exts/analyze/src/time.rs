@@ -0,0 +1,96 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The time spent in the kernel and the userspace.
+
+use cmdproto::proto::BootTimes;
+
+const USEC_PER_MSEC: u64 = 1000;
+const USEC_PER_SEC: u64 = 1000 * USEC_PER_MSEC;
+const USEC_PER_MINUTE: u64 = 60 * USEC_PER_SEC;
+
+/// format the time span in microseconds, such as "1min 2.345s", "2.3s" and "56ms"
+pub(crate) fn format_timespan(usec: u64) -> String {
+    if usec == 0 {
+        return "0".to_string();
+    }
+    if usec < USEC_PER_MSEC {
+        return format!("{}us", usec);
+    }
+    if usec < USEC_PER_SEC {
+        return format!("{}ms", usec / USEC_PER_MSEC);
+    }
+
+    let minutes = usec / USEC_PER_MINUTE;
+    let rest = usec % USEC_PER_MINUTE;
+    let seconds = format!(
+        "{}.{:03}",
+        rest / USEC_PER_SEC,
+        rest % USEC_PER_SEC / USEC_PER_MSEC
+    );
+    let seconds = seconds.trim_end_matches('0').trim_end_matches('.');
+    match (minutes, seconds) {
+        (0, _) => format!("{}s", seconds),
+        (_, "0") => format!("{}min", minutes),
+        _ => format!("{}min {}s", minutes, seconds),
+    }
+}
+
+/// the summary of the boot, the boot must have finished
+pub(crate) fn format_time(boot: &BootTimes) -> Result<String, String> {
+    if boot.finish == 0 {
+        return Err("Bootup is not yet finished, please try again later.".to_string());
+    }
+
+    let userspace = boot.finish.saturating_sub(boot.userspace);
+    Ok(format!(
+        "Startup finished in {} (kernel) + {} (userspace) = {}\n{} reached after {} in userspace",
+        format_timespan(boot.userspace),
+        format_timespan(userspace),
+        format_timespan(boot.finish),
+        boot.finish_target,
+        format_timespan(userspace)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timespan() {
+        assert_eq!(format_timespan(0), "0");
+        assert_eq!(format_timespan(12), "12us");
+        assert_eq!(format_timespan(56_789), "56ms");
+        assert_eq!(format_timespan(2_300_000), "2.3s");
+        assert_eq!(format_timespan(2_345_678), "2.345s");
+        assert_eq!(format_timespan(120_000_000), "2min");
+        assert_eq!(format_timespan(62_345_000), "1min 2.345s");
+    }
+
+    #[test]
+    fn test_format_time() {
+        let mut boot = BootTimes {
+            userspace: 1_500_000,
+            finish_target: "multi-user.target".to_string(),
+            ..Default::default()
+        };
+        assert!(format_time(&boot).is_err());
+
+        boot.finish = 4_200_000;
+        assert_eq!(
+            format_time(&boot).unwrap(),
+            "Startup finished in 1.5s (kernel) + 2.7s (userspace) = 4.2s\n\
+             multi-user.target reached after 2.7s in userspace"
+        );
+    }
+}
@@ -10,52 +10,44 @@
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
 
-//! fstab is used to parse /etc/fstab in the early stage of boot.
+//! fstab turns /etc/fstab into units in the early stage of boot.
 //! # Principle
-//! 1. After fstab is started, it will directly parse /etc/fstab to obtain the device, mount point, and mount attributes that need to be mounted.
-//! 2. According to the configuration order of /etc/fstab, try to mount in turn.
-//! 3. If the corresponding device directory can be found, it will be mounted directly.
-//! 4. If the relevant directory cannot be found, create an inotify detection and mount it after the device is ready.
-//! # limit
-//! 1. The current device only supports configuration as full directory or UUID.
-
-use basic::mount::{is_mount_point, is_swap};
-use basic::{FSTAB_PATH, MOUNT_BIN, SWAP_BIN};
-use inotify::{EventMask, Inotify, WatchMask};
-use std::collections::HashSet;
+//! 1. As a generator, i.e. with the output directory as its argument, fstab writes a mount unit for each file system entry and a swap unit for each swap entry.
+//! 2. The mount units of local file systems are pulled in by local-fs.target, the ones of network file systems (the type is a network file system or the _netdev option is set) by remote-fs.target.
+//! 3. An entry on a device node depends on the device unit with Requires= and After=, so it is mounted once the device shows up.
+//! 4. `noauto` entries are not pulled in by any target, `nofail` entries are pulled in with Wants= and don't order the target after them.
+//! 5. The `x-systemd.requires=`, `x-systemd.before=`, `x-systemd.after=`, `x-systemd.wanted-by=`, `x-systemd.required-by=`, `x-systemd.device-timeout=`, `x-systemd.automount` and `x-systemd.idle-timeout=` options are supported and not passed to mount.
+//! 6. The root file system is already mounted, it is not generated. Without arguments, fstab remounts it read-write.
+
+use basic::mount::mount_point_to_unit_name;
+use basic::time::{parse_sec, USEC_PER_SEC};
+use basic::unit_name::unit_name_from_path;
+use basic::{FSTAB_PATH, LOCAL_FS_TARGET, MOUNT_BIN, REMOTE_FS_TARGET, SWAP_TARGET};
+use std::fs;
+use std::io;
 use std::path::Path;
-use std::process::Command;
+use std::process::{self, Command};
 
 pub mod fstab_item;
 use fstab_item::FSTabItem;
 
-fn mount_one(fstab_item: &FSTabItem) -> i32 {
+/// the file system types that need the network
+const NETWORK_FS_TYPES: [&str; 9] = [
+    "afs", "ceph", "cifs", "smb3", "smbfs", "sshfs", "ncpfs", "nfs", "nfs4",
+];
+
+const NETWORK_ONLINE_TARGET: &str = "network-online.target";
+
+fn remount_root(fstab_items: &[FSTabItem]) -> i32 {
     // -.mount is different. It has already been mounted before
     // fstab.service is started. We mount it as rw.
-    let mount_status = if fstab_item.mount_point == "/" {
-        Command::new(MOUNT_BIN)
-            .args(["/", "--options", "remount", "-w"])
-            .status()
-    } else {
-        /* Already mounted */
-        if is_mount_point(Path::new(&fstab_item.mount_point))
-            && !fstab_item.options.contains("remount")
-        {
-            log::debug!("{} is already mounted", fstab_item.mount_point);
-            return 1;
-        }
-        Command::new(MOUNT_BIN)
-            .args([
-                &fstab_item.device_spec,
-                &fstab_item.mount_point,
-                "--options",
-                &fstab_item.options,
-                "--types",
-                &fstab_item.fs_type,
-            ])
-            .status()
-    };
-    let status = match mount_status {
+    if !fstab_items.iter().any(|i| i.mount_point == "/") {
+        return 0;
+    }
+    let status = match Command::new(MOUNT_BIN)
+        .args(["/", "--options", "remount", "-w"])
+        .status()
+    {
         Ok(status) => status,
         Err(_) => {
             log::error!("Failed to execute {}", MOUNT_BIN);
@@ -65,249 +57,450 @@ fn mount_one(fstab_item: &FSTabItem) -> i32 {
     let r = match status.code() {
         Some(r) => r,
         None => {
-            log::error!("Unexpected error when mount {}", &fstab_item.device_spec);
+            log::error!("Unexpected error when remount /");
             return -1;
         }
     };
     if r != 0 {
-        log::error!(
-            "Failed to mount {}, exitcode: {}",
-            &fstab_item.device_spec,
-            r
-        );
+        log::error!("Failed to remount /, exitcode: {}", r);
         return -1;
     }
-    log::info!("Mounted {}", &fstab_item.device_spec);
+    log::info!("Remounted / read-write");
     0
 }
 
-fn swap_on(fstab_item: &FSTabItem) -> i32 {
-    /* Already swapped on */
-    if is_swap(Path::new(&fstab_item.device_spec)) {
-        log::debug!("{} is already swapped on.", fstab_item.device_spec);
-        return 1;
+fn has_option(options: &str, name: &str) -> bool {
+    options.split(',').any(|o| o.trim() == name)
+}
+
+/// The values of all the "name=value" options, e.g. x-systemd.requires= can be given many times.
+fn option_values(options: &str, name: &str) -> Vec<String> {
+    let prefix = format!("{}=", name);
+    options
+        .split(',')
+        .filter_map(|o| o.trim().strip_prefix(&prefix))
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .collect()
+}
+
+/// The options passed to mount or swapon, the x-systemd.* ones are only for the generator.
+fn filter_generator_options(options: &str) -> String {
+    options
+        .split(',')
+        .map(|o| o.trim())
+        .filter(|o| !o.is_empty() && !o.starts_with("x-systemd."))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn is_network_fs(fstab_item: &FSTabItem) -> bool {
+    NETWORK_FS_TYPES.contains(&fstab_item.fs_type.as_str())
+        || fstab_item.fs_type.starts_with("fuse.sshfs")
+        || has_option(&fstab_item.options, "_netdev")
+}
+
+fn device_unit_name(what: &str) -> Option<String> {
+    if !what.starts_with("/dev/") {
+        return None;
     }
+    Some(unit_name_from_path(what, ".device"))
+}
 
-    let status = match Command::new(SWAP_BIN)
-        .args([&fstab_item.device_spec])
-        .status()
-    {
-        Ok(status) => status,
-        Err(_) => {
-            log::error!("Failed to execute {}", SWAP_BIN);
-            return -1;
+/// x-systemd.requires= and friends accept unit names and paths, a path refers to the device or
+/// the mount point.
+fn dependency_unit_name(dependency: &str) -> String {
+    if !dependency.starts_with('/') {
+        return dependency.to_string();
+    }
+    device_unit_name(dependency).unwrap_or_else(|| mount_point_to_unit_name(dependency))
+}
+
+/// The [Unit] dependencies of an entry, shared by the mount and swap units.
+fn unit_dependencies(fstab_item: &FSTabItem) -> String {
+    let mut content = String::new();
+    if let Some(device) = device_unit_name(&fstab_item.device_spec) {
+        content += &format!("Requires={}\nAfter={}\n", device, device);
+    }
+    for dependency in option_values(&fstab_item.options, "x-systemd.requires") {
+        let unit = dependency_unit_name(&dependency);
+        content += &format!("Requires={}\nAfter={}\n", unit, unit);
+    }
+    for dependency in option_values(&fstab_item.options, "x-systemd.after") {
+        content += &format!("After={}\n", dependency_unit_name(&dependency));
+    }
+    for dependency in option_values(&fstab_item.options, "x-systemd.before") {
+        content += &format!("Before={}\n", dependency_unit_name(&dependency));
+    }
+    content
+}
+
+/// Split the "pri=" option out of the swap options, the rest is passed to swapon as is.
+fn swap_priority_options(options: &str) -> (Option<i32>, String) {
+    let mut priority = None;
+    let mut rest = Vec::new();
+    for option in filter_generator_options(options).split(',') {
+        if option.is_empty() || option == "defaults" {
+            continue;
         }
-    };
-    let r = match status.code() {
-        Some(r) => r,
-        None => {
-            log::error!("Unexpected error when swapon {}", &fstab_item.device_spec);
-            return -1;
+        match option.strip_prefix("pri=") {
+            Some(v) => match v.parse::<i32>() {
+                Ok(p) => priority = Some(p),
+                Err(_) => log::warn!("Invalid swap priority {}, ignoring.", v),
+            },
+            None => rest.push(option.to_string()),
         }
-    };
-    if r != 0 {
-        log::error!(
-            "Failed to swapon {}, exitcode: {}",
-            &fstab_item.device_spec,
-            r
+    }
+    (priority, rest.join(","))
+}
+
+fn swap_unit_content(fstab_item: &FSTabItem) -> String {
+    let (priority, options) = swap_priority_options(&fstab_item.options);
+    let mut content = format!(
+        "[Unit]\nDescription=Swap {}\n{}\n[Swap]\nWhat={}\n",
+        fstab_item.device_spec,
+        unit_dependencies(fstab_item),
+        fstab_item.device_spec
+    );
+    if let Some(p) = priority {
+        content += &format!("Priority={}\n", p);
+    }
+    if !options.is_empty() {
+        content += &format!("Options={}\n", options);
+    }
+    content
+}
+
+/// The mount units of the other entries the mount point is nested in, e.g. /var for /var/log.
+fn parent_mount_units(fstab_item: &FSTabItem, fstab_items: &[FSTabItem]) -> Vec<String> {
+    Path::new(&fstab_item.mount_point)
+        .ancestors()
+        .skip(1)
+        .filter(|dir| *dir != Path::new("/"))
+        .filter(|dir| {
+            fstab_items
+                .iter()
+                .any(|i| i.fs_type != "swap" && Path::new(&i.mount_point) == *dir)
+        })
+        .map(|dir| mount_point_to_unit_name(&dir.to_string_lossy()))
+        .collect()
+}
+
+fn mount_unit_content(fstab_item: &FSTabItem, fstab_items: &[FSTabItem]) -> String {
+    let mut content = format!("[Unit]\nDescription=Mount {}\n", fstab_item.mount_point);
+    let target = if is_network_fs(fstab_item) {
+        content += &format!(
+            "Wants={}\nAfter={}\n",
+            NETWORK_ONLINE_TARGET, NETWORK_ONLINE_TARGET
         );
-        return -1;
+        REMOTE_FS_TARGET
+    } else {
+        LOCAL_FS_TARGET
+    };
+    /* a failing nofail entry doesn't hold the target back */
+    if !has_option(&fstab_item.options, "nofail") {
+        content += &format!("Before={}\n", target);
     }
-    log::info!("Swapped on {}", &fstab_item.device_spec);
-    0
+    content += &unit_dependencies(fstab_item);
+    for parent in parent_mount_units(fstab_item, fstab_items) {
+        content += &format!("Requires={}\nAfter={}\n", parent, parent);
+    }
+
+    content += &format!(
+        "\n[Mount]\nWhat={}\nWhere={}\n",
+        fstab_item.device_spec, fstab_item.mount_point
+    );
+    if !fstab_item.fs_type.is_empty() && fstab_item.fs_type != "auto" {
+        content += &format!("Type={}\n", fstab_item.fs_type);
+    }
+    let options = filter_generator_options(&fstab_item.options);
+    if !options.is_empty() {
+        content += &format!("Options={}\n", options);
+    }
+    content
 }
 
-fn consume_one(fstab_item: &mut FSTabItem) {
-    let r = match fstab_item.fs_type.as_str() {
-        "swap" => swap_on(fstab_item),
-        _ => mount_one(fstab_item),
+fn automount_unit_content(fstab_item: &FSTabItem) -> String {
+    let mut content = format!("[Unit]\nDescription=Automount {}\n", fstab_item.mount_point);
+    if !has_option(&fstab_item.options, "nofail") {
+        let target = if is_network_fs(fstab_item) {
+            REMOTE_FS_TARGET
+        } else {
+            LOCAL_FS_TARGET
+        };
+        content += &format!("Before={}\n", target);
+    }
+    content += &format!("\n[Automount]\nWhere={}\n", fstab_item.mount_point);
+    if let Some(timeout) = option_values(&fstab_item.options, "x-systemd.idle-timeout").last() {
+        content += &format!("TimeoutIdleSec={}\n", timeout);
+    }
+    content
+}
+
+/// Link the unit into the "<target>.wants" or "<target>.requires" directory.
+fn add_dependency_link(dest: &Path, target: &str, suffix: &str, unit_name: &str) -> io::Result<()> {
+    let dir = dest.join(format!("{}.{}", target, suffix));
+    fs::create_dir_all(&dir)?;
+    let link = dir.join(unit_name);
+    if link.exists() {
+        return Ok(());
+    }
+    std::os::unix::fs::symlink(Path::new("..").join(unit_name), link)
+}
+
+/// Pull the unit in by its target and by x-systemd.wanted-by=/x-systemd.required-by=.
+fn add_install_links(
+    dest: &Path,
+    fstab_item: &FSTabItem,
+    target: &str,
+    unit_name: &str,
+) -> io::Result<()> {
+    for unit in option_values(&fstab_item.options, "x-systemd.wanted-by") {
+        add_dependency_link(dest, &unit, "wants", unit_name)?;
+    }
+    for unit in option_values(&fstab_item.options, "x-systemd.required-by") {
+        add_dependency_link(dest, &unit, "requires", unit_name)?;
+    }
+
+    if has_option(&fstab_item.options, "noauto") {
+        return Ok(());
+    }
+    let suffix = if has_option(&fstab_item.options, "nofail") {
+        "wants"
+    } else {
+        "requires"
+    };
+    add_dependency_link(dest, target, suffix, unit_name)
+}
+
+/// x-systemd.device-timeout= limits how long the device is waited for, it goes to a drop-in of
+/// the device unit.
+fn generate_device_timeout(dest: &Path, fstab_item: &FSTabItem) -> io::Result<()> {
+    let timeout = match option_values(&fstab_item.options, "x-systemd.device-timeout").pop() {
+        None => return Ok(()),
+        Some(v) => v,
+    };
+    let device = match device_unit_name(&fstab_item.device_spec) {
+        None => return Ok(()),
+        Some(v) => v,
+    };
+    let usec = match parse_sec(&timeout) {
+        Ok(v) => v,
+        Err(_) => {
+            log::warn!("Invalid device timeout {}, ignoring.", timeout);
+            return Ok(());
+        }
+    };
+    /* 0 and infinity both mean waiting forever */
+    let sec = if usec == u64::MAX {
+        0
+    } else {
+        usec / USEC_PER_SEC + u64::from(usec % USEC_PER_SEC != 0)
     };
-    // set state to 1 if succeeded, -1 if failed.
-    fstab_item.state = if r == 0 { 1 } else { -1 };
+    let dir = dest.join(format!("{}.d", device));
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join("50-device-timeout.conf"),
+        format!("[Unit]\nJobTimeoutSec={}\n", sec),
+    )
+}
+
+fn generate_swap_unit(dest: &Path, fstab_item: &FSTabItem) -> io::Result<()> {
+    let unit_name = unit_name_from_path(&fstab_item.device_spec, ".swap");
+    fs::write(dest.join(&unit_name), swap_unit_content(fstab_item))?;
+    generate_device_timeout(dest, fstab_item)?;
+    add_install_links(dest, fstab_item, SWAP_TARGET, &unit_name)
 }
 
-fn watch_devices(fstab_items: &[FSTabItem]) -> (Inotify, HashSet<String>) {
-    let mut watch_set: HashSet<String> = HashSet::new();
-    let mut inotify = Inotify::init().expect("Failed to init inotify.");
+fn generate_mount_unit(
+    dest: &Path,
+    fstab_item: &FSTabItem,
+    fstab_items: &[FSTabItem],
+) -> io::Result<()> {
+    let unit_name = mount_point_to_unit_name(&fstab_item.mount_point);
+    fs::write(
+        dest.join(&unit_name),
+        mount_unit_content(fstab_item, fstab_items),
+    )?;
+    generate_device_timeout(dest, fstab_item)?;
+
+    let target = if is_network_fs(fstab_item) {
+        REMOTE_FS_TARGET
+    } else {
+        LOCAL_FS_TARGET
+    };
+    /* the automount unit is pulled in instead, it triggers the mount unit on access */
+    if has_option(&fstab_item.options, "x-systemd.automount") {
+        let automount_name = format!("{}.automount", unit_name.trim_end_matches(".mount"));
+        fs::write(
+            dest.join(&automount_name),
+            automount_unit_content(fstab_item),
+        )?;
+        return add_install_links(dest, fstab_item, target, &automount_name);
+    }
+    add_install_links(dest, fstab_item, target, &unit_name)
+}
+
+fn generate(dest: &Path, fstab_items: &[FSTabItem]) -> i32 {
+    let mut ret = 0;
     for fstab_item in fstab_items {
-        let file_path = Path::new(&fstab_item.device_spec);
-        let dir_path = file_path.parent().unwrap();
-        watch_set.insert(String::from(
-            file_path.file_name().unwrap().to_str().unwrap(),
-        ));
-        inotify
-            .add_watch(dir_path, WatchMask::CREATE | WatchMask::MOVED_TO)
-            .expect("Failed to add watch.");
+        let r = if fstab_item.fs_type == "swap" {
+            generate_swap_unit(dest, fstab_item)
+        } else if fstab_item.mount_point == "/" {
+            continue;
+        } else if !fstab_item.mount_point.starts_with('/') {
+            log::warn!(
+                "Mount point {} is not an absolute path, ignoring.",
+                fstab_item.mount_point
+            );
+            continue;
+        } else {
+            generate_mount_unit(dest, fstab_item, fstab_items)
+        };
+        if let Err(e) = r {
+            log::error!(
+                "Failed to generate unit for {}: {}",
+                fstab_item.device_spec,
+                e
+            );
+            ret = 1;
+        }
     }
-    (inotify, watch_set)
+    ret
 }
 
 fn main() {
     log::init_log_to_console("fstab", log::Level::Info);
-    let mut fstab_items: Vec<FSTabItem> = fstab_item::parse(FSTAB_PATH);
-
-    // inotify: monitor, watch_set: what we care.
-    let (mut inotify, _watch_set) = watch_devices(&fstab_items);
-
-    let mut complete_num = 0;
-    loop {
-        // Mount/swap what we can.
-        for fstab_item in &mut fstab_items {
-            if fstab_item.state != 0 || !Path::new(&fstab_item.device_spec).exists() {
-                continue;
-            }
-            consume_one(fstab_item);
-            complete_num += 1;
-        }
-        if complete_num >= fstab_items.len() {
-            break;
-        }
+    let fstab_items: Vec<FSTabItem> = fstab_item::parse(FSTAB_PATH);
 
-        // use inotify to wait device ready.
-        let mut buffer = [0u8; 4096];
-        let mut watch_updated = false;
-        while !watch_updated {
-            let events = inotify
-                .read_events_blocking(&mut buffer)
-                .expect("Failed to read events.");
-            for event in events {
-                if event.mask == EventMask::CREATE || event.mask == EventMask::MOVED_TO {
-                    if let Some(name) = event.name {
-                        log::debug!("File created or moved: {:?}", name);
-                    }
-                    watch_updated = true;
-                }
-            }
-        }
+    // Invoked as a generator: one or three output directories are given.
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 2 || args.len() == 4 {
+        process::exit(generate(Path::new(&args[1]), &fstab_items));
+    }
+
+    // The other entries are mounted by the generated units, only the root is left.
+    if remount_root(&fstab_items) != 0 {
+        process::exit(1);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use inotify::EventMask;
-    use nix::unistd::getuid;
     use std::fs;
-    use std::path::Path;
-    use std::process::Command;
-    use std::sync::mpsc;
-    use std::thread;
-
-    use crate::{fstab_item, mount_one, watch_devices};
-
-    fn create_fstab_items() -> Vec<fstab_item::FSTabItem> {
-        // Create fstab_items and directories.
-        let src_path = Path::new("/tmp/fstab_test/src");
-        let dst_path = Path::new("/tmp/fstab_test/dst");
-        let fstab_str = vec![
-            src_path.to_str().unwrap(),
-            dst_path.to_str().unwrap(),
-            "ext4",
-            "bind",
-            "0",
-            "0",
-        ];
-        let fstab_items = vec![fstab_item::FSTabItem::new(fstab_str)];
-        assert_eq!(fstab_items.len(), 1);
-        fstab_items
+
+    use crate::{
+        filter_generator_options, fstab_item, generate, mount_unit_content, option_values,
+        swap_priority_options,
+    };
+
+    #[test]
+    fn test_option_values() {
+        let options = "rw,x-systemd.requires=a.service,nofail,x-systemd.requires=/dev/sdc";
+        assert_eq!(
+            option_values(options, "x-systemd.requires"),
+            vec!["a.service".to_string(), "/dev/sdc".to_string()]
+        );
+        assert!(option_values(options, "x-systemd.after").is_empty());
+        assert_eq!(filter_generator_options(options), "rw,nofail");
     }
 
-    fn clean() {
-        // Clean
-        if !Path::exists(Path::new("/tmp/fstab_test")) {
-            return;
-        }
-        if let Err(why) = fs::remove_dir_all("/tmp/fstab_test") {
-            panic!("Failed to remove {:?}: {:?}.", "/tmp/fstab_test", why);
-        }
+    #[test]
+    fn test_swap_priority_options() {
+        assert_eq!(swap_priority_options("defaults"), (None, String::new()));
+        assert_eq!(
+            swap_priority_options("pri=10,discard"),
+            (Some(10), "discard".to_string())
+        );
+        assert_eq!(
+            swap_priority_options("pri=x,nofail,x-systemd.device-timeout=10"),
+            (None, "nofail".to_string())
+        );
     }
 
     #[test]
-    fn test_mount_one() {
-        let fstab_items = create_fstab_items();
-        assert_eq!(mount_one(&fstab_items[0]), -1);
-        if !getuid().is_root() {
-            println!("Mount must be run under superuser, skipping.");
-            return;
-        }
-        let src_path = Path::new(&fstab_items[0].device_spec);
-        let dst_path = Path::new(&fstab_items[0].mount_point);
-        if !(Path::exists(src_path) && src_path.is_dir()) {
-            if let Err(why) = fs::create_dir_all(src_path) {
-                clean();
-                panic!("Failed to create {:?}: {:?}", src_path, why);
-            }
-        }
-        if !(Path::exists(dst_path) && dst_path.is_dir()) {
-            if let Err(why) = fs::create_dir_all(dst_path) {
-                clean();
-                panic!("Failed to create {:?}: {:?}", dst_path, why);
-            }
-        }
-        assert_eq!(mount_one(&fstab_items[0]), 0);
-        // cleanup: umount and delete
-        let status = Command::new("/usr/bin/umount")
-            .args([dst_path.as_os_str().to_str().unwrap()])
-            .status();
-        let status = match status {
-            Ok(status) => status,
-            Err(why) => {
-                panic!("Failed to execute /usr/bin/umount: {}", why);
-            }
-        };
-        let r = match status.code() {
-            Some(r) => r,
-            None => {
-                panic!(
-                    "Unexpected error when umount {}",
-                    dst_path.as_os_str().to_str().unwrap()
-                );
-            }
-        };
-        assert_eq!(r, 0);
-        clean();
+    fn test_mount_unit_content() {
+        let fstab_items = vec![
+            fstab_item::FSTabItem::new(vec!["/dev/sdb1", "/var", "ext4", "defaults", "0", "0"]),
+            fstab_item::FSTabItem::new(vec![
+                "/dev/sdb2",
+                "/var/log",
+                "xfs",
+                "nofail,x-systemd.after=a.service",
+                "0",
+                "0",
+            ]),
+            fstab_item::FSTabItem::new(vec!["srv:/data", "/data", "nfs", "defaults", "0", "0"]),
+        ];
+
+        let content = mount_unit_content(&fstab_items[0], &fstab_items);
+        assert!(content.contains("Before=local-fs.target\n"));
+        assert!(content.contains("Requires=dev-sdb1.device\nAfter=dev-sdb1.device\n"));
+        assert!(content.contains("What=/dev/sdb1\nWhere=/var\nType=ext4\n"));
+
+        let content = mount_unit_content(&fstab_items[1], &fstab_items);
+        assert!(!content.contains("Before=local-fs.target"));
+        assert!(content.contains("After=a.service\n"));
+        assert!(content.contains("Requires=var.mount\nAfter=var.mount\n"));
+        assert!(content.contains("Options=nofail\n"));
+
+        let content = mount_unit_content(&fstab_items[2], &fstab_items);
+        assert!(content.contains("Before=remote-fs.target\n"));
+        assert!(content.contains("After=network-online.target\n"));
+        assert!(!content.contains(".device"));
     }
 
     #[test]
-    fn test_watch_devices() {
-        let fstab_items = create_fstab_items();
-        let src_path = String::from(&fstab_items[0].device_spec);
-        let dst_path = String::from(&fstab_items[0].mount_point);
-        if let Err(why) = fs::create_dir_all(&dst_path) {
-            clean();
-            panic!("Failed to create dir ({:?}): {:?}.", dst_path, why);
-        }
+    fn test_generate() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path();
+        let fstab_items = vec![
+            fstab_item::FSTabItem::new(vec!["/dev/sda1", "/", "ext4", "defaults", "1", "1"]),
+            fstab_item::FSTabItem::new(vec!["/dev/sdb2", "none", "swap", "pri=5", "0", "0"]),
+            fstab_item::FSTabItem::new(vec!["/swapfile", "none", "swap", "noauto", "0", "0"]),
+            fstab_item::FSTabItem::new(vec![
+                "/dev/sdc1",
+                "/mnt/data",
+                "ext4",
+                "nofail,x-systemd.device-timeout=1min",
+                "0",
+                "0",
+            ]),
+            fstab_item::FSTabItem::new(vec![
+                "/dev/sdd1",
+                "/mnt/backup",
+                "ext4",
+                "x-systemd.automount,x-systemd.idle-timeout=10min",
+                "0",
+                "0",
+            ]),
+        ];
+        assert_eq!(generate(dest, &fstab_items), 0);
 
-        let (tx, rx) = mpsc::channel();
-        // Create
-        thread::spawn(move || {
-            if let Err(why) = rx.recv() {
-                clean();
-                panic!("Failed to receive ready message: {:?}", why);
-            }
-            if let Err(why) = fs::File::create(&src_path) {
-                clean();
-                panic!("Failed to create file ({:?}): {:?}.", src_path, why);
-            }
-        });
-
-        // use inotify to wait device ready.
-        let (mut inotify, watch_set) = watch_devices(&fstab_items);
-        let mut buffer = [0u8; 4096];
-        if let Err(why) = tx.send("ready") {
-            clean();
-            panic!("Failed to send ready message: {:?}", why);
-        }
-        let events = inotify
-            .read_events_blocking(&mut buffer)
-            .expect("Failed to read events.");
-        for event in events {
-            if event.mask == EventMask::CREATE
-                && watch_set.contains(event.name.unwrap().to_str().unwrap())
-            {
-                println!("Ok.");
-            }
-        }
+        assert!(!dest.join("-.mount").exists());
+
+        let content = fs::read_to_string(dest.join("dev-sdb2.swap")).unwrap();
+        assert!(content.contains("What=/dev/sdb2\n"));
+        assert!(content.contains("Priority=5\n"));
+        assert!(dest.join("swap.target.requires/dev-sdb2.swap").exists());
+
+        let content = fs::read_to_string(dest.join("swapfile.swap")).unwrap();
+        assert!(content.contains("Options=noauto\n"));
+        assert!(!dest.join("swap.target.requires/swapfile.swap").exists());
+
+        assert!(dest.join("mnt-data.mount").exists());
+        assert!(dest.join("local-fs.target.wants/mnt-data.mount").exists());
+        let content =
+            fs::read_to_string(dest.join("dev-sdc1.device.d/50-device-timeout.conf")).unwrap();
+        assert_eq!(content, "[Unit]\nJobTimeoutSec=60\n");
 
-        clean();
+        let content = fs::read_to_string(dest.join("mnt-backup.automount")).unwrap();
+        assert!(content.contains("Where=/mnt/backup\nTimeoutIdleSec=10min\n"));
+        assert!(dest
+            .join("local-fs.target.requires/mnt-backup.automount")
+            .exists());
+        assert!(!dest
+            .join("local-fs.target.requires/mnt-backup.mount")
+            .exists());
     }
 }
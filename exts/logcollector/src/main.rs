@@ -0,0 +1,280 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! logcollector
+//!
+//! Every process writing to the log collector connects to the stream socket, and
+//! sends the header first: the unit name, the identifier and the default priority,
+//! one line each. The following lines are the output of the process. The socket is
+//! only accessible by root, and the unit is taken from the cgroup of the peer if
+//! possible, the one in the header is only used if the peer can't be looked up.
+
+use clap::Parser;
+use constants::{LOG_COLLECTOR_DIR, LOG_STDOUT_SOCKET};
+use logcollector::peer::unit_of_pid;
+use logproto::record::{Record, LOG_PRIORITY_MAX};
+use logproto::storage::{Storage, DEFAULT_MAX_FILES, DEFAULT_MAX_FILE_SIZE};
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::sys::socket::{getsockopt, sockopt::PeerCredentials};
+use std::fs::Permissions;
+use std::io::{ErrorKind, Read};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::prelude::PermissionsExt;
+use std::path::Path;
+use std::process::exit;
+
+/// the first fd passed by the socket unit
+const SD_LISTEN_FDS_START: RawFd = 3;
+/// the longer lines are split
+const LINE_MAX: usize = 48 * 1024;
+/// the priority used if the header doesn't give a valid one, info
+const DEFAULT_PRIORITY: u8 = 6;
+
+/// parse program arguments
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Directory to store the log files
+    #[clap(long, value_parser, default_value = LOG_COLLECTOR_DIR)]
+    directory: String,
+
+    /// Size limit of one log file in bytes
+    #[clap(long, value_parser, default_value_t = DEFAULT_MAX_FILE_SIZE)]
+    max_file_size: u64,
+
+    /// Number of the log files kept
+    #[clap(long, value_parser, default_value_t = DEFAULT_MAX_FILES)]
+    max_files: usize,
+}
+
+struct Header {
+    unit: String,
+    identifier: String,
+    priority: u8,
+}
+
+/// one process writing to the log collector
+struct Connection {
+    stream: UnixStream,
+    pid: i32,
+    unit: Option<String>,
+    header: Option<Header>,
+    header_lines: Vec<String>,
+    buf: Vec<u8>,
+}
+
+impl Connection {
+    fn new(stream: UnixStream) -> Self {
+        /* The credentials are recorded by connect(), that is the executed process. */
+        let pid = match getsockopt(stream.as_raw_fd(), PeerCredentials) {
+            Ok(cred) => cred.pid(),
+            Err(_) => 0,
+        };
+        /* Look the unit up now, the process may exit before the header is complete. */
+        let unit = unit_of_pid(pid);
+        Connection {
+            stream,
+            pid,
+            unit,
+            header: None,
+            header_lines: Vec::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Read the available data, return false if the connection is closed.
+    fn receive(&mut self, storage: &mut Storage) -> bool {
+        let mut data = [0u8; 4096];
+        loop {
+            match self.stream.read(&mut data) {
+                Ok(0) => {
+                    self.flush(storage, true);
+                    return false;
+                }
+                Ok(n) => {
+                    self.buf.extend_from_slice(&data[..n]);
+                    self.flush(storage, false);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return true,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    log::debug!("Failed to read from pid {}: {}", self.pid, e);
+                    self.flush(storage, true);
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Handle the complete lines in the buffer, and the rest if the connection is closed.
+    fn flush(&mut self, storage: &mut Storage, eof: bool) {
+        loop {
+            let line: Vec<u8> = match self.buf.iter().position(|c| *c == b'\n') {
+                Some(pos) => {
+                    let mut line: Vec<u8> = self.buf.drain(..=pos).collect();
+                    line.pop();
+                    line
+                }
+                None if self.buf.len() >= LINE_MAX => self.buf.drain(..LINE_MAX).collect(),
+                None if eof && !self.buf.is_empty() => std::mem::take(&mut self.buf),
+                None => return,
+            };
+            self.handle_line(&String::from_utf8_lossy(&line), storage);
+        }
+    }
+
+    fn handle_line(&mut self, line: &str, storage: &mut Storage) {
+        let header = match &self.header {
+            Some(v) => v,
+            None => {
+                self.header_lines.push(line.to_string());
+                if self.header_lines.len() == 3 {
+                    let mut header = parse_header(&self.header_lines);
+                    if let Some(unit) = &self.unit {
+                        if *unit != header.unit {
+                            log::debug!(
+                                "pid {} claims to be {}, but belongs to {}",
+                                self.pid,
+                                header.unit,
+                                unit
+                            );
+                        }
+                        header.unit = unit.clone();
+                    }
+                    self.header = Some(header);
+                }
+                return;
+            }
+        };
+
+        let record = Record::new(
+            &header.unit,
+            &header.identifier,
+            self.pid,
+            header.priority,
+            line,
+        );
+        if let Err(e) = storage.append(&record) {
+            log::error!("Failed to write the log of {}: {}", header.unit, e);
+        }
+    }
+}
+
+fn parse_header(lines: &[String]) -> Header {
+    let priority = match lines[2].parse::<u8>() {
+        Ok(v) if v <= LOG_PRIORITY_MAX => v,
+        _ => DEFAULT_PRIORITY,
+    };
+    Header {
+        unit: lines[0].clone(),
+        identifier: lines[1].clone(),
+        priority,
+    }
+}
+
+/// Use the socket passed by logcollector.socket, or listen on our own.
+fn listen() -> std::io::Result<UnixListener> {
+    let passed = std::env::var("LISTEN_PID").ok() == Some(std::process::id().to_string())
+        && std::env::var("LISTEN_FDS").ok() == Some("1".to_string());
+    if passed {
+        return Ok(unsafe { UnixListener::from_raw_fd(SD_LISTEN_FDS_START) });
+    }
+
+    let path = Path::new(LOG_STDOUT_SOCKET);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+fn accept(listener: &UnixListener, connections: &mut Vec<Connection>) {
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(e) = stream.set_nonblocking(true) {
+                    log::error!("Failed to set the connection non-blocking: {}", e);
+                    continue;
+                }
+                connections.push(Connection::new(stream));
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => {
+                log::error!("Failed to accept the connection: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+fn main() {
+    /* Don't log to the collector itself. */
+    log::init_log_to_kmsg("logcollector", log::Level::Info);
+    let args = Args::parse();
+
+    let listener = match listen() {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("Failed to listen on {}: {}", LOG_STDOUT_SOCKET, e);
+            exit(1);
+        }
+    };
+    if let Err(e) = listener.set_nonblocking(true) {
+        log::error!("Failed to set the socket non-blocking: {}", e);
+        exit(1);
+    }
+
+    let mut storage = Storage::new(
+        Path::new(&args.directory),
+        args.max_file_size,
+        args.max_files,
+    );
+    let mut connections: Vec<Connection> = Vec::new();
+
+    loop {
+        let mut fds = vec![PollFd::new(listener.as_raw_fd(), PollFlags::POLLIN)];
+        fds.extend(
+            connections
+                .iter()
+                .map(|c| PollFd::new(c.stream.as_raw_fd(), PollFlags::POLLIN)),
+        );
+
+        match poll(&mut fds, -1) {
+            Ok(_) => {}
+            Err(Errno::EINTR) => continue,
+            Err(e) => {
+                log::error!("Failed to poll: {}", e);
+                exit(1);
+            }
+        }
+
+        let ready: Vec<bool> = fds
+            .iter()
+            .map(|fd| matches!(fd.revents(), Some(r) if !r.is_empty()))
+            .collect();
+
+        for i in (0..connections.len()).rev() {
+            if ready[i + 1] && !connections[i].receive(&mut storage) {
+                connections.remove(i);
+            }
+        }
+
+        if ready[0] {
+            accept(&listener, &mut connections);
+        }
+    }
+}
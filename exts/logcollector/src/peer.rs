@@ -0,0 +1,87 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The unit of the process connected to the collector.
+//!
+//! The unit name in the header is written by the process itself, so it is not
+//! trusted. The unit is taken from the cgroup of the peer instead, sysmaster
+//! attaches the spawned process to the cgroup of its unit before connecting.
+
+/// the unit types whose processes may write to the collector
+const UNIT_SUFFIXES: [&str; 5] = [".service", ".socket", ".mount", ".swap", ".scope"];
+
+/// return the unit of the process, None if it has exited or is not in a unit cgroup
+pub fn unit_of_pid(pid: i32) -> Option<String> {
+    if pid <= 0 {
+        return None;
+    }
+    let content = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    parse_cgroup_unit(&content)
+}
+
+/// parse the content of /proc/<pid>/cgroup, the unit is the first component of
+/// the path below the slices, the cgroups below it are created by the unit itself
+pub fn parse_cgroup_unit(content: &str) -> Option<String> {
+    for line in content.lines() {
+        /* hierarchy-ID:controller-list:cgroup-path */
+        let path = match line.splitn(3, ':').nth(2) {
+            Some(v) => v,
+            None => continue,
+        };
+        let unit = path
+            .split('/')
+            .find(|c| !c.is_empty() && !c.ends_with(".slice"));
+        if let Some(u) = unit {
+            if UNIT_SUFFIXES
+                .iter()
+                .any(|s| u.len() > s.len() && u.ends_with(s))
+            {
+                return Some(u.to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cgroup_unit() {
+        assert_eq!(
+            parse_cgroup_unit("0::/system.slice/foo.service\n"),
+            Some("foo.service".to_string())
+        );
+        /* the sub cgroups created by the service itself are skipped */
+        assert_eq!(
+            parse_cgroup_unit("0::/system.slice/foo.service/worker\n"),
+            Some("foo.service".to_string())
+        );
+        /* the scopes created by the service are not the unit of the process */
+        assert_eq!(
+            parse_cgroup_unit("0::/system.slice/foo.service/worker.scope\n"),
+            Some("foo.service".to_string())
+        );
+        assert_eq!(
+            parse_cgroup_unit("0::/system.slice/worker/foo.service\n"),
+            None
+        );
+        assert_eq!(
+            parse_cgroup_unit("12:pids:/\n1:name=sysmaster:/bar.socket\n"),
+            Some("bar.socket".to_string())
+        );
+        assert_eq!(parse_cgroup_unit("0::/system.slice\n"), None);
+        assert_eq!(parse_cgroup_unit("0::/\n"), None);
+        assert_eq!(parse_cgroup_unit(""), None);
+    }
+}
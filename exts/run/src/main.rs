@@ -25,8 +25,9 @@ use core::unit;
 use core::unit::unit_name_is_valid;
 use core::unit::UnitNameFlags;
 use log::Level;
+use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
-use std::process::exit;
+use std::process::{exit, Command};
 use std::str::FromStr;
 use std::{io::Write, os::unix::net::UnixStream};
 
@@ -147,6 +148,10 @@ struct Args {
     #[clap(short('G'), long, required = false)]
     collect: bool,
 
+    /// run the command in a scope unit instead of a service
+    #[clap(long, required = false)]
+    scope: bool,
+
     /// args cmdline
     #[clap()]
     args_cmdline: Vec<String>,
@@ -266,7 +271,14 @@ fn parse_args(args: &mut Args) -> basic::error::Result<()> {
         });
     }
 
-    if args.args_cmdline.is_empty() && args.unit.is_empty() && !with_trigger {
+    if args.scope && with_trigger {
+        log::error!("--scope cannot be combined with trigger units.");
+        return Err(basic::Error::Other {
+            msg: "--scope cannot be combined with trigger units.".to_string(),
+        });
+    }
+
+    if args.args_cmdline.is_empty() && (args.scope || (args.unit.is_empty() && !with_trigger)) {
         log::error!("Command line to execute required.");
         return Err(basic::Error::Other {
             msg: "Command line to execute required.".to_string(),
@@ -471,6 +483,18 @@ fn transient_service_set_properties(args: &Args) -> nix::Result<Vec<UnitProperty
     Ok(properties)
 }
 
+fn transient_scope_set_properties(args: &Args) -> nix::Result<Vec<UnitProperty>> {
+    let mut properties =
+        transient_unit_set_properties(&args.description, args.collect, &args.property)?;
+
+    /* We exec the command ourselves once the scope exists, so adopt our own pid. */
+    properties.push(UnitProperty {
+        key: "PIDs".to_string(),
+        value: std::process::id().to_string(),
+    });
+    Ok(properties)
+}
+
 fn generate_command_request(args: Args) -> nix::Result<CommandRequest> {
     let unit_name;
     let mut unit_properties;
@@ -499,6 +523,11 @@ fn generate_command_request(args: Args) -> nix::Result<CommandRequest> {
             key: "RemainAfterElapse".to_string(),
             value: "false".to_string(),
         });
+    } else if args.scope {
+        unit_name = get_unit_name(&args.unit, ".scope")?;
+        unit_properties = transient_scope_set_properties(&args)?;
+        aux_name = "".to_string();
+        aux_unit_properties = vec![];
     } else {
         unit_name = get_unit_name(&args.unit, ".service")?;
         unit_properties = transient_service_set_properties(&args)?;
@@ -533,6 +562,12 @@ fn main() {
         std::process::exit(-1);
     }
 
+    let scope_cmdline = if args.scope {
+        Some(args.args_cmdline.clone())
+    } else {
+        None
+    };
+
     let command_request = match generate_command_request(args) {
         Err(e) => {
             eprintln!("Unknown unit name or property:{}", e);
@@ -560,18 +595,26 @@ fn main() {
     };
 
     /* We should always print the error message if the returned error code is not 0. */
-    if data.message.is_empty() {
-        exit(0);
+    if !data.message.is_empty() {
+        if data.error_code == 0 || (data.error_code & ERROR_CODE_MASK_PRINT_STDOUT != 0) {
+            /* Don't care if we fail to write the message out. */
+            let _ = writeln!(std::io::stdout(), "{}", data.message);
+        } else {
+            eprintln!("{}", data.message);
+        }
     }
 
-    if data.error_code == 0 || (data.error_code & ERROR_CODE_MASK_PRINT_STDOUT != 0) {
-        /* Don't care if we fail to write the message out. */
-        let _ = writeln!(std::io::stdout(), "{}", data.message);
-    } else {
-        eprintln!("{}", data.message);
+    let error_code = (data.error_code & !ERROR_CODE_MASK_PRINT_STDOUT) as i32;
+    if error_code == 0 {
+        if let Some(cmdline) = scope_cmdline {
+            /* exec() only returns on failure. */
+            let e = Command::new(&cmdline[0]).args(&cmdline[1..]).exec();
+            eprintln!("Failed to execute {}: {}", cmdline[0], e);
+            exit(e.raw_os_error().unwrap_or(1));
+        }
     }
 
-    exit((data.error_code & !ERROR_CODE_MASK_PRINT_STDOUT) as i32);
+    exit(error_code);
 }
 #[cfg(test)]
 mod tests {
@@ -647,6 +690,13 @@ mod tests {
             "sleep",
         ]);
         assert!(parse_args(&mut args).is_ok());
+        assert!(parse_args(&mut Args::parse_from(vec![
+            "sysmaster-run",
+            "--scope",
+            "--unit",
+            "test",
+        ]))
+        .is_err());
         assert!(parse_args(&mut Args::parse_from(vec![
             "sysmaster-run",
             "--unit",
@@ -12,6 +12,8 @@
 
 //! Capability functions
 
+use nix::errno::Errno;
+
 /// from <linux/capability.h>
 #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[allow(non_camel_case_types)]
@@ -224,6 +226,174 @@ impl Capability {
         *self as u8
     }
 }
+/// the last capability known by sysmaster
+pub const CAP_LAST_CAP: Capability = Capability::CHECKPOINT_RESTORE;
+
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x20080522;
+const PROC_CAP_LAST_CAP: &str = "/proc/sys/kernel/cap_last_cap";
+
+#[repr(C)]
+struct CapUserHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct CapUserData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// the bitmask of all the capabilities known by sysmaster
+pub fn all_capabilities() -> u64 {
+    (CAP_LAST_CAP.bitmask() << 1) - 1
+}
+
+/// the last capability supported by the running kernel
+pub fn cap_last_cap() -> u8 {
+    match std::fs::read_to_string(PROC_CAP_LAST_CAP) {
+        Ok(v) => v.trim().parse::<u8>().unwrap_or(CAP_LAST_CAP as u8),
+        Err(_) => CAP_LAST_CAP as u8,
+    }
+}
+
+/// drop all the capabilities that are not in keep from the bounding set
+pub fn capability_bounding_set_drop(keep: u64) -> crate::Result<()> {
+    for cap in 0..=cap_last_cap().min(63) {
+        if keep & (1u64 << cap) != 0 {
+            continue;
+        }
+        let ret = unsafe { libc::prctl(libc::PR_CAPBSET_DROP, cap as libc::c_ulong, 0, 0, 0) };
+        if ret < 0 {
+            /* The capability is not supported by the kernel, skip it. */
+            if Errno::last() == Errno::EINVAL {
+                continue;
+            }
+            return Err(crate::Error::Nix {
+                source: Errno::last(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn capability_get() -> crate::Result<[CapUserData; 2]> {
+    let mut header = CapUserHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let mut data = [CapUserData::default(); 2];
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_capget,
+            &mut header as *mut CapUserHeader,
+            data.as_mut_ptr(),
+        )
+    };
+    if ret < 0 {
+        return Err(crate::Error::Nix {
+            source: Errno::last(),
+        });
+    }
+    Ok(data)
+}
+
+fn capability_set(data: &[CapUserData; 2]) -> crate::Result<()> {
+    let mut header = CapUserHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_capset,
+            &mut header as *mut CapUserHeader,
+            data.as_ptr(),
+        )
+    };
+    if ret < 0 {
+        return Err(crate::Error::Nix {
+            source: Errno::last(),
+        });
+    }
+    Ok(())
+}
+
+/// raise the capabilities in set to the ambient set, they will be kept
+/// after executing a non-privileged program.
+///
+/// The capabilities must be permitted already, so this should be called
+/// after changing the uid with SECBIT_KEEP_CAPS set.
+pub fn capability_ambient_set_apply(set: u64) -> crate::Result<()> {
+    if set == 0 {
+        return Ok(());
+    }
+
+    /* Ambient capabilities must be both permitted and inheritable. */
+    let mut data = capability_get()?;
+    for (i, d) in data.iter_mut().enumerate() {
+        let bits = (set >> (32 * i)) as u32;
+        d.inheritable |= bits & d.permitted;
+        d.effective |= bits & d.permitted;
+    }
+    capability_set(&data)?;
+
+    for cap in 0..=cap_last_cap().min(63) {
+        if set & (1u64 << cap) == 0 {
+            continue;
+        }
+        let ret = unsafe {
+            libc::prctl(
+                libc::PR_CAP_AMBIENT,
+                libc::PR_CAP_AMBIENT_RAISE as libc::c_ulong,
+                cap as libc::c_ulong,
+                0,
+                0,
+            )
+        };
+        if ret < 0 {
+            return Err(crate::Error::Nix {
+                source: Errno::last(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// set the securebits of the current process
+pub fn set_securebits(bits: u32) -> crate::Result<()> {
+    let ret = unsafe { libc::prctl(libc::PR_SET_SECUREBITS, bits as libc::c_ulong, 0, 0, 0) };
+    if ret < 0 {
+        return Err(crate::Error::Nix {
+            source: Errno::last(),
+        });
+    }
+    Ok(())
+}
+
+/// get the securebits of the current process
+pub fn securebits() -> crate::Result<u32> {
+    let ret = unsafe { libc::prctl(libc::PR_GET_SECUREBITS, 0, 0, 0, 0) };
+    if ret < 0 {
+        return Err(crate::Error::Nix {
+            source: Errno::last(),
+        });
+    }
+    Ok(ret as u32)
+}
+
+/// the current process and its children can't gain new privileges through execve
+pub fn set_no_new_privs() -> crate::Result<()> {
+    let ret = unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+    if ret < 0 {
+        return Err(crate::Error::Nix {
+            source: Errno::last(),
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,4 +411,10 @@ mod tests {
 
         assert_eq!(vec.index(), 35);
     }
+
+    #[test]
+    fn all_capabilities_test() {
+        assert_eq!(all_capabilities(), (1 << 41) - 1);
+        assert!(cap_last_cap() > 0);
+    }
 }
@@ -22,14 +22,15 @@ use std::{fs, os::unix::prelude::PermissionsExt, path::PathBuf, process::Command
 ///
 /// * `directories`: The `directories` parameter is a vector of string slices (`&str`) representing the
 /// directories that need to be executed.
+/// * `output`: The directory the generators write the units to, passed as the first argument.
 ///
 /// Returns:
 ///
 /// The function `execute_directories` returns a `std::io::Result<()>`.
-pub fn execute_directories(directories: Vec<&str>) -> std::io::Result<()> {
+pub fn execute_directories(directories: Vec<&str>, output: &str) -> std::io::Result<()> {
     match unsafe { unistd::fork() } {
         Ok(unistd::ForkResult::Child) => {
-            std::process::exit(do_execute(directories).map_or(1, |_| 0))
+            std::process::exit(do_execute(directories, output).map_or(1, |_| 0))
         }
         Ok(unistd::ForkResult::Parent { child }) => match nix::sys::wait::waitpid(child, None) {
             Ok(_) => Ok(()),
@@ -45,25 +46,21 @@ pub fn execute_directories(directories: Vec<&str>) -> std::io::Result<()> {
 /// Arguments:
 ///
 /// * `directories`: A vector of strings representing directories.
+/// * `output`: The output directory of the generators.
 ///
 /// Returns:
 ///
 /// The function `do_execute` returns a `std::io::Result<()>`.
-fn do_execute(directories: Vec<&str>) -> std::io::Result<()> {
+fn do_execute(directories: Vec<&str>, output: &str) -> std::io::Result<()> {
     let mut child = Vec::new();
     for generator in get_generator(directories)? {
-        child.push(
-            match Command::new(&generator)
-                .arg("/etc/sysmaster/system")
-                .spawn()
-            {
-                Ok(pid) => pid,
-                Err(err) => {
-                    log::error!("{:?} spawn err: {}", &generator, err);
-                    continue;
-                }
-            },
-        );
+        child.push(match Command::new(&generator).arg(output).spawn() {
+            Ok(pid) => pid,
+            Err(err) => {
+                log::error!("{:?} spawn err: {}", &generator, err);
+                continue;
+            }
+        });
     }
 
     for mut child in child {
@@ -231,6 +231,63 @@ pub fn file_offset_beyond_memory_size(x: off_t) -> bool {
 pub fn dot_or_dot_dot(name: &str) -> bool {
     name == "." || name == ".."
 }
+
+/// compare the file descriptions of two processes, see kcmp(2)
+const KCMP_FILE: libc::c_int = 0;
+
+/// check whether the two fds refer to the same open file description, i.e.
+/// duplicated by dup() or passed twice, two separate open() of the same file
+/// are not the same.
+pub fn same_fd(a: RawFd, b: RawFd) -> Result<bool> {
+    if a == b {
+        return Ok(true);
+    }
+
+    /* the same open file description always refers to the same inode */
+    let sa = nix::sys::stat::fstat(a).context(NixSnafu)?;
+    let sb = nix::sys::stat::fstat(b).context(NixSnafu)?;
+    if (sa.st_mode ^ sb.st_mode) & SFlag::S_IFMT.bits() != 0 {
+        return Ok(false);
+    }
+    if sa.st_dev != sb.st_dev || sa.st_ino != sb.st_ino {
+        return Ok(false);
+    }
+
+    let pid = nix::unistd::getpid().as_raw();
+    let ret = unsafe { libc::syscall(libc::SYS_kcmp, pid, pid, KCMP_FILE, a, b) };
+    if ret >= 0 {
+        return Ok(ret == 0);
+    }
+    let errno = Errno::last();
+    if !matches!(errno, Errno::ENOSYS | Errno::EPERM | Errno::EACCES) {
+        return Err(Error::Nix { source: errno });
+    }
+
+    /* kcmp() is not available, i.e. disabled in the kernel or filtered by
+     * seccomp, compare the offset and the flags of the file descriptions,
+     * two opens of the same file with the same flags and offset look the same. */
+    Ok(fdinfo_fields(a)? == fdinfo_fields(b)?)
+}
+
+/// the fields of /proc/self/fdinfo/<fd> which belong to the open file
+/// description, the other fields, i.e. FD_CLOEXEC in "flags", are per fd
+fn fdinfo_fields(fd: RawFd) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(format!("/proc/self/fdinfo/{}", fd)).context(IoSnafu)?;
+    let mut fields = Vec::new();
+    for line in content.lines() {
+        if let Some(flags) = line.strip_prefix("flags:") {
+            let flags = i32::from_str_radix(flags.trim(), 8).unwrap_or(0) & !libc::O_CLOEXEC;
+            fields.push(format!("flags:\t{:o}", flags));
+        } else if line.starts_with("pos:")
+            || line.starts_with("mnt_id:")
+            || line.starts_with("ino:")
+        {
+            fields.push(line.to_string());
+        }
+    }
+    Ok(fields)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::fd::{stat_is_char, stat_is_reg};
@@ -244,7 +301,7 @@ mod tests {
         path::Path,
     };
 
-    use super::{dot_or_dot_dot, xopendirat};
+    use super::{dot_or_dot_dot, fdinfo_fields, same_fd, xopendirat};
 
     #[test]
     fn test_stats() {
@@ -291,4 +348,30 @@ mod tests {
         assert!(dot_or_dot_dot(".."));
         assert!(!dot_or_dot_dot("/"));
     }
+
+    #[test]
+    fn test_same_fd() {
+        let file = File::open(Path::new("/dev/null")).unwrap();
+        let dup = nix::unistd::dup(file.as_raw_fd()).unwrap();
+        let other = File::open(Path::new("/dev/zero")).unwrap();
+
+        assert!(same_fd(file.as_raw_fd(), file.as_raw_fd()).unwrap());
+        assert!(same_fd(file.as_raw_fd(), dup).unwrap());
+        assert!(!same_fd(file.as_raw_fd(), other.as_raw_fd()).unwrap());
+        assert!(same_fd(file.as_raw_fd(), -1).is_err());
+        nix::unistd::close(dup).unwrap();
+
+        /* the two ends of a pipe are the same inode, but not the same file */
+        let (rfd, wfd) = nix::unistd::pipe().unwrap();
+        let rdup = nix::unistd::dup(rfd).unwrap();
+        assert!(!same_fd(rfd, wfd).unwrap());
+        assert!(same_fd(rfd, rdup).unwrap());
+        assert!(!same_fd(wfd, rdup).unwrap());
+
+        assert_eq!(fdinfo_fields(rfd).unwrap(), fdinfo_fields(rdup).unwrap());
+        assert_ne!(fdinfo_fields(rfd).unwrap(), fdinfo_fields(wfd).unwrap());
+        for fd in [rfd, wfd, rdup] {
+            nix::unistd::close(fd).unwrap();
+        }
+    }
 }
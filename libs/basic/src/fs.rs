@@ -685,8 +685,39 @@ pub const RUN_TRANSIENT_PATH: &str = "/run/sysmaster/transient";
 pub const ETC_SYSTEM_PATH: &str = "/etc/sysmaster/system";
 /// unit lookup path in /run
 pub const RUN_SYSTEM_PATH: &str = "/run/sysmaster/system";
+/// the output directory of the generators, cleared before each run
+pub const RUN_GENERATOR_PATH: &str = "/run/sysmaster/generator";
 /// unit lookup path in /usr/lib
 pub const LIB_SYSTEM_PATH: &str = "/usr/lib/sysmaster/system";
+/// user unit lookup path in /etc
+pub const ETC_USER_PATH: &str = "/etc/sysmaster/user";
+/// user unit lookup path in /usr/lib
+pub const LIB_USER_PATH: &str = "/usr/lib/sysmaster/user";
+
+/// the runtime directory of the user manager, $XDG_RUNTIME_DIR/sysmaster,
+/// None if XDG_RUNTIME_DIR is not set or not absolute
+pub fn user_runtime_dir() -> Option<String> {
+    let dir = std::env::var("XDG_RUNTIME_DIR").ok()?;
+    if !path_is_abosolute(&dir) {
+        return None;
+    }
+    Some(format!("{}/sysmaster", dir.trim_end_matches('/')))
+}
+
+/// the configuration directory of the user manager, $XDG_CONFIG_HOME/sysmaster,
+/// or ~/.config/sysmaster if XDG_CONFIG_HOME is not set
+pub fn user_config_dir() -> Option<String> {
+    if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
+        if path_is_abosolute(&dir) {
+            return Some(format!("{}/sysmaster", dir.trim_end_matches('/')));
+        }
+    }
+    let home = std::env::var("HOME").ok()?;
+    if !path_is_abosolute(&home) {
+        return None;
+    }
+    Some(format!("{}/.config/sysmaster", home.trim_end_matches('/')))
+}
 
 /// struct LookupPaths
 #[derive(Debug, Clone)]
@@ -703,8 +734,10 @@ pub struct LookupPaths {
     pub generator_late: String,
     /// transient paths
     pub transient: String,
-    /// transient paths
+    /// the path in which the configuration is persistent, i.e. enable, mask and edit
     pub persistent_path: String,
+    /// the path in which the configuration is lost after reboot, i.e. edit --runtime
+    pub runtime_path: String,
 }
 
 impl LookupPaths {
@@ -717,6 +750,7 @@ impl LookupPaths {
             transient: String::from(""),
             search_path: Vec::new(),
             persistent_path: String::from(""),
+            runtime_path: String::from(""),
             preset_path: Vec::new(),
         }
     }
@@ -726,6 +760,7 @@ impl LookupPaths {
         self.search_path.push(RUN_TRANSIENT_PATH.to_string());
         self.search_path.push(ETC_SYSTEM_PATH.to_string());
         self.search_path.push(RUN_SYSTEM_PATH.to_string());
+        self.search_path.push(RUN_GENERATOR_PATH.to_string());
         self.search_path.push(LIB_SYSTEM_PATH.to_string());
 
         self.preset_path
@@ -733,7 +768,33 @@ impl LookupPaths {
         self.preset_path
             .push(format!("{}/{}", LIB_SYSTEM_PATH, "system-preset"));
 
+        self.generator = RUN_GENERATOR_PATH.to_string();
+        self.transient = RUN_TRANSIENT_PATH.to_string();
         self.persistent_path = ETC_SYSTEM_PATH.to_string();
+        self.runtime_path = RUN_SYSTEM_PATH.to_string();
+    }
+
+    /// init lookup paths of the user manager, runtime_dir is $XDG_RUNTIME_DIR/sysmaster,
+    /// config_dir is $XDG_CONFIG_HOME/sysmaster, which is ~/.config/sysmaster usually.
+    pub fn init_user_lookup_paths(&mut self, runtime_dir: &str, config_dir: &str) {
+        let transient = format!("{}/transient", runtime_dir);
+        let persistent = format!("{}/user", config_dir);
+        let runtime = format!("{}/user", runtime_dir);
+
+        self.search_path.push(transient.clone());
+        self.search_path.push(persistent.clone());
+        self.search_path.push(ETC_USER_PATH.to_string());
+        self.search_path.push(runtime.clone());
+        self.search_path.push(LIB_USER_PATH.to_string());
+
+        self.preset_path
+            .push(format!("{}/{}", ETC_USER_PATH, "user-preset"));
+        self.preset_path
+            .push(format!("{}/{}", LIB_USER_PATH, "user-preset"));
+
+        self.transient = transient;
+        self.persistent_path = persistent;
+        self.runtime_path = runtime;
     }
 }
 
@@ -903,6 +964,7 @@ mod tests {
                 "/run/sysmaster/transient",
                 "/etc/sysmaster/system",
                 "/run/sysmaster/system",
+                "/run/sysmaster/generator",
                 "/usr/lib/sysmaster/system"
             ]
         );
@@ -913,7 +975,36 @@ mod tests {
                 "/usr/lib/sysmaster/system/system-preset"
             ]
         );
-        assert_eq!(lp.persistent_path, "/etc/sysmaster/system")
+        assert_eq!(lp.persistent_path, "/etc/sysmaster/system");
+        assert_eq!(lp.runtime_path, "/run/sysmaster/system");
+        assert_eq!(lp.transient, "/run/sysmaster/transient");
+        assert_eq!(lp.generator, "/run/sysmaster/generator");
+    }
+
+    #[test]
+    fn test_init_user_lookup_paths() {
+        let mut lp = LookupPaths::default();
+        lp.init_user_lookup_paths("/run/user/1000/sysmaster", "/home/test/.config/sysmaster");
+        assert_eq!(
+            lp.search_path,
+            vec![
+                "/run/user/1000/sysmaster/transient",
+                "/home/test/.config/sysmaster/user",
+                "/etc/sysmaster/user",
+                "/run/user/1000/sysmaster/user",
+                "/usr/lib/sysmaster/user"
+            ]
+        );
+        assert_eq!(
+            lp.preset_path,
+            vec![
+                "/etc/sysmaster/user/user-preset",
+                "/usr/lib/sysmaster/user/user-preset"
+            ]
+        );
+        assert_eq!(lp.persistent_path, "/home/test/.config/sysmaster/user");
+        assert_eq!(lp.runtime_path, "/run/user/1000/sysmaster/user");
+        assert_eq!(lp.transient, "/run/user/1000/sysmaster/transient");
     }
 
     #[test]
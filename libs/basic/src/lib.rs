@@ -69,6 +69,8 @@ pub mod process;
 pub mod random;
 #[cfg(feature = "rlimit")]
 pub mod rlimit;
+#[cfg(feature = "seccomp")]
+pub mod seccomp;
 #[cfg(feature = "security")]
 pub mod security;
 #[cfg(feature = "sensors")]
@@ -105,12 +107,20 @@ pub use error::*;
 pub const DEFAULT_TARGET: &str = "default.target";
 /// the shutdown target
 pub const SHUTDOWN_TARGET: &str = "shutdown.target";
+/// the target stopping all the units of the user manager before it exits
+pub const EXIT_TARGET: &str = "exit.target";
 /// the socketc target
 pub const SOCKETS_TARGET: &str = "sockets.target";
 /// the timer target
 pub const TIMERS_TARGET: &str = "timers.target";
 /// the path target
 pub const PATHS_TARGET: &str = "paths.target";
+/// the swap target
+pub const SWAP_TARGET: &str = "swap.target";
+/// the target of local file systems
+pub const LOCAL_FS_TARGET: &str = "local-fs.target";
+/// the target of network file systems
+pub const REMOTE_FS_TARGET: &str = "remote-fs.target";
 
 /// early boot targets
 pub const SYSINIT_TARGET: &str = "sysinit.target";
@@ -140,5 +150,8 @@ pub const UMOUNT_BIN: &str = "/usr/bin/umount";
 /// the default swapon path
 pub const SWAP_BIN: &str = "/usr/sbin/swapon";
 
+/// the default swapoff path
+pub const SWAPOFF_BIN: &str = "/usr/sbin/swapoff";
+
 /// the default fstab config file path
 pub const FSTAB_PATH: &str = "/etc/fstab";
@@ -131,7 +131,8 @@ pub fn is_swap(path: &Path) -> bool {
             Err(_) => continue,
             Ok(v) => v,
         };
-        if line.starts_with(device_path.to_str().unwrap()) {
+        /* the first column of /proc/swaps is the swap file or device */
+        if line.split_whitespace().next() == device_path.to_str() {
             return true;
         }
     }
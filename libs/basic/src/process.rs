@@ -230,6 +230,21 @@ fn get_ppid(pid: Pid) -> Result<Pid, Error> {
     Ok(Pid::from_raw(stat.ppid))
 }
 
+/// get the time the process started after system boot, in microseconds
+pub fn process_start_usec(pid: Pid) -> Result<u64> {
+    let path = PathBuf::from(format!("/proc/{}/stat", pid));
+    let stat = Stat::from_reader(File::open(path).context(IoSnafu)?).context(ProcSnafu)?;
+
+    let ticks = procfs::ticks_per_second().context(IoSnafu)?;
+    if ticks <= 0 {
+        return Err(Error::Invalid {
+            what: "clock ticks per second".to_string(),
+        });
+    }
+
+    Ok(stat.starttime * 1_000_000 / ticks as u64)
+}
+
 /// return true if the pid is the child of calling process, other false.
 pub fn my_child(pid: Pid) -> bool {
     if pid.as_raw() <= 1 {
@@ -312,7 +327,15 @@ mod tests {
     use std::process::Command;
     use std::thread;
 
-    use crate::process::wait_pids;
+    use crate::process::{process_start_usec, wait_pids};
+    #[test]
+    fn test_process_start_usec() {
+        let start = process_start_usec(nix::unistd::getpid()).unwrap();
+        let uptime = std::fs::read_to_string("/proc/uptime").unwrap();
+        let uptime: f64 = uptime.split_whitespace().next().unwrap().parse().unwrap();
+        assert!(start <= (uptime * 1_000_000.0) as u64 + 1_000_000);
+    }
+
     #[test]
     fn test_wait_pids() {
         let mut pids: HashSet<i32> = HashSet::new();
@@ -0,0 +1,1300 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Seccomp functions, compile the system call filters to the classic BPF
+//! program and load it into the kernel.
+
+use nix::errno::Errno;
+use std::collections::BTreeSet;
+
+/* from <linux/filter.h> */
+const BPF_LD: u16 = 0x00;
+const BPF_JMP: u16 = 0x05;
+const BPF_RET: u16 = 0x06;
+const BPF_W: u16 = 0x00;
+const BPF_ABS: u16 = 0x20;
+const BPF_JEQ: u16 = 0x10;
+const BPF_JGE: u16 = 0x30;
+const BPF_K: u16 = 0x00;
+
+/* from <linux/seccomp.h> */
+const SECCOMP_MODE_FILTER: libc::c_ulong = 2;
+const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+const SECCOMP_RET_DATA: u32 = 0x0000_ffff;
+
+/* offsets in struct seccomp_data */
+const SECCOMP_DATA_NR: u32 = 0;
+const SECCOMP_DATA_ARCH: u32 = 4;
+
+/* from <linux/audit.h> */
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH_NATIVE: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH_NATIVE: u32 = 0xc000_00b7;
+#[cfg(target_arch = "riscv64")]
+const AUDIT_ARCH_NATIVE: u32 = 0xc000_00f3;
+
+/// the name of the native architecture used by SystemCallArchitectures
+#[cfg(target_arch = "x86_64")]
+pub const NATIVE_ARCH: &str = "x86-64";
+/// the name of the native architecture used by SystemCallArchitectures
+#[cfg(target_arch = "aarch64")]
+pub const NATIVE_ARCH: &str = "arm64";
+/// the name of the native architecture used by SystemCallArchitectures
+#[cfg(target_arch = "riscv64")]
+pub const NATIVE_ARCH: &str = "riscv64";
+
+/* The system call tables are only known for the architectures above, the filters
+ * are refused when they are loaded on the others. */
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
+const AUDIT_ARCH_NATIVE: u32 = 0;
+/// the name of the native architecture used by SystemCallArchitectures
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
+pub const NATIVE_ARCH: &str = std::env::consts::ARCH;
+
+/// whether the seccomp filters can be loaded on the native architecture
+const ARCH_SUPPORTED: bool = cfg!(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+));
+
+/// the system calls of the x32 ABI have this bit set on x86_64
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+/// the system calls available on all the supported architectures
+#[cfg(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+))]
+const SYSCALLS_COMMON: &[(&str, libc::c_long)] = &[
+    ("accept", libc::SYS_accept),
+    ("accept4", libc::SYS_accept4),
+    ("acct", libc::SYS_acct),
+    ("add_key", libc::SYS_add_key),
+    ("adjtimex", libc::SYS_adjtimex),
+    ("bind", libc::SYS_bind),
+    ("bpf", libc::SYS_bpf),
+    ("brk", libc::SYS_brk),
+    ("capget", libc::SYS_capget),
+    ("capset", libc::SYS_capset),
+    ("chdir", libc::SYS_chdir),
+    ("chroot", libc::SYS_chroot),
+    ("clock_adjtime", libc::SYS_clock_adjtime),
+    ("clock_getres", libc::SYS_clock_getres),
+    ("clock_gettime", libc::SYS_clock_gettime),
+    ("clock_nanosleep", libc::SYS_clock_nanosleep),
+    ("clock_settime", libc::SYS_clock_settime),
+    ("clone", libc::SYS_clone),
+    ("clone3", libc::SYS_clone3),
+    ("close", libc::SYS_close),
+    ("close_range", libc::SYS_close_range),
+    ("connect", libc::SYS_connect),
+    ("copy_file_range", libc::SYS_copy_file_range),
+    ("delete_module", libc::SYS_delete_module),
+    ("dup", libc::SYS_dup),
+    ("dup3", libc::SYS_dup3),
+    ("epoll_create1", libc::SYS_epoll_create1),
+    ("epoll_ctl", libc::SYS_epoll_ctl),
+    ("epoll_pwait", libc::SYS_epoll_pwait),
+    ("eventfd2", libc::SYS_eventfd2),
+    ("execve", libc::SYS_execve),
+    ("execveat", libc::SYS_execveat),
+    ("exit", libc::SYS_exit),
+    ("exit_group", libc::SYS_exit_group),
+    ("faccessat", libc::SYS_faccessat),
+    ("faccessat2", libc::SYS_faccessat2),
+    ("fallocate", libc::SYS_fallocate),
+    ("fanotify_init", libc::SYS_fanotify_init),
+    ("fanotify_mark", libc::SYS_fanotify_mark),
+    ("fchdir", libc::SYS_fchdir),
+    ("fchmod", libc::SYS_fchmod),
+    ("fchmodat", libc::SYS_fchmodat),
+    ("fchown", libc::SYS_fchown),
+    ("fchownat", libc::SYS_fchownat),
+    ("fcntl", libc::SYS_fcntl),
+    ("fdatasync", libc::SYS_fdatasync),
+    ("fgetxattr", libc::SYS_fgetxattr),
+    ("finit_module", libc::SYS_finit_module),
+    ("flistxattr", libc::SYS_flistxattr),
+    ("flock", libc::SYS_flock),
+    ("fremovexattr", libc::SYS_fremovexattr),
+    ("fsconfig", libc::SYS_fsconfig),
+    ("fsetxattr", libc::SYS_fsetxattr),
+    ("fsmount", libc::SYS_fsmount),
+    ("fsopen", libc::SYS_fsopen),
+    ("fspick", libc::SYS_fspick),
+    ("fstat", libc::SYS_fstat),
+    ("fstatfs", libc::SYS_fstatfs),
+    ("fsync", libc::SYS_fsync),
+    ("ftruncate", libc::SYS_ftruncate),
+    ("futex", libc::SYS_futex),
+    ("get_mempolicy", libc::SYS_get_mempolicy),
+    ("get_robust_list", libc::SYS_get_robust_list),
+    ("getcpu", libc::SYS_getcpu),
+    ("getcwd", libc::SYS_getcwd),
+    ("getdents64", libc::SYS_getdents64),
+    ("getegid", libc::SYS_getegid),
+    ("geteuid", libc::SYS_geteuid),
+    ("getgid", libc::SYS_getgid),
+    ("getgroups", libc::SYS_getgroups),
+    ("getitimer", libc::SYS_getitimer),
+    ("getpeername", libc::SYS_getpeername),
+    ("getpgid", libc::SYS_getpgid),
+    ("getpid", libc::SYS_getpid),
+    ("getppid", libc::SYS_getppid),
+    ("getpriority", libc::SYS_getpriority),
+    ("getrandom", libc::SYS_getrandom),
+    ("getresgid", libc::SYS_getresgid),
+    ("getresuid", libc::SYS_getresuid),
+    ("getrlimit", libc::SYS_getrlimit),
+    ("getrusage", libc::SYS_getrusage),
+    ("getsid", libc::SYS_getsid),
+    ("getsockname", libc::SYS_getsockname),
+    ("getsockopt", libc::SYS_getsockopt),
+    ("gettid", libc::SYS_gettid),
+    ("gettimeofday", libc::SYS_gettimeofday),
+    ("getuid", libc::SYS_getuid),
+    ("getxattr", libc::SYS_getxattr),
+    ("init_module", libc::SYS_init_module),
+    ("inotify_add_watch", libc::SYS_inotify_add_watch),
+    ("inotify_init1", libc::SYS_inotify_init1),
+    ("inotify_rm_watch", libc::SYS_inotify_rm_watch),
+    ("io_cancel", libc::SYS_io_cancel),
+    ("io_destroy", libc::SYS_io_destroy),
+    ("io_getevents", libc::SYS_io_getevents),
+    ("io_setup", libc::SYS_io_setup),
+    ("io_submit", libc::SYS_io_submit),
+    ("io_uring_enter", libc::SYS_io_uring_enter),
+    ("io_uring_register", libc::SYS_io_uring_register),
+    ("io_uring_setup", libc::SYS_io_uring_setup),
+    ("ioctl", libc::SYS_ioctl),
+    ("ioprio_get", libc::SYS_ioprio_get),
+    ("ioprio_set", libc::SYS_ioprio_set),
+    ("kcmp", libc::SYS_kcmp),
+    ("kexec_load", libc::SYS_kexec_load),
+    ("keyctl", libc::SYS_keyctl),
+    ("kill", libc::SYS_kill),
+    ("lgetxattr", libc::SYS_lgetxattr),
+    ("linkat", libc::SYS_linkat),
+    ("listen", libc::SYS_listen),
+    ("listxattr", libc::SYS_listxattr),
+    ("llistxattr", libc::SYS_llistxattr),
+    ("lookup_dcookie", libc::SYS_lookup_dcookie),
+    ("lremovexattr", libc::SYS_lremovexattr),
+    ("lseek", libc::SYS_lseek),
+    ("lsetxattr", libc::SYS_lsetxattr),
+    ("madvise", libc::SYS_madvise),
+    ("mbind", libc::SYS_mbind),
+    ("membarrier", libc::SYS_membarrier),
+    ("memfd_create", libc::SYS_memfd_create),
+    ("migrate_pages", libc::SYS_migrate_pages),
+    ("mincore", libc::SYS_mincore),
+    ("mkdirat", libc::SYS_mkdirat),
+    ("mknodat", libc::SYS_mknodat),
+    ("mlock", libc::SYS_mlock),
+    ("mlock2", libc::SYS_mlock2),
+    ("mlockall", libc::SYS_mlockall),
+    ("mmap", libc::SYS_mmap),
+    ("mount", libc::SYS_mount),
+    ("move_mount", libc::SYS_move_mount),
+    ("move_pages", libc::SYS_move_pages),
+    ("mprotect", libc::SYS_mprotect),
+    ("mq_getsetattr", libc::SYS_mq_getsetattr),
+    ("mq_notify", libc::SYS_mq_notify),
+    ("mq_open", libc::SYS_mq_open),
+    ("mq_timedreceive", libc::SYS_mq_timedreceive),
+    ("mq_timedsend", libc::SYS_mq_timedsend),
+    ("mq_unlink", libc::SYS_mq_unlink),
+    ("mremap", libc::SYS_mremap),
+    ("msgctl", libc::SYS_msgctl),
+    ("msgget", libc::SYS_msgget),
+    ("msgrcv", libc::SYS_msgrcv),
+    ("msgsnd", libc::SYS_msgsnd),
+    ("msync", libc::SYS_msync),
+    ("munlock", libc::SYS_munlock),
+    ("munlockall", libc::SYS_munlockall),
+    ("munmap", libc::SYS_munmap),
+    ("name_to_handle_at", libc::SYS_name_to_handle_at),
+    ("nanosleep", libc::SYS_nanosleep),
+    ("newfstatat", libc::SYS_newfstatat),
+    ("open_by_handle_at", libc::SYS_open_by_handle_at),
+    ("open_tree", libc::SYS_open_tree),
+    ("openat", libc::SYS_openat),
+    ("openat2", libc::SYS_openat2),
+    ("perf_event_open", libc::SYS_perf_event_open),
+    ("personality", libc::SYS_personality),
+    ("pidfd_getfd", libc::SYS_pidfd_getfd),
+    ("pidfd_open", libc::SYS_pidfd_open),
+    ("pidfd_send_signal", libc::SYS_pidfd_send_signal),
+    ("pipe2", libc::SYS_pipe2),
+    ("pivot_root", libc::SYS_pivot_root),
+    ("pkey_alloc", libc::SYS_pkey_alloc),
+    ("pkey_free", libc::SYS_pkey_free),
+    ("pkey_mprotect", libc::SYS_pkey_mprotect),
+    ("ppoll", libc::SYS_ppoll),
+    ("prctl", libc::SYS_prctl),
+    ("pread64", libc::SYS_pread64),
+    ("preadv", libc::SYS_preadv),
+    ("preadv2", libc::SYS_preadv2),
+    ("prlimit64", libc::SYS_prlimit64),
+    ("process_vm_readv", libc::SYS_process_vm_readv),
+    ("process_vm_writev", libc::SYS_process_vm_writev),
+    ("pselect6", libc::SYS_pselect6),
+    ("ptrace", libc::SYS_ptrace),
+    ("pwrite64", libc::SYS_pwrite64),
+    ("pwritev", libc::SYS_pwritev),
+    ("pwritev2", libc::SYS_pwritev2),
+    ("quotactl", libc::SYS_quotactl),
+    ("read", libc::SYS_read),
+    ("readahead", libc::SYS_readahead),
+    ("readlinkat", libc::SYS_readlinkat),
+    ("readv", libc::SYS_readv),
+    ("reboot", libc::SYS_reboot),
+    ("recvfrom", libc::SYS_recvfrom),
+    ("recvmmsg", libc::SYS_recvmmsg),
+    ("recvmsg", libc::SYS_recvmsg),
+    ("remap_file_pages", libc::SYS_remap_file_pages),
+    ("removexattr", libc::SYS_removexattr),
+    ("renameat2", libc::SYS_renameat2),
+    ("request_key", libc::SYS_request_key),
+    ("restart_syscall", libc::SYS_restart_syscall),
+    ("rseq", libc::SYS_rseq),
+    ("rt_sigaction", libc::SYS_rt_sigaction),
+    ("rt_sigpending", libc::SYS_rt_sigpending),
+    ("rt_sigprocmask", libc::SYS_rt_sigprocmask),
+    ("rt_sigqueueinfo", libc::SYS_rt_sigqueueinfo),
+    ("rt_sigreturn", libc::SYS_rt_sigreturn),
+    ("rt_sigsuspend", libc::SYS_rt_sigsuspend),
+    ("rt_sigtimedwait", libc::SYS_rt_sigtimedwait),
+    ("rt_tgsigqueueinfo", libc::SYS_rt_tgsigqueueinfo),
+    ("sched_get_priority_max", libc::SYS_sched_get_priority_max),
+    ("sched_get_priority_min", libc::SYS_sched_get_priority_min),
+    ("sched_getaffinity", libc::SYS_sched_getaffinity),
+    ("sched_getattr", libc::SYS_sched_getattr),
+    ("sched_getparam", libc::SYS_sched_getparam),
+    ("sched_getscheduler", libc::SYS_sched_getscheduler),
+    ("sched_rr_get_interval", libc::SYS_sched_rr_get_interval),
+    ("sched_setaffinity", libc::SYS_sched_setaffinity),
+    ("sched_setattr", libc::SYS_sched_setattr),
+    ("sched_setparam", libc::SYS_sched_setparam),
+    ("sched_setscheduler", libc::SYS_sched_setscheduler),
+    ("sched_yield", libc::SYS_sched_yield),
+    ("seccomp", libc::SYS_seccomp),
+    ("semctl", libc::SYS_semctl),
+    ("semget", libc::SYS_semget),
+    ("semop", libc::SYS_semop),
+    ("semtimedop", libc::SYS_semtimedop),
+    ("sendmmsg", libc::SYS_sendmmsg),
+    ("sendmsg", libc::SYS_sendmsg),
+    ("sendto", libc::SYS_sendto),
+    ("set_mempolicy", libc::SYS_set_mempolicy),
+    ("set_robust_list", libc::SYS_set_robust_list),
+    ("set_tid_address", libc::SYS_set_tid_address),
+    ("setdomainname", libc::SYS_setdomainname),
+    ("setfsgid", libc::SYS_setfsgid),
+    ("setfsuid", libc::SYS_setfsuid),
+    ("setgid", libc::SYS_setgid),
+    ("setgroups", libc::SYS_setgroups),
+    ("sethostname", libc::SYS_sethostname),
+    ("setitimer", libc::SYS_setitimer),
+    ("setns", libc::SYS_setns),
+    ("setpgid", libc::SYS_setpgid),
+    ("setpriority", libc::SYS_setpriority),
+    ("setregid", libc::SYS_setregid),
+    ("setresgid", libc::SYS_setresgid),
+    ("setresuid", libc::SYS_setresuid),
+    ("setreuid", libc::SYS_setreuid),
+    ("setrlimit", libc::SYS_setrlimit),
+    ("setsid", libc::SYS_setsid),
+    ("setsockopt", libc::SYS_setsockopt),
+    ("settimeofday", libc::SYS_settimeofday),
+    ("setuid", libc::SYS_setuid),
+    ("setxattr", libc::SYS_setxattr),
+    ("shmat", libc::SYS_shmat),
+    ("shmctl", libc::SYS_shmctl),
+    ("shmdt", libc::SYS_shmdt),
+    ("shmget", libc::SYS_shmget),
+    ("shutdown", libc::SYS_shutdown),
+    ("sigaltstack", libc::SYS_sigaltstack),
+    ("signalfd4", libc::SYS_signalfd4),
+    ("socket", libc::SYS_socket),
+    ("socketpair", libc::SYS_socketpair),
+    ("splice", libc::SYS_splice),
+    ("statfs", libc::SYS_statfs),
+    ("statx", libc::SYS_statx),
+    ("swapoff", libc::SYS_swapoff),
+    ("swapon", libc::SYS_swapon),
+    ("symlinkat", libc::SYS_symlinkat),
+    ("sync", libc::SYS_sync),
+    ("sync_file_range", libc::SYS_sync_file_range),
+    ("syncfs", libc::SYS_syncfs),
+    ("sysinfo", libc::SYS_sysinfo),
+    ("tee", libc::SYS_tee),
+    ("tgkill", libc::SYS_tgkill),
+    ("timer_create", libc::SYS_timer_create),
+    ("timer_delete", libc::SYS_timer_delete),
+    ("timer_getoverrun", libc::SYS_timer_getoverrun),
+    ("timer_gettime", libc::SYS_timer_gettime),
+    ("timer_settime", libc::SYS_timer_settime),
+    ("timerfd_create", libc::SYS_timerfd_create),
+    ("timerfd_gettime", libc::SYS_timerfd_gettime),
+    ("timerfd_settime", libc::SYS_timerfd_settime),
+    ("times", libc::SYS_times),
+    ("tkill", libc::SYS_tkill),
+    ("truncate", libc::SYS_truncate),
+    ("umask", libc::SYS_umask),
+    ("umount2", libc::SYS_umount2),
+    ("uname", libc::SYS_uname),
+    ("unlinkat", libc::SYS_unlinkat),
+    ("unshare", libc::SYS_unshare),
+    ("userfaultfd", libc::SYS_userfaultfd),
+    ("utimensat", libc::SYS_utimensat),
+    ("vhangup", libc::SYS_vhangup),
+    ("vmsplice", libc::SYS_vmsplice),
+    ("wait4", libc::SYS_wait4),
+    ("waitid", libc::SYS_waitid),
+    ("write", libc::SYS_write),
+    ("writev", libc::SYS_writev),
+];
+
+/// the system calls only available on x86_64
+#[cfg(target_arch = "x86_64")]
+const SYSCALLS_ARCH: &[(&str, libc::c_long)] = &[
+    ("_sysctl", libc::SYS__sysctl),
+    ("access", libc::SYS_access),
+    ("afs_syscall", libc::SYS_afs_syscall),
+    ("alarm", libc::SYS_alarm),
+    ("arch_prctl", libc::SYS_arch_prctl),
+    ("chmod", libc::SYS_chmod),
+    ("chown", libc::SYS_chown),
+    ("creat", libc::SYS_creat),
+    ("create_module", libc::SYS_create_module),
+    ("dup2", libc::SYS_dup2),
+    ("epoll_create", libc::SYS_epoll_create),
+    ("epoll_ctl_old", libc::SYS_epoll_ctl_old),
+    ("epoll_wait", libc::SYS_epoll_wait),
+    ("epoll_wait_old", libc::SYS_epoll_wait_old),
+    ("eventfd", libc::SYS_eventfd),
+    ("fadvise64", libc::SYS_fadvise64),
+    ("fork", libc::SYS_fork),
+    ("futimesat", libc::SYS_futimesat),
+    ("get_kernel_syms", libc::SYS_get_kernel_syms),
+    ("get_thread_area", libc::SYS_get_thread_area),
+    ("getdents", libc::SYS_getdents),
+    ("getpgrp", libc::SYS_getpgrp),
+    ("getpmsg", libc::SYS_getpmsg),
+    ("inotify_init", libc::SYS_inotify_init),
+    ("ioperm", libc::SYS_ioperm),
+    ("iopl", libc::SYS_iopl),
+    ("kexec_file_load", libc::SYS_kexec_file_load),
+    ("lchown", libc::SYS_lchown),
+    ("link", libc::SYS_link),
+    ("lstat", libc::SYS_lstat),
+    ("mkdir", libc::SYS_mkdir),
+    ("mknod", libc::SYS_mknod),
+    ("modify_ldt", libc::SYS_modify_ldt),
+    ("open", libc::SYS_open),
+    ("pause", libc::SYS_pause),
+    ("pipe", libc::SYS_pipe),
+    ("poll", libc::SYS_poll),
+    ("putpmsg", libc::SYS_putpmsg),
+    ("query_module", libc::SYS_query_module),
+    ("readlink", libc::SYS_readlink),
+    ("rename", libc::SYS_rename),
+    ("renameat", libc::SYS_renameat),
+    ("rmdir", libc::SYS_rmdir),
+    ("security", libc::SYS_security),
+    ("select", libc::SYS_select),
+    ("sendfile", libc::SYS_sendfile),
+    ("set_thread_area", libc::SYS_set_thread_area),
+    ("signalfd", libc::SYS_signalfd),
+    ("stat", libc::SYS_stat),
+    ("symlink", libc::SYS_symlink),
+    ("sysfs", libc::SYS_sysfs),
+    ("time", libc::SYS_time),
+    ("tuxcall", libc::SYS_tuxcall),
+    ("unlink", libc::SYS_unlink),
+    ("uselib", libc::SYS_uselib),
+    ("ustat", libc::SYS_ustat),
+    ("utime", libc::SYS_utime),
+    ("utimes", libc::SYS_utimes),
+    ("vfork", libc::SYS_vfork),
+    ("vserver", libc::SYS_vserver),
+];
+
+/// the system calls only available on aarch64
+#[cfg(target_arch = "aarch64")]
+const SYSCALLS_ARCH: &[(&str, libc::c_long)] = &[
+    ("kexec_file_load", libc::SYS_kexec_file_load),
+    ("renameat", libc::SYS_renameat),
+];
+
+/// the system calls only available on riscv64
+#[cfg(target_arch = "riscv64")]
+const SYSCALLS_ARCH: &[(&str, libc::c_long)] = &[
+    ("fadvise64", libc::SYS_fadvise64),
+    ("sendfile", libc::SYS_sendfile),
+];
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
+const SYSCALLS_COMMON: &[(&str, libc::c_long)] = &[];
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
+const SYSCALLS_ARCH: &[(&str, libc::c_long)] = &[];
+
+/// the predefined system call groups, a group can include other groups
+const SYSCALL_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "@aio",
+        &[
+            "io_cancel",
+            "io_destroy",
+            "io_getevents",
+            "io_pgetevents",
+            "io_setup",
+            "io_submit",
+            "io_uring_enter",
+            "io_uring_register",
+            "io_uring_setup",
+        ],
+    ),
+    (
+        "@basic-io",
+        &[
+            "close",
+            "close_range",
+            "dup",
+            "dup2",
+            "dup3",
+            "lseek",
+            "pread64",
+            "preadv",
+            "preadv2",
+            "pwrite64",
+            "pwritev",
+            "pwritev2",
+            "read",
+            "readv",
+            "write",
+            "writev",
+        ],
+    ),
+    ("@chown", &["chown", "fchown", "fchownat", "lchown"]),
+    (
+        "@clock",
+        &["adjtimex", "clock_adjtime", "clock_settime", "settimeofday"],
+    ),
+    (
+        "@credentials",
+        &[
+            "capget",
+            "getegid",
+            "geteuid",
+            "getgid",
+            "getgroups",
+            "getresgid",
+            "getresuid",
+            "getuid",
+        ],
+    ),
+    (
+        "@debug",
+        &["lookup_dcookie", "perf_event_open", "pidfd_getfd", "ptrace"],
+    ),
+    (
+        "@default",
+        &[
+            "arch_prctl",
+            "brk",
+            "clock_getres",
+            "clock_gettime",
+            "clock_nanosleep",
+            "execve",
+            "exit",
+            "exit_group",
+            "futex",
+            "get_robust_list",
+            "get_thread_area",
+            "getegid",
+            "geteuid",
+            "getgid",
+            "getgroups",
+            "getpgid",
+            "getpgrp",
+            "getpid",
+            "getppid",
+            "getrandom",
+            "getresgid",
+            "getresuid",
+            "getrlimit",
+            "getsid",
+            "gettid",
+            "gettimeofday",
+            "getuid",
+            "membarrier",
+            "mmap",
+            "mprotect",
+            "munmap",
+            "nanosleep",
+            "pause",
+            "prlimit64",
+            "restart_syscall",
+            "rseq",
+            "rt_sigreturn",
+            "sched_getaffinity",
+            "sched_yield",
+            "set_robust_list",
+            "set_thread_area",
+            "set_tid_address",
+            "time",
+        ],
+    ),
+    (
+        "@file-system",
+        &[
+            "access",
+            "chdir",
+            "chmod",
+            "close",
+            "creat",
+            "faccessat",
+            "faccessat2",
+            "fallocate",
+            "fchdir",
+            "fchmod",
+            "fchmodat",
+            "fcntl",
+            "fgetxattr",
+            "flistxattr",
+            "fremovexattr",
+            "fsetxattr",
+            "fstat",
+            "fstatfs",
+            "ftruncate",
+            "futimesat",
+            "getcwd",
+            "getdents",
+            "getdents64",
+            "getxattr",
+            "inotify_add_watch",
+            "inotify_init",
+            "inotify_init1",
+            "inotify_rm_watch",
+            "lgetxattr",
+            "link",
+            "linkat",
+            "listxattr",
+            "llistxattr",
+            "lremovexattr",
+            "lsetxattr",
+            "lstat",
+            "mkdir",
+            "mkdirat",
+            "mknod",
+            "mknodat",
+            "newfstatat",
+            "open",
+            "openat",
+            "openat2",
+            "readlink",
+            "readlinkat",
+            "removexattr",
+            "rename",
+            "renameat",
+            "renameat2",
+            "rmdir",
+            "setxattr",
+            "stat",
+            "statfs",
+            "statx",
+            "symlink",
+            "symlinkat",
+            "truncate",
+            "unlink",
+            "unlinkat",
+            "utime",
+            "utimensat",
+            "utimes",
+        ],
+    ),
+    (
+        "@io-event",
+        &[
+            "epoll_create",
+            "epoll_create1",
+            "epoll_ctl",
+            "epoll_pwait",
+            "epoll_wait",
+            "eventfd",
+            "eventfd2",
+            "poll",
+            "ppoll",
+            "pselect6",
+            "select",
+        ],
+    ),
+    (
+        "@ipc",
+        &[
+            "memfd_create",
+            "mq_getsetattr",
+            "mq_notify",
+            "mq_open",
+            "mq_timedreceive",
+            "mq_timedsend",
+            "mq_unlink",
+            "msgctl",
+            "msgget",
+            "msgrcv",
+            "msgsnd",
+            "pipe",
+            "pipe2",
+            "process_vm_readv",
+            "process_vm_writev",
+            "semctl",
+            "semget",
+            "semop",
+            "semtimedop",
+            "shmat",
+            "shmctl",
+            "shmdt",
+            "shmget",
+        ],
+    ),
+    ("@keyring", &["add_key", "keyctl", "request_key"]),
+    (
+        "@memlock",
+        &["mlock", "mlock2", "mlockall", "munlock", "munlockall"],
+    ),
+    ("@module", &["delete_module", "finit_module", "init_module"]),
+    (
+        "@mount",
+        &[
+            "chroot",
+            "fsconfig",
+            "fsmount",
+            "fsopen",
+            "fspick",
+            "mount",
+            "move_mount",
+            "open_tree",
+            "pivot_root",
+            "umount2",
+        ],
+    ),
+    (
+        "@network-io",
+        &[
+            "accept",
+            "accept4",
+            "bind",
+            "connect",
+            "getpeername",
+            "getsockname",
+            "getsockopt",
+            "listen",
+            "recvfrom",
+            "recvmmsg",
+            "recvmsg",
+            "sendmmsg",
+            "sendmsg",
+            "sendto",
+            "setsockopt",
+            "shutdown",
+            "socket",
+            "socketpair",
+        ],
+    ),
+    (
+        "@obsolete",
+        &[
+            "_sysctl",
+            "afs_syscall",
+            "create_module",
+            "get_kernel_syms",
+            "getpmsg",
+            "putpmsg",
+            "query_module",
+            "security",
+            "sysfs",
+            "tuxcall",
+            "uselib",
+            "ustat",
+            "vserver",
+        ],
+    ),
+    (
+        "@privileged",
+        &[
+            "@chown",
+            "@clock",
+            "@module",
+            "@raw-io",
+            "@reboot",
+            "@swap",
+            "_sysctl",
+            "acct",
+            "bpf",
+            "capset",
+            "chroot",
+            "fanotify_init",
+            "fanotify_mark",
+            "open_by_handle_at",
+            "pivot_root",
+            "quotactl",
+            "setdomainname",
+            "setfsgid",
+            "setfsuid",
+            "setgid",
+            "setgroups",
+            "sethostname",
+            "setregid",
+            "setresgid",
+            "setresuid",
+            "setreuid",
+            "setuid",
+            "vhangup",
+        ],
+    ),
+    (
+        "@process",
+        &[
+            "capget",
+            "clone",
+            "clone3",
+            "execveat",
+            "fork",
+            "getrusage",
+            "kill",
+            "pidfd_open",
+            "pidfd_send_signal",
+            "prctl",
+            "rt_sigqueueinfo",
+            "rt_tgsigqueueinfo",
+            "setns",
+            "tgkill",
+            "times",
+            "tkill",
+            "unshare",
+            "vfork",
+            "wait4",
+            "waitid",
+        ],
+    ),
+    ("@raw-io", &["ioperm", "iopl"]),
+    ("@reboot", &["kexec_file_load", "kexec_load", "reboot"]),
+    (
+        "@resources",
+        &[
+            "ioprio_set",
+            "mbind",
+            "migrate_pages",
+            "move_pages",
+            "prlimit64",
+            "sched_setaffinity",
+            "sched_setattr",
+            "sched_setparam",
+            "sched_setscheduler",
+            "set_mempolicy",
+            "setpriority",
+            "setrlimit",
+        ],
+    ),
+    (
+        "@setuid",
+        &[
+            "setgid",
+            "setgroups",
+            "setregid",
+            "setresgid",
+            "setresuid",
+            "setreuid",
+            "setuid",
+        ],
+    ),
+    (
+        "@signal",
+        &[
+            "rt_sigaction",
+            "rt_sigpending",
+            "rt_sigprocmask",
+            "rt_sigsuspend",
+            "rt_sigtimedwait",
+            "sigaltstack",
+            "signalfd",
+            "signalfd4",
+        ],
+    ),
+    ("@swap", &["swapoff", "swapon"]),
+    (
+        "@sync",
+        &[
+            "fdatasync",
+            "fsync",
+            "msync",
+            "sync",
+            "sync_file_range",
+            "syncfs",
+        ],
+    ),
+    (
+        "@system-service",
+        &[
+            "@aio",
+            "@basic-io",
+            "@chown",
+            "@default",
+            "@file-system",
+            "@io-event",
+            "@ipc",
+            "@keyring",
+            "@memlock",
+            "@network-io",
+            "@process",
+            "@resources",
+            "@setuid",
+            "@signal",
+            "@sync",
+            "@timer",
+            "capget",
+            "capset",
+            "copy_file_range",
+            "fadvise64",
+            "flock",
+            "get_mempolicy",
+            "getcpu",
+            "getpriority",
+            "ioctl",
+            "ioprio_get",
+            "kcmp",
+            "madvise",
+            "mincore",
+            "mremap",
+            "name_to_handle_at",
+            "personality",
+            "readahead",
+            "remap_file_pages",
+            "sched_get_priority_max",
+            "sched_get_priority_min",
+            "sched_getattr",
+            "sched_getparam",
+            "sched_getscheduler",
+            "sched_rr_get_interval",
+            "sendfile",
+            "setfsgid",
+            "setfsuid",
+            "setpgid",
+            "setsid",
+            "splice",
+            "sysinfo",
+            "tee",
+            "umask",
+            "uname",
+            "userfaultfd",
+            "vmsplice",
+        ],
+    ),
+    (
+        "@timer",
+        &[
+            "alarm",
+            "getitimer",
+            "setitimer",
+            "timer_create",
+            "timer_delete",
+            "timer_getoverrun",
+            "timer_gettime",
+            "timer_settime",
+            "timerfd_create",
+            "timerfd_gettime",
+            "timerfd_settime",
+            "times",
+        ],
+    ),
+];
+
+/// the errno names accepted by SystemCallErrorNumber
+const ERRNO_NAMES: &[(&str, i32)] = &[
+    ("EPERM", libc::EPERM),
+    ("ENOENT", libc::ENOENT),
+    ("ESRCH", libc::ESRCH),
+    ("EINTR", libc::EINTR),
+    ("EIO", libc::EIO),
+    ("ENXIO", libc::ENXIO),
+    ("E2BIG", libc::E2BIG),
+    ("ENOEXEC", libc::ENOEXEC),
+    ("EBADF", libc::EBADF),
+    ("ECHILD", libc::ECHILD),
+    ("EAGAIN", libc::EAGAIN),
+    ("ENOMEM", libc::ENOMEM),
+    ("EACCES", libc::EACCES),
+    ("EFAULT", libc::EFAULT),
+    ("EBUSY", libc::EBUSY),
+    ("EEXIST", libc::EEXIST),
+    ("EXDEV", libc::EXDEV),
+    ("ENODEV", libc::ENODEV),
+    ("ENOTDIR", libc::ENOTDIR),
+    ("EISDIR", libc::EISDIR),
+    ("EINVAL", libc::EINVAL),
+    ("ENFILE", libc::ENFILE),
+    ("EMFILE", libc::EMFILE),
+    ("ENOTTY", libc::ENOTTY),
+    ("ETXTBSY", libc::ETXTBSY),
+    ("EFBIG", libc::EFBIG),
+    ("ENOSPC", libc::ENOSPC),
+    ("ESPIPE", libc::ESPIPE),
+    ("EROFS", libc::EROFS),
+    ("EMLINK", libc::EMLINK),
+    ("EPIPE", libc::EPIPE),
+    ("ENOSYS", libc::ENOSYS),
+    ("ENOTSUP", libc::ENOTSUP),
+    ("EOPNOTSUPP", libc::EOPNOTSUPP),
+    ("ENOTSOCK", libc::ENOTSOCK),
+    ("EPROTONOSUPPORT", libc::EPROTONOSUPPORT),
+    ("EAFNOSUPPORT", libc::EAFNOSUPPORT),
+    ("ENETUNREACH", libc::ENETUNREACH),
+    ("ECONNREFUSED", libc::ECONNREFUSED),
+    ("ETIMEDOUT", libc::ETIMEDOUT),
+];
+
+/// parse the errno name like "EPERM" or the errno number
+pub fn parse_errno(s: &str) -> Option<i32> {
+    if let Ok(v) = s.parse::<i32>() {
+        if v > 0 && v < 4096 {
+            return Some(v);
+        }
+        return None;
+    }
+    ERRNO_NAMES
+        .iter()
+        .find(|(name, _)| *name == s)
+        .map(|(_, v)| *v)
+}
+
+/// get the number of the system call on the native architecture
+pub fn syscall_number(name: &str) -> Option<u32> {
+    SYSCALLS_COMMON
+        .iter()
+        .chain(SYSCALLS_ARCH.iter())
+        .find(|(n, _)| *n == name)
+        .map(|(_, nr)| *nr as u32)
+}
+
+fn syscall_group(name: &str) -> Option<&'static [&'static str]> {
+    SYSCALL_GROUPS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, members)| *members)
+}
+
+/// whether the name is a system call of the native architecture or a predefined group
+pub fn syscall_name_is_valid(name: &str) -> bool {
+    if name.starts_with('@') {
+        return syscall_group(name).is_some();
+    }
+    /* The names can't be checked without the system call table, the filter
+     * fails when it's loaded. */
+    if !ARCH_SUPPORTED {
+        return !name.is_empty();
+    }
+    syscall_number(name).is_some()
+}
+
+fn syscall_expand_one(name: &str, res: &mut BTreeSet<u32>) {
+    match syscall_group(name) {
+        Some(members) => {
+            for m in members {
+                syscall_expand_one(m, res);
+            }
+        }
+        /* The group members may be not available on this architecture, skip them. */
+        None => {
+            if let Some(nr) = syscall_number(name) {
+                res.insert(nr);
+            }
+        }
+    }
+}
+
+/// expand the system call names and groups to the system call numbers
+pub fn syscall_expand<'a>(names: impl IntoIterator<Item = &'a String>) -> BTreeSet<u32> {
+    let mut res = BTreeSet::new();
+    for name in names {
+        syscall_expand_one(name, &mut res);
+    }
+    res
+}
+
+/// the action taken by the seccomp filter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeccompAction {
+    /// allow the system call
+    Allow,
+    /// fail the system call with the errno
+    Errno(i32),
+    /// kill the whole process
+    KillProcess,
+}
+
+impl SeccompAction {
+    fn ret(&self) -> u32 {
+        match self {
+            SeccompAction::Allow => SECCOMP_RET_ALLOW,
+            SeccompAction::Errno(e) => SECCOMP_RET_ERRNO | (*e as u32 & SECCOMP_RET_DATA),
+            SeccompAction::KillProcess => SECCOMP_RET_KILL_PROCESS,
+        }
+    }
+}
+
+/// the classic BPF instruction, the same as struct sock_filter
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SockFilter {
+    code: u16,
+    jt: u8,
+    jf: u8,
+    k: u32,
+}
+
+impl SockFilter {
+    fn stmt(code: u16, k: u32) -> Self {
+        SockFilter {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    fn jump(code: u16, k: u32, jt: u8, jf: u8) -> Self {
+        SockFilter { code, jt, jf, k }
+    }
+}
+
+#[repr(C)]
+struct SockFprog {
+    len: libc::c_ushort,
+    filter: *const SockFilter,
+}
+
+/// the seccomp filter of the system calls
+pub struct SeccompFilter {
+    syscalls: BTreeSet<u32>,
+    match_action: SeccompAction,
+    default_action: SeccompAction,
+    arch_action: SeccompAction,
+}
+
+impl SeccompFilter {
+    /// create a filter, match_action is taken for the added system calls,
+    /// default_action for the others and arch_action for the system calls
+    /// from the non-native architectures.
+    pub fn new(
+        match_action: SeccompAction,
+        default_action: SeccompAction,
+        arch_action: SeccompAction,
+    ) -> Self {
+        SeccompFilter {
+            syscalls: BTreeSet::new(),
+            match_action,
+            default_action,
+            arch_action,
+        }
+    }
+
+    /// the action taken for the system calls from the non-native architectures
+    pub fn arch_action(&self) -> SeccompAction {
+        self.arch_action
+    }
+
+    /// add the system calls taking match_action
+    pub fn add_syscalls(&mut self, syscalls: &BTreeSet<u32>) {
+        self.syscalls.extend(syscalls.iter());
+    }
+
+    /// compile the filter to the BPF program
+    pub fn compile(&self) -> Vec<SockFilter> {
+        let mut prog = vec![
+            SockFilter::stmt(BPF_LD | BPF_W | BPF_ABS, SECCOMP_DATA_ARCH),
+            SockFilter::jump(BPF_JMP | BPF_JEQ | BPF_K, AUDIT_ARCH_NATIVE, 1, 0),
+            SockFilter::stmt(BPF_RET | BPF_K, self.arch_action.ret()),
+            SockFilter::stmt(BPF_LD | BPF_W | BPF_ABS, SECCOMP_DATA_NR),
+        ];
+
+        /* The x32 system calls share the arch with x86_64, treat them as non-native. */
+        #[cfg(target_arch = "x86_64")]
+        {
+            prog.push(SockFilter::jump(
+                BPF_JMP | BPF_JGE | BPF_K,
+                X32_SYSCALL_BIT,
+                0,
+                1,
+            ));
+            prog.push(SockFilter::stmt(BPF_RET | BPF_K, self.arch_action.ret()));
+        }
+
+        /* Use a pair of instructions for each system call, the jump offsets
+         * of the classic BPF are limited to 255. */
+        if self.match_action != self.default_action {
+            for nr in &self.syscalls {
+                prog.push(SockFilter::jump(BPF_JMP | BPF_JEQ | BPF_K, *nr, 0, 1));
+                prog.push(SockFilter::stmt(BPF_RET | BPF_K, self.match_action.ret()));
+            }
+        }
+        prog.push(SockFilter::stmt(BPF_RET | BPF_K, self.default_action.ret()));
+        prog
+    }
+
+    /// load the filter into the kernel for the current process
+    ///
+    /// PR_SET_NO_NEW_PRIVS is set if the process doesn't have CAP_SYS_ADMIN.
+    /// EOPNOTSUPP is returned on the architectures whose system calls are unknown.
+    pub fn load(&self) -> crate::Result<()> {
+        if !ARCH_SUPPORTED {
+            return Err(crate::Error::Nix {
+                source: Errno::EOPNOTSUPP,
+            });
+        }
+
+        let prog = self.compile();
+        let fprog = SockFprog {
+            len: prog.len() as libc::c_ushort,
+            filter: prog.as_ptr(),
+        };
+
+        let set_seccomp = || unsafe {
+            libc::prctl(
+                libc::PR_SET_SECCOMP,
+                SECCOMP_MODE_FILTER,
+                &fprog as *const SockFprog,
+                0,
+                0,
+            )
+        };
+
+        if set_seccomp() == 0 {
+            return Ok(());
+        }
+        if Errno::last() != Errno::EACCES {
+            return Err(crate::Error::Nix {
+                source: Errno::last(),
+            });
+        }
+
+        if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } < 0 {
+            return Err(crate::Error::Nix {
+                source: Errno::last(),
+            });
+        }
+        if set_seccomp() < 0 {
+            return Err(crate::Error::Nix {
+                source: Errno::last(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    ))]
+    fn test_syscall_number() {
+        assert_eq!(syscall_number("read"), Some(libc::SYS_read as u32));
+        assert_eq!(syscall_number("execve"), Some(libc::SYS_execve as u32));
+        assert_eq!(syscall_number("foo"), None);
+        assert!(syscall_name_is_valid("@system-service"));
+        assert!(!syscall_name_is_valid("@foo"));
+    }
+
+    #[test]
+    #[cfg(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    ))]
+    fn test_syscall_expand() {
+        let names = vec!["@system-service".to_string()];
+        let res = syscall_expand(&names);
+        /* nested groups are expanded */
+        assert!(res.contains(&(libc::SYS_read as u32)));
+        assert!(res.contains(&(libc::SYS_socket as u32)));
+        assert!(res.contains(&(libc::SYS_execve as u32)));
+        assert!(!res.contains(&(libc::SYS_reboot as u32)));
+    }
+
+    #[test]
+    fn test_parse_errno() {
+        assert_eq!(parse_errno("EPERM"), Some(libc::EPERM));
+        assert_eq!(parse_errno("13"), Some(13));
+        assert_eq!(parse_errno("0"), None);
+        assert_eq!(parse_errno("EFOO"), None);
+    }
+
+    #[test]
+    fn test_compile() {
+        let mut filter = SeccompFilter::new(
+            SeccompAction::Errno(libc::EPERM),
+            SeccompAction::Allow,
+            SeccompAction::KillProcess,
+        );
+        let mut syscalls = BTreeSet::new();
+        syscalls.insert(libc::SYS_reboot as u32);
+        filter.add_syscalls(&syscalls);
+        let prog = filter.compile();
+        assert_eq!(
+            prog[2],
+            SockFilter::stmt(BPF_RET | BPF_K, SECCOMP_RET_KILL_PROCESS)
+        );
+
+        let header = if cfg!(target_arch = "x86_64") { 6 } else { 4 };
+        assert_eq!(prog.len(), header + 3);
+        assert_eq!(
+            prog[header],
+            SockFilter::jump(BPF_JMP | BPF_JEQ | BPF_K, libc::SYS_reboot as u32, 0, 1)
+        );
+        assert_eq!(
+            prog[header + 1],
+            SockFilter::stmt(BPF_RET | BPF_K, SECCOMP_RET_ERRNO | libc::EPERM as u32)
+        );
+        assert_eq!(
+            prog[header + 2],
+            SockFilter::stmt(BPF_RET | BPF_K, SECCOMP_RET_ALLOW)
+        );
+    }
+}
@@ -57,6 +57,35 @@ pub fn unit_name_unescape(s: &str) -> Result<String, Errno> {
     }
 }
 
+/// Escape the string to be used in a unit name, '/' is turned into '-', and the characters
+/// which are not allowed in unit names are turned into "\xNN".
+pub fn unit_name_escape(s: &str) -> String {
+    let mut ret = String::new();
+    for (i, c) in s.bytes().enumerate() {
+        match c {
+            b'/' => ret.push('-'),
+            /* a leading dot would make a hidden file */
+            b'.' if i == 0 => ret.push_str("\\x2e"),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b':' | b'_' | b'.' => ret.push(c as char),
+            _ => ret.push_str(&format!("\\x{:02x}", c)),
+        }
+    }
+    ret
+}
+
+/// Convert the path to the unit name with the given suffix, e.g. "/dev/sda1" and ".device"
+/// become "dev-sda1.device", the root directory becomes "-".
+pub fn unit_name_from_path(path: &str, suffix: &str) -> String {
+    let parts: Vec<&str> = path
+        .split('/')
+        .filter(|p| !p.is_empty() && *p != ".")
+        .collect();
+    if parts.is_empty() {
+        return format!("-{}", suffix);
+    }
+    format!("{}{}", unit_name_escape(&parts.join("/")), suffix)
+}
+
 /// Get the content between the first '@' and the last '.' from unit name.
 pub fn unit_name_to_instance(unit_name: &str) -> CString {
     let mut p = match unit_name.find('@') {
@@ -75,3 +104,27 @@ pub fn unit_name_to_instance(unit_name: &str) -> CString {
 
     CString::new(&unit_name[p..d]).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_name_from_path() {
+        assert_eq!(unit_name_from_path("/dev/sda1", ".swap"), "dev-sda1.swap");
+        assert_eq!(unit_name_from_path("/", ".mount"), "-.mount");
+        assert_eq!(
+            unit_name_from_path("//var/./swapfile/", ".swap"),
+            "var-swapfile.swap"
+        );
+        assert_eq!(
+            unit_name_from_path("/dev/mapper/vg-swap", ".swap"),
+            "dev-mapper-vg\\x2dswap.swap"
+        );
+        assert_eq!(unit_name_from_path("/.hidden", ".swap"), "\\x2ehidden.swap");
+        assert_eq!(
+            unit_name_unescape("dev-mapper-vg\\x2dswap").unwrap(),
+            "dev/mapper/vg-swap"
+        );
+    }
+}
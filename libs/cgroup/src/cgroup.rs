@@ -18,6 +18,7 @@ use basic::CGROUP_SYSMASTER;
 use basic::INIT_SCOPE;
 use basic::IN_SET;
 use basic::SYSMASTER_SLICE;
+use lazy_static::lazy_static;
 use nix::libc;
 use nix::sys::signal::Signal;
 use nix::sys::statfs::{statfs, FsType};
@@ -29,6 +30,7 @@ use std::io;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::RwLock;
 use walkdir::{DirEntry, WalkDir};
 
 #[cfg(target_env = "musl")]
@@ -42,6 +44,8 @@ type FsTypeT = libc::c_long;
 pub const CG_BASE_DIR: &str = "/sys/fs/cgroup";
 #[cfg(feature = "linux")]
 const CGROUP_PROCS: &str = "cgroup.procs";
+#[cfg(feature = "linux")]
+const CGROUP_SUBTREE_CONTROL: &str = "cgroup.subtree_control";
 
 const CG_UNIFIED_DIR: &str = "/sys/fs/cgroup/unified";
 const CG_V1_DIR: &str = "/sys/fs/cgroup/sysmaster";
@@ -54,6 +58,21 @@ pub const CG_BASE_DIR: &str = "/run/sysmaster/cgroup";
 #[cfg(feature = "hongmeng")]
 const CGROUP_PROCS: &str = "procs";
 
+lazy_static! {
+    /// the cgroup all the cg_path are relative to, empty means the root of the hierarchy
+    static ref CG_ROOT: RwLock<String> = RwLock::new(String::new());
+}
+
+/// set the cgroup all the cg_path are relative to, i.e. the subtree delegated to a user manager.
+pub fn cg_set_root(root: &Path) {
+    let root = root.to_string_lossy();
+    *CG_ROOT.write().unwrap() = root.trim_start_matches('/').to_string();
+}
+
+fn cg_root() -> PathBuf {
+    PathBuf::from(CG_ROOT.read().unwrap().as_str())
+}
+
 /// return the cgroup mounted type, if not support cgroup return CgroupErr.
 pub fn cg_type() -> Result<CgType> {
     let stat = statfs(CG_BASE_DIR).map_err(|_| Error::NotSupported)?;
@@ -112,13 +131,13 @@ fn cg_abs_path(cg_path: &Path, suffix: &Path) -> Result<PathBuf> {
     let base_path = cgtype_to_path(cg_type);
     log::debug!("cgroup root path is: {}", base_path);
     let path_buf: PathBuf = PathBuf::from(base_path);
-    Ok(path_buf.join(cg_path).join(suffix))
+    Ok(path_buf.join(cg_root()).join(cg_path).join(suffix))
 }
 
 #[cfg(feature = "hongmeng")]
 fn cg_abs_path(cg_path: &Path, suffix: &Path) -> Result<PathBuf> {
     let path_buf: PathBuf = PathBuf::from(CG_BASE_DIR);
-    Ok(path_buf.join(cg_path).join(suffix))
+    Ok(path_buf.join(cg_root()).join(cg_path).join(suffix))
 }
 
 /// attach the pid to the controller which is depend the cg_path
@@ -152,11 +171,110 @@ pub fn cg_create(cg_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// remove the cg_path which is relative to cg_abs_path, and the sub cgroups in it.
+/// the cgroups must be empty, it's not an error if the cg_path does not exist.
+pub fn cg_remove(cg_path: &Path) -> Result<()> {
+    log::debug!("cgroup remove path {:?}", cg_path);
+    let abs_cg_path: PathBuf = cg_abs_path(cg_path, &PathBuf::from(""))?;
+    if !abs_cg_path.exists() {
+        return Ok(());
+    }
+
+    remove_dir(&abs_cg_path)
+}
+
+/// return the absolute path of the attribute file of the cgroup, i.e. cgroup.events.
+pub fn cg_attribute_path(cg_path: &Path, attribute: &str) -> Result<PathBuf> {
+    cg_abs_path(cg_path, &PathBuf::from(attribute))
+}
+
+/// write the value to the attribute file of the cgroup, i.e. memory.max.
+pub fn cg_set_attribute(cg_path: &Path, attribute: &str, value: &str) -> Result<()> {
+    log::debug!("cgroup set {:?} {} to {}", cg_path, attribute, value);
+    let attr_path = cg_abs_path(cg_path, &PathBuf::from(attribute))?;
+    if !attr_path.exists() {
+        return Err(Error::NotFound {
+            what: attr_path.to_string_lossy().to_string(),
+        });
+    }
+
+    fs::write(attr_path, value).context(IoSnafu)?;
+
+    Ok(())
+}
+
+/// read the attribute file of the cgroup, i.e. memory.current, the trailing newline is removed.
+pub fn cg_get_attribute(cg_path: &Path, attribute: &str) -> Result<String> {
+    let attr_path = cg_abs_path(cg_path, &PathBuf::from(attribute))?;
+    let value = fs::read_to_string(attr_path).context(IoSnafu)?;
+    Ok(value.trim_end().to_string())
+}
+
+/// enable the controllers for the cg_path, the controllers are enabled in
+/// cgroup.subtree_control of all the ancestors of cg_path, only valid in cgroup v2.
+#[cfg(feature = "linux")]
+pub fn cg_enable_controllers(cg_path: &Path, controllers: &[&str]) -> Result<()> {
+    if cg_type()? != CgType::UnifiedV2 {
+        return Err(Error::NotSupported);
+    }
+
+    let mut ancestors: Vec<&Path> = cg_path.ancestors().skip(1).collect();
+    ancestors.reverse();
+    for ancestor in ancestors {
+        let subtree = cg_abs_path(ancestor, &PathBuf::from(CGROUP_SUBTREE_CONTROL))?;
+        let enabled = fs::read_to_string(&subtree).context(IoSnafu)?;
+        for controller in controllers {
+            if enabled.split_whitespace().any(|c| c == *controller) {
+                continue;
+            }
+            fs::write(&subtree, format!("+{}", controller)).context(IoSnafu)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// escape the cg_path which is conflicts with controller name.
 pub fn cg_escape(id: &str) -> &str {
     id
 }
 
+/// convert the slice name to the cgroup path, every '-' in the name
+/// introduces one more level, i.e. a-b.slice -> a.slice/a-b.slice
+pub fn cg_slice_to_path(slice: &str) -> Result<PathBuf> {
+    if slice == "-.slice" {
+        return Ok(PathBuf::new());
+    }
+
+    let prefix = match slice.strip_suffix(".slice") {
+        Some(p) => p,
+        None => {
+            return Err(Error::DataFormat {
+                data: slice.to_string(),
+            })
+        }
+    };
+
+    if prefix.is_empty()
+        || prefix.starts_with('-')
+        || prefix.ends_with('-')
+        || prefix.contains("--")
+        || prefix.contains('/')
+    {
+        return Err(Error::DataFormat {
+            data: slice.to_string(),
+        });
+    }
+
+    let mut path = PathBuf::new();
+    for (i, _) in prefix.match_indices('-') {
+        path.push(format!("{}.slice", cg_escape(&prefix[..i])));
+    }
+    path.push(cg_escape(slice));
+
+    Ok(path)
+}
+
 fn get_pids(cg_path: &Path, item: &str) -> Result<Vec<Pid>> {
     let path = cg_abs_path(cg_path, &PathBuf::from(item))?;
     let file = fs::OpenOptions::new()
@@ -328,7 +446,8 @@ fn cg_kill(cg_path: &Path, signal: Signal, flags: CgFlags, pids: HashSet<Pid>) -
 /// kill all the process in the cg_path, and remove the dir of the cg_path.
 /// cg_path: the controller that will be killed.
 /// signal: send signal to the process in the cgroup.
-/// flags: the flags that will be operated on the controller.
+/// flags: the flags that will be operated on the controller, the sub cgroups are
+/// killed too with RECURSIVE.
 /// pids: not kill the process which is in the pids.
 pub fn cg_kill_recursive(
     cg_path: &Path,
@@ -337,8 +456,25 @@ pub fn cg_kill_recursive(
     pids: HashSet<Pid>,
 ) -> Result<()> {
     // kill cgroups
-    // todo kill sub cgroups
-    cg_kill(cg_path, signal, flags, pids)?;
+    cg_kill(cg_path, signal, flags, pids.clone())?;
+
+    // kill sub cgroups
+    if flags.contains(CgFlags::RECURSIVE) {
+        let abs_cg_path = cg_abs_path(cg_path, &PathBuf::from(""))?;
+        if let Ok(entries) = fs::read_dir(abs_cg_path) {
+            for entry in entries.flatten() {
+                if !entry.file_type().map_or(false, |t| t.is_dir()) {
+                    continue;
+                }
+                cg_kill_recursive(
+                    &cg_path.join(entry.file_name()),
+                    signal,
+                    flags,
+                    pids.clone(),
+                )?;
+            }
+        }
+    }
 
     if flags.contains(CgFlags::REMOVE) {
         let abs_cg_path = cg_abs_path(cg_path, &PathBuf::from(""))?;
@@ -506,6 +642,24 @@ pub fn cg_is_empty_recursive(cg_path: &PathBuf) -> Result<bool> {
     return Ok(true);
 }
 
+/// return the cgroup of the pid in the unified hierarchy, 0 means the calling process.
+pub fn cg_pid_get_path(pid: Pid) -> Result<PathBuf> {
+    let procfs = if pid.as_raw() == 0 {
+        "/proc/self/cgroup".to_string()
+    } else {
+        format!("/proc/{}/cgroup", pid)
+    };
+    let content = fs::read_to_string(procfs).context(IoSnafu)?;
+    for line in content.lines() {
+        if let Some(path) = line.strip_prefix("0::") {
+            return Ok(PathBuf::from(path));
+        }
+    }
+    Err(Error::NotFound {
+        what: "unified cgroup of the process".to_string(),
+    })
+}
+
 /// create cgroup path and attach pid to this cgroup
 pub fn cg_create_and_attach(cg_path: &Path, pid: Pid) -> Result<bool> {
     cg_create(cg_path)?;
@@ -747,6 +901,81 @@ mod tests {
         assert!(!pids.contains(&pid));
     }
 
+    #[test]
+    fn test_cg_kill_recursive() {
+        use crate::CgFlags;
+        use nix::sys::signal::Signal;
+        use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+        use nix::unistd::{fork, ForkResult};
+        use std::thread;
+        use std::{collections::HashSet, time::Duration};
+
+        if !nix::unistd::getuid().is_root() {
+            println!("Unprivileged users cannot create cgroups, skipping.");
+            return;
+        }
+        if !matches!(super::cg_type(), Ok(CgType::UnifiedV2)) {
+            println!("cgroup v2 is not mounted, skipping.");
+            return;
+        }
+
+        let cg_path = PathBuf::from("sysmaster-test-kill.slice");
+        let sub_path = cg_path.join("sub");
+        assert!(super::cg_create(&sub_path).is_ok());
+
+        let pid = match unsafe { fork() } {
+            Ok(ForkResult::Parent { child }) => child,
+            Ok(ForkResult::Child) => {
+                thread::sleep(Duration::from_secs(78));
+                std::process::exit(0);
+            }
+            Err(_e) => return,
+        };
+        assert!(super::cg_attach(pid, &sub_path).is_ok());
+
+        // the process in the sub cgroup is left alone without RECURSIVE
+        let ret =
+            super::cg_kill_recursive(&cg_path, Signal::SIGKILL, CgFlags::empty(), HashSet::new());
+        assert!(ret.is_ok());
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(
+            waitpid(pid, Some(WaitPidFlag::WNOHANG)).unwrap(),
+            WaitStatus::StillAlive
+        );
+
+        let ret = super::cg_kill_recursive(
+            &cg_path,
+            Signal::SIGKILL,
+            CgFlags::RECURSIVE,
+            HashSet::new(),
+        );
+        assert!(ret.is_ok());
+        assert!(matches!(
+            waitpid(pid, None).unwrap(),
+            WaitStatus::Signaled(_, Signal::SIGKILL, _)
+        ));
+
+        assert!(super::cg_remove(&sub_path).is_ok());
+        assert!(super::cg_remove(&cg_path).is_ok());
+    }
+
+    #[test]
+    fn test_cg_slice_to_path() {
+        assert_eq!(super::cg_slice_to_path("-.slice").unwrap(), PathBuf::new());
+        assert_eq!(
+            super::cg_slice_to_path("system.slice").unwrap(),
+            PathBuf::from("system.slice")
+        );
+        assert_eq!(
+            super::cg_slice_to_path("a-b-c.slice").unwrap(),
+            PathBuf::from("a.slice/a-b.slice/a-b-c.slice")
+        );
+        assert!(super::cg_slice_to_path("foo.service").is_err());
+        assert!(super::cg_slice_to_path("-foo.slice").is_err());
+        assert!(super::cg_slice_to_path("foo-.slice").is_err());
+        assert!(super::cg_slice_to_path("a--b.slice").is_err());
+    }
+
     #[test]
     fn test_cg_controllers() {
         if !nix::unistd::getuid().is_root() {
@@ -20,13 +20,22 @@ use bitflags::bitflags;
 mod cgroup;
 pub mod error;
 pub use crate::cgroup::cg_attach;
+pub use crate::cgroup::cg_attribute_path;
 pub use crate::cgroup::cg_controllers;
 pub use crate::cgroup::cg_create;
 pub use crate::cgroup::cg_create_and_attach;
+#[cfg(feature = "linux")]
+pub use crate::cgroup::cg_enable_controllers;
 pub use crate::cgroup::cg_escape;
+pub use crate::cgroup::cg_get_attribute;
 pub use crate::cgroup::cg_get_pids;
 pub use crate::cgroup::cg_is_empty_recursive;
 pub use crate::cgroup::cg_kill_recursive;
+pub use crate::cgroup::cg_pid_get_path;
+pub use crate::cgroup::cg_remove;
+pub use crate::cgroup::cg_set_attribute;
+pub use crate::cgroup::cg_set_root;
+pub use crate::cgroup::cg_slice_to_path;
 pub use crate::cgroup::cg_type;
 pub use crate::cgroup::CgController;
 pub use crate::cgroup::CG_BASE_DIR;
@@ -40,6 +49,8 @@ bitflags! {
         const IGNORE_SELF = 1 << 1;
         /// remove the cgroup dir agter kill it
         const REMOVE = 1 << 2;
+        /// kill the process in the sub cgroups too
+        const RECURSIVE = 1 << 3;
     }
 }
 
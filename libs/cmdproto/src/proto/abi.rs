@@ -2,7 +2,7 @@
 #[rustfmt::skip]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CommandRequest {
-    #[prost(oneof="command_request::RequestData", tags="1, 2, 3, 4, 5, 6, 7")]
+    #[prost(oneof="command_request::RequestData", tags="1, 2, 3, 4, 5, 6, 7, 8, 9, 10")]
     pub request_data: ::core::option::Option<command_request::RequestData>,
 }
 /// Nested message and enum types in `CommandRequest`.
@@ -31,6 +31,15 @@ pub mod command_request {
         ///transient unit commands
         #[prost(message, tag="7")]
         Trancomm(super::TransientUnitComm),
+        ///unit property commands
+        #[prost(message, tag="8")]
+        Pcomm(super::UnitPropertyComm),
+        ///edit the override drop-in of a unit
+        #[prost(message, tag="9")]
+        Ecomm(super::UnitEditComm),
+        ///dependency tree of a unit
+        #[prost(message, tag="10")]
+        Dcomm(super::UnitDependencyComm),
     }
 }
 /// Command Response from server
@@ -46,6 +55,185 @@ pub struct CommandResponse {
     /// if not 2xx，message include more information
     #[prost(string, tag="3")]
     pub message: ::prost::alloc::string::String,
+    /// the status of the units, for UnitComm STATUS
+    #[prost(message, repeated, tag="4")]
+    pub unit_status: ::prost::alloc::vec::Vec<UnitStatus>,
+    /// the loaded units, for MngrComm LISTUNITS
+    #[prost(message, repeated, tag="5")]
+    pub unit_list: ::prost::alloc::vec::Vec<UnitListEntry>,
+    /// the jobs in the job queue, for JobComm LIST
+    #[prost(message, repeated, tag="6")]
+    pub jobs: ::prost::alloc::vec::Vec<JobEntry>,
+    /// the properties of the unit, for UnitPropertyComm SHOW
+    #[prost(message, repeated, tag="7")]
+    pub unit_properties: ::prost::alloc::vec::Vec<transient_unit_comm::UnitProperty>,
+    /// the dependency tree of the unit, for UnitDependencyComm
+    #[prost(message, repeated, tag="8")]
+    pub dependencies: ::prost::alloc::vec::Vec<UnitDependency>,
+    /// the boot and unit timestamps, for MngrComm BOOTTIMES
+    #[prost(message, optional, tag="9")]
+    pub boot_times: ::core::option::Option<BootTimes>,
+    /// the most recent ordering cycles found in transactions, for JobComm CYCLES
+    #[prost(message, repeated, tag="10")]
+    pub ordering_cycles: ::prost::alloc::vec::Vec<OrderingCycle>,
+}
+/// One line of list-units
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitListEntry {
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub load_state: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub active_state: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub sub_state: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub description: ::prost::alloc::string::String,
+}
+/// One node of the dependency tree, in depth-first order
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitDependency {
+    /// 0 for the unit itself, 1 for its direct dependencies...
+    #[prost(uint32, tag="1")]
+    pub depth: u32,
+    #[prost(string, tag="2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub active_state: ::prost::alloc::string::String,
+}
+/// The state change timestamps of one unit, CLOCK_MONOTONIC in microseconds, 0 for never
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitTimes {
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub active_state: ::prost::alloc::string::String,
+    /// leaving inactive, i.e. entering activating
+    #[prost(uint64, tag="3")]
+    pub inactive_exit: u64,
+    #[prost(uint64, tag="4")]
+    pub active_enter: u64,
+    /// leaving active, i.e. entering deactivating
+    #[prost(uint64, tag="5")]
+    pub active_exit: u64,
+    #[prost(uint64, tag="6")]
+    pub inactive_enter: u64,
+    /// the units ordered before this one by After=
+    #[prost(string, repeated, tag="7")]
+    pub after: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// The boot timestamps, CLOCK_MONOTONIC in microseconds, 0 for unknown
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BootTimes {
+    /// the start of init, the kernel boots before it
+    #[prost(uint64, tag="1")]
+    pub userspace: u64,
+    /// the target of the default job becomes active
+    #[prost(uint64, tag="2")]
+    pub finish: u64,
+    /// CLOCK_REALTIME at the time of CLOCK_MONOTONIC 0
+    #[prost(uint64, tag="3")]
+    pub realtime_base: u64,
+    #[prost(message, repeated, tag="4")]
+    pub units: ::prost::alloc::vec::Vec<UnitTimes>,
+    /// the target of the default job, i.e. multi-user.target
+    #[prost(string, tag="5")]
+    pub finish_target: ::prost::alloc::string::String,
+}
+/// One job waiting or running in the job queue
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JobEntry {
+    #[prost(uint64, tag="1")]
+    pub id: u64,
+    #[prost(string, tag="2")]
+    pub unit: ::prost::alloc::string::String,
+    /// start, stop, reload, restart...
+    #[prost(string, tag="3")]
+    pub job_type: ::prost::alloc::string::String,
+    /// waiting or running
+    #[prost(string, tag="4")]
+    pub state: ::prost::alloc::string::String,
+}
+/// An ordering cycle found in a transaction
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderingCycle {
+    /// CLOCK_REALTIME in microseconds
+    #[prost(uint64, tag="1")]
+    pub timestamp: u64,
+    /// the job the transaction is built for, i.e. "multi-user.target/start"
+    #[prost(string, tag="2")]
+    pub anchor: ::prost::alloc::string::String,
+    /// each job is ordered after the next one, and the last one after the first one
+    #[prost(string, repeated, tag="3")]
+    pub jobs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// the job deleted to break the cycle, empty if the transaction is refused
+    #[prost(string, tag="4")]
+    pub dropped: ::prost::alloc::string::String,
+}
+/// One process in the cgroup of the unit
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitProcess {
+    #[prost(uint32, tag="1")]
+    pub pid: u32,
+    #[prost(string, tag="2")]
+    pub cmdline: ::prost::alloc::string::String,
+}
+/// The runtime status of one unit, the timestamps are realtime in microseconds, 0 for never
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitStatus {
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub documentation: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub load_state: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub active_state: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub sub_state: ::prost::alloc::string::String,
+    #[prost(uint64, tag="7")]
+    pub state_change_timestamp: u64,
+    #[prost(uint64, tag="8")]
+    pub active_enter_timestamp: u64,
+    #[prost(uint64, tag="9")]
+    pub active_exit_timestamp: u64,
+    #[prost(uint64, tag="10")]
+    pub inactive_enter_timestamp: u64,
+    #[prost(uint64, tag="11")]
+    pub inactive_exit_timestamp: u64,
+    /// 0 if there is no such process
+    #[prost(uint32, tag="12")]
+    pub main_pid: u32,
+    #[prost(uint32, tag="13")]
+    pub control_pid: u32,
+    #[prost(string, tag="14")]
+    pub cgroup_path: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag="15")]
+    pub processes: ::prost::alloc::vec::Vec<UnitProcess>,
+    /// read from the cgroup, unset if not available
+    #[prost(uint64, optional, tag="16")]
+    pub memory_current: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag="17")]
+    pub cpu_usage_nsec: ::core::option::Option<u64>,
+    /// the last exited main process, at most one of them is set
+    #[prost(int32, optional, tag="18")]
+    pub exit_code: ::core::option::Option<i32>,
+    #[prost(int32, optional, tag="19")]
+    pub exit_signal: ::core::option::Option<i32>,
+    /// sent by the service with STATUS=
+    #[prost(string, optional, tag="20")]
+    pub status_text: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[rustfmt::skip]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -54,6 +242,9 @@ pub struct UnitComm {
     pub action: i32,
     #[prost(string, repeated, tag="2")]
     pub units: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// the signal sent to the processes of the units by KILL
+    #[prost(int32, tag="3")]
+    pub signal: i32,
 }
 /// Nested message and enum types in `UnitComm`.
 pub mod unit_comm {
@@ -92,6 +283,7 @@ pub mod unit_file {
         Unmask = 4,
         Getdef = 5,
         Setdef = 6,
+        Revert = 7,
     }
 }
 #[rustfmt::skip]
@@ -110,6 +302,7 @@ pub mod job_comm {
     pub enum Action {
         List = 0,
         Cancel = 1,
+        Cycles = 2,
     }
 }
 #[rustfmt::skip]
@@ -127,6 +320,7 @@ pub mod mngr_comm {
         Reload = 0,
         Reexec = 1,
         Listunits = 2,
+        Boottimes = 3,
     }
 }
 #[rustfmt::skip]
@@ -186,3 +380,64 @@ pub mod transient_unit_comm {
         pub unit_properties: ::prost::alloc::vec::Vec<UnitProperty>,
     }
 }
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitPropertyComm {
+    #[prost(enumeration="unit_property_comm::Action", tag="1")]
+    pub action: i32,
+    #[prost(string, tag="2")]
+    pub unit: ::prost::alloc::string::String,
+    /// the properties to set, for SET
+    #[prost(message, repeated, tag="3")]
+    pub properties: ::prost::alloc::vec::Vec<transient_unit_comm::UnitProperty>,
+    /// write the properties to /run instead of /etc, lost after reboot
+    #[prost(bool, tag="4")]
+    pub runtime: bool,
+}
+/// Nested message and enum types in `UnitPropertyComm`.
+pub mod unit_property_comm {
+    #[rustfmt::skip]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Action {
+        Show = 0,
+        Set = 1,
+    }
+}
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitEditComm {
+    #[prost(string, tag="1")]
+    pub unit: ::prost::alloc::string::String,
+    /// the new content of override.conf, the override is removed if it is empty
+    #[prost(string, tag="2")]
+    pub content: ::prost::alloc::string::String,
+    /// write the override to /run instead of /etc, lost after reboot
+    #[prost(bool, tag="3")]
+    pub runtime: bool,
+}
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitDependencyComm {
+    #[prost(string, tag="1")]
+    pub unit: ::prost::alloc::string::String,
+    #[prost(enumeration="unit_dependency_comm::Mode", tag="2")]
+    pub mode: i32,
+    /// expand the dependencies of all units, not only of the targets
+    #[prost(bool, tag="3")]
+    pub all: bool,
+}
+/// Nested message and enum types in `UnitDependencyComm`.
+pub mod unit_dependency_comm {
+    #[rustfmt::skip]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Mode {
+        /// Requires, Requisite, BindsTo, Wants, ConsistsOf and Upholds
+        Forward = 0,
+        /// RequiredBy, RequisiteOf, BoundBy, WantedBy, PartOf and UpheldBy
+        Reverse = 1,
+        After = 2,
+        Before = 3,
+    }
+}
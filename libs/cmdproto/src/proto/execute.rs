@@ -12,8 +12,11 @@
 
 //! Convert the command request into the corresponding execution action
 use super::{
-    mngr_comm, sys_comm, transient_unit_comm, unit_comm, CommandRequest, CommandResponse, MngrComm,
-    RequestData, SwitchRootComm, SysComm, TransientUnitComm, UnitComm, UnitFile,
+    job_comm, mngr_comm, sys_comm, transient_unit_comm, unit_comm, unit_dependency_comm,
+    unit_property_comm, BootTimes, CommandRequest, CommandResponse, JobComm, JobEntry, MngrComm,
+    OrderingCycle, RequestData, SwitchRootComm, SysComm, TransientUnitComm, UnitComm,
+    UnitDependency, UnitDependencyComm, UnitEditComm, UnitFile, UnitListEntry, UnitPropertyComm,
+    UnitStatus,
 };
 
 use crate::error::*;
@@ -35,8 +38,6 @@ pub(crate) trait Executer {
 pub trait ExecuterAction {
     #[allow(missing_docs)]
     type Error: Display + Into<nix::Error>;
-    #[allow(missing_docs)]
-    type Status: Display + Into<nix::Error>;
     /// start the unit_name
     fn start(&self, unit_name: &str) -> Result<(), Self::Error>;
     /// stop the unit_name
@@ -49,10 +50,41 @@ pub trait ExecuterAction {
     fn isolate(&self, unit_name: &str) -> Result<(), Self::Error>;
     /// reset the failed unit_name
     fn reset_failed(&self, unit_name: &str) -> Result<(), Self::Error>;
+    /// send the signal to the processes of unit_name
+    fn kill(&self, unit_name: &str, signal: i32) -> Result<(), Self::Error>;
     /// show the status of unit_name
-    fn status(&self, unit_name: &str) -> Result<Self::Status, Self::Error>;
+    fn status(&self, unit_name: &str) -> Result<UnitStatus, Self::Error>;
+    /// show the properties of unit_name, including the parsed configuration and the runtime state
+    fn show(&self, unit_name: &str) -> Result<Vec<transient_unit_comm::UnitProperty>, Self::Error>;
+    /// set the properties of unit_name, write them to a drop-in in /run if runtime is true,
+    /// otherwise in /etc
+    fn set_property(
+        &self,
+        unit_name: &str,
+        properties: &[transient_unit_comm::UnitProperty],
+        runtime: bool,
+    ) -> Result<(), Self::Error>;
+    /// check the content and install it as the override drop-in of unit_name, in /run if
+    /// runtime is true, otherwise in /etc; remove the override if the content is empty
+    fn edit(&self, unit_name: &str, content: &str, runtime: bool) -> Result<(), Self::Error>;
+    /// the dependency tree of unit_name in depth-first order, the first one is unit_name itself;
+    /// only the targets are expanded unless all is true
+    fn list_dependencies(
+        &self,
+        unit_name: &str,
+        mode: unit_dependency_comm::Mode,
+        all: bool,
+    ) -> Result<Vec<UnitDependency>, Self::Error>;
     /// list all units
-    fn list_units(&self) -> Result<String, Self::Error>;
+    fn list_units(&self) -> Result<Vec<UnitListEntry>, Self::Error>;
+    /// the boot timestamps and the state change timestamps of all units
+    fn boot_times(&self) -> Result<BootTimes, Self::Error>;
+    /// list the jobs in the job queue
+    fn list_jobs(&self) -> Result<Vec<JobEntry>, Self::Error>;
+    /// cancel the job of job_id
+    fn cancel_job(&self, job_id: u64) -> Result<(), Self::Error>;
+    /// the most recent ordering cycles found in transactions, the oldest first
+    fn list_ordering_cycles(&self) -> Result<Vec<OrderingCycle>, Self::Error>;
     /// suspend host
     fn suspend(&self) -> Result<i32, Self::Error>;
     /// poweroff host
@@ -69,6 +101,8 @@ pub trait ExecuterAction {
     fn mask(&self, unit_name: &str) -> Result<(), Self::Error>;
     /// unmask unit_name
     fn unmask(&self, unit_name: &str) -> Result<(), Self::Error>;
+    /// remove the drop-ins, the mask and the local copy of unit_name in /etc and /run
+    fn revert(&self, unit_name: &str) -> Result<(), Self::Error>;
     /// daemon-reload
     fn daemon_reload(&self);
     /// daemon-reexec
@@ -109,8 +143,12 @@ where
         Some(RequestData::Mcomm(param)) => param.execute(manager, None, cred),
         Some(RequestData::Syscomm(param)) => param.execute(manager, Some(call_back), cred),
         Some(RequestData::Ufile(param)) => param.execute(manager, Some(call_back), cred),
+        Some(RequestData::Jcomm(param)) => param.execute(manager, None, cred),
         Some(RequestData::Srcomm(param)) => param.execute(manager, None, cred),
         Some(RequestData::Trancomm(param)) => param.execute(manager, None, cred),
+        Some(RequestData::Pcomm(param)) => param.execute(manager, Some(call_back), cred),
+        Some(RequestData::Ecomm(param)) => param.execute(manager, Some(call_back), cred),
+        Some(RequestData::Dcomm(param)) => param.execute(manager, Some(call_back), cred),
         _ => CommandResponse::default(),
     }
 }
@@ -132,15 +170,19 @@ fn response_if_credential_dissatisfied(
                 error_code: 1,
                 message: "Failed to execute your command: cannot determine user credentials."
                     .to_string(),
+                ..Default::default()
             })
         }
         Some(v) => v.uid(),
     };
-    if sender != 0 && !command_is_allowed_for_nonroot {
+    /* the user manager accepts the commands of its owner */
+    let owner = unsafe { nix::libc::getuid() };
+    if sender != 0 && sender != owner && !command_is_allowed_for_nonroot {
         return Some(CommandResponse {
             status: StatusCode::OK.as_u16() as _,
             error_code: 1,
             message: "Failed to execute your command: Operation not permitted.".to_string(),
+            ..Default::default()
         });
     }
     None
@@ -163,6 +205,7 @@ impl Executer for UnitComm {
         let mut reply = String::new();
         let mut units: Vec<String> = Vec::new();
         let mut error_code: u32 = 0;
+        let mut unit_status = Vec::new();
         for unit_name in &self.units {
             if call_back.is_none() {
                 units.push(unit_name.to_string());
@@ -175,13 +218,17 @@ impl Executer for UnitComm {
         match self.action() {
             unit_comm::Action::Status => {
                 for unit in units {
-                    new_line_break(&mut reply);
                     match manager.status(&unit) {
                         Ok(status) => {
-                            reply += &status.to_string();
-                            error_code = status.into() as u32 | ERROR_CODE_MASK_PRINT_STDOUT;
+                            // systemd will return 3 if the unit's state is failed or inactive.
+                            error_code = match status.active_state.as_str() {
+                                "failed" | "inactive" => 3,
+                                _ => 0,
+                            };
+                            unit_status.push(status);
                         }
                         Err(e) => {
+                            new_line_break(&mut reply);
                             reply =
                                 format!("{}Failed to show the status of {}: {}", reply, unit, e);
                             error_code = e.into() as u32;
@@ -243,12 +290,22 @@ impl Executer for UnitComm {
                     }
                 }
             }
-            _ => todo!(),
+            unit_comm::Action::Kill => {
+                for unit in units {
+                    if let Err(e) = manager.kill(&unit, self.signal) {
+                        new_line_break(&mut reply);
+                        reply = format!("{}Failed to kill {}: {}", reply, unit, e);
+                        error_code = e.into() as u32;
+                    }
+                }
+            }
         }
         CommandResponse {
             status: StatusCode::OK.as_u16() as _,
             error_code,
             message: reply,
+            unit_status,
+            ..Default::default()
         }
     }
 }
@@ -262,7 +319,7 @@ impl Executer for MngrComm {
     ) -> CommandResponse {
         if let Some(v) = response_if_credential_dissatisfied(
             cred,
-            [mngr_comm::Action::Listunits].contains(&self.action()),
+            [mngr_comm::Action::Listunits, mngr_comm::Action::Boottimes].contains(&self.action()),
         ) {
             return v;
         }
@@ -287,10 +344,11 @@ impl Executer for MngrComm {
             }
 
             mngr_comm::Action::Listunits => match manager.list_units() {
-                Ok(m) => CommandResponse {
+                Ok(unit_list) => CommandResponse {
                     status: StatusCode::OK.as_u16() as _,
                     error_code: 0,
-                    message: m,
+                    unit_list,
+                    ..Default::default()
                 },
                 Err(e) => {
                     let error_message = format!("Failed to list all units:{}", e);
@@ -298,6 +356,25 @@ impl Executer for MngrComm {
                         status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
                         error_code: e.into() as u32,
                         message: error_message,
+                        ..Default::default()
+                    }
+                }
+            },
+
+            mngr_comm::Action::Boottimes => match manager.boot_times() {
+                Ok(boot_times) => CommandResponse {
+                    status: StatusCode::OK.as_u16() as _,
+                    error_code: 0,
+                    boot_times: Some(boot_times),
+                    ..Default::default()
+                },
+                Err(e) => {
+                    let error_message = format!("Failed to get the boot times: {}", e);
+                    CommandResponse {
+                        status: StatusCode::OK.as_u16() as _,
+                        error_code: e.into() as u32,
+                        message: error_message,
+                        ..Default::default()
                     }
                 }
             },
@@ -343,7 +420,221 @@ impl Executer for SysComm {
                 status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
                 error_code: e.into() as u32,
                 message: String::from("error."),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Executer for JobComm {
+    fn execute(
+        self,
+        manager: Rc<impl ExecuterAction>,
+        _call_back: Option<fn(&str) -> String>,
+        cred: Option<UnixCredentials>,
+    ) -> CommandResponse {
+        if let Some(v) = response_if_credential_dissatisfied(
+            cred,
+            [job_comm::Action::List, job_comm::Action::Cycles].contains(&self.action()),
+        ) {
+            return v;
+        }
+
+        match self.action() {
+            job_comm::Action::List => match manager.list_jobs() {
+                Ok(jobs) => CommandResponse {
+                    status: StatusCode::OK.as_u16() as _,
+                    error_code: 0,
+                    jobs,
+                    ..Default::default()
+                },
+                Err(e) => {
+                    let error_message = format!("Failed to list jobs:{}", e);
+                    CommandResponse {
+                        status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
+                        error_code: e.into() as u32,
+                        message: error_message,
+                        ..Default::default()
+                    }
+                }
+            },
+            job_comm::Action::Cycles => match manager.list_ordering_cycles() {
+                Ok(ordering_cycles) => CommandResponse {
+                    status: StatusCode::OK.as_u16() as _,
+                    error_code: 0,
+                    ordering_cycles,
+                    ..Default::default()
+                },
+                Err(e) => {
+                    let error_message = format!("Failed to list ordering cycles: {}", e);
+                    CommandResponse {
+                        status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
+                        error_code: e.into() as u32,
+                        message: error_message,
+                        ..Default::default()
+                    }
+                }
+            },
+            job_comm::Action::Cancel => {
+                let job_id = match self.job_id.parse::<u64>() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        return CommandResponse {
+                            status: StatusCode::OK.as_u16() as _,
+                            error_code: nix::Error::EINVAL as u32,
+                            message: format!("Invalid job id: {}", self.job_id),
+                            ..Default::default()
+                        }
+                    }
+                };
+                match manager.cancel_job(job_id) {
+                    Ok(_) => CommandResponse {
+                        status: StatusCode::OK.as_u16() as _,
+                        error_code: 0,
+                        ..Default::default()
+                    },
+                    Err(e) => {
+                        let error_message = format!("Failed to cancel job {}: {}", job_id, e);
+                        CommandResponse {
+                            status: StatusCode::OK.as_u16() as _,
+                            error_code: e.into() as u32,
+                            message: error_message,
+                            ..Default::default()
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Executer for UnitPropertyComm {
+    fn execute(
+        self,
+        manager: Rc<impl ExecuterAction>,
+        call_back: Option<fn(&str) -> String>,
+        cred: Option<UnixCredentials>,
+    ) -> CommandResponse {
+        if let Some(v) = response_if_credential_dissatisfied(
+            cred,
+            [unit_property_comm::Action::Show].contains(&self.action()),
+        ) {
+            return v;
+        }
+
+        let unit = match call_back {
+            None => self.unit.clone(),
+            Some(f) => f(&self.unit),
+        };
+
+        match self.action() {
+            unit_property_comm::Action::Show => match manager.show(&unit) {
+                Ok(unit_properties) => CommandResponse {
+                    status: StatusCode::OK.as_u16() as _,
+                    error_code: 0,
+                    unit_properties,
+                    ..Default::default()
+                },
+                Err(e) => {
+                    let error_message = format!("Failed to show the properties of {}: {}", unit, e);
+                    CommandResponse {
+                        status: StatusCode::OK.as_u16() as _,
+                        error_code: e.into() as u32,
+                        message: error_message,
+                        ..Default::default()
+                    }
+                }
             },
+            unit_property_comm::Action::Set => {
+                match manager.set_property(&unit, &self.properties, self.runtime) {
+                    Ok(_) => CommandResponse {
+                        status: StatusCode::OK.as_u16() as _,
+                        error_code: 0,
+                        ..Default::default()
+                    },
+                    Err(e) => {
+                        let error_message =
+                            format!("Failed to set the properties of {}: {}", unit, e);
+                        CommandResponse {
+                            status: StatusCode::OK.as_u16() as _,
+                            error_code: e.into() as u32,
+                            message: error_message,
+                            ..Default::default()
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Executer for UnitDependencyComm {
+    fn execute(
+        self,
+        manager: Rc<impl ExecuterAction>,
+        call_back: Option<fn(&str) -> String>,
+        cred: Option<UnixCredentials>,
+    ) -> CommandResponse {
+        if let Some(v) = response_if_credential_dissatisfied(cred, true) {
+            return v;
+        }
+
+        let unit = match call_back {
+            None => self.unit.clone(),
+            Some(f) => f(&self.unit),
+        };
+
+        match manager.list_dependencies(&unit, self.mode(), self.all) {
+            Ok(dependencies) => CommandResponse {
+                status: StatusCode::OK.as_u16() as _,
+                error_code: 0,
+                dependencies,
+                ..Default::default()
+            },
+            Err(e) => {
+                let error_message = format!("Failed to list the dependencies of {}: {}", unit, e);
+                CommandResponse {
+                    status: StatusCode::OK.as_u16() as _,
+                    error_code: e.into() as u32,
+                    message: error_message,
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
+
+impl Executer for UnitEditComm {
+    fn execute(
+        self,
+        manager: Rc<impl ExecuterAction>,
+        call_back: Option<fn(&str) -> String>,
+        cred: Option<UnixCredentials>,
+    ) -> CommandResponse {
+        if let Some(v) = response_if_credential_dissatisfied(cred, false) {
+            return v;
+        }
+
+        let unit = match call_back {
+            None => self.unit.clone(),
+            Some(f) => f(&self.unit),
+        };
+
+        match manager.edit(&unit, &self.content, self.runtime) {
+            Ok(_) => CommandResponse {
+                status: StatusCode::OK.as_u16() as _,
+                error_code: 0,
+                ..Default::default()
+            },
+            Err(e) => {
+                let error_message = format!("Failed to edit {}: {}", unit, e);
+                CommandResponse {
+                    status: StatusCode::OK.as_u16() as _,
+                    error_code: e.into() as u32,
+                    message: error_message,
+                    ..Default::default()
+                }
+            }
         }
     }
 }
@@ -407,6 +698,15 @@ impl Executer for UnitFile {
                     }
                 }
             }
+            super::unit_file::Action::Revert => {
+                for unit in units {
+                    if let Err(e) = manager.revert(&unit) {
+                        new_line_break(&mut reply);
+                        reply = format!("{} Failed to revert {}: {}", reply, unit, e);
+                        error_code = e.into() as u32;
+                    }
+                }
+            }
             _ => todo!(),
         };
 
@@ -414,6 +714,7 @@ impl Executer for UnitFile {
             status: StatusCode::OK.as_u16() as _,
             error_code,
             message: reply,
+            ..Default::default()
         }
     }
 }
@@ -439,6 +740,7 @@ impl Executer for SwitchRootComm {
                 status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
                 error_code: e.into() as u32,
                 message: String::from("error."),
+                ..Default::default()
             },
         }
     }
@@ -460,6 +762,7 @@ impl Executer for TransientUnitComm {
                 status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
                 error_code: 1,
                 message: String::from("error."),
+                ..Default::default()
             };
         }
 
@@ -477,6 +780,7 @@ impl Executer for TransientUnitComm {
                 status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
                 message: e.to_string(),
                 error_code: e.into() as u32,
+                ..Default::default()
             },
         }
     }
@@ -31,6 +31,18 @@ impl CommandRequest {
             request_data: Some(RequestData::Ucomm(UnitComm {
                 action: action.into(),
                 units,
+                signal: 0,
+            })),
+        }
+    }
+
+    /// Create a new command request to send the signal to the processes of units
+    pub fn new_killcomm(units: Vec<String>, signal: i32) -> Self {
+        Self {
+            request_data: Some(RequestData::Ucomm(UnitComm {
+                action: unit_comm::Action::Kill.into(),
+                units,
+                signal,
             })),
         }
     }
@@ -44,6 +56,16 @@ impl CommandRequest {
         }
     }
 
+    /// Create a new command request for job
+    pub fn new_jobcomm(action: job_comm::Action, job_id: String) -> Self {
+        Self {
+            request_data: Some(RequestData::Jcomm(JobComm {
+                action: action.into(),
+                job_id,
+            })),
+        }
+    }
+
     /// Create a new command request for system
     pub fn new_syscomm(action: sys_comm::Action, force: bool) -> Self {
         Self {
@@ -71,6 +93,55 @@ impl CommandRequest {
         }
     }
 
+    /// Create a new command request for showing the properties of the unit
+    pub fn new_show_property_comm(unit: String) -> Self {
+        Self {
+            request_data: Some(RequestData::Pcomm(UnitPropertyComm {
+                action: unit_property_comm::Action::Show.into(),
+                unit,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Create a new command request for setting the properties of the unit
+    pub fn new_set_property_comm(
+        unit: String,
+        properties: Vec<transient_unit_comm::UnitProperty>,
+        runtime: bool,
+    ) -> Self {
+        Self {
+            request_data: Some(RequestData::Pcomm(UnitPropertyComm {
+                action: unit_property_comm::Action::Set.into(),
+                unit,
+                properties,
+                runtime,
+            })),
+        }
+    }
+
+    /// Create a new command request for replacing the override drop-in of the unit
+    pub fn new_edit_comm(unit: String, content: String, runtime: bool) -> Self {
+        Self {
+            request_data: Some(RequestData::Ecomm(UnitEditComm {
+                unit,
+                content,
+                runtime,
+            })),
+        }
+    }
+
+    /// Create a new command request for the dependency tree of the unit
+    pub fn new_dependency_comm(unit: String, mode: unit_dependency_comm::Mode, all: bool) -> Self {
+        Self {
+            request_data: Some(RequestData::Dcomm(UnitDependencyComm {
+                unit,
+                mode: mode.into(),
+                all,
+            })),
+        }
+    }
+
     /// Create a new command request for start transient unit
     pub fn new_transient_unit_comm(
         job_mode: &str,
@@ -24,9 +24,18 @@ pub const INIT_SOCKET: &str = "/run/sysmaster/init";
 /// sysmaster send this to init to keep alive
 pub const ALIVE: &str = "ALIVE01234567890";
 
+/// The sysmaster binary, also used to verify the unit files offline
+pub const SYSMASTER_BIN: &str = "/usr/lib/sysmaster/sysmaster";
+
 /// Socket used to transfer message between sysmaster and sctl
 pub const PRIVATE_SOCKET: &str = "/run/sysmaster/private";
 
+/// Socket the log collector receives the service output from
+pub const LOG_STDOUT_SOCKET: &str = "/run/sysmaster/log/stdout";
+
+/// Directory the log collector stores the service output in
+pub const LOG_COLLECTOR_DIR: &str = "/var/log/sysmaster/collector";
+
 /// Default log file path when LogTarget is configured to "file"
 pub const LOG_FILE_PATH: &str = "/var/log/sysmaster/sysmaster.log";
 
@@ -0,0 +1,180 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! One line of the service output and its on-disk format.
+//!
+//! Each record takes one line of the log file, the fields are separated by tabs:
+//! `<usec>\t<unit>\t<identifier>\t<pid>\t<priority>\t<message>`. The message is
+//! the last field, so it may contain tabs itself.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// the highest (least important) syslog priority, debug
+pub const LOG_PRIORITY_MAX: u8 = 7;
+
+/// one line of the service output
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// the realtime in microseconds when the line is received
+    pub timestamp: u64,
+    /// the unit the process belongs to
+    pub unit: String,
+    /// the name of the executed program
+    pub identifier: String,
+    /// the pid of the process which connected to the collector
+    pub pid: i32,
+    /// the syslog priority, 0 (emerg) to 7 (debug)
+    pub priority: u8,
+    /// the line without the trailing newline
+    pub message: String,
+}
+
+impl Record {
+    /// create a record received now, a "<N>" prefix of the message overrides the priority
+    pub fn new(unit: &str, identifier: &str, pid: i32, priority: u8, message: &str) -> Self {
+        let (priority, message) = match strip_priority_prefix(message) {
+            Some((p, m)) => (p, m),
+            None => (priority, message),
+        };
+
+        Record {
+            timestamp: realtime_usec(),
+            unit: unit.to_string(),
+            identifier: identifier.to_string(),
+            pid,
+            priority,
+            message: message.to_string(),
+        }
+    }
+
+    /// serialize the record to one line, with the trailing newline
+    pub fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            self.timestamp,
+            escape(&self.unit),
+            escape(&self.identifier),
+            self.pid,
+            self.priority,
+            self.message.replace('\n', " ")
+        )
+    }
+
+    /// parse one line written by to_line, return None if the line is broken
+    pub fn from_line(line: &str) -> Option<Self> {
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        let mut fields = line.splitn(6, '\t');
+        let timestamp = fields.next()?.parse::<u64>().ok()?;
+        let unit = fields.next()?.to_string();
+        let identifier = fields.next()?.to_string();
+        let pid = fields.next()?.parse::<i32>().ok()?;
+        let priority = fields.next()?.parse::<u8>().ok()?;
+        if priority > LOG_PRIORITY_MAX {
+            return None;
+        }
+        let message = fields.next()?.to_string();
+
+        Some(Record {
+            timestamp,
+            unit,
+            identifier,
+            pid,
+            priority,
+            message,
+        })
+    }
+}
+
+impl fmt::Display for Record {
+    /// "2023-01-01 08:00:00.000000 foo.service foo[1234]: message"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = (self.timestamp / 1_000_000) as libc::time_t;
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        unsafe { libc::localtime_r(&time, &mut tm) };
+        write!(
+            f,
+            "{:0>4}-{:0>2}-{:0>2} {:0>2}:{:0>2}:{:0>2}.{:0>6} {} {}[{}]: {}",
+            tm.tm_year + 1900, /* tm_year is years since 1900 */
+            tm.tm_mon + 1,     /* tm_mon is months since Jan: [0, 11] */
+            tm.tm_mday,
+            tm.tm_hour,
+            tm.tm_min,
+            tm.tm_sec,
+            self.timestamp % 1_000_000,
+            self.unit,
+            self.identifier,
+            self.pid,
+            self.message
+        )
+    }
+}
+
+fn realtime_usec() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_micros() as u64,
+        Err(_) => 0,
+    }
+}
+
+/// The unit name and the identifier are separated by tabs, don't let them break the line.
+fn escape(s: &str) -> String {
+    s.replace(['\t', '\n'], " ")
+}
+
+/// Programs can prefix the line with "<N>" to choose the priority, like printing to kmsg.
+fn strip_priority_prefix(message: &str) -> Option<(u8, &str)> {
+    let bytes = message.as_bytes();
+    if bytes.len() < 3 || bytes[0] != b'<' || bytes[2] != b'>' {
+        return None;
+    }
+    if !bytes[1].is_ascii_digit() {
+        return None;
+    }
+    let priority = bytes[1] - b'0';
+    if priority > LOG_PRIORITY_MAX {
+        return None;
+    }
+    Some((priority, &message[3..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_line() {
+        let record = Record::new("foo.service", "foo", 1234, 6, "hello\tworld");
+        let line = record.to_line();
+        assert!(line.ends_with("\thello\tworld\n"));
+        assert_eq!(Record::from_line(&line).unwrap(), record);
+
+        assert!(Record::from_line("").is_none());
+        assert!(Record::from_line("abc\tfoo.service\tfoo\t1\t6\thello").is_none());
+        assert!(Record::from_line("1\tfoo.service\tfoo\t1\t8\thello").is_none());
+        assert!(Record::from_line("1\tfoo.service\tfoo\t1\t6").is_none());
+    }
+
+    #[test]
+    fn test_priority_prefix() {
+        let record = Record::new("foo.service", "foo", 1, 6, "<3>failed");
+        assert_eq!(record.priority, 3);
+        assert_eq!(record.message, "failed");
+
+        let record = Record::new("foo.service", "foo", 1, 6, "<9>failed");
+        assert_eq!(record.priority, 6);
+        assert_eq!(record.message, "<9>failed");
+
+        let record = Record::new("foo.service", "foo", 1, 3, "<3");
+        assert_eq!(record.message, "<3");
+    }
+}
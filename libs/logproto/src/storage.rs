@@ -0,0 +1,189 @@
+// Copyright (c) 2022 Huawei Technologies Co.,Ltd. All rights reserved.
+//
+// sysMaster is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2.
+// You may obtain a copy of Mulan PSL v2 at:
+//         http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The rotating log files.
+//!
+//! The records are appended to `messages` in the log directory. When the file
+//! grows beyond the size limit, it is renamed to `messages.1`, the older ones are
+//! shifted to `messages.2` and so on, and the oldest beyond the file limit is removed.
+
+use crate::record::Record;
+use std::fs::{self, DirBuilder, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+
+/// the name of the file being written
+pub const LOG_FILE_NAME: &str = "messages";
+/// the default size limit of one file
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 8 * 1024 * 1024;
+/// the default number of the files kept, including the one being written
+pub const DEFAULT_MAX_FILES: usize = 4;
+
+/// the log files in one directory
+pub struct Storage {
+    dir: PathBuf,
+    max_file_size: u64,
+    max_files: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl Storage {
+    /// create the storage, the directory is created when the first record is written
+    pub fn new(dir: &Path, max_file_size: u64, max_files: usize) -> Self {
+        Storage {
+            dir: dir.to_path_buf(),
+            max_file_size,
+            max_files: max_files.max(1),
+            file: None,
+            size: 0,
+        }
+    }
+
+    /// append one record, rotate the files if the current one is full
+    pub fn append(&mut self, record: &Record) -> io::Result<()> {
+        let line = record.to_line();
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.size > 0 && self.size + line.len() as u64 > self.max_file_size {
+            self.rotate()?;
+        }
+
+        let file = match self.file.as_mut() {
+            None => return Err(io::Error::from(io::ErrorKind::NotFound)),
+            Some(v) => v,
+        };
+        file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        DirBuilder::new()
+            .recursive(true)
+            .mode(0o750)
+            .create(&self.dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o640)
+            .open(self.dir.join(LOG_FILE_NAME))?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        let _ = fs::remove_file(rotated_path(&self.dir, self.max_files - 1));
+        for i in (0..self.max_files - 1).rev() {
+            let from = rotated_path(&self.dir, i);
+            if !from.exists() {
+                continue;
+            }
+            fs::rename(&from, rotated_path(&self.dir, i + 1))?;
+        }
+        self.open()
+    }
+}
+
+/// `messages` for 0, `messages.N` for the rotated ones
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE_NAME)
+    } else {
+        dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+    }
+}
+
+/// read the records from the oldest to the newest, only the ones of the unit if it is given
+pub fn read_records(dir: &Path, unit: Option<&str>) -> io::Result<Vec<Record>> {
+    let mut paths = Vec::new();
+    for i in 0.. {
+        let path = rotated_path(dir, i);
+        if !path.exists() {
+            break;
+        }
+        paths.push(path);
+    }
+
+    let mut res = Vec::new();
+    for path in paths.iter().rev() {
+        let file = match File::open(path) {
+            Ok(v) => v,
+            /* Rotated while reading. */
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for line in BufReader::new(file).lines() {
+            let record = match Record::from_line(&line?) {
+                None => continue,
+                Some(v) => v,
+            };
+            if unit.is_none() || unit == Some(record.unit.as_str()) {
+                res.push(record);
+            }
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = Record::new("foo.service", "foo", 1, 6, "0123456789");
+        let len = record.to_line().len() as u64;
+
+        /* Two records in one file, three files at most. */
+        let mut storage = Storage::new(dir.path(), len * 2, 3);
+        for i in 0..7 {
+            let record = Record::new("foo.service", "foo", i, 6, "0123456789");
+            storage.append(&record).unwrap();
+        }
+        storage
+            .append(&Record::new("bar.service", "bar", 7, 6, "0123456789"))
+            .unwrap();
+
+        assert!(dir.path().join("messages.2").exists());
+        assert!(!dir.path().join("messages.3").exists());
+
+        let records = read_records(dir.path(), None).unwrap();
+        let pids: Vec<i32> = records.iter().map(|r| r.pid).collect();
+        assert_eq!(pids, vec![2, 3, 4, 5, 6, 7]);
+
+        let records = read_records(dir.path(), Some("bar.service")).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].pid, 7);
+    }
+
+    #[test]
+    fn test_storage_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = Record::new("foo.service", "foo", 1, 6, "hello");
+        Storage::new(dir.path(), DEFAULT_MAX_FILE_SIZE, DEFAULT_MAX_FILES)
+            .append(&record)
+            .unwrap();
+        Storage::new(dir.path(), DEFAULT_MAX_FILE_SIZE, DEFAULT_MAX_FILES)
+            .append(&record)
+            .unwrap();
+        assert_eq!(read_records(dir.path(), None).unwrap().len(), 2);
+        assert!(read_records(&dir.path().join("none"), None)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -96,6 +96,10 @@ pub(crate) fn gen_entry_parse(field: &Field) -> Result<TokenStream> {
                     }
                     Err(_) => {
                         log::warn!("Failed to parse {} for key {}, ignoring.", __pair.0, __pair.1);
+                        __source.report(unit_parser::internal::Error::ValueParsingError {
+                            key: __pair.0.to_string(),
+                            value: __pair.1.to_string(),
+                        });
                     }
                 }
             },
@@ -107,6 +111,10 @@ pub(crate) fn gen_entry_parse(field: &Field) -> Result<TokenStream> {
                         }
                         Err(_) => {
                             log::warn!("Failed to parse {} for key {}, ignoring.", __pair.0, __pair.1);
+                            __source.report(unit_parser::internal::Error::ValueParsingError {
+                                key: __pair.0.to_string(),
+                                value: __part.to_string(),
+                            });
                         }
                     }
                 }
@@ -128,19 +136,25 @@ pub(crate) fn gen_entry_parse(field: &Field) -> Result<TokenStream> {
         },
     };
     let parser = match attributes.parser {
-        Some(entry_parser) => quote! {
-            let __value = #entry_parser(__pair.1.as_str()).unwrap();
-            #apply_value;
-        },
-        None => quote! {
-            let __value = unit_parser::internal::UnitEntry::parse_from_str(__pair.1.as_str()).unwrap();
-            #apply_value;
-        },
+        Some(entry_parser) => quote! { #entry_parser(__pair.1.as_str()) },
+        None => quote! { unit_parser::internal::UnitEntry::parse_from_str(__pair.1.as_str()) },
     };
 
+    /* keep the previous value and remember the error if the value is invalid */
     Ok(quote! {
         #key => {
-            #parser
+            match #parser {
+                Ok(__value) => {
+                    #apply_value;
+                }
+                Err(_) => {
+                    log::warn!("Failed to parse {} for key {}, ignoring.", __pair.1, __pair.0);
+                    __source.report(unit_parser::internal::Error::ValueParsingError {
+                        key: __pair.0.to_string(),
+                        value: __pair.1.to_string(),
+                    });
+                }
+            }
         }
     })
 }
@@ -45,6 +45,9 @@ pub fn gen_section_derives(input: DeriveInput) -> Result<TokenStream> {
                         #( #entry_parsers ),*
                         _ => {
                             log::warn!("{} is not a valid key.", __pair.0);
+                            __source.report(unit_parser::internal::Error::UnknownEntryError {
+                                key: __pair.0.to_string(),
+                            });
                         }
                     }
                 }
@@ -88,7 +91,9 @@ pub(crate) fn gen_section_parse(field: &Field) -> Result<TokenStream> {
                 fn assert_impl<T: Default>() {}
                 assert_impl::<#ty>();
             };
-            let _ = unit_parser::internal::UnitSection::__parse_section(&mut __section, &mut __res.#name);
+            if let Err(__e) = unit_parser::internal::UnitSection::__parse_section(&mut __section, &mut __res.#name) {
+                __section.report(__e);
+            }
         }
     };
 
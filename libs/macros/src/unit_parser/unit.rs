@@ -55,6 +55,8 @@ pub fn gen_unit_derives(input: DeriveInput) -> syn::Result<TokenStream> {
                         #( #parse_parsers ),*
                         _ => {
                             log::debug!("{} is not a valid section.", __section.name);
+                            /* skip the entries, the section may be parsed by another config */
+                            for _ in __section.by_ref() {}
                         }
                     }
                     __source.progress(__section.finish());
@@ -1,6 +1,6 @@
 //! Definitions for parsing-related traits.
 use crate::{
-    error::ReadFileSnafu,
+    error::{LocatedError, ReadFileSnafu},
     internal::Error,
     parser::{SectionParser, UnitParser},
 };
@@ -36,13 +36,38 @@ pub trait UnitConfig: Sized + Default {
 
     /// A convenient function that opens the file that needs to be loaded.
     fn __load<S: AsRef<Path>>(path: S, unit_name: &str, res: &mut Self) -> Result<()> {
+        match Self::__load_located(path, unit_name, res)
+            .into_iter()
+            .next()
+        {
+            None => Ok(()),
+            Some((_, e)) => Err(e),
+        }
+    }
+
+    /// Loads the file like [UnitConfig::__load], but goes on after an invalid line, value
+    /// or unknown key, all the errors are returned with their line numbers.
+    fn __load_located<S: AsRef<Path>>(
+        path: S,
+        unit_name: &str,
+        res: &mut Self,
+    ) -> Vec<(usize, Error)> {
         let path = path.as_ref();
-        let mut file = File::open(path).context(ReadFileSnafu { path })?;
         let mut content = String::new();
-        file.read_to_string(&mut content)
-            .context(ReadFileSnafu { path })?;
+        let read = File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut content))
+            .context(ReadFileSnafu { path });
+        if let Err(e) = read {
+            return vec![(0, e)];
+        }
         let parser = crate::parser::UnitParser::new(content.as_ref(), (unit_name,));
-        Self::__parse_unit(parser, res)
+        let errors = parser.errors();
+        if let Err(e) = Self::__parse_unit(parser, res) {
+            errors.borrow_mut().push((0, e));
+        }
+        let mut errors = errors.take();
+        errors.sort_by_key(|(line, _)| *line);
+        errors
     }
 
     /// Loads a unit with the given config file list and unit name
@@ -67,6 +92,44 @@ pub trait UnitConfig: Sized + Default {
 
         Ok(result)
     }
+
+    /// Checks the given config files strictly, unlike [UnitConfig::load_config],
+    /// the first unreadable file, invalid value or unknown key is returned as an error.
+    ///
+    /// paths: full paths of the given config file
+    ///
+    /// unit_name: the full unit name
+    fn verify_config<P: AsRef<Path>>(paths: Vec<P>, unit_name: &str) -> Result<()> {
+        match Self::verify_config_located(paths, unit_name)
+            .into_iter()
+            .next()
+        {
+            None => Ok(()),
+            Some(e) => Err(e.error),
+        }
+    }
+
+    /// Checks the given config files like [UnitConfig::verify_config], but all the errors
+    /// are returned in order with the files and lines where they are found.
+    ///
+    /// paths: full paths of the given config file
+    ///
+    /// unit_name: the full unit name
+    fn verify_config_located<P: AsRef<Path>>(paths: Vec<P>, unit_name: &str) -> Vec<LocatedError> {
+        let mut result = Self::default();
+        Self::__load_default(&mut result);
+        let mut errors = Vec::new();
+        for path in paths.iter() {
+            for (line, error) in Self::__load_located(path, unit_name, &mut result) {
+                errors.push(LocatedError {
+                    path: path.as_ref().to_path_buf(),
+                    line,
+                    error,
+                });
+            }
+        }
+        errors
+    }
 }
 
 /// The trait that needs to be implemented on each section of the unit.
@@ -1,6 +1,6 @@
 //! Definitions for all possible errors used in this crate.
 use snafu::Snafu;
-use std::{io, path::PathBuf};
+use std::{fmt, io, path::PathBuf};
 
 // TODO: change errors to `log::warn`s to prevent one bad file from stalling the entire loading process
 /// Errors used in crate.
@@ -44,6 +44,12 @@ pub enum Error {
     #[snafu(display("Missing section with key {}, which is required.", key))]
     SectionMissingError { key: String },
 
+    #[snafu(display("Unknown entry with key {}.", key))]
+    UnknownEntryError { key: String },
+
+    #[snafu(display("Failed to parse line: {}.", content))]
+    InvalidLineError { content: String },
+
     #[snafu(display("Failed to parse {} as the value of entry with key {}.", value, key))]
     ValueParsingError { key: String, value: String },
 
@@ -53,3 +59,24 @@ pub enum Error {
     #[snafu(display("Invalid specifier: {}", specifier))]
     InvalidSpecifierError { specifier: char },
 }
+
+/// An error found in a unit file, with the line where it is found.
+#[derive(Debug)]
+pub struct LocatedError {
+    /// the unit file or drop-in
+    pub path: PathBuf,
+    /// the line number starting from 1, 0 if the error is about the whole file
+    pub line: usize,
+    /// the error itself
+    pub error: Error,
+}
+
+impl fmt::Display for LocatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.path.display(), self.error)
+        } else {
+            write!(f, "{}:{}: {}", self.path.display(), self.line, self.error)
+        }
+    }
+}
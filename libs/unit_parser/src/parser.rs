@@ -1,4 +1,5 @@
 //! Parser struct definitions.
+use crate::error::Error;
 use crate::specifiers::{resolve, SpecifierContext};
 use nom::{
     branch::alt,
@@ -9,25 +10,39 @@ use nom::{
     sequence::{delimited, separated_pair},
     IResult,
 };
-// TODO: error callsite marking
+use std::{cell::RefCell, rc::Rc};
+
+/// The errors reported during parsing, with the line numbers where they are found.
+pub type ReportedErrors = Rc<RefCell<Vec<(usize, Error)>>>;
 
 /// A parser for parsing a whole unit.
 pub struct UnitParser<'a> {
+    /// The whole input, used to locate the cursor
+    source: &'a str,
     /// Parsing cursor
     inner: &'a str,
     /// Specifier resolve context
     context: SpecifierContext<'a>,
+    /// Errors reported by the parser and the sections
+    errors: ReportedErrors,
 }
 
 impl<'a> UnitParser<'a> {
     /// Creates a new [UnitParser] with input, scan paths and specifier resolve context.
     pub fn new(input: &'a str, context: SpecifierContext<'a>) -> Self {
         UnitParser {
+            source: input,
             inner: input,
             context,
+            errors: Rc::new(RefCell::new(Vec::new())),
         }
     }
 
+    /// Returns the errors reported so far, shared with the parser.
+    pub fn errors(&self) -> ReportedErrors {
+        Rc::clone(&self.errors)
+    }
+
     /// Moves the inner cursor forward by updating the `inner` field.
     pub fn progress(&mut self, i: &'a str) {
         self.inner = i;
@@ -39,28 +54,52 @@ impl<'a> Iterator for UnitParser<'a> {
     type Item = SectionParser<'a>;
     fn next(&mut self) -> Option<Self::Item> {
         loop {
+            let (start, _) = gaps(self.inner).unwrap_or((self.inner, ()));
+            let line = line_number(self.source, start);
             if let Ok((i, name)) = section_header(self.inner) {
                 self.inner = i;
                 return Some(SectionParser {
                     name,
+                    source: self.source,
                     inner: self.inner,
+                    line,
                     context: self.context,
+                    errors: Rc::clone(&self.errors),
                 });
-            } else {
-                let temp: IResult<&str, &str> = take_until("\n")(self.inner);
-                match temp {
-                    Ok((i, _)) => {
-                        self.inner = i.trim_start_matches('\n');
-                    }
-                    Err(_) => {
-                        return None;
-                    }
-                }
             }
+
+            /* an entry outside of any section */
+            let (i, content) = split_line(start);
+            if !content.trim().is_empty() {
+                self.errors.borrow_mut().push((
+                    line,
+                    Error::InvalidLineError {
+                        content: content.trim().to_string(),
+                    },
+                ));
+            }
+            if i.is_empty() {
+                return None;
+            }
+            self.inner = i.trim_start_matches('\n');
         }
     }
 }
 
+/// Splits the first line off, returns the rest starting with the newline and the first line.
+fn split_line(i: &str) -> (&str, &str) {
+    let temp: IResult<&str, &str> = take_until("\n")(i);
+    match temp {
+        Ok(v) => v,
+        Err(_) => ("", i),
+    }
+}
+
+/// The line number of the cursor, which is a suffix of the source, starting from 1.
+fn line_number(source: &str, cursor: &str) -> usize {
+    source[..source.len() - cursor.len()].matches('\n').count() + 1
+}
+
 /// Parses a section header.
 fn section_header(i: &str) -> IResult<&str, &str> {
     let (i, _) = gaps(i)?;
@@ -74,10 +113,16 @@ fn section_header(i: &str) -> IResult<&str, &str> {
 pub struct SectionParser<'a> {
     /// Section name
     pub name: &'a str,
+    /// The whole input, used to locate the cursor
+    source: &'a str,
     /// Parsing cursor
     inner: &'a str,
+    /// The line of the section header or the last entry
+    line: usize,
     /// Specifier resolve context
     context: SpecifierContext<'a>,
+    /// Errors reported by the parser and the sections
+    errors: ReportedErrors,
 }
 
 impl<'a> SectionParser<'a> {
@@ -85,17 +130,44 @@ impl<'a> SectionParser<'a> {
     pub fn finish(self) -> &'a str {
         self.inner
     }
+
+    /// Returns the line of the last entry, or the section header if no entry is parsed yet.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Reports an error at the line of the last entry, or the section header if no entry
+    /// is parsed yet.
+    pub fn report(&self, error: Error) {
+        self.errors.borrow_mut().push((self.line, error));
+    }
 }
 
 /// [SectionParser] is a [std::iter::Iterator] that yields [(&str, String)], which represents a key-value pair.
 impl<'a> Iterator for SectionParser<'a> {
     type Item = (&'a str, String);
     fn next(&mut self) -> Option<Self::Item> {
-        if let Ok((i, result)) = entry(self.inner, self.context) {
+        loop {
+            let line = line_number(self.source, self.inner);
+            if let Ok((i, result)) = entry(self.inner, self.context) {
+                self.inner = i;
+                self.line = line;
+                return Some(result);
+            }
+            if self.inner.trim().is_empty() || section_header(self.inner).is_ok() {
+                return None;
+            }
+
+            /* skip the invalid line and go on with the next entry */
+            let (i, content) = split_line(self.inner);
+            self.errors.borrow_mut().push((
+                line,
+                Error::InvalidLineError {
+                    content: content.trim().to_string(),
+                },
+            ));
+            let (i, _) = gaps(i).unwrap_or((i, ()));
             self.inner = i;
-            Some(result)
-        } else {
-            None
         }
     }
 }
@@ -175,3 +247,44 @@ fn gaps(i: &str) -> IResult<&str, ()> {
     let comment = delimited(is_a("#;"), is_not("\n\r"), is_a("\n\r"));
     value((), many0(alt((multispace1, comment))))(i)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_lines() {
+        let input = "junk\n# comment\n[Unit]\nDescription=a\nnot an entry\n\nAfter=b\n[Service]\nType=simple\n";
+        let mut parser = UnitParser::new(input, ("a.service",));
+        let errors = parser.errors();
+
+        let mut entries = Vec::new();
+        while let Some(mut section) = parser.next() {
+            while let Some((key, value)) = section.next() {
+                entries.push((section.name, key, value, section.line()));
+            }
+            parser.progress(section.finish());
+        }
+        assert_eq!(
+            entries,
+            vec![
+                ("Unit", "Description", "a".to_string(), 4),
+                ("Unit", "After", "b".to_string(), 7),
+                ("Service", "Type", "simple".to_string(), 9),
+            ]
+        );
+
+        let errors: Vec<(usize, String)> = errors
+            .take()
+            .into_iter()
+            .map(|(line, e)| (line, e.to_string()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (1, "Failed to parse line: junk.".to_string()),
+                (5, "Failed to parse line: not an entry.".to_string()),
+            ]
+        );
+    }
+}